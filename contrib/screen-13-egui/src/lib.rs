@@ -1,5 +1,5 @@
 pub mod prelude {
-    pub use super::{egui, Egui};
+    pub use super::{egui, CallbackFn, Egui};
 }
 
 pub use egui;
@@ -9,52 +9,63 @@ use {
     bytemuck::cast_slice,
     egui_winit::winit::{event::Event, event_loop::EventLoop, window::Window},
     screen_13::prelude::*,
-    std::{borrow::Cow, collections::HashMap, sync::Arc},
+    std::{
+        borrow::Cow,
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    },
 };
 
+type CallbackFnBox = Box<dyn FnMut(&mut RenderGraph, AnyImageNode, egui::PaintCallbackInfo) + Send>;
+
+/// A custom paint callback which records passes into the egui render target.
+///
+/// The callback receives the render graph, the egui target image and the viewport and clip
+/// rectangles of the callback; passes should set the clip rectangle as their scissor.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use screen_13_egui::prelude::*;
+/// # fn ui(ui: &mut egui::Ui) {
+/// let rect = ui.available_rect_before_wrap();
+/// ui.painter().add(egui::PaintCallback {
+///     rect,
+///     callback: Arc::new(CallbackFn::new(|render_graph, target, info| {
+///         let clip = info.clip_rect_in_pixels();
+///
+///         // Record passes which draw into target here
+///     })),
+/// });
+/// # }
+/// ```
+pub struct CallbackFn(Mutex<CallbackFnBox>);
+
+impl CallbackFn {
+    pub fn new(
+        f: impl FnMut(&mut RenderGraph, AnyImageNode, egui::PaintCallbackInfo) + Send + 'static,
+    ) -> Self {
+        Self(Mutex::new(Box::new(f)))
+    }
+}
+
 pub struct Egui {
     pub ctx: egui::Context,
     egui_winit: egui_winit::State,
     textures: HashMap<egui::TextureId, Arc<Lease<Image>>>,
     cache: HashPool,
+    device: Arc<Device>,
     ppl: Arc<GraphicPipeline>,
     next_tex_id: u64,
+    sampler_ppls: HashMap<SamplerInfo, Arc<GraphicPipeline>>,
+    user_samplers: HashMap<egui::TextureId, SamplerInfo>,
     user_textures: HashMap<egui::TextureId, AnyImageNode>,
 }
 
 impl Egui {
     pub fn new(device: &Arc<Device>, display_target: &dyn HasDisplayHandle) -> Self {
-        let ppl = Arc::new(
-            GraphicPipeline::create(
-                device,
-                GraphicPipelineInfoBuilder::default()
-                    .blend(BlendMode {
-                        blend_enable: true,
-                        src_color_blend_factor: vk::BlendFactor::ONE,
-                        dst_color_blend_factor: vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
-                        color_blend_op: vk::BlendOp::ADD,
-                        src_alpha_blend_factor: vk::BlendFactor::ONE,
-                        dst_alpha_blend_factor: vk::BlendFactor::ONE,
-                        alpha_blend_op: vk::BlendOp::ADD,
-                        color_write_mask: vk::ColorComponentFlags::R
-                            | vk::ColorComponentFlags::G
-                            | vk::ColorComponentFlags::B
-                            | vk::ColorComponentFlags::A,
-                    })
-                    .cull_mode(vk::CullModeFlags::NONE),
-                [
-                    Shader::new_vertex(
-                        inline_spirv::include_spirv!("shaders/vert.glsl", vert, vulkan1_2)
-                            .as_slice(),
-                    ),
-                    Shader::new_fragment(
-                        inline_spirv::include_spirv!("shaders/frag.glsl", frag, vulkan1_2)
-                            .as_slice(),
-                    ),
-                ],
-            )
-            .unwrap(),
-        );
+        let ppl = create_pipeline(device, None);
 
         let ctx = egui::Context::default();
         let max_texture_side = Some(
@@ -79,7 +90,10 @@ impl Egui {
             egui_winit,
             textures: HashMap::default(),
             cache: HashPool::new(device),
+            device: Arc::clone(device),
             next_tex_id: 0,
+            sampler_ppls: HashMap::default(),
+            user_samplers: HashMap::default(),
             user_textures: HashMap::default(),
         }
     }
@@ -92,7 +106,21 @@ impl Egui {
         let mut bound_tex = deltas
             .set
             .iter()
-            .map(|(id, delta)| {
+            .filter_map(|(id, delta)| {
+                // Partial updates are clamped to the existing texture; empty updates are skipped
+                let region = match delta.pos {
+                    Some(pos) => {
+                        let image = self
+                            .textures
+                            .get(id)
+                            .expect("Tried updating undefined texture.");
+
+                        Some(update_region(delta, pos, image.info)?)
+                    }
+                    None if delta.image.width() == 0 || delta.image.height() == 0 => return None,
+                    None => None,
+                };
+
                 let pixels = match &delta.image {
                     egui::ImageData::Color(image) => {
                         assert_eq!(image.width() * image.height(), image.pixels.len());
@@ -115,40 +143,13 @@ impl Egui {
                     render_graph.bind_node(buf)
                 };
 
-                if let Some(pos) = delta.pos {
+                if let Some(region) = region {
                     let image = AnyImageNode::ImageLease(
-                        self.textures
-                            .remove(id)
-                            .expect("Tried updating undefined texture.")
-                            .bind(render_graph),
+                        self.textures.remove(id).unwrap().bind(render_graph),
                     );
 
-                    render_graph.copy_buffer_to_image_region(
-                        tmp_buf,
-                        image,
-                        vk::BufferImageCopy {
-                            buffer_offset: 0,
-                            buffer_row_length: delta.image.width() as u32,
-                            buffer_image_height: delta.image.height() as u32,
-                            image_offset: vk::Offset3D {
-                                x: pos[0] as i32,
-                                y: pos[1] as i32,
-                                z: 0,
-                            },
-                            image_extent: vk::Extent3D {
-                                width: delta.image.width() as u32,
-                                height: delta.image.height() as u32,
-                                depth: 1,
-                            },
-                            image_subresource: vk::ImageSubresourceLayers {
-                                aspect_mask: vk::ImageAspectFlags::COLOR,
-                                mip_level: 0,
-                                base_array_layer: 0,
-                                layer_count: 1,
-                            },
-                        },
-                    );
-                    (*id, image)
+                    render_graph.copy_buffer_to_image_region(tmp_buf, image, region);
+                    Some((*id, image))
                 } else {
                     let image = AnyImageNode::ImageLease(
                         self.cache
@@ -164,7 +165,7 @@ impl Egui {
 
                     render_graph.copy_buffer_to_image(tmp_buf, image);
                     render_graph.unbind_node(tmp_buf);
-                    (*id, image)
+                    Some((*id, image))
                 }
             })
             .collect::<HashMap<_, _>>();
@@ -203,6 +204,7 @@ impl Egui {
         }
 
         self.next_tex_id = 0;
+        self.user_samplers.clear();
     }
 
     fn draw_primitive(
@@ -214,10 +216,12 @@ impl Egui {
     ) {
         let target = target.into();
         let target_info = render_graph.node_info(target);
+        let pixels_per_point = self.ctx.pixels_per_point();
+
         for egui::ClippedPrimitive {
             clip_rect,
             primitive,
-        } in self.ctx.tessellate(shapes, self.ctx.pixels_per_point())
+        } in self.ctx.tessellate(shapes, pixels_per_point)
         {
             match primitive {
                 egui::epaint::Primitive::Mesh(mesh) => {
@@ -227,6 +231,12 @@ impl Egui {
                         continue;
                     }
                     let texture = bound_tex.get(&mesh.texture_id).unwrap();
+                    let ppl = self
+                        .user_samplers
+                        .get(&mesh.texture_id)
+                        .map(|sampler| &self.sampler_ppls[sampler])
+                        .unwrap_or(&self.ppl)
+                        .clone();
 
                     let idx_buf = {
                         let mut buf = self
@@ -261,8 +271,6 @@ impl Egui {
                         screen_size: [f32; 2],
                     }
 
-                    let pixels_per_point = self.ctx.pixels_per_point();
-
                     let push_constants = PushConstants {
                         screen_size: [
                             target_info.width as f32 / pixels_per_point,
@@ -282,7 +290,7 @@ impl Egui {
 
                     render_graph
                         .begin_pass("Egui pass")
-                        .bind_pipeline(&self.ppl)
+                        .bind_pipeline(&ppl)
                         .access_node(idx_buf, AccessType::IndexBuffer)
                         .access_node(vert_buf, AccessType::VertexBuffer)
                         .access_descriptor((0, 0), *texture, AccessType::FragmentShaderReadOther)
@@ -296,7 +304,21 @@ impl Egui {
                             subpass.draw_indexed(num_indices, 1, 0, 0, 0);
                         });
                 }
-                _ => panic!("Primitiv callback not yet supported."),
+                egui::epaint::Primitive::Callback(callback) => {
+                    // Callbacks meant for other integrations are ignored
+                    let Some(callback_fn) = callback.callback.downcast_ref::<CallbackFn>() else {
+                        continue;
+                    };
+
+                    let info = egui::PaintCallbackInfo {
+                        viewport: callback.rect,
+                        clip_rect,
+                        pixels_per_point,
+                        screen_size_px: [target_info.width, target_info.height],
+                    };
+
+                    (callback_fn.0.lock().unwrap())(render_graph, target, info);
+                }
             }
         }
     }
@@ -339,4 +361,165 @@ impl Egui {
         self.user_textures.insert(id, tex.into());
         id
     }
+
+    /// Registers a texture which is drawn using the given sampler instead of the default linear
+    /// sampler, for instance `SamplerInfo::NEAREST` for pixel-art.
+    ///
+    /// Like [`Egui::register_texture`], textures must be registered again each frame.
+    pub fn register_texture_with_sampler(
+        &mut self,
+        tex: impl Into<AnyImageNode>,
+        sampler: impl Into<SamplerInfo>,
+    ) -> egui::TextureId {
+        let sampler = sampler.into();
+        let id = self.register_texture(tex);
+
+        let device = &self.device;
+        self.sampler_ppls
+            .entry(sampler)
+            .or_insert_with(|| create_pipeline(device, Some(sampler)));
+        self.user_samplers.insert(id, sampler);
+
+        id
+    }
+}
+
+fn create_pipeline(device: &Arc<Device>, sampler: Option<SamplerInfo>) -> Arc<GraphicPipeline> {
+    let mut frag = Shader::new_fragment(
+        inline_spirv::include_spirv!("shaders/frag.glsl", frag, vulkan1_2).as_slice(),
+    );
+
+    if let Some(sampler) = sampler {
+        frag = frag.image_sampler(0, sampler);
+    }
+
+    Arc::new(
+        GraphicPipeline::create(
+            device,
+            GraphicPipelineInfoBuilder::default()
+                .blend(BlendMode {
+                    blend_enable: true,
+                    src_color_blend_factor: vk::BlendFactor::ONE,
+                    dst_color_blend_factor: vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
+                    color_blend_op: vk::BlendOp::ADD,
+                    src_alpha_blend_factor: vk::BlendFactor::ONE,
+                    dst_alpha_blend_factor: vk::BlendFactor::ONE,
+                    alpha_blend_op: vk::BlendOp::ADD,
+                    color_write_mask: vk::ColorComponentFlags::R
+                        | vk::ColorComponentFlags::G
+                        | vk::ColorComponentFlags::B
+                        | vk::ColorComponentFlags::A,
+                })
+                .cull_mode(vk::CullModeFlags::NONE),
+            [
+                Shader::new_vertex(
+                    inline_spirv::include_spirv!("shaders/vert.glsl", vert, vulkan1_2).as_slice(),
+                ),
+                frag,
+            ],
+        )
+        .unwrap(),
+    )
+}
+
+/// Returns the copy region of a partial texture update at `pos`, clamped to the bounds of the
+/// texture image, or `None` if the update does not cover any texels.
+fn update_region(
+    delta: &egui::epaint::ImageDelta,
+    pos: [usize; 2],
+    image_info: ImageInfo,
+) -> Option<vk::BufferImageCopy> {
+    let (x, y) = (pos[0] as u32, pos[1] as u32);
+    let width = (delta.image.width() as u32).min(image_info.width.saturating_sub(x));
+    let height = (delta.image.height() as u32).min(image_info.height.saturating_sub(y));
+
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    Some(vk::BufferImageCopy {
+        buffer_offset: 0,
+        buffer_row_length: delta.image.width() as u32,
+        buffer_image_height: delta.image.height() as u32,
+        image_offset: vk::Offset3D {
+            x: x as i32,
+            y: y as i32,
+            z: 0,
+        },
+        image_extent: vk::Extent3D {
+            width,
+            height,
+            depth: 1,
+        },
+        image_subresource: vk::ImageSubresourceLayers {
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            mip_level: 0,
+            base_array_layer: 0,
+            layer_count: 1,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn font_image_info() -> ImageInfo {
+        ImageInfo::image_2d(
+            64,
+            32,
+            vk::Format::R8G8B8A8_UNORM,
+            vk::ImageUsageFlags::SAMPLED | vk::ImageUsageFlags::TRANSFER_DST,
+        )
+    }
+
+    fn image_delta(width: usize, height: usize, pos: [usize; 2]) -> egui::epaint::ImageDelta {
+        egui::epaint::ImageDelta::partial(
+            pos,
+            egui::ColorImage::new([width, height], egui::Color32::TRANSPARENT),
+            egui::TextureOptions::default(),
+        )
+    }
+
+    #[test]
+    pub fn update_region_clamped() {
+        let delta = image_delta(16, 16, [56, 24]);
+        let region = update_region(&delta, delta.pos.unwrap(), font_image_info()).unwrap();
+
+        assert_eq!(region.buffer_row_length, 16);
+        assert_eq!(region.buffer_image_height, 16);
+        assert_eq!(region.image_offset.x, 56);
+        assert_eq!(region.image_offset.y, 24);
+        assert_eq!(region.image_extent.width, 8);
+        assert_eq!(region.image_extent.height, 8);
+    }
+
+    #[test]
+    pub fn update_region_inside() {
+        let delta = image_delta(16, 8, [4, 2]);
+        let region = update_region(&delta, delta.pos.unwrap(), font_image_info()).unwrap();
+
+        assert_eq!(region.image_offset.x, 4);
+        assert_eq!(region.image_offset.y, 2);
+        assert_eq!(region.image_extent.width, 16);
+        assert_eq!(region.image_extent.height, 8);
+    }
+
+    #[test]
+    pub fn update_region_out_of_bounds() {
+        let delta = image_delta(16, 16, [64, 0]);
+
+        assert!(update_region(&delta, delta.pos.unwrap(), font_image_info()).is_none());
+    }
+
+    #[test]
+    pub fn update_region_zero_size() {
+        let info = font_image_info();
+
+        for (width, height) in [(0, 0), (0, 16), (16, 0)] {
+            let delta = image_delta(width, height, [8, 8]);
+
+            assert!(update_region(&delta, delta.pos.unwrap(), info).is_none());
+        }
+    }
 }
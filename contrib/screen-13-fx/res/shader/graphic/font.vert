@@ -12,13 +12,19 @@ layout(set = 0, binding = 0) uniform sampler2D pages_sampler_nnr[NUM_PAGES];
 layout(location = 0) in vec2 position_in;
 layout(location = 1) in vec2 texcoord_in;
 layout(location = 2) in int page_in;
+layout(location = 3) in uint glyph_color_in;
+layout(location = 4) in uint outline_color_in;
 
 layout(location = 0) out vec2 texcoord_out;
 layout(location = 1) out int page_out;
+layout(location = 2) out vec4 glyph_color_out;
+layout(location = 3) out vec4 outline_color_out;
 
 void main() {
     texcoord_out = texcoord_in / textureSize(pages_sampler_nnr[page_in], 0);
     page_out = page_in;
+    glyph_color_out = unpackUnorm4x8(glyph_color_in);
+    outline_color_out = unpackUnorm4x8(outline_color_in);
 
     gl_Position = push_constants.view_proj
         * vec4(position_in * push_constants.framebuffer_extent_inverse, 0, 1);
@@ -0,0 +1,36 @@
+#version 460 core
+
+layout(constant_id = 0) const uint NUM_PAGES = 1;
+
+layout(push_constant) uniform PushConstants {
+    layout(offset = 80) float border_width;
+} push_constants;
+
+layout(set = 0, binding = 0) uniform sampler2D pages_sampler_lne[NUM_PAGES];
+
+layout(location = 0) in vec2 texcoord;
+layout(location = 1) in flat int page_in;
+layout(location = 2) in flat vec4 glyph_color;
+layout(location = 3) in flat vec4 border_color;
+
+layout(location = 0) out vec4 color;
+
+// Distance values of the glyph edge: larger values are inside the glyph
+const float EDGE = 0.5;
+
+void main() {
+    float distance = texture(pages_sampler_lne[page_in], texcoord).r;
+    float smoothing = fwidth(distance);
+    float glyph_alpha = smoothstep(EDGE - smoothing, EDGE + smoothing, distance);
+
+    if (push_constants.border_width > 0.0) {
+        float border_edge = EDGE - push_constants.border_width;
+        float border_alpha = smoothstep(border_edge - smoothing, border_edge + smoothing, distance);
+
+        color = mix(border_color, glyph_color, glyph_alpha);
+        color.a *= border_alpha;
+    } else {
+        color = glyph_color;
+        color.a *= glyph_alpha;
+    }
+}
\ No newline at end of file
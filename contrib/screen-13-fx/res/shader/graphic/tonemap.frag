@@ -0,0 +1,69 @@
+#version 460 core
+
+layout(location = 0) in vec2 uv;
+
+layout(push_constant) uniform PushConstants {
+    layout(offset = 64) float exposure;
+    layout(offset = 68) uint operator;
+} push_constants;
+
+layout(set = 0, binding = 0) uniform sampler2D image_sampler_llr;
+
+layout(location = 0) out vec4 color;
+
+const uint OPERATOR_ACES = 0;
+const uint OPERATOR_REINHARD = 1;
+const uint OPERATOR_UNCHARTED2 = 2;
+
+// Krzysztof Narkowicz: ACES Filmic Tone Mapping Curve
+vec3 aces(vec3 x) {
+    const float a = 2.51;
+    const float b = 0.03;
+    const float c = 2.43;
+    const float d = 0.59;
+    const float e = 0.14;
+
+    return clamp((x * (a * x + b)) / (x * (c * x + d) + e), 0.0, 1.0);
+}
+
+vec3 reinhard(vec3 x) {
+    return x / (1.0 + x);
+}
+
+// John Hable: Uncharted 2 Tone Mapping
+vec3 uncharted2_partial(vec3 x) {
+    const float A = 0.15;
+    const float B = 0.50;
+    const float C = 0.10;
+    const float D = 0.20;
+    const float E = 0.02;
+    const float F = 0.30;
+
+    return ((x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F)) - E / F;
+}
+
+vec3 uncharted2(vec3 x) {
+    const float EXPOSURE_BIAS = 2.0;
+    const vec3 WHITE = vec3(11.2);
+
+    return uncharted2_partial(x * EXPOSURE_BIAS) / uncharted2_partial(WHITE);
+}
+
+void main()
+{
+    vec3 image_sample = texture(image_sampler_llr, uv).rgb * push_constants.exposure;
+
+    switch (push_constants.operator) {
+        case OPERATOR_ACES:
+            image_sample = aces(image_sample);
+            break;
+        case OPERATOR_REINHARD:
+            image_sample = reinhard(image_sample);
+            break;
+        case OPERATOR_UNCHARTED2:
+            image_sample = uncharted2(image_sample);
+            break;
+    }
+
+    color = vec4(image_sample, 1.0);
+}
\ No newline at end of file
@@ -1,22 +1,21 @@
 use {
     anyhow::Context,
     bmfont::BMFont,
-    bytemuck::{cast, cast_slice},
+    bytemuck::cast_slice,
     glam::{vec3, Mat4},
     inline_spirv::include_spirv,
     screen_13::prelude::*,
-    std::sync::Arc,
+    std::{iter::repeat, sync::Arc},
 };
 
 type Color = [u8; 4];
 
-fn color_to_unorm(color: Color) -> [u8; 16] {
-    cast([
-        (color[0] as f32 / u8::MAX as f32).to_ne_bytes(),
-        (color[1] as f32 / u8::MAX as f32).to_ne_bytes(),
-        (color[2] as f32 / u8::MAX as f32).to_ne_bytes(),
-        (color[3] as f32 / u8::MAX as f32).to_ne_bytes(),
-    ])
+/// Size, in bytes, of each vertex: position, texcoord, page index, glyph and outline colors.
+const VERTEX_SIZE: usize = 28;
+
+/// Packs a color so that it may be read using `unpackUnorm4x8`.
+fn color_to_u32(color: Color) -> u32 {
+    u32::from_le_bytes(color)
 }
 
 /// Holds a decoded bitmap Font.
@@ -24,6 +23,7 @@ fn color_to_unorm(color: Color) -> [u8; 16] {
 pub struct BitmapFont {
     cache: HashPool,
     font: BMFont,
+    line_height: Option<u32>,
     pages: Vec<Arc<Image>>,
     pipeline: Arc<GraphicPipeline>,
 }
@@ -33,29 +33,42 @@ impl BitmapFont {
         device: &Arc<Device>,
         font: BMFont,
         pages: impl Into<Vec<Arc<Image>>>,
+    ) -> anyhow::Result<Self> {
+        Self::create(
+            device,
+            font,
+            pages.into(),
+            Shader::new_vertex(include_spirv!("res/shader/graphic/font.vert", vert).as_slice()),
+            Shader::new_fragment(include_spirv!("res/shader/graphic/font.frag", frag).as_slice()),
+        )
+    }
+
+    /// Creates a font which renders glyphs using the given shaders. The shaders are specialized
+    /// with the number of pages.
+    pub(super) fn create(
+        device: &Arc<Device>,
+        font: BMFont,
+        pages: Vec<Arc<Image>>,
+        vertex_shader: ShaderBuilder,
+        fragment_shader: ShaderBuilder,
     ) -> anyhow::Result<Self> {
         let cache = HashPool::new(device);
-        let pages = pages.into();
         let num_pages = pages.len() as u32;
+        let specialization_info = SpecializationInfo::new(
+            [vk::SpecializationMapEntry {
+                constant_id: 0,
+                offset: 0,
+                size: 4,
+            }],
+            num_pages.to_ne_bytes(),
+        );
         let pipeline = Arc::new(
             GraphicPipeline::create(
                 device,
                 GraphicPipelineInfoBuilder::default().blend(BlendMode::ALPHA),
                 [
-                    Shader::new_vertex(
-                        include_spirv!("res/shader/graphic/font.vert", vert).as_slice(),
-                    ),
-                    Shader::new_fragment(
-                        include_spirv!("res/shader/graphic/font.frag", frag).as_slice(),
-                    )
-                    .specialization_info(SpecializationInfo::new(
-                        [vk::SpecializationMapEntry {
-                            constant_id: 0,
-                            offset: 0,
-                            size: 4,
-                        }],
-                        num_pages.to_ne_bytes(),
-                    )),
+                    vertex_shader.specialization_info(specialization_info.clone()),
+                    fragment_shader.specialization_info(specialization_info),
                 ],
             )
             .context("Unable to create bitmap font pipeline")?,
@@ -64,18 +77,47 @@ impl BitmapFont {
         Ok(Self {
             cache,
             font,
+            line_height: None,
             pages,
             pipeline,
         })
     }
 
+    /// Returns the glyphs of the given text, placing each line of text (_separated by `\n`_) at
+    /// the current line height.
+    fn layout(&self, text: &str) -> Vec<BitmapGlyph> {
+        let Some(line_height) = self.line_height else {
+            return self.font.parse(text);
+        };
+
+        let mut glyphs = vec![];
+
+        for (line_idx, line) in text.split('\n').enumerate() {
+            let y = (line_idx as u32 * line_height) as i32;
+
+            glyphs.extend(self.font.parse(line).into_iter().map(|mut glyph| {
+                glyph.screen_rect.y += y;
+                glyph
+            }));
+        }
+
+        glyphs
+    }
+
+    /// Returns the distance, in pixels, between lines of text.
+    pub fn line_height(&self) -> u32 {
+        self.line_height.unwrap_or_else(|| self.font.line_height())
+    }
+
     // TODO: Add description and example showing layout area, top/bottom explanation, etc
     /// Returns the position and area, in pixels, required to render the given text.
     ///
+    /// Text may contain `\n` line breaks. Kerning pairs of the font data are applied to each line.
+    ///
     /// **_NOTE:_** The 'start' of the render area is at the zero coordinate, however it may extend
     /// into the negative x direction due to ligatures.
     pub fn measure(&self, text: &str) -> ([i32; 2], [u32; 2]) {
-        let parse = self.font.parse(text);
+        let parse = self.layout(text);
 
         // TODO: Use if we enable parsing errors on bmfont library
         // if parse.is_err() {
@@ -135,22 +177,41 @@ impl BitmapFont {
         self.print_scale_scissor(graph, image, x, y, color, text, scale, None);
     }
 
-    // TODO: Better API, but not sure what, probably builder-something
+    /// Prints a sequence of differently colored runs of text, in a single draw, as if the runs
+    /// were one string.
+    ///
+    /// The pen is advanced between runs (_kerning pairs which span runs are applied_) and runs may
+    /// contain `\n` line breaks.
+    pub fn print_runs<S>(
+        &mut self,
+        graph: &mut RenderGraph,
+        image: impl Into<AnyImageNode>,
+        x: f32,
+        y: f32,
+        runs: &[(S, BitmapGlyphColor)],
+    ) where
+        S: AsRef<str>,
+    {
+        self.print_runs_scale_scissor(graph, image, x, y, runs, 1.0, None, None);
+    }
+
+    /// Records a pass which draws the given runs of text. If `border_width` is specified it is
+    /// pushed as a fragment shader constant at offset `80`.
     #[allow(clippy::too_many_arguments)]
-    pub fn print_scale_scissor(
+    pub(super) fn print_runs_scale_scissor<S>(
         &mut self,
         graph: &mut RenderGraph,
         image: impl Into<AnyImageNode>,
         x: f32,
         y: f32,
-        color: impl Into<BitmapGlyphColor>,
-        text: impl AsRef<str>,
+        runs: &[(S, BitmapGlyphColor)],
         scale: f32,
         scissor: Option<(i32, i32, u32, u32)>,
-    ) {
-        let color = color.into();
+        border_width: Option<f32>,
+    ) where
+        S: AsRef<str>,
+    {
         let image = image.into();
-        let text = text.as_ref();
         let image_info = graph.node_info(image);
         let transform = Mat4::from_translation(vec3(-1.0, -1.0, 0.0))
             * Mat4::from_scale(vec3(2.0 * scale, 2.0 * scale, 1.0))
@@ -160,7 +221,22 @@ impl BitmapFont {
                 0.0,
             ));
 
-        let vertex_buf_len = 120 * text.chars().count() as vk::DeviceSize;
+        // Layout all runs at once so that the pen position and kerning carry across runs
+        let text = runs
+            .iter()
+            .map(|(text, _)| text.as_ref())
+            .collect::<String>();
+        let glyphs = self.layout(&text);
+
+        // Each run colors as many glyphs as it would have produced on its own
+        let colors = runs.iter().flat_map(|(text, color)| {
+            let glyph_color = color_to_u32(color.solid());
+            let outline_color = color_to_u32(color.outline());
+
+            repeat((glyph_color, outline_color)).take(self.font.parse(text.as_ref()).len())
+        });
+
+        let vertex_buf_len = (6 * VERTEX_SIZE * glyphs.len().max(1)) as vk::DeviceSize;
         let mut vertex_buf = self
             .cache
             .lease(BufferInfo::host_mem(
@@ -176,25 +252,20 @@ impl BitmapFont {
                 &mut Buffer::mapped_slice_mut(&mut vertex_buf)[0..vertex_buf_len as usize];
 
             let mut offset = 0;
-            for (data, char) in self.font.parse(text).map(|char| (char.tessellate(), char)) {
-                vertex_buf[offset..offset + 16].copy_from_slice(&data[0]);
-                vertex_buf[offset + 20..offset + 36].copy_from_slice(&data[1]);
-                vertex_buf[offset + 40..offset + 56].copy_from_slice(&data[2]);
-                vertex_buf[offset + 60..offset + 76].copy_from_slice(&data[3]);
-                vertex_buf[offset + 80..offset + 96].copy_from_slice(&data[4]);
-                vertex_buf[offset + 100..offset + 116].copy_from_slice(&data[5]);
-
-                let page_idx = char.page_index as i32;
-                let page_idx = page_idx.to_ne_bytes();
-                vertex_buf[offset + 16..offset + 20].copy_from_slice(&page_idx);
-                vertex_buf[offset + 36..offset + 40].copy_from_slice(&page_idx);
-                vertex_buf[offset + 56..offset + 60].copy_from_slice(&page_idx);
-                vertex_buf[offset + 76..offset + 80].copy_from_slice(&page_idx);
-                vertex_buf[offset + 96..offset + 100].copy_from_slice(&page_idx);
-                vertex_buf[offset + 116..offset + 120].copy_from_slice(&page_idx);
-
-                vertex_count += 6;
-                offset += 120;
+            for (char, (glyph_color, outline_color)) in glyphs.iter().zip(colors) {
+                let page_idx = (char.page_index as i32).to_ne_bytes();
+                let glyph_color = glyph_color.to_ne_bytes();
+                let outline_color = outline_color.to_ne_bytes();
+
+                for data in char.tessellate() {
+                    vertex_buf[offset..offset + 16].copy_from_slice(&data);
+                    vertex_buf[offset + 16..offset + 20].copy_from_slice(&page_idx);
+                    vertex_buf[offset + 20..offset + 24].copy_from_slice(&glyph_color);
+                    vertex_buf[offset + 24..offset + 28].copy_from_slice(&outline_color);
+
+                    vertex_count += 1;
+                    offset += VERTEX_SIZE;
+                }
             }
         }
 
@@ -224,13 +295,98 @@ impl BitmapFont {
             subpass
                 .push_constants(cast_slice(&transform.to_cols_array()))
                 .push_constants_offset(64, &(1.0 / image_info.width as f32).to_ne_bytes())
-                .push_constants_offset(68, &(1.0 / image_info.height as f32).to_ne_bytes())
-                .push_constants_offset(80, &color_to_unorm(color.solid()))
-                .push_constants_offset(96, &color_to_unorm(color.outline()))
+                .push_constants_offset(68, &(1.0 / image_info.height as f32).to_ne_bytes());
+
+            if let Some(border_width) = border_width {
+                subpass.push_constants_offset(80, &border_width.to_ne_bytes());
+            }
+
+            subpass
                 .bind_vertex_buffer(vertex_buf)
                 .draw(vertex_count, 1, 0, 0);
         });
     }
+
+    // TODO: Better API, but not sure what, probably builder-something
+    #[allow(clippy::too_many_arguments)]
+    pub fn print_scale_scissor(
+        &mut self,
+        graph: &mut RenderGraph,
+        image: impl Into<AnyImageNode>,
+        x: f32,
+        y: f32,
+        color: impl Into<BitmapGlyphColor>,
+        text: impl AsRef<str>,
+        scale: f32,
+        scissor: Option<(i32, i32, u32, u32)>,
+    ) {
+        self.print_runs_scale_scissor(
+            graph,
+            image,
+            x,
+            y,
+            &[(text, color.into())],
+            scale,
+            scissor,
+            None,
+        );
+    }
+
+    /// Prints text which is word-wrapped so that no line is wider than `max_width` pixels.
+    ///
+    /// Words which are wider than `max_width` are printed on a line of their own.
+    #[allow(clippy::too_many_arguments)]
+    pub fn print_wrapped(
+        &mut self,
+        graph: &mut RenderGraph,
+        image: impl Into<AnyImageNode>,
+        x: f32,
+        y: f32,
+        color: impl Into<BitmapGlyphColor>,
+        text: impl AsRef<str>,
+        max_width: u32,
+    ) {
+        let text = self.wrap(text.as_ref(), max_width);
+        self.print(graph, image, x, y, color, text);
+    }
+
+    /// Sets the distance, in pixels, between lines of text.
+    ///
+    /// When `None` (_the default_) the line height of the font data is used.
+    pub fn set_line_height(&mut self, line_height: impl Into<Option<u32>>) {
+        self.line_height = line_height.into();
+    }
+
+    /// Returns the given text with `\n` line breaks inserted between words so that no line is
+    /// wider than `max_width` pixels.
+    pub fn wrap(&self, text: &str, max_width: u32) -> String {
+        let mut res = String::with_capacity(text.len());
+
+        for (line_idx, line) in text.split('\n').enumerate() {
+            if line_idx > 0 {
+                res.push('\n');
+            }
+
+            let mut line_start = res.len();
+
+            for (word_idx, word) in line.split(' ').enumerate() {
+                if word_idx > 0 {
+                    let (_, [width, _]) = self.measure(&format!("{} {word}", &res[line_start..]));
+
+                    if width > max_width {
+                        res.push('\n');
+                        line_start = res.len();
+                    } else {
+                        res.push(' ');
+                    }
+                }
+
+                res.push_str(word);
+            }
+        }
+
+        res
+    }
 }
 
 pub enum BitmapGlyphColor {
@@ -242,14 +398,14 @@ pub enum BitmapGlyphColor {
 impl BitmapGlyphColor {
     const TRANSARENT: Color = [0, 0, 0, u8::MAX];
 
-    fn outline(&self) -> Color {
+    pub(super) fn outline(&self) -> Color {
         match self {
             Self::Outline(color) => *color,
             _ => Self::TRANSARENT,
         }
     }
 
-    fn solid(&self) -> Color {
+    pub(super) fn solid(&self) -> Color {
         match self {
             Self::Outline(_) => Self::TRANSARENT,
             Self::Solid(color) => *color,
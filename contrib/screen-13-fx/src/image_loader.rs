@@ -1,42 +1,84 @@
 use {
-    super::BitmapFont, anyhow::Context, bmfont::BMFont, inline_spirv::include_spirv, log::info,
-    screen_13::prelude::*, std::sync::Arc,
+    super::{BitmapFont, SdfFont},
+    anyhow::{bail, ensure, Context},
+    bmfont::BMFont,
+    bytemuck::cast_slice,
+    inline_spirv::include_spirv,
+    log::info,
+    screen_13::{
+        driver::{format_texel_block_extent, format_texel_block_size},
+        prelude::*,
+    },
+    std::sync::Arc,
 };
 
 #[cfg(debug_assertions)]
 use log::warn;
 
+/// Selects the source of one channel of an image converted by [`ImageLoader::convert_bitmap`]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ImageChannel {
+    R,
+    G,
+    B,
+    A,
+    Zero,
+    One,
+}
+
+impl ImageChannel {
+    /// Reverses the color channels of BGR or BGRA pixels.
+    pub const BGRA: [Self; 4] = [Self::B, Self::G, Self::R, Self::A];
+
+    /// Leaves all channels unchanged.
+    pub const IDENTITY: [Self; 4] = [Self::R, Self::G, Self::B, Self::A];
+
+    fn pack(swizzle: [Self; 4]) -> u32 {
+        u32::from_le_bytes(swizzle.map(|channel| channel as u8))
+    }
+}
+
 /// Describes the channels and pixel stride of an image format
+///
+/// The channels of 16-bit formats must be native-endian: for example, the 16-bit samples of PNG
+/// files are big-endian and must be swapped on most hosts.
 #[derive(Clone, Copy, Debug)]
 pub enum ImageFormat {
     R8,
     R8G8,
     R8G8B8,
     R8G8B8A8,
+    R16,
+    R16G16,
+    R16G16B16A16,
 }
 
 impl ImageFormat {
     fn stride(self) -> usize {
         match self {
             Self::R8 => 1,
-            Self::R8G8 => 2,
+            Self::R8G8 | Self::R16 => 2,
             Self::R8G8B8 => 3,
-            Self::R8G8B8A8 => 4,
+            Self::R8G8B8A8 | Self::R16G16 => 4,
+            Self::R16G16B16A16 => 8,
         }
     }
 }
 
 #[derive(Debug)]
 pub struct ImageLoader {
+    generate_mipmaps: bool,
     pool: HashPool,
     _decode_r_rg: Arc<ComputePipeline>,
     decode_rgb_rgba: Arc<ComputePipeline>,
+    decode_rgba_rgba: Arc<ComputePipeline>,
     pub device: Arc<Device>,
 }
 
 impl ImageLoader {
     pub fn new(device: &Arc<Device>) -> Result<Self, DriverError> {
         Ok(Self {
+            generate_mipmaps: false,
             pool: HashPool::new(device),
             _decode_r_rg: Arc::new(ComputePipeline::create(
                 device,
@@ -53,6 +95,14 @@ impl ImageLoader {
                         .as_slice(),
                 ),
             )?),
+            decode_rgba_rgba: Arc::new(ComputePipeline::create(
+                device,
+                ComputePipelineInfo::default(),
+                Shader::new_compute(
+                    include_spirv!("res/shader/compute/decode_bitmap_rgba_rgba.comp", comp)
+                        .as_slice(),
+                ),
+            )?),
             device: Arc::clone(device),
         })
     }
@@ -66,15 +116,28 @@ impl ImageLoader {
         is_temporary: bool,
     ) -> anyhow::Result<Arc<Image>> {
         let format = match format {
-            ImageFormat::R8 | ImageFormat::R8G8 => {
+            ImageFormat::R8 => {
+                if is_srgb {
+                    bail!("Unsupported format: R8_SRGB");
+                } else {
+                    vk::Format::R8_UNORM
+                }
+            }
+            ImageFormat::R8G8 => {
                 if is_temporary {
                     vk::Format::R8G8_UINT
                 } else if is_srgb {
-                    panic!("Unsupported format: R8G8_SRGB");
+                    bail!("Unsupported format: R8G8_SRGB");
                 } else {
                     vk::Format::R8G8_UNORM
                 }
             }
+            ImageFormat::R16 | ImageFormat::R16G16 | ImageFormat::R16G16B16A16 if is_srgb => {
+                bail!("Unsupported format: {format:?} SRGB");
+            }
+            ImageFormat::R16 => vk::Format::R16_UNORM,
+            ImageFormat::R16G16 => vk::Format::R16G16_UNORM,
+            ImageFormat::R16G16B16A16 => vk::Format::R16G16B16A16_UNORM,
             ImageFormat::R8G8B8 | ImageFormat::R8G8B8A8 => {
                 if is_temporary {
                     vk::Format::R8G8B8A8_UINT
@@ -95,22 +158,40 @@ impl ImageLoader {
                 | vk::ImageUsageFlags::TRANSFER_SRC
         };
 
+        let mip_level_count = if self.generate_mipmaps && !is_temporary {
+            u32::BITS - width.max(height).leading_zeros()
+        } else {
+            1
+        };
+
         Ok(Arc::new(
             Image::create(
                 &self.device,
-                ImageInfo::image_2d(width, height, format, usage),
+                ImageInfo::image_2d(width, height, format, usage)
+                    .to_builder()
+                    .mip_level_count(mip_level_count),
             )
             .context("Unable to create new image")?,
         ))
     }
 
+    /// Decodes an image, converting and swizzling channels on the GPU as required.
+    ///
+    /// `swizzle` selects the source of each channel of the resulting image. `R8G8B8` pixels are
+    /// expanded to four channels where the alpha channel is always one, and `R8G8B8` or `R8G8B8A8`
+    /// pixels may be swizzled, for instance using [`ImageChannel::BGRA`] for BGR or BGRA data. All
+    /// other formats only support [`ImageChannel::IDENTITY`].
+    ///
+    /// Returns an error if `is_srgb` is set for an `R8`, `R8G8` or 16-bit format, which have no sRGB
+    /// equivalent.
     #[allow(clippy::too_many_arguments)]
-    pub fn decode_bitmap(
+    pub fn convert_bitmap(
         &mut self,
         queue_family_index: usize,
         queue_index: usize,
         pixels: &[u8],
         format: ImageFormat,
+        swizzle: [ImageChannel; 4],
         width: u32,
         height: u32,
         is_srgb: bool,
@@ -133,17 +214,21 @@ impl ImageLoader {
             warn!("unused data");
         }
 
+        let is_identity = swizzle == ImageChannel::IDENTITY;
+
+        ensure!(
+            is_identity || matches!(format, ImageFormat::R8G8B8 | ImageFormat::R8G8B8A8),
+            "Unsupported swizzle for format: {format:?}"
+        );
+
+        let swizzle = ImageChannel::pack(swizzle);
+
         let mut render_graph = RenderGraph::new();
         let image =
             render_graph.bind_node(self.create_image(format, width, height, is_srgb, false)?);
 
         // Fill the image from the temporary buffer
         match format {
-            ImageFormat::R8 => {
-                // This format requires a conversion
-                info!("Converting R to RG");
-                todo!()
-            }
             ImageFormat::R8G8B8 => {
                 // This format requires a conversion
                 //info!("Converting RGB to RGBA");
@@ -199,13 +284,50 @@ impl ImageLoader {
                     .write_descriptor(1, temp_image)
                     .record_compute(move |compute, _| {
                         compute
-                            .push_constants(&(pixel_buf_stride >> 2).to_ne_bytes())
+                            .push_constants(cast_slice(&[pixel_buf_stride >> 2, swizzle]))
                             .dispatch(dispatch_x, dispatch_y, 1);
                     })
                     .submit_pass()
                     .copy_image(temp_image, image);
             }
-            ImageFormat::R8G8 | ImageFormat::R8G8B8A8 => {
+            ImageFormat::R8G8B8A8 if !is_identity => {
+                let pixel_buf_len = format.stride() * (width * height) as usize;
+
+                // Lease a temporary buffer from the cache pool
+                let mut pixel_buf = self.pool.lease(BufferInfo::host_mem(
+                    pixel_buf_len as _,
+                    vk::BufferUsageFlags::STORAGE_BUFFER,
+                ))?;
+
+                {
+                    // Fill the temporary buffer with the bitmap pixels
+                    let pixel_buf = &mut Buffer::mapped_slice_mut(&mut pixel_buf)[0..pixel_buf_len];
+                    pixel_buf.copy_from_slice(&pixels[0..pixel_buf_len]);
+                }
+
+                let pixel_buf = render_graph.bind_node(pixel_buf);
+                let temp_image =
+                    render_graph.bind_node(self.create_image(format, width, height, false, true)?);
+
+                render_graph
+                    .begin_pass("Swizzle RGBA image")
+                    .bind_pipeline(&self.decode_rgba_rgba)
+                    .read_descriptor(0, pixel_buf)
+                    .write_descriptor(1, temp_image)
+                    .record_compute(move |compute, _| {
+                        compute
+                            .push_constants(cast_slice(&[width, swizzle]))
+                            .dispatch(width, height, 1);
+                    })
+                    .submit_pass()
+                    .copy_image(temp_image, image);
+            }
+            ImageFormat::R8
+            | ImageFormat::R8G8
+            | ImageFormat::R8G8B8A8
+            | ImageFormat::R16
+            | ImageFormat::R16G16
+            | ImageFormat::R16G16B16A16 => {
                 // Lease a temporary buffer from the pool
                 let mut pixel_buf = self.pool.lease(BufferInfo::host_mem(
                     pixels.len() as _,
@@ -223,6 +345,12 @@ impl ImageLoader {
             }
         }
 
+        if self.generate_mipmaps {
+            render_graph
+                .generate_mipmaps(image)
+                .context("Unable to generate mipmaps")?;
+        }
+
         let image = render_graph.unbind_node(image);
 
         render_graph
@@ -232,6 +360,30 @@ impl ImageLoader {
         Ok(image)
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub fn decode_bitmap(
+        &mut self,
+        queue_family_index: usize,
+        queue_index: usize,
+        pixels: &[u8],
+        format: ImageFormat,
+        width: u32,
+        height: u32,
+        is_srgb: bool,
+    ) -> anyhow::Result<Arc<Image>> {
+        self.convert_bitmap(
+            queue_family_index,
+            queue_index,
+            pixels,
+            format,
+            ImageChannel::IDENTITY,
+            width,
+            height,
+            is_srgb,
+        )
+    }
+
+    /// Decodes an image which is sampled as linear color data, such as a normal map.
     pub fn decode_linear(
         &mut self,
         queue_family_index: usize,
@@ -252,6 +404,8 @@ impl ImageLoader {
         )
     }
 
+    /// Decodes an image which is sampled as sRGB color data, such as an albedo map, using an
+    /// `_SRGB` format so that the hardware converts samples to linear color.
     pub fn decode_srgb(
         &mut self,
         queue_family_index: usize,
@@ -272,6 +426,136 @@ impl ImageLoader {
         )
     }
 
+    /// Loads an image from block-compressed texel data, such as the BC7 or ASTC contents of a
+    /// KTX2 or DDS file, without decompressing it.
+    ///
+    /// `block_data` must contain `mip_levels` tightly packed mip levels, largest first.
+    #[allow(clippy::too_many_arguments)]
+    pub fn load_compressed(
+        &mut self,
+        queue_family_index: usize,
+        queue_index: usize,
+        format: vk::Format,
+        block_data: &[u8],
+        width: u32,
+        height: u32,
+        mip_levels: u32,
+    ) -> anyhow::Result<Arc<Image>> {
+        info!(
+            "loading {}x{} {:?} image ({} K)",
+            width,
+            height,
+            format,
+            block_data.len() / 1024
+        );
+
+        let (block_width, block_height) = format_texel_block_extent(format);
+        let block_size = format_texel_block_size(format, vk::ImageAspectFlags::COLOR)
+            .with_context(|| format!("Unknown format: {format:?}"))?;
+
+        ensure!(
+            block_width > 1 || block_height > 1,
+            "Not a block-compressed format: {format:?}"
+        );
+        ensure!(
+            mip_levels > 0 && mip_levels <= u32::BITS - width.max(height).leading_zeros(),
+            "Invalid mip level count: {mip_levels}"
+        );
+
+        let required_features =
+            vk::FormatFeatureFlags::SAMPLED_IMAGE | vk::FormatFeatureFlags::TRANSFER_DST;
+        let format_properties = Device::format_properties(&self.device, format);
+
+        ensure!(
+            format_properties
+                .optimal_tiling_features
+                .contains(required_features),
+            "Unsupported format: {format:?}"
+        );
+
+        // Each mip level is tightly packed, so buffer offsets are always block-aligned
+        let mut regions = Vec::with_capacity(mip_levels as _);
+        let mut block_data_len = 0;
+        for mip_level in 0..mip_levels {
+            let mip_width = (width >> mip_level).max(1);
+            let mip_height = (height >> mip_level).max(1);
+
+            regions.push(vk::BufferImageCopy {
+                buffer_offset: block_data_len,
+                buffer_row_length: 0,
+                buffer_image_height: 0,
+                image_subresource: vk::ImageSubresourceLayers {
+                    aspect_mask: vk::ImageAspectFlags::COLOR,
+                    mip_level,
+                    base_array_layer: 0,
+                    layer_count: 1,
+                },
+                image_offset: vk::Offset3D::default(),
+                image_extent: vk::Extent3D {
+                    width: mip_width,
+                    height: mip_height,
+                    depth: 1,
+                },
+            });
+
+            block_data_len += mip_width.div_ceil(block_width) as vk::DeviceSize
+                * mip_height.div_ceil(block_height) as vk::DeviceSize
+                * block_size as vk::DeviceSize;
+        }
+
+        ensure!(
+            block_data.len() as vk::DeviceSize >= block_data_len,
+            "Insufficient data: {} < {block_data_len}",
+            block_data.len()
+        );
+
+        #[cfg(debug_assertions)]
+        if block_data.len() as vk::DeviceSize > block_data_len {
+            warn!("unused data");
+        }
+
+        let mut render_graph = RenderGraph::new();
+        let image = render_graph.bind_node(Arc::new(
+            Image::create(
+                &self.device,
+                ImageInfo::image_2d(
+                    width,
+                    height,
+                    format,
+                    vk::ImageUsageFlags::SAMPLED | vk::ImageUsageFlags::TRANSFER_DST,
+                )
+                .to_builder()
+                .mip_level_count(mip_levels),
+            )
+            .context("Unable to create new image")?,
+        ));
+
+        // Lease a temporary buffer from the pool
+        let mut block_buf = self.pool.lease(BufferInfo::host_mem(
+            block_data_len,
+            vk::BufferUsageFlags::TRANSFER_SRC,
+        ))?;
+
+        {
+            // Fill the temporary buffer with the compressed blocks
+            let block_data = &block_data[0..block_data_len as usize];
+            let block_buf =
+                &mut Buffer::mapped_slice_mut(&mut block_buf)[0..block_data_len as usize];
+            block_buf.copy_from_slice(block_data);
+        }
+
+        let block_buf = render_graph.bind_node(block_buf);
+        render_graph.copy_buffer_to_image_regions(block_buf, image, regions);
+
+        let image = render_graph.unbind_node(image);
+
+        render_graph
+            .resolve()
+            .submit(&mut self.pool, queue_family_index, queue_index)?;
+
+        Ok(image)
+    }
+
     pub fn load_bitmap_font<'a>(
         &mut self,
         queue_family_index: usize,
@@ -295,4 +579,40 @@ impl ImageLoader {
 
         BitmapFont::new(&self.device, font, pages)
     }
+
+    /// Loads a signed distance field font where each page contains pixels of the given `format`.
+    ///
+    /// Only the red channel of each page is used as the distance field.
+    pub fn load_sdf_font<'a>(
+        &mut self,
+        queue_family_index: usize,
+        queue_index: usize,
+        font: BMFont,
+        format: ImageFormat,
+        pages: impl IntoIterator<Item = (&'a [u8], u32, u32)>,
+    ) -> anyhow::Result<SdfFont> {
+        let pages = pages
+            .into_iter()
+            .map(|(pixels, width, height)| {
+                self.decode_linear(
+                    queue_family_index,
+                    queue_index,
+                    pixels,
+                    format,
+                    width,
+                    height,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        SdfFont::new(&self.device, font, pages)
+    }
+
+    /// Sets whether decoded images have a full mip chain generated on the GPU.
+    ///
+    /// Mip levels are generated using [`RenderGraph::generate_mipmaps`], which requires that the
+    /// format of the image supports linear blit filtering; decoding returns an error otherwise.
+    pub fn set_generate_mipmaps(&mut self, enabled: bool) {
+        self.generate_mipmaps = enabled;
+    }
 }
@@ -1,6 +1,7 @@
 pub mod prelude {
     pub use super::{
-        BitmapFont, BitmapGlyphColor, ComputePresenter, GraphicPresenter, ImageFormat, ImageLoader,
+        BitmapFont, BitmapGlyphColor, ComputePresenter, GraphicPresenter, ImageChannel,
+        ImageFormat, ImageLoader, ScaleMode, SdfFont, TonemapOperator, TonemapPresenter,
         Transition, TransitionPipeline,
     };
 }
@@ -8,11 +9,13 @@ pub mod prelude {
 mod bitmap_font;
 mod image_loader;
 mod presenter;
+mod sdf_font;
 mod transition;
 
 pub use self::{
     bitmap_font::{BitmapFont, BitmapGlyphColor},
-    image_loader::{ImageFormat, ImageLoader},
-    presenter::{ComputePresenter, GraphicPresenter},
+    image_loader::{ImageChannel, ImageFormat, ImageLoader},
+    presenter::{ComputePresenter, GraphicPresenter, ScaleMode, TonemapOperator, TonemapPresenter},
+    sdf_font::SdfFont,
     transition::{Transition, TransitionPipeline},
 };
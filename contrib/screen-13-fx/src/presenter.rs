@@ -78,12 +78,25 @@ impl ComputePresenter {
 }
 
 pub struct GraphicPresenter {
+    nearest_pipeline: Arc<GraphicPipeline>,
     pipeline: Arc<GraphicPipeline>,
 }
 
 impl GraphicPresenter {
     pub fn new(device: &Arc<Device>) -> Result<Self, DriverError> {
         Ok(Self {
+            nearest_pipeline: Arc::new(GraphicPipeline::create(
+                device,
+                GraphicPipelineInfo::default(),
+                [
+                    Shader::new_vertex(
+                        include_spirv!("res/shader/graphic/present.vert", vert).as_slice(),
+                    ),
+                    Shader::new_fragment(
+                        include_spirv!("res/shader/graphic/present_nearest.frag", frag).as_slice(),
+                    ),
+                ],
+            )?),
             pipeline: Arc::new(GraphicPipeline::create(
                 device,
                 GraphicPipelineInfo::default(),
@@ -99,36 +112,189 @@ impl GraphicPresenter {
         })
     }
 
+    /// Presents `image` onto `swapchain`, resized according to `scale_mode`.
     pub fn present_image(
         &self,
         graph: &mut RenderGraph,
         image: impl Into<AnyImageNode>,
         swapchain: SwapchainImageNode,
+        scale_mode: ScaleMode,
     ) {
         let image = image.into();
         let image_info = graph.node_info(image);
         let swapchain_info = graph.node_info(swapchain);
+        let transform = scale_transform(scale_mode, image_info, swapchain_info);
+        let pipeline = if matches!(scale_mode, ScaleMode::IntegerScale { .. }) {
+            &self.nearest_pipeline
+        } else {
+            &self.pipeline
+        };
 
-        let (image_width, image_height) = (image_info.width as f32, image_info.height as f32);
-        let (swapchain_width, swapchain_height) =
-            (swapchain_info.width as f32, swapchain_info.height as f32);
+        let mut pass = graph
+            .begin_pass("present (from graphic)")
+            .bind_pipeline(pipeline)
+            .read_descriptor(0, image);
 
-        let scale = (swapchain_width / image_width).max(swapchain_height / image_height);
-        let transform = Mat4::from_scale(vec3(
-            scale * image_width / swapchain_width,
-            scale * image_height / swapchain_height,
-            1.0,
-        ));
+        // Letterboxed images do not cover the whole swapchain
+        if matches!(scale_mode, ScaleMode::Fit | ScaleMode::IntegerScale { .. }) {
+            pass = pass.clear_color_value(0, swapchain, [0.0, 0.0, 0.0, 1.0]);
+        }
+
+        pass.store_color(0, swapchain)
+            .record_subpass(move |subpass, _| {
+                // Draw a quad with implicit vertices (no buffer)
+                subpass.push_constants(cast_slice(&transform.to_cols_array()));
+                subpass.draw(6, 1, 0, 0);
+            });
+    }
+}
+
+/// Describes how [`GraphicPresenter`] resizes images which do not match the swapchain size.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum ScaleMode {
+    /// Scales the image to cover the swapchain, preserving aspect ratio and cropping any excess.
+    #[default]
+    Fill,
+
+    /// Scales the image to fit inside the swapchain, preserving aspect ratio and filling any
+    /// remaining area with black bars.
+    Fit,
+
+    /// Scales the image by the largest whole number which fits inside the swapchain, using
+    /// nearest filtering, and centers it with black bars around any remaining area.
+    ///
+    /// Useful for pixel art. Images larger than the swapchain are cropped.
+    IntegerScale {
+        /// Limits the scale factor, if set.
+        max_scale: Option<u32>,
+    },
+
+    /// Scales the image to exactly match the swapchain, ignoring aspect ratio.
+    Stretch,
+}
+
+/// Selects the curve used by [`TonemapPresenter`] to map HDR color into the displayable range.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum TonemapOperator {
+    /// The filmic ACES curve fit by Krzysztof Narkowicz.
+    #[default]
+    Aces,
+
+    /// Simple Reinhard, `x / (1 + x)`.
+    Reinhard,
+
+    /// The filmic curve by John Hable, as used in Uncharted 2.
+    Uncharted2,
+}
+
+/// Presents high dynamic range images, such as `R16G16B16A16_SFLOAT` render targets, to a
+/// swapchain.
+///
+/// The tonemapped result is linear color and should be presented to an sRGB swapchain.
+pub struct TonemapPresenter {
+    pipeline: Arc<GraphicPipeline>,
+}
+
+impl TonemapPresenter {
+    pub fn new(device: &Arc<Device>) -> Result<Self, DriverError> {
+        Ok(Self {
+            pipeline: Arc::new(GraphicPipeline::create(
+                device,
+                GraphicPipelineInfo::default(),
+                [
+                    Shader::new_vertex(
+                        include_spirv!("res/shader/graphic/present.vert", vert).as_slice(),
+                    ),
+                    Shader::new_fragment(
+                        include_spirv!("res/shader/graphic/tonemap.frag", frag).as_slice(),
+                    ),
+                ],
+            )?),
+        })
+    }
+
+    /// Tonemaps `image` onto `swapchain` after multiplying it by `exposure`.
+    pub fn present_image(
+        &self,
+        graph: &mut RenderGraph,
+        image: impl Into<AnyImageNode>,
+        swapchain: SwapchainImageNode,
+        operator: TonemapOperator,
+        exposure: f32,
+    ) {
+        let image = image.into();
+        let image_info = graph.node_info(image);
+        let swapchain_info = graph.node_info(swapchain);
+
+        let transform = scale_transform(ScaleMode::Fill, image_info, swapchain_info);
+        let operator = match operator {
+            TonemapOperator::Aces => 0u32,
+            TonemapOperator::Reinhard => 1,
+            TonemapOperator::Uncharted2 => 2,
+        };
 
         graph
-            .begin_pass("present (from graphic)")
+            .begin_pass("present (tonemap)")
             .bind_pipeline(&self.pipeline)
             .read_descriptor(0, image)
             .store_color(0, swapchain)
             .record_subpass(move |subpass, _| {
                 // Draw a quad with implicit vertices (no buffer)
-                subpass.push_constants(cast_slice(&transform.to_cols_array()));
-                subpass.draw(6, 1, 0, 0);
+                subpass
+                    .push_constants(cast_slice(&transform.to_cols_array()))
+                    .push_constants_offset(64, &exposure.to_ne_bytes())
+                    .push_constants_offset(68, &operator.to_ne_bytes())
+                    .draw(6, 1, 0, 0);
             });
     }
 }
+
+fn scale_transform(
+    scale_mode: ScaleMode,
+    image_info: ImageInfo,
+    swapchain_info: ImageInfo,
+) -> Mat4 {
+    let (image_width, image_height) = (image_info.width as f32, image_info.height as f32);
+    let (swapchain_width, swapchain_height) =
+        (swapchain_info.width as f32, swapchain_info.height as f32);
+
+    let (scale_x, scale_y) = (
+        swapchain_width / image_width,
+        swapchain_height / image_height,
+    );
+    let scale = match scale_mode {
+        ScaleMode::Fill => scale_x.max(scale_y),
+        ScaleMode::Fit => scale_x.min(scale_y),
+        ScaleMode::IntegerScale { max_scale } => {
+            let mut scale = (swapchain_info.width / image_info.width)
+                .min(swapchain_info.height / image_info.height);
+
+            if let Some(max_scale) = max_scale {
+                scale = scale.min(max_scale);
+            }
+
+            let scale = scale.max(1);
+            let (width, height) = (scale * image_info.width, scale * image_info.height);
+
+            // Keep texels aligned to whole pixels when the bars have an odd size
+            let left = (swapchain_info.width as i32 - width as i32) / 2;
+            let top = (swapchain_info.height as i32 - height as i32) / 2;
+            let center_x = (2 * left + width as i32) as f32 / swapchain_width - 1.0;
+            let center_y = (2 * top + height as i32) as f32 / swapchain_height - 1.0;
+
+            return Mat4::from_translation(vec3(center_x, center_y, 0.0))
+                * Mat4::from_scale(vec3(
+                    width as f32 / swapchain_width,
+                    height as f32 / swapchain_height,
+                    1.0,
+                ));
+        }
+        ScaleMode::Stretch => return Mat4::IDENTITY,
+    };
+
+    Mat4::from_scale(vec3(
+        scale * image_width / swapchain_width,
+        scale * image_height / swapchain_height,
+        1.0,
+    ))
+}
@@ -0,0 +1,117 @@
+use {
+    super::{BitmapFont, BitmapGlyphColor},
+    bmfont::BMFont,
+    inline_spirv::include_spirv,
+    screen_13::prelude::*,
+    std::sync::Arc,
+};
+
+/// Holds a decoded signed distance field font.
+///
+/// Unlike [`BitmapFont`], glyphs remain crisp when printed at any scale. Each page must contain a
+/// distance field in the red channel where values greater than `0.5` are inside of a glyph, such
+/// as the atlases produced by `msdf-bmfont` using the `sdf` field type.
+///
+/// Glyphs may be drawn with a border by setting a border width. Drop shadows may be drawn by
+/// printing the same text, offset and in the shadow color, before the text itself.
+#[derive(Debug)]
+pub struct SdfFont {
+    border_color: [u8; 4],
+    border_width: f32,
+    font: BitmapFont,
+}
+
+impl SdfFont {
+    pub fn new(
+        device: &Arc<Device>,
+        font: BMFont,
+        pages: impl Into<Vec<Arc<Image>>>,
+    ) -> anyhow::Result<Self> {
+        let font = BitmapFont::create(
+            device,
+            font,
+            pages.into(),
+            Shader::new_vertex(include_spirv!("res/shader/graphic/sdf_font.vert", vert).as_slice()),
+            Shader::new_fragment(
+                include_spirv!("res/shader/graphic/sdf_font.frag", frag).as_slice(),
+            ),
+        )?;
+
+        Ok(Self {
+            border_color: [0, 0, 0, u8::MAX],
+            border_width: 0.0,
+            font,
+        })
+    }
+
+    /// Returns the distance, in pixels, between lines of text.
+    pub fn line_height(&self) -> u32 {
+        self.font.line_height()
+    }
+
+    /// Returns the position and area, in pixels, required to render the given text.
+    ///
+    /// See [`BitmapFont::measure`].
+    pub fn measure(&self, text: &str) -> ([i32; 2], [u32; 2]) {
+        self.font.measure(text)
+    }
+
+    pub fn print(
+        &mut self,
+        graph: &mut RenderGraph,
+        image: impl Into<AnyImageNode>,
+        x: f32,
+        y: f32,
+        color: impl Into<BitmapGlyphColor>,
+        text: impl AsRef<str>,
+    ) {
+        self.print_scale(graph, image, x, y, color, text, 1.0);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn print_scale(
+        &mut self,
+        graph: &mut RenderGraph,
+        image: impl Into<AnyImageNode>,
+        x: f32,
+        y: f32,
+        color: impl Into<BitmapGlyphColor>,
+        text: impl AsRef<str>,
+        scale: f32,
+    ) {
+        let color = BitmapGlyphColor::SolidOutline(color.into().solid(), self.border_color);
+
+        self.font.print_runs_scale_scissor(
+            graph,
+            image,
+            x,
+            y,
+            &[(text, color)],
+            scale,
+            None,
+            Some(self.border_width),
+        );
+    }
+
+    /// Sets the color of glyph borders.
+    pub fn set_border_color(&mut self, color: impl Into<BitmapGlyphColor>) {
+        self.border_color = color.into().solid();
+    }
+
+    /// Sets the width of glyph borders, in distance field units.
+    ///
+    /// The edge of each glyph is at `0.5`, so the width must be less than `0.5`. A width of `0.0`
+    /// (_the default_) disables borders.
+    pub fn set_border_width(&mut self, width: f32) {
+        debug_assert!((0.0..0.5).contains(&width), "invalid border width");
+
+        self.border_width = width;
+    }
+
+    /// Sets the distance, in pixels, between lines of text.
+    ///
+    /// See [`BitmapFont::set_line_height`].
+    pub fn set_line_height(&mut self, line_height: impl Into<Option<u32>>) {
+        self.font.set_line_height(line_height);
+    }
+}
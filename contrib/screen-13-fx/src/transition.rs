@@ -45,6 +45,12 @@ pub enum Transition {
         smoothness: f32,
         opening: bool,
     },
+    /// Reveals the second image inside a circle which grows from `center` until it covers the
+    /// first image.
+    CircleReveal {
+        center: [f32; 2],
+        smoothness: f32,
+    },
     ColorDistance {
         power: f32,
     },
@@ -91,6 +97,12 @@ pub enum Transition {
         displacement_map: AnyImageNode,
         strength: f32,
     },
+    /// Replaces texels of the first image with the second image in the order given by the red
+    /// channel of `noise_image`.
+    Dissolve {
+        noise_image: AnyImageNode,
+        smoothness: f32,
+    },
     DoomScreen {
         /// Number of total bars/columns
         bars: i32,
@@ -306,6 +318,7 @@ impl Transition {
             Self::Circle { .. } => TransitionType::Circle,
             Self::CircleCrop { .. } => TransitionType::CircleCrop,
             Self::CircleOpen { .. } => TransitionType::CircleOpen,
+            Self::CircleReveal { .. } => TransitionType::CircleReveal,
             Self::ColorDistance { .. } => TransitionType::ColorDistance,
             Self::ColorPhase { .. } => TransitionType::ColorPhase,
             Self::CoordFromIn => TransitionType::CoordFromIn,
@@ -319,6 +332,7 @@ impl Transition {
             Self::DirectionalWarp { .. } => TransitionType::DirectionalWarp,
             Self::DirectionalWipe { .. } => TransitionType::DirectionalWipe,
             Self::Displacement { .. } => TransitionType::Displacement,
+            Self::Dissolve { .. } => TransitionType::Dissolve,
             Self::DoomScreen { .. } => TransitionType::DoomScreen,
             Self::Doorway { .. } => TransitionType::Doorway,
             Self::Dreamy => TransitionType::Dreamy,
@@ -463,16 +477,21 @@ impl TransitionPipeline {
         extend_push_constants(transition, &mut push_consts);
 
         // TODO: Handle displacement and luma in an if case, below
-        render_graph
+        let mut pass = render_graph
             .begin_pass(format!("transition {transition_ty:?}"))
             .bind_pipeline(&pipeline)
             .read_descriptor(0, a_image)
             .read_descriptor(1, b_image)
-            .write_descriptor(2, dest_image)
-            .record_compute(move |compute, _| {
-                compute.push_constants(push_consts.as_slice());
-                compute.dispatch(dest_info.width, dest_info.height, 1);
-            });
+            .write_descriptor(2, dest_image);
+
+        if let Transition::Dissolve { noise_image, .. } = transition {
+            pass = pass.read_descriptor(3, noise_image);
+        }
+
+        pass.record_compute(move |compute, _| {
+            compute.push_constants(push_consts.as_slice());
+            compute.dispatch(dest_info.width, dest_info.height, 1);
+        });
     }
 
     fn pipeline(&mut self, transition_ty: TransitionType) -> Arc<ComputePipeline> {
@@ -526,6 +545,10 @@ impl TransitionPipeline {
                             include_spirv!("res/shader/transition/circle_open.comp", comp)
                                 .as_slice()
                         }
+                        TransitionType::CircleReveal => {
+                            include_spirv!("res/shader/transition/circle_reveal.comp", comp)
+                                .as_slice()
+                        }
                         TransitionType::ColorDistance => {
                             include_spirv!("res/shader/transition/color_distance.comp", comp)
                                 .as_slice()
@@ -574,6 +597,9 @@ impl TransitionPipeline {
                             include_spirv!("res/shader/transition/displacement.comp", comp)
                                 .as_slice()
                         }
+                        TransitionType::Dissolve => {
+                            include_spirv!("res/shader/transition/dissolve.comp", comp).as_slice()
+                        }
                         TransitionType::DoomScreen => {
                             include_spirv!("res/shader/transition/doom_screen.comp", comp)
                                 .as_slice()
@@ -849,6 +875,11 @@ fn extend_push_constants(transition: Transition, push_consts: &mut Vec<u8>) {
             push_consts.extend_from_slice(&smoothness.to_ne_bytes());
             push_consts.extend_from_slice(&(opening as u32).to_ne_bytes());
         }
+        Transition::CircleReveal { center, smoothness } => {
+            push_consts.extend_from_slice(&smoothness.to_ne_bytes());
+            push_consts.extend_from_slice(&center[0].to_ne_bytes());
+            push_consts.extend_from_slice(&center[1].to_ne_bytes());
+        }
         Transition::ColorDistance { power } => {
             push_consts.extend_from_slice(&power.to_ne_bytes());
         }
@@ -925,6 +956,9 @@ fn extend_push_constants(transition: Transition, push_consts: &mut Vec<u8>) {
         Transition::Displacement { strength, .. } => {
             push_consts.extend_from_slice(&strength.to_ne_bytes());
         }
+        Transition::Dissolve { smoothness, .. } => {
+            push_consts.extend_from_slice(&smoothness.to_ne_bytes());
+        }
         Transition::DoomScreen {
             bars,
             amplitude,
@@ -1192,6 +1226,7 @@ enum TransitionType {
     Circle,
     CircleCrop,
     CircleOpen,
+    CircleReveal,
     ColorDistance,
     ColorPhase,
     CoordFromIn,
@@ -1205,6 +1240,7 @@ enum TransitionType {
     DirectionalWarp,
     DirectionalWipe,
     Displacement,
+    Dissolve,
     DoomScreen,
     Doorway,
     Dreamy,
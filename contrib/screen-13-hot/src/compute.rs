@@ -1,19 +1,21 @@
 use {
-    super::{compile_shader_and_watch, create_watcher, shader::HotShader},
+    super::{
+        compile_shader_and_watch, create_watcher, driver_error, shader::HotShader, FileChanges,
+        DEFAULT_DEBOUNCE,
+    },
     log::info,
     notify::RecommendedWatcher,
     screen_13::prelude::*,
-    std::sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc,
-    },
+    std::{sync::Arc, time::Duration},
 };
 
 #[derive(Debug)]
 pub struct HotComputePipeline {
+    changes: Arc<FileChanges>,
+    debounce: Duration,
     device: Arc<Device>,
-    has_changes: Arc<AtomicBool>,
     instance: Arc<ComputePipeline>,
+    last_error: Option<String>,
     shader: HotShader,
     watcher: RecommendedWatcher,
 }
@@ -26,17 +28,20 @@ impl HotComputePipeline {
     ) -> Result<Self, DriverError> {
         let shader = shader.into();
 
-        let (mut watcher, has_changes) = create_watcher();
-        let compiled_shader = compile_shader_and_watch(&shader, &mut watcher)?;
+        let (mut watcher, changes) = create_watcher();
+        let compiled_shader =
+            compile_shader_and_watch(&shader, &mut watcher).map_err(driver_error)?;
 
         let instance = Arc::new(ComputePipeline::create(device, info, compiled_shader)?);
 
         let device = Arc::clone(device);
 
         Ok(Self {
+            changes,
+            debounce: DEFAULT_DEBOUNCE,
             device,
-            has_changes,
             instance,
+            last_error: None,
             shader,
             watcher,
         })
@@ -48,28 +53,62 @@ impl HotComputePipeline {
         &self.instance
     }
 
+    /// Re-compiles the shader source code, whether or not it has changed, and returns the most
+    /// recent compilation.
+    ///
+    /// If compilation fails the previous pipeline is returned and the error is available from
+    /// [`HotComputePipeline::last_error`].
+    pub fn force_rebuild(&mut self) -> &Arc<ComputePipeline> {
+        let (mut watcher, changes) = create_watcher();
+        let instance =
+            compile_shader_and_watch(&self.shader, &mut watcher).and_then(|compiled_shader| {
+                Ok(ComputePipeline::create(
+                    &self.device,
+                    self.instance.info,
+                    compiled_shader,
+                )?)
+            });
+
+        match instance {
+            Ok(instance) => {
+                self.changes = changes;
+                self.watcher = watcher;
+                self.instance = Arc::new(instance);
+                self.last_error = None;
+            }
+            Err(err) => self.last_error = Some(format!("{err:#}")),
+        }
+
+        self.cold()
+    }
+
     /// Returns the most recent compilation after checking for changes, and if needed re-compiling
     /// the shader source code.
     pub fn hot(&mut self) -> &Arc<ComputePipeline> {
-        let has_changes = self.has_changes.swap(false, Ordering::Relaxed);
-
-        if has_changes {
+        if self.changes.take_settled(self.debounce) {
             info!("Shader change detected");
 
-            let (mut watcher, has_changes) = create_watcher();
-            if let Ok(compiled_shader) = compile_shader_and_watch(&self.shader, &mut watcher) {
-                if let Ok(instance) =
-                    ComputePipeline::create(&self.device, self.instance.info, compiled_shader)
-                {
-                    self.has_changes = has_changes;
-                    self.watcher = watcher;
-                    self.instance = Arc::new(instance);
-                }
-            }
+            self.force_rebuild();
         }
 
         self.cold()
     }
+
+    /// Returns the error of the most recent failed re-compilation, or `None` if the most recent
+    /// compilation succeeded.
+    ///
+    /// While this returns an error the previously compiled pipeline remains in use.
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    /// Sets the amount of time, after the most recent change to a watched file, before
+    /// [`HotComputePipeline::hot`] re-compiles the shader source code.
+    ///
+    /// The default value is [`DEFAULT_DEBOUNCE`].
+    pub fn set_debounce(&mut self, debounce: Duration) {
+        self.debounce = debounce;
+    }
 }
 
 impl AsRef<ComputePipeline> for HotComputePipeline {
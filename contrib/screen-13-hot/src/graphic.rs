@@ -1,19 +1,21 @@
 use {
-    super::{compile_shader_and_watch, create_watcher, shader::HotShader},
+    super::{
+        compile_shader_and_watch, create_watcher, driver_error, shader::HotShader, FileChanges,
+        DEFAULT_DEBOUNCE,
+    },
     log::info,
     notify::RecommendedWatcher,
     screen_13::prelude::*,
-    std::sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc,
-    },
+    std::{sync::Arc, time::Duration},
 };
 
 #[derive(Debug)]
 pub struct HotGraphicPipeline {
+    changes: Arc<FileChanges>,
+    debounce: Duration,
     device: Arc<Device>,
-    has_changes: Arc<AtomicBool>,
     instance: Arc<GraphicPipeline>,
+    last_error: Option<String>,
     shaders: Box<[HotShader]>,
     watcher: RecommendedWatcher,
 }
@@ -32,20 +34,23 @@ impl HotGraphicPipeline {
             .map(|shader| shader.into())
             .collect::<Box<_>>();
 
-        let (mut watcher, has_changes) = create_watcher();
+        let (mut watcher, changes) = create_watcher();
         let compiled_shaders = shaders
             .iter()
             .map(|shader| compile_shader_and_watch(shader, &mut watcher))
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map_err(driver_error)?;
 
         let instance = Arc::new(GraphicPipeline::create(device, info, compiled_shaders)?);
 
         let device = Arc::clone(device);
 
         Ok(Self {
+            changes,
+            debounce: DEFAULT_DEBOUNCE,
             device,
-            has_changes,
             instance,
+            last_error: None,
             shaders,
             watcher,
         })
@@ -57,33 +62,66 @@ impl HotGraphicPipeline {
         &self.instance
     }
 
+    /// Re-compiles the shader source code, whether or not it has changed, and returns the most
+    /// recent compilation.
+    ///
+    /// If compilation fails the previous pipeline is returned and the error is available from
+    /// [`HotGraphicPipeline::last_error`].
+    pub fn force_rebuild(&mut self) -> &Arc<GraphicPipeline> {
+        let (mut watcher, changes) = create_watcher();
+        let instance = self
+            .shaders
+            .iter()
+            .map(|shader| compile_shader_and_watch(shader, &mut watcher))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .and_then(|compiled_shaders| {
+                Ok(GraphicPipeline::create(
+                    &self.device,
+                    self.instance.info,
+                    compiled_shaders,
+                )?)
+            });
+
+        match instance {
+            Ok(instance) => {
+                self.changes = changes;
+                self.watcher = watcher;
+                self.instance = Arc::new(instance);
+                self.last_error = None;
+            }
+            Err(err) => self.last_error = Some(format!("{err:#}")),
+        }
+
+        self.cold()
+    }
+
     /// Returns the most recent compilation after checking for changes, and if needed re-compiling
     /// the shader source code.
     pub fn hot(&mut self) -> &Arc<GraphicPipeline> {
-        let has_changes = self.has_changes.swap(false, Ordering::Relaxed);
-
-        if has_changes {
+        if self.changes.take_settled(self.debounce) {
             info!("Shader change detected");
 
-            let (mut watcher, has_changes) = create_watcher();
-            if let Ok(compiled_shaders) = self
-                .shaders
-                .iter()
-                .map(|shader| compile_shader_and_watch(shader, &mut watcher))
-                .collect::<Result<Vec<_>, DriverError>>()
-            {
-                if let Ok(instance) =
-                    GraphicPipeline::create(&self.device, self.instance.info, compiled_shaders)
-                {
-                    self.has_changes = has_changes;
-                    self.watcher = watcher;
-                    self.instance = Arc::new(instance);
-                }
-            }
+            self.force_rebuild();
         }
 
         self.cold()
     }
+
+    /// Returns the error of the most recent failed re-compilation, or `None` if the most recent
+    /// compilation succeeded.
+    ///
+    /// While this returns an error the previously compiled pipeline remains in use.
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    /// Sets the amount of time, after the most recent change to a watched file, before
+    /// [`HotGraphicPipeline::hot`] re-compiles the shader source code.
+    ///
+    /// The default value is [`DEFAULT_DEBOUNCE`].
+    pub fn set_debounce(&mut self, debounce: Duration) {
+        self.debounce = debounce;
+    }
 }
 
 impl AsRef<GraphicPipeline> for HotGraphicPipeline {
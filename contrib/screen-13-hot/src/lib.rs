@@ -14,7 +14,7 @@ pub mod prelude {
 
 use {
     self::shader::HotShader,
-    log::{error, info},
+    log::{error, info, warn},
     notify::{recommended_watcher, Event, EventKind, RecommendedWatcher},
     screen_13::prelude::*,
     shader_prepper::{
@@ -24,43 +24,122 @@ use {
     shaderc::{CompileOptions, Compiler, ShaderKind, SourceLanguage},
     std::{
         collections::HashSet,
-        fs::read_to_string,
+        fs::{create_dir_all, read, read_to_string, remove_file, rename, write},
+        hash::{Hash, Hasher},
         io::{Error, ErrorKind},
         path::{Path, PathBuf},
-        sync::{
-            atomic::{AtomicBool, Ordering},
-            Arc, OnceLock,
-        },
+        process,
+        sync::{Arc, Mutex, OnceLock},
+        time::{Duration, Instant},
     },
 };
 
+/// The default amount of time, after the most recent change to a watched file, before hot
+/// pipelines re-compile.
+///
+/// Editors often write a file several times per save; waiting coalesces those writes into a single
+/// re-compilation.
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Records the time of the most recent change to any watched file.
+#[derive(Debug, Default)]
+struct FileChanges(Mutex<Option<Instant>>);
+
+impl FileChanges {
+    fn notify(&self) {
+        *self.0.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// Returns `true` once for each set of changes, after no further changes have happened for
+    /// `debounce`.
+    fn take_settled(&self, debounce: Duration) -> bool {
+        let mut last_change = self.0.lock().unwrap();
+
+        match *last_change {
+            Some(instant) if instant.elapsed() >= debounce => {
+                *last_change = None;
+
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
 struct CompiledShader {
     files_included: HashSet<PathBuf>,
     spirv_code: Vec<u8>,
 }
 
+/// The version of the `shaderc` dependency, which is part of each cached SPIR-V key.
+///
+/// _NOTE:_ Keep this in sync with `Cargo.toml`.
+const SHADERC_VERSION: &str = "0.8";
+
+/// A hasher which produces the same value across runs and builds, unlike `DefaultHasher`.
+///
+/// This is 64-bit FNV-1a and is used to key the on-disk SPIR-V cache.
+#[derive(Debug)]
+pub(crate) struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
+
+/// Returns `true` if the given code is word-aligned and begins with the SPIR-V magic number.
+fn is_spirv(code: &[u8]) -> bool {
+    const MAGIC: u32 = 0x0723_0203;
+
+    code.len() >= 4
+        && code.len() % 4 == 0
+        && [
+            u32::from_le_bytes([code[0], code[1], code[2], code[3]]),
+            u32::from_be_bytes([code[0], code[1], code[2], code[3]]),
+        ]
+        .contains(&MAGIC)
+}
+
 fn compile_shader(
     path: impl AsRef<Path>,
     entry_name: &str,
     shader_kind: Option<ShaderKind>,
     additional_opts: Option<&CompileOptions<'_>>,
+    include_dirs: &[PathBuf],
+    cache: Option<(&Path, u64)>,
 ) -> anyhow::Result<CompiledShader> {
     info!("Compiling: {}", path.as_ref().display());
 
     let path = path.as_ref().to_path_buf();
     let shader_kind = shader_kind.unwrap_or_else(|| guess_shader_kind(&path));
 
-    #[derive(Default)]
-    struct FileIncludeProvider(HashSet<PathBuf>);
+    struct FileIncludeProvider<'a> {
+        files_included: HashSet<PathBuf>,
+        include_dirs: &'a [PathBuf],
+    }
 
-    impl IncludeProvider for FileIncludeProvider {
+    impl IncludeProvider for FileIncludeProvider<'_> {
         type IncludeContext = PathBuf;
 
         fn get_include(
             &mut self,
             path: &ResolvedIncludePath,
         ) -> Result<String, BoxedIncludeProviderError> {
-            self.0.insert(PathBuf::from(&path.0));
+            self.files_included.insert(PathBuf::from(&path.0));
 
             Ok(read_to_string(&path.0)?)
         }
@@ -70,7 +149,17 @@ fn compile_shader(
             path: &str,
             context: &Self::IncludeContext,
         ) -> Result<ResolvedInclude<Self::IncludeContext>, BoxedIncludeProviderError> {
-            let path = context.join(path);
+            // Includes are relative to the including file first, then each include directory
+            let relative_path = context.join(path);
+            let path = if relative_path.exists() {
+                relative_path
+            } else {
+                self.include_dirs
+                    .iter()
+                    .map(|include_dir| include_dir.join(path))
+                    .find(|path| path.exists())
+                    .unwrap_or(relative_path)
+            };
 
             Ok(ResolvedInclude {
                 resolved_path: ResolvedIncludePath(path.to_str().unwrap_or_default().to_string()),
@@ -82,7 +171,10 @@ fn compile_shader(
         }
     }
 
-    let mut file_include_provider = FileIncludeProvider::default();
+    let mut file_include_provider = FileIncludeProvider {
+        files_included: HashSet::new(),
+        include_dirs,
+    };
     let source_code = process_file(
         path.to_string_lossy().as_ref(),
         &mut file_include_provider,
@@ -96,7 +188,33 @@ fn compile_shader(
     .iter()
     .map(|chunk| chunk.source.as_str())
     .collect::<String>();
-    let files_included = file_include_provider.0;
+    let files_included = file_include_provider.files_included;
+
+    // Cached code is keyed by the options and the preprocessed source, which contains all includes
+    let cache_path = cache.map(|(cache_dir, options_key)| {
+        let mut hasher = StableHasher::default();
+        SHADERC_VERSION.hash(&mut hasher);
+        shaderc::get_spirv_version().hash(&mut hasher);
+        options_key.hash(&mut hasher);
+        source_code.hash(&mut hasher);
+
+        let file_stem = path.file_stem().unwrap_or_default().to_string_lossy();
+
+        cache_dir.join(format!("{file_stem}-{:016x}.spv", hasher.finish()))
+    });
+
+    if let Some(spirv_code) = cache_path
+        .as_ref()
+        .and_then(|cache_path| read(cache_path).ok())
+        .filter(|spirv_code| is_spirv(spirv_code))
+    {
+        info!("Using cached SPIR-V: {}", path.display());
+
+        return Ok(CompiledShader {
+            files_included,
+            spirv_code,
+        });
+    }
 
     static COMPILER: OnceLock<Compiler> = OnceLock::new();
     let spirv_code = COMPILER
@@ -121,6 +239,25 @@ fn compile_shader(
         .as_binary_u8()
         .to_vec();
 
+    if let Some(cache_path) = cache_path {
+        // Write to a temporary file first so that readers never see a partially written file
+        let temp_path = cache_path.with_extension(format!("spv.{}.tmp", process::id()));
+        if let Err(err) = cache_path
+            .parent()
+            .map(create_dir_all)
+            .unwrap_or(Ok(()))
+            .and_then(|_| write(&temp_path, &spirv_code))
+            .and_then(|_| rename(&temp_path, &cache_path))
+        {
+            remove_file(&temp_path).ok();
+
+            warn!(
+                "Unable to write cached SPIR-V {}: {err}",
+                cache_path.display()
+            );
+        }
+    }
+
     Ok(CompiledShader {
         files_included,
         spirv_code,
@@ -130,7 +267,7 @@ fn compile_shader(
 fn compile_shader_and_watch(
     shader: &HotShader,
     watcher: &mut RecommendedWatcher,
-) -> Result<ShaderBuilder, DriverError> {
+) -> anyhow::Result<ShaderBuilder> {
     let mut base_shader = Shader::new(shader.stage, shader.compile_and_watch(watcher)?.as_slice());
 
     base_shader = base_shader.entry_name(shader.entry_name.clone());
@@ -142,21 +279,47 @@ fn compile_shader_and_watch(
     Ok(base_shader)
 }
 
-fn create_watcher() -> (RecommendedWatcher, Arc<AtomicBool>) {
-    let has_changes = Arc::new(AtomicBool::new(false));
-    let has_changes_clone = Arc::clone(&has_changes);
+/// Converts a compilation error into the driver error it was caused by, if any.
+fn driver_error(err: anyhow::Error) -> DriverError {
+    err.downcast().unwrap_or(DriverError::InvalidData)
+}
+
+fn create_watcher() -> (RecommendedWatcher, Arc<FileChanges>) {
+    let changes = Arc::new(FileChanges::default());
+    let changes_clone = Arc::clone(&changes);
     let watcher = recommended_watcher(move |event: notify::Result<Event>| {
         let event = event.unwrap_or_else(|_| Event::new(EventKind::Any));
         if matches!(
             event.kind,
             EventKind::Any | EventKind::Modify(_) | EventKind::Other
-        ) {
-            has_changes_clone.store(true, Ordering::Relaxed);
+        ) && (event.paths.is_empty() || !event.paths.iter().all(is_temporary_file))
+        {
+            changes_clone.notify();
         }
     })
     .unwrap();
 
-    (watcher, has_changes)
+    (watcher, changes)
+}
+
+/// Returns `true` for the swap, backup and lock files editors write alongside the file being edited.
+fn is_temporary_file(path: impl AsRef<Path>) -> bool {
+    let file_name = path
+        .as_ref()
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy())
+        .unwrap_or_default();
+
+    file_name.ends_with('~')
+        || file_name.starts_with(".#")
+        || file_name == "4913"
+        || matches!(
+            path.as_ref()
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+                .as_deref(),
+            Some("bak" | "swo" | "swp" | "swx" | "tmp")
+        )
 }
 
 fn guess_shader_kind(path: impl AsRef<Path>) -> ShaderKind {
@@ -1,13 +1,17 @@
 pub use shaderc::{OptimizationLevel, SourceLanguage, SpirvVersion};
 
 use {
-    super::{compile_shader, guess_shader_source_language},
+    super::{compile_shader, guess_shader_source_language, StableHasher},
+    anyhow::Context,
     derive_builder::{Builder, UninitializedFieldError},
     log::{debug, error},
     notify::{RecommendedWatcher, RecursiveMode, Watcher},
     screen_13::prelude::*,
     shaderc::{CompileOptions, EnvVersion, ShaderKind, TargetEnv},
-    std::path::{Path, PathBuf},
+    std::{
+        hash::{Hash, Hasher},
+        path::{Path, PathBuf},
+    },
 };
 
 /// Describes a shader program which runs on some pipeline stage.
@@ -23,12 +27,49 @@ use {
     pattern = "owned"
 )]
 pub struct HotShader {
+    /// A directory where compiled SPIR-V code is cached, so that unchanged shaders do not need to
+    /// be re-compiled the next time the program runs.
+    ///
+    /// Cached code is keyed by the preprocessed source code, including all included files, and the
+    /// compilation options; stale files are never used but are also not removed.
+    ///
+    /// The default value is `None`, which disables caching.
+    #[builder(default, setter(into, strip_option))]
+    pub cache_dir: Option<PathBuf>,
+
     /// The name of the entry point which will be executed by this shader.
     ///
     /// The default value is `main`.
     #[builder(default = "\"main\".to_owned()")]
     pub entry_name: String,
 
+    /// Enables 16-bit types, such as `half` and `min16float`, in HLSL source code.
+    #[builder(default)]
+    pub hlsl_16bit_types: bool,
+
+    /// Enables the `SPV_GOOGLE_hlsl_functionality1` extension, which decorates the SPIR-V output
+    /// with the HLSL semantics of each input and output.
+    #[builder(default)]
+    pub hlsl_functionality1: bool,
+
+    /// Uses the HLSL `register` and `packoffset` assignments of resources instead of
+    /// automatically assigning descriptor bindings.
+    #[builder(default)]
+    pub hlsl_io_mapping: bool,
+
+    /// Uses HLSL packing rules instead of GLSL rules when laying out uniform and storage buffer
+    /// members.
+    #[builder(default)]
+    pub hlsl_offsets: bool,
+
+    /// Directories which are searched, in order, for included files which are not found relative
+    /// to the including file.
+    ///
+    /// Included files found in these directories are watched for changes like any other included
+    /// file.
+    #[builder(default, setter(strip_option))]
+    pub include_dirs: Option<Vec<PathBuf>>,
+
     /// Macro definitions.
     #[builder(default, setter(strip_option))]
     pub macro_definitions: Option<Vec<(String, Option<String>)>>,
@@ -239,7 +280,7 @@ impl HotShader {
     pub(super) fn compile_and_watch(
         &self,
         watcher: &mut RecommendedWatcher,
-    ) -> Result<Vec<u8>, DriverError> {
+    ) -> anyhow::Result<Vec<u8>> {
         let shader_kind = match self.stage {
             vk::ShaderStageFlags::ANY_HIT_KHR => ShaderKind::AnyHit,
             vk::ShaderStageFlags::CALLABLE_KHR => ShaderKind::Callable,
@@ -271,7 +312,7 @@ impl HotShader {
 
         additional_opts.set_target_env(TargetEnv::Vulkan, EnvVersion::Vulkan1_2 as _);
 
-        if let Some(language) = self.source_language.or_else(|| {
+        let source_language = self.source_language.or_else(|| {
             let language = guess_shader_source_language(&self.path);
 
             if let Some(language) = language {
@@ -279,7 +320,9 @@ impl HotShader {
             }
 
             language
-        }) {
+        });
+
+        if let Some(language) = source_language {
             additional_opts.set_source_language(language);
         }
 
@@ -293,17 +336,38 @@ impl HotShader {
             additional_opts.set_warnings_as_errors();
         }
 
+        if self.hlsl_16bit_types {
+            additional_opts.set_hlsl_16bit_types(true);
+        }
+
+        if self.hlsl_functionality1 {
+            additional_opts.set_hlsl_functionality1(true);
+        }
+
+        if self.hlsl_io_mapping {
+            additional_opts.set_hlsl_io_mapping(true);
+        }
+
+        if self.hlsl_offsets {
+            additional_opts.set_hlsl_offsets(true);
+        }
+
         let res = compile_shader(
             &self.path,
             &self.entry_name,
             Some(shader_kind),
             Some(&additional_opts),
+            self.include_dirs.as_deref().unwrap_or_default(),
+            self.cache_dir
+                .as_deref()
+                .map(|cache_dir| (cache_dir, self.options_key(shader_kind, source_language))),
         )
         .map_err(|err| {
             error!("Unable to compile shader {}: {err}", self.path.display());
 
-            DriverError::InvalidData
-        })?;
+            err
+        })
+        .with_context(|| format!("Unable to compile shader {}", self.path.display()))?;
 
         for path in res.files_included {
             watcher
@@ -319,6 +383,30 @@ impl HotShader {
     }
 }
 
+impl HotShader {
+    /// Hashes each option which affects the compiled SPIR-V code.
+    fn options_key(&self, shader_kind: ShaderKind, source_language: Option<SourceLanguage>) -> u64 {
+        let mut hasher = StableHasher::default();
+
+        self.entry_name.hash(&mut hasher);
+        self.hlsl_16bit_types.hash(&mut hasher);
+        self.hlsl_functionality1.hash(&mut hasher);
+        self.hlsl_io_mapping.hash(&mut hasher);
+        self.hlsl_offsets.hash(&mut hasher);
+        self.macro_definitions.hash(&mut hasher);
+        self.warnings_as_errors.hash(&mut hasher);
+
+        // These shaderc types do not implement Hash
+        format!(
+            "{shader_kind:?} {source_language:?} {:?} {:?}",
+            self.optimization_level, self.target_spirv
+        )
+        .hash(&mut hasher);
+
+        hasher.finish()
+    }
+}
+
 impl From<HotShaderBuilder> for HotShader {
     fn from(builder: HotShaderBuilder) -> HotShader {
         builder.build()
@@ -345,6 +433,30 @@ impl HotShaderBuilder {
             .expect("All required fields set at initialization")
     }
 
+    /// Defines a macro with a value, as if by `#define name value`.
+    ///
+    /// Useful for compiling permutations of one shader file, for example `define("SHADOWS", 1)`.
+    pub fn define(self, name: impl Into<String>, value: impl ToString) -> Self {
+        self.macro_definition(name, Some(value.to_string()))
+    }
+
+    /// Defines a macro without a value, as if by `#define name`.
+    pub fn define_flag(self, name: impl Into<String>) -> Self {
+        self.macro_definition(name, None::<String>)
+    }
+
+    /// Adds a directory which is searched for included files.
+    ///
+    /// May be called multiple times; directories are searched in the order they are added.
+    pub fn include_dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.include_dirs
+            .get_or_insert_with(|| Some(vec![]))
+            .get_or_insert_with(Vec::new)
+            .push(path.into());
+
+        self
+    }
+
     /// Defines a single macro.
     pub fn macro_definition(
         mut self,
@@ -9,5 +9,8 @@ layout(location = 0) out vec4 output_color;
 
 void main()
 {
-    output_color = input_color * texture(image_sampler_llb, input_texcoord);
+    // The font atlas is alpha-only: glyphs are white
+    float alpha = texture(image_sampler_llb, input_texcoord).r;
+
+    output_color = input_color * vec4(1.0, 1.0, 1.0, alpha);
 }
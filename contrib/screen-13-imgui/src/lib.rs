@@ -1,11 +1,12 @@
 pub mod prelude {
-    pub use super::{imgui, Condition, ImGui, Ui};
+    pub use super::{imgui, Condition, ImGui, ImGuiInfo, ImGuiInfoBuilder, Ui};
 }
 
 pub use imgui::{self, Condition, Ui};
 
 use {
     bytemuck::cast_slice,
+    derive_builder::{Builder, UninitializedFieldError},
     imgui::{Context, DrawCmd, DrawCmdParams},
     imgui_winit_support::{
         winit::{event::Event, window::Window},
@@ -20,6 +21,7 @@ use {
 pub struct ImGui {
     context: Context,
     font_atlas_image: Option<Arc<Lease<Image>>>,
+    info: ImGuiInfo,
     pipeline: Arc<GraphicPipeline>,
     platform: WinitPlatform,
     pool: HashPool,
@@ -27,6 +29,12 @@ pub struct ImGui {
 
 impl ImGui {
     pub fn new(device: &Arc<Device>) -> Self {
+        Self::create(device, ImGuiInfo::default())
+    }
+
+    /// Creates a new `ImGui` which draws into output images described by `info`.
+    pub fn create(device: &Arc<Device>, info: impl Into<ImGuiInfo>) -> Self {
+        let info = info.into();
         let mut context = Context::create();
         let platform = WinitPlatform::init(&mut context);
         let pool = HashPool::new(device);
@@ -47,13 +55,14 @@ impl ImGui {
         Self {
             context,
             font_atlas_image: None,
+            info,
             pipeline,
             platform,
             pool,
         }
     }
 
-    // TODO: This produces an image which is RGBA8 UNORM and has STORAGE set. *We* don't need storage here and should instead ask the user what settings to give the output image.....
+    /// Draws the GUI into a new image with the format and usage of this instance's [`ImGuiInfo`].
     pub fn draw(
         &mut self,
         dt: f32,
@@ -96,12 +105,8 @@ impl ImGui {
                 .lease(ImageInfo::image_2d(
                     window.inner_size().width,
                     window.inner_size().height,
-                    vk::Format::R8G8B8A8_UNORM,
-                    vk::ImageUsageFlags::COLOR_ATTACHMENT
-                        | vk::ImageUsageFlags::SAMPLED
-                        | vk::ImageUsageFlags::STORAGE
-                        | vk::ImageUsageFlags::TRANSFER_DST
-                        | vk::ImageUsageFlags::TRANSFER_SRC, // TODO: Make TRANSFER_SRC an "extra flags"
+                    self.info.format,
+                    self.info.usage | vk::ImageUsageFlags::COLOR_ATTACHMENT,
                 ))
                 .unwrap();
             image.as_mut().name = Some("ImGui Output".to_string());
@@ -112,8 +117,15 @@ impl ImGui {
         let display_pos = draw_data.display_pos;
         let framebuffer_scale = draw_data.framebuffer_scale;
 
+        // Clear using a render pass so that TRANSFER_DST usage is not required
         if draw_data.draw_lists_count() == 0 {
-            render_graph.clear_color_image(image);
+            render_graph
+                .begin_pass("imgui")
+                .bind_pipeline(&self.pipeline)
+                .read_descriptor(0, font_atlas_image)
+                .clear_color(0, image)
+                .store_color(0, image)
+                .record_subpass(|_, _| {});
 
             return image;
         }
@@ -251,7 +263,7 @@ impl ImGui {
             },
         ]);
 
-        let texture = fonts.build_rgba32_texture(); // TODO: Fix fb channel writes and use alpha8!
+        let texture = fonts.build_alpha8_texture();
         let temp_buf_len = texture.data.len();
         let mut temp_buf = self
             .pool
@@ -273,10 +285,8 @@ impl ImGui {
                 .lease(ImageInfo::image_2d(
                     texture.width,
                     texture.height,
-                    vk::Format::R8G8B8A8_UNORM,
-                    vk::ImageUsageFlags::SAMPLED
-                        | vk::ImageUsageFlags::STORAGE
-                        | vk::ImageUsageFlags::TRANSFER_DST,
+                    vk::Format::R8_UNORM,
+                    vk::ImageUsageFlags::SAMPLED | vk::ImageUsageFlags::TRANSFER_DST,
                 ))
                 .unwrap();
             image.as_mut().name = Some("ImGui Font Atlas".to_string());
@@ -289,3 +299,64 @@ impl ImGui {
         self.font_atlas_image = Some(render_graph.unbind_node(image));
     }
 }
+
+/// Information used to create an [`ImGui`] instance.
+#[derive(Builder, Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[builder(
+    build_fn(private, name = "fallible_build", error = "ImGuiInfoBuilderError"),
+    derive(Clone, Copy, Debug),
+    pattern = "owned"
+)]
+#[non_exhaustive]
+pub struct ImGuiInfo {
+    /// The format of output images.
+    ///
+    /// The default value is `R8G8B8A8_UNORM`.
+    #[builder(default = "vk::Format::R8G8B8A8_UNORM")]
+    pub format: vk::Format,
+
+    /// The usage of output images.
+    ///
+    /// `COLOR_ATTACHMENT` is always added. The default value is `COLOR_ATTACHMENT | SAMPLED`.
+    #[builder(default = "vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::SAMPLED")]
+    pub usage: vk::ImageUsageFlags,
+}
+
+impl ImGuiInfo {
+    /// Converts an `ImGuiInfo` into an `ImGuiInfoBuilder`.
+    pub fn to_builder(self) -> ImGuiInfoBuilder {
+        ImGuiInfoBuilder {
+            format: Some(self.format),
+            usage: Some(self.usage),
+        }
+    }
+}
+
+impl Default for ImGuiInfo {
+    fn default() -> Self {
+        ImGuiInfoBuilder::default().build()
+    }
+}
+
+impl From<ImGuiInfoBuilder> for ImGuiInfo {
+    fn from(info: ImGuiInfoBuilder) -> Self {
+        info.build()
+    }
+}
+
+impl ImGuiInfoBuilder {
+    /// Builds a new `ImGuiInfo`.
+    pub fn build(self) -> ImGuiInfo {
+        self.fallible_build()
+            .expect("All required fields set at initialization")
+    }
+}
+
+#[derive(Debug)]
+struct ImGuiInfoBuilderError;
+
+impl From<UninitializedFieldError> for ImGuiInfoBuilderError {
+    fn from(_: UninitializedFieldError) -> Self {
+        Self
+    }
+}
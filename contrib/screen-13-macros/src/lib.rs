@@ -0,0 +1,166 @@
+//! Derive macros for _Screen 13_.
+
+use {
+    proc_macro::TokenStream,
+    proc_macro2::Span,
+    quote::quote,
+    std::collections::HashSet,
+    syn::{
+        parenthesized, parse_macro_input, spanned::Spanned, token::Paren, Data, DeriveInput, Error,
+        Fields, Ident, LitInt, Member,
+    },
+};
+
+/// Implements `screen_13::driver::shader::Vertex` for a `#[repr(C)]` struct.
+///
+/// Each field read by the vertex shader must specify a `#[vertex(location = N, format = F)]`
+/// attribute, where `F` is the name of a `vk::Format` constant. Fields without an attribute are
+/// skipped. Attribute offsets are the field offsets of the struct and the binding stride is the
+/// size of the struct.
+///
+/// # Examples
+///
+/// ```
+/// use {screen_13::prelude::*, screen_13_macros::Vertex};
+///
+/// #[derive(Clone, Copy, Vertex)]
+/// #[repr(C)]
+/// struct MyVertex {
+///     #[vertex(location = 0, format = R32G32B32_SFLOAT)]
+///     position: [f32; 3],
+///
+///     #[vertex(location = 1, format = R8G8B8A8_UNORM)]
+///     color: [u8; 4],
+/// }
+///
+/// let attributes = MyVertex::attributes(0);
+///
+/// assert_eq!(attributes.len(), 2);
+/// assert_eq!(attributes[1].offset, 12);
+/// assert_eq!(
+///     MyVertex::binding(0, vk::VertexInputRate::VERTEX).stride,
+///     16
+/// );
+/// ```
+#[proc_macro_derive(Vertex, attributes(vertex))]
+pub fn derive_vertex(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    derive_vertex_impl(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn derive_vertex_impl(input: DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    if !is_repr_c(&input)? {
+        return Err(Error::new(
+            Span::call_site(),
+            "Vertex requires #[repr(C)] so that field offsets are stable",
+        ));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            Fields::Unnamed(fields) => &fields.unnamed,
+            Fields::Unit => {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "Vertex cannot be derived for unit structs",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "Vertex can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut attributes = vec![];
+    let mut locations = HashSet::new();
+
+    for (idx, field) in fields.iter().enumerate() {
+        let member = field
+            .ident
+            .clone()
+            .map(Member::Named)
+            .unwrap_or_else(|| Member::Unnamed(idx.into()));
+
+        for attr in &field.attrs {
+            if !attr.path().is_ident("vertex") {
+                continue;
+            }
+
+            let mut location = None;
+            let mut format = None;
+
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("location") {
+                    location = Some(meta.value()?.parse::<LitInt>()?);
+                } else if meta.path.is_ident("format") {
+                    format = Some(meta.value()?.parse::<Ident>()?);
+                } else {
+                    return Err(meta.error("expected `location` or `format`"));
+                }
+
+                Ok(())
+            })?;
+
+            let location = location.ok_or_else(|| Error::new(attr.span(), "missing `location`"))?;
+            let format = format.ok_or_else(|| Error::new(attr.span(), "missing `format`"))?;
+
+            if !locations.insert(location.base10_parse::<u32>()?) {
+                return Err(Error::new(location.span(), "duplicate vertex location"));
+            }
+
+            attributes.push(quote! {
+                ::screen_13::driver::ash::vk::VertexInputAttributeDescription {
+                    location: #location,
+                    binding,
+                    format: ::screen_13::driver::ash::vk::Format::#format,
+                    offset: ::core::mem::offset_of!(Self, #member) as u32,
+                }
+            });
+        }
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::screen_13::driver::shader::Vertex for #ident #ty_generics #where_clause {
+            fn attributes(
+                binding: u32,
+            ) -> ::std::vec::Vec<::screen_13::driver::ash::vk::VertexInputAttributeDescription> {
+                ::std::vec![#(#attributes),*]
+            }
+        }
+    })
+}
+
+fn is_repr_c(input: &DeriveInput) -> Result<bool, Error> {
+    let mut res = false;
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("repr") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("C") {
+                res = true;
+            } else if meta.input.peek(Paren) {
+                // Skip arguments such as `align(16)`
+                let content;
+                parenthesized!(content in meta.input);
+                content.parse::<proc_macro2::TokenStream>()?;
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(res)
+}
@@ -3,8 +3,12 @@ use {
         driver::device::Device,
         graph::{node::SwapchainImageNode, RenderGraph},
     },
-    std::sync::Arc,
-    winit::{dpi::PhysicalPosition, event::Event, window::Window},
+    std::{sync::Arc, time::Duration},
+    winit::{
+        dpi::PhysicalPosition,
+        event::Event,
+        window::{Window, WindowAttributes, WindowId},
+    },
 };
 
 /// Centers the mouse cursor within the window.
@@ -29,14 +33,23 @@ pub struct FrameContext<'a> {
     /// A slice of events that have occurred since the previous frame.
     pub events: &'a [Event<()>],
 
+    /// The time elapsed between the start of the previous frame and the start of this frame.
+    ///
+    /// The first frame drawn for a window has a frame time of zero.
+    pub frame_time: Duration,
+
     /// The height, in pixels, of the current frame.
     pub height: u32,
 
+    pub(crate) new_windows: &'a mut Vec<WindowAttributes>,
+
     /// A render graph which rendering commands should be recorded into.
     ///
     /// Make sure to write to `swapchain_image` as part of this graph.
     pub render_graph: &'a mut RenderGraph,
 
+    pub(crate) resized: bool,
+
     /// A pre-bound image node for the swapchain image to be drawn.
     pub swapchain_image: SwapchainImageNode,
 
@@ -56,6 +69,14 @@ impl FrameContext<'_> {
         *self.will_exit = true;
     }
 
+    /// Returns the new frame width and height if this is the first frame drawn since the window
+    /// was resized.
+    ///
+    /// Size-dependent resources, such as depth images, may be recreated when this returns `Some`.
+    pub fn resized(&self) -> Option<(u32, u32)> {
+        self.resized.then_some((self.width, self.height))
+    }
+
     /// Returns the frame width divided by the frame height.
     pub fn render_aspect_ratio(&self) -> f32 {
         self.width as f32 / self.height as f32
@@ -66,8 +87,22 @@ impl FrameContext<'_> {
         center_cursor(self.window);
     }
 
+    /// Opens an additional operating system window which shares the device of this frame.
+    ///
+    /// The window is created after the current frame has been drawn. Frames drawn for each window
+    /// may be told apart using [`FrameContext::window_id`]. Closing a window does not close any
+    /// other windows.
+    pub fn open_window(&mut self, attributes: WindowAttributes) {
+        self.new_windows.push(attributes);
+    }
+
     /// Sets the mouse cursor at the specified position within the window.
     pub fn set_cursor_position(&self, x: u32, y: u32) {
         set_cursor_position(self.window, x, y);
     }
+
+    /// Returns the identifier of the operating system window relating to this frame.
+    pub fn window_id(&self) -> WindowId {
+        self.window.id()
+    }
 }
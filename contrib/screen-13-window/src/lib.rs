@@ -16,12 +16,17 @@ use {
         pool::hash::HashPool,
         Display,
     },
-    std::{error, fmt, sync::Arc},
+    std::{
+        collections::HashMap,
+        error, fmt,
+        sync::Arc,
+        time::{Duration, Instant},
+    },
     winit::{
         application::ApplicationHandler,
         error::EventLoopError,
         event::{DeviceEvent, DeviceId, Event, WindowEvent},
-        event_loop::{ActiveEventLoop, EventLoop},
+        event_loop::{ActiveEventLoop, ControlFlow, EventLoop},
         monitor::MonitorHandle,
         window::{WindowAttributes, WindowId},
     },
@@ -58,15 +63,51 @@ impl Window {
         F: FnMut(FrameContext),
     {
         struct Application<F> {
-            active_window: Option<ActiveWindow>,
             data: WindowData,
             device: Arc<Device>,
             draw_fn: F,
             error: Option<WindowError>,
             primary_monitor: Option<MonitorHandle>,
+            windows: HashMap<WindowId, ActiveWindow>,
         }
 
-        impl<F> Application<F> {
+        impl<F> Application<F>
+        where
+            F: FnMut(FrameContext),
+        {
+            fn create_active_window(
+                &mut self,
+                event_loop: &ActiveEventLoop,
+                attributes: WindowAttributes,
+            ) -> Result<WindowId, WindowError> {
+                let window = event_loop.create_window(attributes).map_err(|err| {
+                    warn!("Unable to create window: {err}");
+
+                    EventLoopError::Os(err)
+                })?;
+                let (display, swapchain) =
+                    self.create_display_swapchain(&window).map_err(|err| {
+                        warn!("Unable to create swapchain: {err}");
+
+                        err
+                    })?;
+                let window_id = window.id();
+
+                self.windows.insert(
+                    window_id,
+                    ActiveWindow {
+                        display,
+                        display_resize: false,
+                        events: vec![],
+                        frame_started: None,
+                        swapchain,
+                        window,
+                    },
+                );
+
+                Ok(window_id)
+            }
+
             fn create_display_swapchain(
                 &mut self,
                 window: &winit::window::Window,
@@ -95,6 +136,10 @@ impl Window {
                     swapchain_info = swapchain_info.sync_display(v_sync);
                 }
 
+                if let Some(present_modes) = self.data.present_modes.clone() {
+                    swapchain_info = swapchain_info.present_modes(present_modes);
+                }
+
                 let swapchain = Swapchain::new(&self.device, surface, swapchain_info)?;
 
                 info!("Created swapchain");
@@ -102,6 +147,39 @@ impl Window {
                 Ok((display, swapchain))
             }
 
+            fn draw(&mut self, event_loop: &ActiveEventLoop, window_id: WindowId) {
+                let Some(active_window) = self.windows.get_mut(&window_id) else {
+                    return;
+                };
+
+                let mut new_windows = vec![];
+
+                if !active_window.draw(
+                    &self.device,
+                    self.data.frames_in_flight,
+                    &mut self.draw_fn,
+                    &mut new_windows,
+                ) {
+                    event_loop.exit();
+
+                    return;
+                }
+
+                // When limited, redraws are requested once the next frame is due
+                if self.data.frame_rate_limit.is_none() {
+                    active_window.window.request_redraw();
+                }
+
+                for attributes in new_windows {
+                    if let Err(err) = self.create_active_window(event_loop, attributes) {
+                        self.error = Some(err);
+                        event_loop.exit();
+
+                        return;
+                    }
+                }
+            }
+
             fn window_mode_attributes(
                 &self,
                 attributes: WindowAttributes,
@@ -174,10 +252,43 @@ impl Window {
         where
             F: FnMut(FrameContext),
         {
-            fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {
-                if let Some(ActiveWindow { window, .. }) = self.active_window.as_ref() {
-                    window.request_redraw();
+            fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+                let Some(frame_rate_limit) = self.data.frame_rate_limit else {
+                    for ActiveWindow { window, .. } in self.windows.values() {
+                        window.request_redraw();
+                    }
+
+                    return;
+                };
+
+                // Frames are due relative to when the previous frame started, so the time spent
+                // drawing counts towards the limit
+                let frame_duration = Duration::from_secs_f32(frame_rate_limit.recip());
+                let now = Instant::now();
+                let mut next_frame: Option<Instant> = None;
+
+                for ActiveWindow {
+                    frame_started,
+                    window,
+                    ..
+                } in self.windows.values()
+                {
+                    let frame_due = frame_started
+                        .map(|frame_started| frame_started + frame_duration)
+                        .unwrap_or(now);
+
+                    if frame_due <= now {
+                        window.request_redraw();
+                    } else {
+                        next_frame = Some(next_frame.map_or(frame_due, |next| next.min(frame_due)));
+                    }
                 }
+
+                event_loop.set_control_flow(
+                    next_frame
+                        .map(ControlFlow::WaitUntil)
+                        .unwrap_or(ControlFlow::Wait),
+                );
             }
 
             fn device_event(
@@ -186,58 +297,37 @@ impl Window {
                 device_id: DeviceId,
                 event: DeviceEvent,
             ) {
-                if let Some(ActiveWindow { events, .. }) = self.active_window.as_mut() {
-                    events.push(Event::DeviceEvent { device_id, event });
+                for ActiveWindow { events, .. } in self.windows.values_mut() {
+                    events.push(Event::DeviceEvent {
+                        device_id,
+                        event: event.clone(),
+                    });
                 }
             }
 
             fn resumed(&mut self, event_loop: &ActiveEventLoop) {
                 info!("Resumed");
 
+                if !self.windows.is_empty() {
+                    return;
+                }
+
                 self.data.attributes = self.window_mode_attributes(
                     self.data.attributes.clone(),
                     self.data.window_mode_override,
                 );
 
-                let window = match event_loop.create_window(self.data.attributes.clone()) {
-                    Err(err) => {
-                        warn!("Unable to create window: {err}");
-
-                        self.error = Some(EventLoopError::Os(err).into());
-                        event_loop.exit();
-
-                        return;
-                    }
-                    Ok(res) => res,
-                };
-                let (display, swapchain) = match self.create_display_swapchain(&window) {
+                match self.create_active_window(event_loop, self.data.attributes.clone()) {
                     Err(err) => {
-                        warn!("Unable to create swapchain: {err}");
-
-                        self.error = Some(err.into());
+                        self.error = Some(err);
                         event_loop.exit();
-
-                        return;
                     }
-                    Ok(res) => res,
-                };
-
-                let mut active_window = ActiveWindow {
-                    display,
-                    events: vec![],
-                    swapchain,
-                    window,
-                };
-
-                if !active_window.draw(&self.device, &mut self.draw_fn) {
-                    event_loop.exit();
+                    Ok(window_id) => self.draw(event_loop, window_id),
                 }
-
-                self.active_window = Some(active_window);
             }
 
             fn user_event(&mut self, _event_loop: &ActiveEventLoop, event: ()) {
-                if let Some(ActiveWindow { events, .. }) = self.active_window.as_mut() {
+                for ActiveWindow { events, .. } in self.windows.values_mut() {
                     events.push(Event::UserEvent(event));
                 }
             }
@@ -248,27 +338,37 @@ impl Window {
                 window_id: WindowId,
                 event: WindowEvent,
             ) {
-                if let Some(active_window) = self.active_window.as_mut() {
-                    match &event {
-                        WindowEvent::CloseRequested => {
-                            info!("Close requested");
+                match &event {
+                    WindowEvent::CloseRequested => {
+                        info!("Close requested");
+
+                        if let Some(mut active_window) = self.windows.remove(&window_id) {
+                            // Previous frames may still be using the swapchain images
+                            if let Err(err) = active_window.display.wait_idle() {
+                                warn!("Unable to wait for display: {err}");
+                            }
+                        }
 
+                        if self.windows.is_empty() {
                             event_loop.exit();
                         }
-                        WindowEvent::RedrawRequested => {
-                            if !active_window.draw(&self.device, &mut self.draw_fn) {
-                                event_loop.exit();
-                            }
-                        }
-                        WindowEvent::Resized(size) => {
+
+                        return;
+                    }
+                    WindowEvent::RedrawRequested => self.draw(event_loop, window_id),
+                    WindowEvent::Resized(size) => {
+                        if let Some(active_window) = self.windows.get_mut(&window_id) {
                             let mut swapchain_info = active_window.swapchain.info();
                             swapchain_info.width = size.width;
                             swapchain_info.height = size.height;
                             active_window.swapchain.set_info(swapchain_info);
+                            active_window.display_resize = true;
                         }
-                        _ => (),
                     }
+                    _ => (),
+                }
 
+                if let Some(active_window) = self.windows.get_mut(&window_id) {
                     active_window
                         .events
                         .push(Event::WindowEvent { window_id, event });
@@ -278,19 +378,44 @@ impl Window {
 
         struct ActiveWindow {
             display: Display,
+            display_resize: bool,
             events: Vec<Event<()>>,
+            frame_started: Option<Instant>,
             swapchain: Swapchain,
             window: winit::window::Window,
         }
 
         impl ActiveWindow {
-            fn draw(&mut self, device: &Arc<Device>, mut f: impl FnMut(FrameContext)) -> bool {
+            fn draw(
+                &mut self,
+                device: &Arc<Device>,
+                frames_in_flight: Option<usize>,
+                mut f: impl FnMut(FrameContext),
+                new_windows: &mut Vec<WindowAttributes>,
+            ) -> bool {
+                let frame_started = Instant::now();
+                let frame_time = self
+                    .frame_started
+                    .map(|prev_frame_started| frame_started - prev_frame_started)
+                    .unwrap_or_default();
+                self.frame_started = Some(frame_started);
+
+                if let Some(frames_in_flight) = frames_in_flight {
+                    if let Err(err) = self.display.wait_for_frames_in_flight(frames_in_flight) {
+                        warn!("Unable to wait for frames in flight: {err}");
+
+                        return false;
+                    }
+                }
+
                 if let Ok(swapchain_image) = self.swapchain.acquire_next_image() {
                     self.window.pre_present_notify();
 
                     let mut render_graph = RenderGraph::new();
                     let swapchain_image = render_graph.bind_node(swapchain_image);
                     let swapchain_info = self.swapchain.info();
+                    let resized = self.display_resize;
+                    self.display_resize = false;
 
                     let mut will_exit = false;
 
@@ -299,8 +424,11 @@ impl Window {
                     f(FrameContext {
                         device,
                         events: &self.events,
+                        frame_time,
                         height: swapchain_info.height,
+                        new_windows,
                         render_graph: &mut render_graph,
+                        resized,
                         swapchain_image,
                         width: swapchain_info.width,
                         will_exit: &mut will_exit,
@@ -329,19 +457,17 @@ impl Window {
 
                 profiling::finish_frame!();
 
-                self.window.request_redraw();
-
                 true
             }
         }
 
         let mut app = Application {
-            active_window: None,
             data: self.data,
             device: self.device,
             draw_fn,
             error: None,
             primary_monitor: None,
+            windows: HashMap::new(),
         };
 
         self.event_loop.run_app(&mut app)?;
@@ -366,7 +492,10 @@ pub struct WindowBuilder {
     attributes: WindowAttributes,
     cmd_buf_count: usize,
     device_info: DeviceInfo,
+    frame_rate_limit: Option<f32>,
+    frames_in_flight: Option<usize>,
     image_count: Option<u32>,
+    present_modes: Option<Vec<vk::PresentModeKHR>>,
     surface_format_fn: Option<Box<dyn Fn(&[vk::SurfaceFormatKHR]) -> vk::SurfaceFormatKHR>>,
     v_sync: Option<bool>,
     window_mode_override: Option<Option<FullscreenMode>>,
@@ -381,7 +510,10 @@ impl WindowBuilder {
             data: WindowData {
                 attributes: self.attributes,
                 cmd_buf_count: self.cmd_buf_count,
+                frame_rate_limit: self.frame_rate_limit,
+                frames_in_flight: self.frames_in_flight,
                 image_count: self.image_count,
+                present_modes: self.present_modes,
                 surface_format_fn: self.surface_format_fn,
                 v_sync: self.v_sync,
                 window_mode_override: self.window_mode_override,
@@ -421,6 +553,10 @@ impl WindowBuilder {
     /// A function to select the desired swapchain surface image format.
     ///
     /// By default linear color space will be selected unless it is not available.
+    ///
+    /// Wide-gamut and HDR color spaces, such as [`vk::ColorSpaceKHR::HDR10_ST2084_EXT`], may be
+    /// selected using [`Surface::find_format`] when [`Surface::is_swapchain_colorspace_enabled`]
+    /// is `true`.
     pub fn desired_surface_format<F>(mut self, f: F) -> Self
     where
         F: 'static + Fn(&[vk::SurfaceFormatKHR]) -> vk::SurfaceFormatKHR,
@@ -437,6 +573,41 @@ impl WindowBuilder {
         self
     }
 
+    /// A prioritized list of the desired swapchain presentation modes.
+    ///
+    /// When set, takes precedence over [`v_sync`](Self::v_sync). If none of the modes are supported
+    /// by the surface the presentation mode is chosen as if this function had not been called.
+    pub fn present_modes(mut self, present_modes: Vec<vk::PresentModeKHR>) -> Self {
+        self.present_modes = Some(present_modes);
+        self
+    }
+
+    /// Limits the rate at which frames are drawn, in frames per second, without busy-waiting.
+    ///
+    /// Time spent drawing each frame counts towards the limit. When combined with
+    /// [`v_sync`](Self::v_sync) the lower of the two frame rates is used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fps` is not a positive number.
+    pub fn frame_rate_limit(mut self, fps: f32) -> Self {
+        assert!(fps > 0.0, "frame rate limit must be positive");
+
+        self.frame_rate_limit = Some(fps);
+        self
+    }
+
+    /// Limits the number of frames which may be recorded before the GPU has finished executing
+    /// them; drawing the next frame blocks until an earlier frame completes.
+    ///
+    /// Fewer frames in flight reduces input latency while more frames improves throughput. The
+    /// effective limit is never greater than [`command_buffer_count`](Self::command_buffer_count),
+    /// which is also the default, and is independent of the swapchain image count.
+    pub fn frames_in_flight(mut self, count: usize) -> Self {
+        self.frames_in_flight = Some(count);
+        self
+    }
+
     /// Sets up fullscreen mode. In addition, decorations are set to `false` and maximized is set to
     /// `true`.
     ///
@@ -487,7 +658,10 @@ impl fmt::Debug for WindowBuilder {
             .field("attributes", &self.attributes)
             .field("cmd_buffer_count", &self.cmd_buf_count)
             .field("device_info", &self.device_info)
+            .field("frame_rate_limit", &self.frame_rate_limit)
+            .field("frames_in_flight", &self.frames_in_flight)
             .field("image_count", &self.image_count)
+            .field("present_modes", &self.present_modes)
             .field(
                 "surface_format_fn",
                 &self.surface_format_fn.as_ref().map(|_| ()),
@@ -504,7 +678,10 @@ impl Default for WindowBuilder {
             attributes: Default::default(),
             cmd_buf_count: 5,
             device_info: Default::default(),
+            frame_rate_limit: None,
+            frames_in_flight: None,
             image_count: None,
+            present_modes: None,
             surface_format_fn: None,
             v_sync: None,
             window_mode_override: None,
@@ -515,7 +692,10 @@ impl Default for WindowBuilder {
 struct WindowData {
     attributes: WindowAttributes,
     cmd_buf_count: usize,
+    frame_rate_limit: Option<f32>,
+    frames_in_flight: Option<usize>,
     image_count: Option<u32>,
+    present_modes: Option<Vec<vk::PresentModeKHR>>,
     surface_format_fn: Option<Box<dyn Fn(&[vk::SurfaceFormatKHR]) -> vk::SurfaceFormatKHR>>,
     v_sync: Option<bool>,
     window_mode_override: Option<Option<FullscreenMode>>,
@@ -36,7 +36,7 @@ fn main() -> Result<(), DriverError> {
 
     // Resolve and wait (or you can check has_executed without blocking) - alternatively you might
     // use device.queue_wait_idle(0) or device.device_wait_idle() - but those block on larger scopes
-    let cmd_buf = render_graph
+    let (cmd_buf, _) = render_graph
         .resolve()
         .submit(&mut HashPool::new(&device), 0, 0)?;
 
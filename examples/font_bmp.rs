@@ -154,7 +154,12 @@ fn main() -> anyhow::Result<()> {
         let color = [196, 172, 230u8];
         small_10px_font.print_scale(frame.render_graph, image_node, x, y, color, text, scale);
 
-        display.present_image(frame.render_graph, image_node, frame.swapchain_image);
+        display.present_image(
+            frame.render_graph,
+            image_node,
+            frame.swapchain_image,
+            ScaleMode::Fill,
+        );
     })?;
 
     Ok(())
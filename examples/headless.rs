@@ -0,0 +1,51 @@
+use {screen_13::prelude::*, std::sync::Arc};
+
+const FRAME_COUNT: usize = 8;
+
+/// Example demonstrating rendering frames without a window or surface.
+///
+/// A `HeadlessDisplay` is used in place of a swapchain: each frame is copied to host memory and
+/// passed to a function once it has executed, while later frames are still being recorded. Instead
+/// of printing the pixels you might encode them as images or video.
+fn main() -> Result<(), DisplayError> {
+    pretty_env_logger::init();
+
+    let device = Arc::new(Device::create_headless(DeviceInfo::default())?);
+    let mut display = HeadlessDisplay::new(
+        &device,
+        Box::new(HashPool::new(&device)),
+        3,
+        0,
+        |frame_idx, pixels, width, height, fmt| {
+            println!(
+                "Frame {frame_idx}: {width}x{height} {fmt:?} first pixel {:?}",
+                &pixels[0..4]
+            );
+        },
+    )?;
+
+    let image = Arc::new(Image::create(
+        &device,
+        ImageInfo::image_2d(
+            320,
+            200,
+            vk::Format::R8G8B8A8_UNORM,
+            vk::ImageUsageFlags::TRANSFER_DST | vk::ImageUsageFlags::TRANSFER_SRC,
+        ),
+    )?);
+
+    for frame_idx in 0..FRAME_COUNT {
+        let brightness = frame_idx as f32 / (FRAME_COUNT - 1) as f32;
+
+        let mut render_graph = RenderGraph::new();
+        let image = render_graph.bind_node(&image);
+        render_graph.clear_color_image_value(image, [brightness, brightness, brightness, 1.0]);
+
+        display.resolve_image(render_graph, image)?;
+    }
+
+    // Frames which are still executing are passed to the function once they have finished
+    display.flush()?;
+
+    Ok(())
+}
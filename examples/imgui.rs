@@ -44,7 +44,7 @@ fn main() -> Result<(), WindowError> {
 
         // Use the draw function callback to do some fun meant-for-debug-mode GUI stuff
         let gui_image = imgui.draw(
-            0.016,
+            frame.frame_time.as_secs_f32(),
             frame.events,
             frame.window,
             frame.render_graph,
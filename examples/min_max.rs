@@ -50,6 +50,7 @@ fn main() -> Result<(), DriverError> {
     render_graph
         .resolve()
         .submit(&mut HashPool::new(&device), 0, 0)?
+        .0
         .wait_until_executed()?;
 
     // For each image we have reduced each 2x2 pixel group into the min/max values of each group
@@ -0,0 +1,301 @@
+use {
+    bytemuck::{cast_slice, NoUninit},
+    inline_spirv::inline_spirv,
+    screen_13::prelude::*,
+    std::{mem::size_of, sync::Arc},
+};
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 32;
+
+/// Example demonstrating inline ray tracing (ray queries) from a regular compute shader.
+///
+/// Unlike the ray tracing pipeline examples, no shader binding table is required: the compute
+/// shader binds the top-level acceleration structure as a descriptor and uses `rayQueryEXT` to
+/// trace one ray per invocation. The hits are read back and printed to the console.
+fn main() -> Result<(), DriverError> {
+    pretty_env_logger::init();
+
+    let device = Arc::new(Device::create_headless(DeviceInfo::default())?);
+
+    // The ray query feature is enabled automatically during device creation when supported
+    if !Device::supports(&device, DeviceFeature::RayQuery) {
+        println!("Ray queries are not supported by this device");
+
+        return Ok(());
+    }
+
+    let accel_struct_scratch_offset_alignment = device
+        .physical_device
+        .accel_struct_properties
+        .as_ref()
+        .unwrap()
+        .min_accel_struct_scratch_offset_alignment
+        as vk::DeviceSize;
+
+    let ray_query_pipeline = create_ray_query_pipeline(&device)?;
+
+    // ------------------------------------------------------------------------------------------ //
+    // Generate the geometry and load it into buffers
+    // ------------------------------------------------------------------------------------------ //
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, NoUninit)]
+    #[allow(dead_code)]
+    struct Vertex {
+        pos: [f32; 3],
+    }
+
+    const VERTICES: [Vertex; 3] = [
+        Vertex {
+            pos: [-0.75, 0.75, 0.0],
+        },
+        Vertex {
+            pos: [0.75, 0.75, 0.0],
+        },
+        Vertex {
+            pos: [0.0, -0.75, 0.0],
+        },
+    ];
+
+    const INDICES: [u32; 3] = [0, 1, 2];
+
+    let index_buf = Arc::new(Buffer::create_from_slice(
+        &device,
+        vk::BufferUsageFlags::ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY_KHR
+            | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS,
+        cast_slice(&INDICES),
+    )?);
+    let vertex_buf = Arc::new(Buffer::create_from_slice(
+        &device,
+        vk::BufferUsageFlags::ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY_KHR
+            | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS,
+        cast_slice(&VERTICES),
+    )?);
+
+    // ------------------------------------------------------------------------------------------ //
+    // Create the bottom and top level acceleration structures
+    // ------------------------------------------------------------------------------------------ //
+
+    let blas_geometry_info = AccelerationStructureGeometryInfo::blas([(
+        AccelerationStructureGeometry::opaque(
+            1,
+            AccelerationStructureGeometryData::triangles(
+                Buffer::device_address(&index_buf),
+                vk::IndexType::UINT32,
+                3,
+                None,
+                Buffer::device_address(&vertex_buf),
+                vk::Format::R32G32B32_SFLOAT,
+                12,
+            ),
+        ),
+        vk::AccelerationStructureBuildRangeInfoKHR::default().primitive_count(1),
+    )]);
+    let blas_size = AccelerationStructure::size_of(&device, &blas_geometry_info);
+    let blas = Arc::new(AccelerationStructure::create(
+        &device,
+        AccelerationStructureInfo::blas(blas_size.create_size),
+    )?);
+
+    let instances = [vk::AccelerationStructureInstanceKHR {
+        transform: vk::TransformMatrixKHR {
+            matrix: [
+                1.0, 0.0, 0.0, 0.0, //
+                0.0, 1.0, 0.0, 0.0, //
+                0.0, 0.0, 1.0, 0.0, //
+            ],
+        },
+        instance_custom_index_and_mask: vk::Packed24_8::new(0, 0xff),
+        instance_shader_binding_table_record_offset_and_flags: vk::Packed24_8::new(
+            0,
+            vk::GeometryInstanceFlagsKHR::TRIANGLE_FACING_CULL_DISABLE.as_raw() as _,
+        ),
+        acceleration_structure_reference: vk::AccelerationStructureReferenceKHR {
+            device_handle: AccelerationStructure::device_address(&blas),
+        },
+    }];
+    let instance_buf = Arc::new(Buffer::create_from_slice(
+        &device,
+        vk::BufferUsageFlags::ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY_KHR
+            | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS,
+        AccelerationStructure::instance_slice(&instances),
+    )?);
+
+    let tlas_geometry_info = AccelerationStructureGeometryInfo::tlas([(
+        AccelerationStructureGeometry::opaque(
+            1,
+            AccelerationStructureGeometryData::instances(Buffer::device_address(&instance_buf)),
+        ),
+        vk::AccelerationStructureBuildRangeInfoKHR::default().primitive_count(1),
+    )]);
+    let tlas_size = AccelerationStructure::size_of(&device, &tlas_geometry_info);
+    let tlas = Arc::new(AccelerationStructure::create(
+        &device,
+        AccelerationStructureInfo::tlas(tlas_size.create_size),
+    )?);
+
+    // ------------------------------------------------------------------------------------------ //
+    // Build the acceleration structures and trace them using a compute shader
+    // ------------------------------------------------------------------------------------------ //
+
+    let mut render_graph = RenderGraph::new();
+    let index_node = render_graph.bind_node(&index_buf);
+    let vertex_node = render_graph.bind_node(&vertex_buf);
+    let instance_node = render_graph.bind_node(&instance_buf);
+    let blas_node = render_graph.bind_node(&blas);
+    let tlas_node = render_graph.bind_node(&tlas);
+
+    {
+        let scratch_buf = render_graph.bind_node(Buffer::create(
+            &device,
+            BufferInfo::device_mem(
+                blas_size.build_size,
+                vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS | vk::BufferUsageFlags::STORAGE_BUFFER,
+            )
+            .to_builder()
+            .alignment(accel_struct_scratch_offset_alignment),
+        )?);
+        let scratch_data = render_graph.node_device_address(scratch_buf);
+
+        render_graph
+            .begin_pass("Build BLAS")
+            .access_node(index_node, AccessType::AccelerationStructureBuildRead)
+            .access_node(vertex_node, AccessType::AccelerationStructureBuildRead)
+            .access_node(scratch_buf, AccessType::AccelerationStructureBufferWrite)
+            .access_node(blas_node, AccessType::AccelerationStructureBuildWrite)
+            .record_acceleration(move |accel, _| {
+                accel.build_structure(&blas_geometry_info, blas_node, scratch_data);
+            });
+    }
+
+    {
+        let scratch_buf = render_graph.bind_node(Buffer::create(
+            &device,
+            BufferInfo::device_mem(
+                tlas_size.build_size,
+                vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS | vk::BufferUsageFlags::STORAGE_BUFFER,
+            )
+            .to_builder()
+            .alignment(accel_struct_scratch_offset_alignment),
+        )?);
+        let scratch_data = render_graph.node_device_address(scratch_buf);
+
+        render_graph
+            .begin_pass("Build TLAS")
+            .access_node(blas_node, AccessType::AccelerationStructureBuildRead)
+            .access_node(instance_node, AccessType::AccelerationStructureBuildRead)
+            .access_node(scratch_buf, AccessType::AccelerationStructureBufferWrite)
+            .access_node(tlas_node, AccessType::AccelerationStructureBuildWrite)
+            .record_acceleration(move |accel, _| {
+                accel.build_structure(&tlas_geometry_info, tlas_node, scratch_data);
+            });
+    }
+
+    let hit_buf = render_graph.bind_node(Buffer::create(
+        &device,
+        BufferInfo::host_mem(
+            (WIDTH * HEIGHT) as vk::DeviceSize * size_of::<u32>() as vk::DeviceSize,
+            vk::BufferUsageFlags::STORAGE_BUFFER,
+        ),
+    )?);
+
+    // The ray tracing access type is synchronized using the compute stage in compute passes
+    render_graph
+        .begin_pass("Ray query")
+        .bind_pipeline(&ray_query_pipeline)
+        .access_node(blas_node, AccessType::ComputeShaderReadOther)
+        .access_descriptor(
+            0,
+            tlas_node,
+            AccessType::RayTracingShaderReadAccelerationStructure,
+        )
+        .write_descriptor(1, hit_buf)
+        .record_compute(|compute, _| {
+            compute
+                .push_constants(cast_slice(&[WIDTH, HEIGHT]))
+                .dispatch(WIDTH.div_ceil(8), HEIGHT.div_ceil(8), 1);
+        });
+
+    let hit_buf = render_graph.unbind_node(hit_buf);
+
+    render_graph
+        .resolve()
+        .submit(&mut HashPool::new(&device), 0, 0)?
+        .0
+        .wait_until_executed()?;
+
+    let hits: &[u32] = cast_slice(Buffer::mapped_slice(&hit_buf));
+    for row in hits.chunks_exact(WIDTH as _) {
+        println!(
+            "{}",
+            row.iter()
+                .map(|&hit| if hit != 0 { '#' } else { '.' })
+                .collect::<String>()
+        );
+    }
+
+    Ok(())
+}
+
+fn create_ray_query_pipeline(device: &Arc<Device>) -> Result<Arc<ComputePipeline>, DriverError> {
+    Ok(Arc::new(ComputePipeline::create(
+        device,
+        ComputePipelineInfo::default(),
+        Shader::new_compute(
+            inline_spirv!(
+                r#"
+                #version 460
+                #extension GL_EXT_ray_query : require
+
+                layout(local_size_x = 8, local_size_y = 8) in;
+
+                layout(push_constant) uniform PushConstants {
+                    uint width;
+                    uint height;
+                } push_const;
+
+                layout(binding = 0) uniform accelerationStructureEXT tlas;
+                layout(binding = 1) buffer Hits {
+                    uint hits[];
+                } hit_buf;
+
+                void main() {
+                    uvec2 size = uvec2(push_const.width, push_const.height);
+
+                    if (any(greaterThanEqual(gl_GlobalInvocationID.xy, size))) {
+                        return;
+                    }
+
+                    vec2 uv = (vec2(gl_GlobalInvocationID.xy) + 0.5) / vec2(size) * 2.0 - 1.0;
+                    vec3 origin = vec3(uv, -1.0);
+                    vec3 direction = vec3(0.0, 0.0, 1.0);
+
+                    rayQueryEXT ray_query;
+                    rayQueryInitializeEXT(
+                        ray_query,
+                        tlas,
+                        gl_RayFlagsOpaqueEXT | gl_RayFlagsTerminateOnFirstHitEXT,
+                        0xff,
+                        origin,
+                        0.001,
+                        direction,
+                        10.0
+                    );
+
+                    while (rayQueryProceedEXT(ray_query)) {}
+
+                    bool hit = rayQueryGetIntersectionTypeEXT(ray_query, true)
+                        == gl_RayQueryCommittedIntersectionTriangleEXT;
+                    uint idx = gl_GlobalInvocationID.y * size.x + gl_GlobalInvocationID.x;
+
+                    hit_buf.hits[idx] = uint(hit);
+                }
+                "#,
+                comp,
+                vulkan1_2
+            )
+            .as_slice(),
+        ),
+    )?))
+}
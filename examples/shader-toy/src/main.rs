@@ -303,7 +303,12 @@ fn main() -> anyhow::Result<()> {
                 });
 
             // Done!
-            display.present_image(frame.render_graph, input, frame.swapchain_image);
+            display.present_image(
+                frame.render_graph,
+                input,
+                frame.swapchain_image,
+                ScaleMode::Fill,
+            );
 
             // Unbind things from this graph (we want them back for the next frame!)
             flowers_image_binding = Some(frame.render_graph.unbind_node(flowers_image));
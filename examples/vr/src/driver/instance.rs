@@ -179,6 +179,7 @@ impl Instance {
                     InstanceCreateError::VulkanUnsupported
                 })?;
 
+            let enabled_features = Device::supported_features(&vk_physical_device);
             let device =
                 Device::create_ash_device(&vk_instance, &vk_physical_device, true, |create_info| {
                     let device = xr_instance
@@ -204,7 +205,14 @@ impl Instance {
                     InstanceCreateError::VulkanUnsupported
                 })?;
             let device = Arc::new(
-                Device::load(vk_instance, vk_physical_device, device, true).map_err(|err| {
+                Device::load(
+                    vk_instance,
+                    vk_physical_device,
+                    device,
+                    true,
+                    enabled_features,
+                )
+                .map_err(|err| {
                     error!("Vulkan device: {err}");
 
                     InstanceCreateError::VulkanUnsupported
@@ -471,7 +471,7 @@ fn main() -> anyhow::Result<()> {
         // the image - afterwards we keep the submitted command buffer around (including all
         // in-flight resources) so that nothing is dropped until that image is actually done.
         swapchain.wait_image(xr::Duration::INFINITE).unwrap();
-        let cmd_buf = render_graph
+        let (cmd_buf, _) = render_graph
             .resolve()
             .submit(&mut pool, queue_family_index as _, 0)
             .unwrap();
@@ -506,6 +506,9 @@ fn main() -> anyhow::Result<()> {
         )?;
     }
 
+    // Finish all in-flight frames before the command buffers in `graphs` are dropped
+    Device::wait_idle(device)?;
+
     trace!("OK");
 
     Ok(())
@@ -768,6 +771,7 @@ fn load_texture(
     render_graph
         .resolve()
         .submit(&mut LazyPool::new(device), queue_family_index as _, 0)?
+        .0
         .wait_until_executed()?;
 
     Ok(texture)
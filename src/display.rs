@@ -1,12 +1,19 @@
 use {
     super::{
         driver::{
-            device::Device, image_access_layout, swapchain::SwapchainImage, CommandBuffer,
-            CommandBufferInfo, DescriptorPool, DescriptorPoolInfo, DriverError, RenderPass,
-            RenderPassInfo,
+            buffer::{Buffer, BufferInfo},
+            device::Device,
+            format_aspect_mask, format_texel_block_extent, format_texel_block_size,
+            image_access_layout,
+            swapchain::SwapchainImage,
+            CommandBuffer, CommandBufferInfo, DescriptorPool, DescriptorPoolInfo, DriverError,
+            RenderPass, RenderPassInfo, SubmitToken,
         },
-        graph::{node::SwapchainImageNode, RenderGraph},
-        pool::Pool,
+        graph::{
+            node::{AnyImageNode, SwapchainImageNode},
+            RenderGraph,
+        },
+        pool::{Lease, Pool},
     },
     ash::vk,
     log::trace,
@@ -80,6 +87,8 @@ impl Display {
         let mut resolver = render_graph.resolve();
         let wait_dst_stage_mask = resolver.node_pipeline_stages(swapchain_image);
 
+        self.retire_executed();
+
         self.cmd_buf_idx += 1;
         self.cmd_buf_idx %= self.cmd_bufs.len();
 
@@ -131,14 +140,7 @@ impl Display {
         resolver.record_unscheduled_passes(self.pool.as_mut(), cmd_buf)?;
 
         unsafe {
-            Self::submit(
-                cmd_buf,
-                vk::SubmitInfo::default()
-                    .command_buffers(from_ref(cmd_buf))
-                    .signal_semaphores(from_ref(&swapchain_image.rendered))
-                    .wait_semaphores(from_ref(&swapchain_image.acquired))
-                    .wait_dst_stage_mask(from_ref(&wait_dst_stage_mask)),
-            )?;
+            Self::submit(cmd_buf, &swapchain_image, wait_dst_stage_mask)?;
         }
 
         let elapsed = Instant::now() - started;
@@ -151,22 +153,141 @@ impl Display {
         Ok(swapchain_image)
     }
 
+    /// Drops the resources of previously displayed frames which have finished executing, without
+    /// blocking.
+    ///
+    /// Leased resources return to the pool as soon as possible instead of when their command
+    /// buffer is next reused, so that following frames may lease them again.
+    #[profiling::function]
+    fn retire_executed(&mut self) {
+        for cmd_buf in self.cmd_bufs.iter_mut() {
+            if cmd_buf.has_executed().unwrap_or_default() {
+                CommandBuffer::drop_fenced(cmd_buf);
+            }
+        }
+    }
+
     #[profiling::function]
-    unsafe fn submit(cmd_buf: &CommandBuffer, submit_info: vk::SubmitInfo<'_>) -> Result<(), ()> {
+    unsafe fn submit(
+        cmd_buf: &mut CommandBuffer,
+        swapchain_image: &SwapchainImage,
+        wait_dst_stage_mask: vk::PipelineStageFlags,
+    ) -> Result<(), ()> {
         use std::slice::from_ref;
 
         cmd_buf
             .device
             .end_command_buffer(**cmd_buf)
             .map_err(|_| ())?;
+
+        // The swapchain semaphores are binary semaphores, so they use a value of zero
+        let acquired = SubmitToken {
+            semaphore: swapchain_image.acquired,
+            value: 0,
+        };
+
+        cmd_buf.submit_token = Device::queue_submit(
+            &cmd_buf.device,
+            cmd_buf.info.queue_family_index as _,
+            0,
+            **cmd_buf,
+            cmd_buf.fence,
+            from_ref(&(acquired, wait_dst_stage_mask)),
+            from_ref(&swapchain_image.rendered),
+        )
+        .map_err(|_| ())?;
+
+        Ok(())
+    }
+
+    /// Submits a command buffer which does not wait on or signal any swapchain semaphores.
+    #[profiling::function]
+    unsafe fn submit_headless(cmd_buf: &mut CommandBuffer) -> Result<(), ()> {
         cmd_buf
             .device
-            .queue_submit(
-                cmd_buf.device.queues[cmd_buf.info.queue_family_index as usize][0],
-                from_ref(&submit_info),
-                cmd_buf.fence,
-            )
-            .map_err(|_| ())
+            .end_command_buffer(**cmd_buf)
+            .map_err(|_| ())?;
+
+        cmd_buf.submit_token = Device::queue_submit(
+            &cmd_buf.device,
+            cmd_buf.info.queue_family_index as _,
+            0,
+            **cmd_buf,
+            cmd_buf.fence,
+            &[],
+            &[],
+        )
+        .map_err(|_| ())?;
+
+        Ok(())
+    }
+
+    /// Blocks until fewer than `frames_in_flight` previously displayed frames are still executing,
+    /// releasing the resources of any frames which have finished.
+    ///
+    /// Call this before acquiring the next swapchain image in order to bound how many frames the
+    /// CPU may record ahead of the GPU: lower values reduce latency while higher values improve
+    /// throughput. A value of zero is treated as one.
+    ///
+    /// _NOTE:_ The command buffer count given to [`Display::new`] is always an upper bound on the
+    /// number of frames in flight because [`Display::resolve_image`] waits for the oldest command
+    /// buffer before reusing it; values greater than the command buffer count have no effect.
+    #[profiling::function]
+    pub fn wait_for_frames_in_flight(
+        &mut self,
+        frames_in_flight: usize,
+    ) -> Result<(), DriverError> {
+        let frames_in_flight = frames_in_flight.max(1);
+        let cmd_buf_count = self.cmd_bufs.len();
+        let mut pending = Vec::with_capacity(cmd_buf_count);
+
+        // Oldest submission first; the most recent submission used cmd_buf_idx
+        for offset in 1..=cmd_buf_count {
+            let cmd_buf_idx = (self.cmd_buf_idx + offset) % cmd_buf_count;
+            let cmd_buf = &mut self.cmd_bufs[cmd_buf_idx];
+
+            if cmd_buf.has_executed()? {
+                CommandBuffer::drop_fenced(cmd_buf);
+            } else {
+                pending.push(cmd_buf_idx);
+            }
+        }
+
+        let wait_count = (pending.len() + 1).saturating_sub(frames_in_flight);
+
+        for cmd_buf_idx in pending.into_iter().take(wait_count) {
+            let cmd_buf = &mut self.cmd_bufs[cmd_buf_idx];
+
+            Device::wait_for_fence(&cmd_buf.device, &cmd_buf.fence)?;
+            CommandBuffer::drop_fenced(cmd_buf);
+        }
+
+        Ok(())
+    }
+
+    /// Blocks until all previously displayed frames have finished executing and releases the
+    /// resources they used.
+    ///
+    /// Call this before dropping the display or any resources used by recent frames, such as
+    /// during shutdown.
+    #[profiling::function]
+    pub fn wait_idle(&mut self) -> Result<(), DriverError> {
+        let Some(cmd_buf) = self.cmd_bufs.first() else {
+            return Ok(());
+        };
+
+        let fences = self
+            .cmd_bufs
+            .iter()
+            .map(|cmd_buf| cmd_buf.fence)
+            .collect::<Box<_>>();
+        Device::wait_for_fences(&cmd_buf.device, &fences)?;
+
+        for cmd_buf in self.cmd_bufs.iter_mut() {
+            CommandBuffer::drop_fenced(cmd_buf);
+        }
+
+        Ok(())
     }
 
     #[profiling::function]
@@ -189,6 +310,347 @@ impl Debug for Display {
     }
 }
 
+/// A display interface which copies each frame to host memory instead of presenting it.
+///
+/// Frames are resolved using the same pacing as [`Display`]: up to the given number of command
+/// buffers may be in flight and each is only reused after it has finished executing. Once a frame
+/// has executed its pixels are passed, in order, to the frame function along with the frame index,
+/// width, height, and format of the image.
+///
+/// _NOTE:_ Frames which are still executing are not passed to the frame function until
+/// [`HeadlessDisplay::flush`] is called.
+pub struct HeadlessDisplay {
+    cmd_buf_idx: usize,
+    frame_fn: Box<HeadlessFrameFn>,
+    frame_idx: usize,
+    frames: Box<[HeadlessFrame]>,
+    pool: Box<dyn ResolverPool>,
+}
+
+type HeadlessFrameFn = dyn FnMut(usize, &[u8], u32, u32, vk::Format) + Send;
+
+impl HeadlessDisplay {
+    /// Constructs a new `HeadlessDisplay` object.
+    pub fn new(
+        device: &Arc<Device>,
+        pool: Box<dyn ResolverPool>,
+        cmd_buf_count: usize,
+        queue_family_index: u32,
+        frame_fn: impl FnMut(usize, &[u8], u32, u32, vk::Format) + Send + 'static,
+    ) -> Result<Self, DriverError> {
+        let mut frames = Vec::with_capacity(cmd_buf_count);
+        for _ in 0..cmd_buf_count {
+            frames.push(HeadlessFrame {
+                buf: None,
+                cmd_buf: CommandBuffer::create(device, CommandBufferInfo::new(queue_family_index))?,
+                readback: None,
+            });
+        }
+        let frames = frames.into_boxed_slice();
+
+        Ok(Self {
+            cmd_buf_idx: 0,
+            frame_fn: Box::new(frame_fn),
+            frame_idx: 0,
+            frames,
+            pool,
+        })
+    }
+
+    /// Blocks until all resolved frames have executed and passes them to the frame function.
+    #[profiling::function]
+    pub fn flush(&mut self) -> Result<(), DriverError> {
+        for offset in 1..=self.frames.len() {
+            let cmd_buf_idx = (self.cmd_buf_idx + offset) % self.frames.len();
+            let frame = &mut self.frames[cmd_buf_idx];
+
+            Device::wait_for_fence(&frame.cmd_buf.device, &frame.cmd_buf.fence)?;
+            CommandBuffer::drop_fenced(&mut frame.cmd_buf);
+            frame.read_back(&mut self.frame_fn);
+        }
+
+        Ok(())
+    }
+
+    /// Passes any frames which have finished executing to the frame function, without blocking.
+    #[profiling::function]
+    pub fn poll(&mut self) -> Result<(), DriverError> {
+        // Oldest frame first so that frames are always passed in order
+        for offset in 1..=self.frames.len() {
+            let cmd_buf_idx = (self.cmd_buf_idx + offset) % self.frames.len();
+            let frame = &mut self.frames[cmd_buf_idx];
+
+            if !frame.cmd_buf.has_executed()? {
+                break;
+            }
+
+            CommandBuffer::drop_fenced(&mut frame.cmd_buf);
+            frame.read_back(&mut self.frame_fn);
+        }
+
+        Ok(())
+    }
+
+    /// Copies mip level zero of the first layer of the given image to host memory using passes
+    /// specified in `render_graph`.
+    ///
+    /// The image must be readable as a transfer source. Blocks if all command buffers are in
+    /// flight, passing the oldest frame to the frame function once it has executed.
+    ///
+    /// Only the depth aspect of depth/stencil images is copied, because buffer copies may only use
+    /// a single aspect.
+    #[profiling::function]
+    pub fn resolve_image(
+        &mut self,
+        mut render_graph: RenderGraph,
+        image: impl Into<AnyImageNode>,
+    ) -> Result<(), DisplayError> {
+        let image = image.into();
+        let image_info = render_graph.node_info(image);
+        let mut aspect_mask = format_aspect_mask(image_info.fmt);
+
+        if aspect_mask.contains(vk::ImageAspectFlags::DEPTH) {
+            aspect_mask = vk::ImageAspectFlags::DEPTH;
+        }
+
+        let (block_width, block_height) = format_texel_block_extent(image_info.fmt);
+        let block_size =
+            format_texel_block_size(image_info.fmt, aspect_mask).ok_or(DriverError::Unsupported)?;
+        let len = image_info.width.div_ceil(block_width) as vk::DeviceSize
+            * image_info.height.div_ceil(block_height) as vk::DeviceSize
+            * image_info.depth as vk::DeviceSize
+            * block_size as vk::DeviceSize;
+
+        self.cmd_buf_idx += 1;
+        self.cmd_buf_idx %= self.frames.len();
+
+        let frame = unsafe { self.frames.get_unchecked_mut(self.cmd_buf_idx) };
+
+        unsafe {
+            Display::wait_for_fence(&mut frame.cmd_buf)?;
+        }
+
+        frame.read_back(&mut self.frame_fn);
+
+        let buf = match frame.buf.take() {
+            Some(buf) if buf.info.size >= len => buf,
+            _ => Arc::new(Buffer::create(
+                &frame.cmd_buf.device,
+                BufferInfo::host_mem(len, vk::BufferUsageFlags::empty()),
+            )?),
+        };
+        let buf_node = render_graph.bind_node(Arc::clone(&buf));
+        render_graph.copy_image_to_buffer_region(
+            image,
+            buf_node,
+            vk::BufferImageCopy {
+                buffer_offset: 0,
+                buffer_row_length: image_info.width,
+                buffer_image_height: image_info.height,
+                image_subresource: vk::ImageSubresourceLayers {
+                    aspect_mask,
+                    mip_level: 0,
+                    base_array_layer: 0,
+                    layer_count: 1,
+                },
+                image_offset: Default::default(),
+                image_extent: vk::Extent3D {
+                    depth: image_info.depth,
+                    height: image_info.height,
+                    width: image_info.width,
+                },
+            },
+        );
+
+        let mut resolver = render_graph.resolve();
+
+        unsafe {
+            Display::begin(&frame.cmd_buf)?;
+        }
+
+        resolver.record_node(&mut *self.pool, &mut frame.cmd_buf, buf_node)?;
+        resolver.record_unscheduled_passes(&mut *self.pool, &mut frame.cmd_buf)?;
+
+        unsafe {
+            Display::submit_headless(&mut frame.cmd_buf)?;
+        }
+
+        CommandBuffer::push_fenced_drop(&mut frame.cmd_buf, resolver);
+
+        frame.readback = Some(HeadlessReadback {
+            buf,
+            fmt: image_info.fmt,
+            frame_idx: self.frame_idx,
+            height: image_info.height,
+            len: len as _,
+            width: image_info.width,
+        });
+        self.frame_idx += 1;
+
+        Ok(())
+    }
+}
+
+impl Debug for HeadlessDisplay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("HeadlessDisplay")
+    }
+}
+
+struct HeadlessFrame {
+    buf: Option<Arc<Buffer>>,
+    cmd_buf: CommandBuffer,
+    readback: Option<HeadlessReadback>,
+}
+
+impl HeadlessFrame {
+    /// Passes the pixels of an executed frame to `frame_fn` and keeps the buffer for reuse.
+    fn read_back(&mut self, frame_fn: &mut HeadlessFrameFn) {
+        if let Some(HeadlessReadback {
+            buf,
+            fmt,
+            frame_idx,
+            height,
+            len,
+            width,
+        }) = self.readback.take()
+        {
+            frame_fn(
+                frame_idx,
+                &Buffer::mapped_slice(&buf)[..len],
+                width,
+                height,
+                fmt,
+            );
+
+            self.buf = Some(buf);
+        }
+    }
+}
+
+struct HeadlessReadback {
+    buf: Arc<Buffer>,
+    fmt: vk::Format,
+    frame_idx: usize,
+    height: u32,
+    len: usize,
+    width: u32,
+}
+
+/// Submits render graphs without a display while allowing several to execute at once.
+///
+/// Each frame of the ring owns a pool and the command buffer of its last submission. Submitting a
+/// render graph only blocks if the frame it will use is still executing from an earlier lap of the
+/// ring, so the next render graph may be recorded and submitted while previous ones execute. The
+/// resources of each frame are released as soon as it has executed.
+#[derive(Debug)]
+pub struct HeadlessRing<P> {
+    frame_idx: usize,
+    frames: Box<[HeadlessRingFrame<P>]>,
+}
+
+#[derive(Debug)]
+struct HeadlessRingFrame<P> {
+    cmd_buf: Option<Lease<CommandBuffer>>,
+    pool: P,
+}
+
+impl<P> HeadlessRing<P> {
+    /// Constructs a new `HeadlessRing` of `frame_count` frames, each using a pool returned by
+    /// `pool_fn`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frame_count` is zero.
+    pub fn new(frame_count: usize, mut pool_fn: impl FnMut() -> P) -> Self {
+        assert_ne!(frame_count, 0, "frame count must be non-zero");
+
+        let frames = (0..frame_count)
+            .map(|_| HeadlessRingFrame {
+                cmd_buf: None,
+                pool: pool_fn(),
+            })
+            .collect();
+
+        Self {
+            frame_idx: 0,
+            frames,
+        }
+    }
+
+    /// Returns the pool of the frame which will be used by the next call to
+    /// [`submit`](Self::submit).
+    ///
+    /// Resources leased from this pool while building the next render graph are returned to it
+    /// once that render graph has executed.
+    pub fn pool(&mut self) -> &mut P {
+        &mut self.frames[self.frame_idx].pool
+    }
+
+    /// Releases the resources of any frames which have finished executing, without blocking.
+    #[profiling::function]
+    pub fn poll(&mut self) -> Result<(), DriverError> {
+        for frame in self.frames.iter_mut() {
+            if let Some(cmd_buf) = &mut frame.cmd_buf {
+                if cmd_buf.has_executed()? {
+                    CommandBuffer::drop_fenced(cmd_buf);
+                    frame.cmd_buf = None;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolves and submits `render_graph` using the pool of the current frame, first waiting for
+    /// the previous submission of that frame to finish executing.
+    #[profiling::function]
+    pub fn submit(
+        &mut self,
+        render_graph: RenderGraph,
+        queue_family_index: usize,
+        queue_index: usize,
+    ) -> Result<(), DriverError>
+    where
+        P: Pool<CommandBufferInfo, CommandBuffer>
+            + Pool<DescriptorPoolInfo, DescriptorPool>
+            + Pool<RenderPassInfo, RenderPass>,
+    {
+        self.poll()?;
+
+        let frame = &mut self.frames[self.frame_idx];
+
+        if let Some(mut cmd_buf) = frame.cmd_buf.take() {
+            cmd_buf.wait_until_executed()?;
+            CommandBuffer::drop_fenced(&mut cmd_buf);
+        }
+
+        let (cmd_buf, _) =
+            render_graph
+                .resolve()
+                .submit(&mut frame.pool, queue_family_index, queue_index)?;
+        frame.cmd_buf = Some(cmd_buf);
+
+        self.frame_idx += 1;
+        self.frame_idx %= self.frames.len();
+
+        Ok(())
+    }
+
+    /// Blocks until all submitted frames have finished executing and releases their resources.
+    #[profiling::function]
+    pub fn wait_idle(&mut self) -> Result<(), DriverError> {
+        for frame in self.frames.iter_mut() {
+            if let Some(mut cmd_buf) = frame.cmd_buf.take() {
+                cmd_buf.wait_until_executed()?;
+                CommandBuffer::drop_fenced(&mut cmd_buf);
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Describes error conditions relating to physical displays.
 #[derive(Debug)]
 pub enum DisplayError {
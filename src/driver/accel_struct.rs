@@ -2,7 +2,9 @@
 
 use {
     super::{
-        access_type_from_u8, access_type_into_u8, device::Device, Buffer, BufferInfo, DriverError,
+        access_type_from_u8, access_type_into_u8,
+        device::{Device, DeviceFeature},
+        Buffer, BufferInfo, DriverError,
     },
     ash::vk,
     derive_builder::{Builder, UninitializedFieldError},
@@ -52,7 +54,7 @@ use {
 /// [fully qualified syntax]: https://doc.rust-lang.org/book/ch19-03-advanced-traits.html#fully-qualified-syntax-for-disambiguation-calling-methods-with-the-same-name
 #[derive(Debug)]
 pub struct AccelerationStructure {
-    accel_struct: (vk::AccelerationStructureKHR, Buffer),
+    pub(crate) accel_struct: (vk::AccelerationStructureKHR, Buffer),
     device: Arc<Device>,
 
     /// Information used to create this object.
@@ -89,7 +91,13 @@ impl AccelerationStructure {
         device: &Arc<Device>,
         info: impl Into<AccelerationStructureInfo>,
     ) -> Result<Self, DriverError> {
-        debug_assert!(device.physical_device.accel_struct_properties.is_some());
+        if !Device::supports(device, DeviceFeature::AccelerationStructure) {
+            warn!("acceleration structure feature not enabled");
+
+            return Err(DriverError::FeatureNotEnabled(
+                DeviceFeature::AccelerationStructure,
+            ));
+        }
 
         let info = info.into();
 
@@ -135,6 +143,31 @@ impl AccelerationStructure {
         })
     }
 
+    /// Creates a new acceleration structure on the given device which is suitable as the
+    /// destination of a compacting copy.
+    ///
+    /// `compacted_size` is the value written by
+    /// [`Acceleration::write_compacted_size`](super::super::graph::pass_ref::Acceleration::write_compacted_size)
+    /// and must only be read back after the command buffer which built the source acceleration
+    /// structure has finished executing.
+    ///
+    /// See
+    /// [`RenderGraph::copy_acceleration_structure`](super::super::graph::RenderGraph::copy_acceleration_structure).
+    #[profiling::function]
+    pub fn create_compacted(
+        device: &Arc<Device>,
+        ty: vk::AccelerationStructureTypeKHR,
+        compacted_size: vk::DeviceSize,
+    ) -> Result<Self, DriverError> {
+        Self::create(
+            device,
+            AccelerationStructureInfo {
+                ty,
+                size: compacted_size,
+            },
+        )
+    }
+
     /// Keeps track of some `next_access` which affects this object.
     ///
     /// Returns the previous access for which a pipeline barrier should be used to prevent data
@@ -221,6 +254,11 @@ impl AccelerationStructure {
         }
     }
 
+    /// Returns the size, in bytes, of the device memory allocated for this object.
+    pub(crate) fn memory_size(this: &Self) -> vk::DeviceSize {
+        Buffer::memory_size(&this.accel_struct.1)
+    }
+
     /// Helper function which is used to prepare instance buffers.
     pub fn instance_slice(instances: &[vk::AccelerationStructureInstanceKHR]) -> &[u8] {
         use std::slice::from_raw_parts;
@@ -630,6 +668,72 @@ impl<G> AccelerationStructureGeometryInfo<G> {
     }
 }
 
+impl
+    AccelerationStructureGeometryInfo<(
+        AccelerationStructureGeometry,
+        vk::AccelerationStructureBuildRangeInfoKHR,
+    )>
+{
+    /// Returns `true` if an acceleration structure built using `self` may be updated using `next`.
+    ///
+    /// Updates require that the acceleration structure was built using
+    /// [`vk::BuildAccelerationStructureFlagsKHR::ALLOW_UPDATE`] and that the topology (type, flags,
+    /// geometry layout and primitive counts) is unchanged; only the geometry or instance data
+    /// itself may differ.
+    pub fn is_update_compatible(&self, next: &Self) -> bool {
+        use AccelerationStructureGeometryData as Data;
+
+        self.flags
+            .contains(vk::BuildAccelerationStructureFlagsKHR::ALLOW_UPDATE)
+            && self.ty == next.ty
+            && self.flags == next.flags
+            && self.geometries.len() == next.geometries.len()
+            && self.geometries.iter().zip(next.geometries.iter()).all(
+                |((prev_geometry, prev_range), (next_geometry, next_range))| {
+                    prev_geometry.max_primitive_count == next_geometry.max_primitive_count
+                        && prev_geometry.flags == next_geometry.flags
+                        && prev_range.primitive_count == next_range.primitive_count
+                        && match (prev_geometry.geometry, next_geometry.geometry) {
+                            (Data::AABBs { .. }, Data::AABBs { .. }) => true,
+                            (
+                                Data::Instances {
+                                    array_of_pointers: prev_array_of_pointers,
+                                    ..
+                                },
+                                Data::Instances {
+                                    array_of_pointers: next_array_of_pointers,
+                                    ..
+                                },
+                            ) => prev_array_of_pointers == next_array_of_pointers,
+                            (
+                                Data::Triangles {
+                                    index_type: prev_index_type,
+                                    max_vertex: prev_max_vertex,
+                                    transform_addr: prev_transform_addr,
+                                    vertex_format: prev_vertex_format,
+                                    ..
+                                },
+                                Data::Triangles {
+                                    index_type: next_index_type,
+                                    max_vertex: next_max_vertex,
+                                    transform_addr: next_transform_addr,
+                                    vertex_format: next_vertex_format,
+                                    ..
+                                },
+                            ) => {
+                                prev_index_type == next_index_type
+                                    && prev_max_vertex == next_max_vertex
+                                    && prev_transform_addr.is_some()
+                                        == next_transform_addr.is_some()
+                                    && prev_vertex_format == next_vertex_format
+                            }
+                            _ => false,
+                        }
+                },
+            )
+    }
+}
+
 /// Information used to create an [`AccelerationStructure`] instance.
 #[derive(Builder, Clone, Copy, Debug, Eq, Hash, PartialEq)]
 #[builder(
@@ -814,4 +918,41 @@ mod tests {
     pub fn accel_struct_info_builder_uninit_size() {
         Builder::default().build();
     }
+
+    fn tlas_info(
+        instance_count: u32,
+        instance_addr: vk::DeviceAddress,
+    ) -> AccelerationStructureGeometryInfo<(
+        AccelerationStructureGeometry,
+        vk::AccelerationStructureBuildRangeInfoKHR,
+    )> {
+        AccelerationStructureGeometryInfo::tlas([(
+            AccelerationStructureGeometry::opaque(
+                instance_count,
+                AccelerationStructureGeometryData::instances(instance_addr),
+            ),
+            vk::AccelerationStructureBuildRangeInfoKHR {
+                primitive_count: instance_count,
+                ..Default::default()
+            },
+        )])
+        .flags(vk::BuildAccelerationStructureFlagsKHR::ALLOW_UPDATE)
+    }
+
+    #[test]
+    pub fn accel_struct_update_compatible() {
+        assert!(tlas_info(4, 0x1000).is_update_compatible(&tlas_info(4, 0x2000)));
+    }
+
+    #[test]
+    pub fn accel_struct_update_incompatible() {
+        // Primitive counts must match
+        assert!(!tlas_info(4, 0x1000).is_update_compatible(&tlas_info(5, 0x1000)));
+
+        // Source must allow updates
+        let prev = tlas_info(4, 0x1000).flags(vk::BuildAccelerationStructureFlagsKHR::empty());
+        let next = prev.clone();
+
+        assert!(!prev.is_update_compatible(&next));
+    }
 }
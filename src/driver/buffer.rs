@@ -1,7 +1,11 @@
 //! Buffer resource types
 
 use {
-    super::{access_type_from_u8, access_type_into_u8, device::Device, DriverError},
+    super::{
+        access_type_from_u8, access_type_into_u8,
+        device::{Device, DeviceFeature},
+        DriverError,
+    },
     ash::vk,
     derive_builder::{Builder, UninitializedFieldError},
     gpu_allocator::{
@@ -14,6 +18,7 @@ use {
         fmt::{Debug, Formatter},
         mem::ManuallyDrop,
         ops::{Deref, Range},
+        slice,
         sync::{
             atomic::{AtomicU8, Ordering},
             Arc,
@@ -95,6 +100,22 @@ impl Buffer {
         trace!("create: {:?}", info);
 
         debug_assert_ne!(info.size, 0, "Size must be non-zero");
+        debug_assert!(
+            info.alignment.is_power_of_two(),
+            "Alignment must be a power of two"
+        );
+
+        if info
+            .usage
+            .contains(vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS)
+            && !Device::supports(device, DeviceFeature::BufferDeviceAddress)
+        {
+            warn!("buffer device address feature not enabled");
+
+            return Err(DriverError::FeatureNotEnabled(
+                DeviceFeature::BufferDeviceAddress,
+            ));
+        }
 
         let device = Arc::clone(device);
         let buffer_info = vk::BufferCreateInfo::default()
@@ -112,6 +133,18 @@ impl Buffer {
         let mut requirements = unsafe { device.get_buffer_memory_requirements(buffer) };
         requirements.alignment = requirements.alignment.max(info.alignment);
 
+        if info.mappable {
+            // Non-coherent memory is flushed and invalidated in whole atoms, so mappable buffers
+            // must not share an atom with any other allocation
+            let non_coherent_atom_size = device
+                .physical_device
+                .properties_v1_0
+                .limits
+                .non_coherent_atom_size;
+            requirements.alignment = requirements.alignment.max(non_coherent_atom_size);
+            requirements.size = requirements.size.next_multiple_of(non_coherent_atom_size);
+        }
+
         let memory_location = if info.mappable {
             MemoryLocation::CpuToGpu
         } else {
@@ -283,8 +316,134 @@ impl Buffer {
             .copy_from_slice(slice);
     }
 
+    /// Copies `slice` into mapped memory at `offset` through a shared reference.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that no references to the mapped memory exist and that the device is
+    /// not accessing the written range.
+    pub(crate) unsafe fn copy_from_slice_unchecked(
+        this: &Self,
+        offset: vk::DeviceSize,
+        slice: &[u8],
+    ) {
+        debug_assert!(
+            this.info.mappable,
+            "Buffer is not mappable - create using mappable flag"
+        );
+        debug_assert!(
+            offset + slice.len() as vk::DeviceSize <= this.info.size,
+            "slice must be within the buffer"
+        );
+
+        let dst = this.allocation.mapped_ptr().unwrap().as_ptr() as *mut u8;
+        std::ptr::copy_nonoverlapping(slice.as_ptr(), dst.add(offset as _), slice.len());
+    }
+
+    /// Makes host writes to a range of mapped memory available to the device.
+    ///
+    /// This is required after writing to [`Self::mapped_slice_mut`] when the buffer is not
+    /// [coherent](Self::is_coherent), and does nothing otherwise. Pass [`vk::WHOLE_SIZE`] as `size`
+    /// to flush the remainder of the buffer.
+    #[profiling::function]
+    pub fn flush_range(
+        this: &Self,
+        offset: vk::DeviceSize,
+        size: vk::DeviceSize,
+    ) -> Result<(), DriverError> {
+        if Self::is_coherent(this) {
+            return Ok(());
+        }
+
+        unsafe {
+            this.device
+                .flush_mapped_memory_ranges(slice::from_ref(&Self::mapped_memory_range(
+                    this, offset, size,
+                )))
+        }
+        .map_err(|err| {
+            warn!("{err}");
+
+            DriverError::OutOfMemory
+        })
+    }
+
+    /// Makes device writes to a range of mapped memory visible to the host.
+    ///
+    /// This is required before reading from [`Self::mapped_slice`] when the buffer is not
+    /// [coherent](Self::is_coherent), and does nothing otherwise. Pass [`vk::WHOLE_SIZE`] as `size`
+    /// to invalidate the remainder of the buffer.
+    #[profiling::function]
+    pub fn invalidate_range(
+        this: &Self,
+        offset: vk::DeviceSize,
+        size: vk::DeviceSize,
+    ) -> Result<(), DriverError> {
+        if Self::is_coherent(this) {
+            return Ok(());
+        }
+
+        unsafe {
+            this.device.invalidate_mapped_memory_ranges(slice::from_ref(
+                &Self::mapped_memory_range(this, offset, size),
+            ))
+        }
+        .map_err(|err| {
+            warn!("{err}");
+
+            DriverError::OutOfMemory
+        })
+    }
+
+    /// Returns `true` if host writes to mapped memory are visible to the device, and device writes
+    /// visible to the host, without calling [`Self::flush_range`] or [`Self::invalidate_range`].
+    ///
+    /// Buffers which are not mappable are always coherent.
+    pub fn is_coherent(this: &Self) -> bool {
+        !this.info.mappable
+            || this
+                .allocation
+                .memory_properties()
+                .contains(vk::MemoryPropertyFlags::HOST_COHERENT)
+    }
+
+    /// Returns the range of device memory of this buffer, expanded to whole non-coherent atoms.
+    fn mapped_memory_range(
+        this: &Self,
+        offset: vk::DeviceSize,
+        size: vk::DeviceSize,
+    ) -> vk::MappedMemoryRange<'static> {
+        debug_assert!(offset <= this.info.size, "offset must be within the buffer");
+
+        let non_coherent_atom_size = this
+            .device
+            .physical_device
+            .properties_v1_0
+            .limits
+            .non_coherent_atom_size;
+        let allocation_end = this.allocation.offset() + this.allocation.size();
+        let start = this.allocation.offset() + offset;
+        let end = start.saturating_add(size.min(this.info.size - offset));
+        let start = start - start % non_coherent_atom_size;
+        let end = end
+            .next_multiple_of(non_coherent_atom_size)
+            .min(allocation_end);
+
+        vk::MappedMemoryRange::default()
+            .memory(this.allocation.memory())
+            .offset(start)
+            .size(end - start)
+    }
+
+    /// Returns the size, in bytes, of the device memory allocated for this object.
+    pub(crate) fn memory_size(this: &Self) -> vk::DeviceSize {
+        this.allocation.size()
+    }
+
     /// Returns the device address of this object.
     ///
+    /// See [`Buffer::try_device_address`] for a non-panicking version of this function.
+    ///
     /// # Panics
     ///
     /// Panics if the buffer was not created with the `SHADER_DEVICE_ADDRESS` usage flag.
@@ -322,6 +481,75 @@ impl Buffer {
         }
     }
 
+    /// Returns the device address of this object, which may be used as a `buffer_reference`
+    /// pointer within shader code.
+    ///
+    /// Returns [`DriverError::InvalidData`] if the buffer was not created with the
+    /// `SHADER_DEVICE_ADDRESS` usage flag, or [`DriverError::FeatureNotEnabled`] if the
+    /// [`buffer_device_address`](super::physical_device::Vulkan12Features::buffer_device_address)
+    /// feature is not enabled.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::buffer::{Buffer, BufferInfo};
+    /// # use screen_13::driver::compute::{ComputePipeline, ComputePipelineInfo};
+    /// # use screen_13::driver::shader::Shader;
+    /// # use screen_13::graph::RenderGraph;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let mut my_graph = RenderGraph::new();
+    /// # let info = ComputePipelineInfo::default();
+    /// # let shader = Shader::new_compute([0u8; 1].as_slice());
+    /// # let my_compute_pipeline = Arc::new(ComputePipeline::create(&device, info, shader)?);
+    /// let usage = vk::BufferUsageFlags::STORAGE_BUFFER | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS;
+    /// let info = BufferInfo::device_mem(1024, usage);
+    /// let my_buf = Buffer::create(&device, info)?;
+    /// let addr = Buffer::try_device_address(&my_buf)?;
+    ///
+    /// // The shader declares a push constant of type `layout(buffer_reference) buffer Foo`
+    /// my_graph
+    ///     .begin_pass("my pass")
+    ///     .bind_pipeline(&my_compute_pipeline)
+    ///     .record_compute(move |compute, _| {
+    ///         compute.push_constants(&addr.to_ne_bytes()).dispatch(1, 1, 1);
+    ///     });
+    /// # Ok(()) }
+    /// ```
+    #[profiling::function]
+    pub fn try_device_address(this: &Self) -> Result<vk::DeviceAddress, DriverError> {
+        if !this
+            .info
+            .usage
+            .contains(vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS)
+        {
+            warn!("buffer was not created with SHADER_DEVICE_ADDRESS usage");
+
+            return Err(DriverError::InvalidData);
+        }
+
+        if !this
+            .device
+            .physical_device
+            .features_v1_2
+            .buffer_device_address
+        {
+            warn!("buffer device address feature not enabled");
+
+            return Err(DriverError::FeatureNotEnabled(
+                DeviceFeature::BufferDeviceAddress,
+            ));
+        }
+
+        Ok(Self::device_address(this))
+    }
+
     /// Returns a mapped slice.
     ///
     /// # Panics
@@ -623,11 +851,30 @@ impl From<BufferSubresource> for Range<vk::DeviceSize> {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use {super::*, crate::driver::device::test_device};
 
     type Info = BufferInfo;
     type Builder = BufferInfoBuilder;
 
+    #[test]
+    pub fn buffer_alignment() {
+        let Some(device) = test_device() else {
+            return;
+        };
+
+        for alignment in [256, 4096, 65536] {
+            let buf = Buffer::create(
+                &device,
+                Info::device_mem(42, vk::BufferUsageFlags::STORAGE_BUFFER)
+                    .to_builder()
+                    .alignment(alignment),
+            )
+            .unwrap();
+
+            assert_eq!(buf.allocation.offset() % alignment, 0);
+        }
+    }
+
     #[test]
     pub fn buffer_info() {
         let info = Info::device_mem(0, vk::BufferUsageFlags::empty());
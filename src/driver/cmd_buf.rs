@@ -17,6 +17,9 @@ pub struct CommandBuffer {
     pub info: CommandBufferInfo,
 
     pub(crate) pool: vk::CommandPool,
+
+    /// The queue timeline value signalled by the previous submission.
+    pub(crate) submit_token: SubmitToken,
 }
 
 impl CommandBuffer {
@@ -63,6 +66,7 @@ impl CommandBuffer {
             fence,
             info,
             pool,
+            submit_token: Default::default(),
         })
     }
 
@@ -78,7 +82,10 @@ impl CommandBuffer {
 
     /// Returns `true` after the GPU has executed the previous submission to this command buffer.
     ///
-    /// See [`Self::wait_until_executed`] to block while checking.
+    /// This polls the fence status (`vkGetFenceStatus`) and never blocks, so it may be used to
+    /// reclaim resources opportunistically, for example once per frame. See
+    /// [`Self::wait_until_executed`] to block while checking.
+    #[doc(alias = "is_executed")]
     #[profiling::function]
     pub fn has_executed(&self) -> Result<bool, DriverError> {
         let res = unsafe { self.device.get_fence_status(self.fence) };
@@ -105,6 +112,19 @@ impl CommandBuffer {
         this.droppables.push(Box::new(thing_to_drop));
     }
 
+    /// Returns a token which represents the completion of the previous submission to this command
+    /// buffer.
+    ///
+    /// See [`Resolver::wait_on`](crate::graph::Resolver::wait_on) to make another submission wait
+    /// on the device for this one, without blocking the current thread.
+    ///
+    /// _NOTE:_ The token refers to the timeline semaphore of the queue which this command buffer
+    /// was submitted to, so it remains valid for as long as the device. A command buffer which has
+    /// not been submitted returns a token which is always complete.
+    pub fn submit_token(&self) -> SubmitToken {
+        self.submit_token
+    }
+
     /// Stalls by blocking the current thread until the GPU has executed the previous submission to
     /// this command buffer.
     ///
@@ -145,6 +165,17 @@ impl Drop for CommandBuffer {
     }
 }
 
+/// A lightweight handle to a point on the timeline of submitted device work.
+///
+/// Tokens are returned by [`Resolver::submit`](crate::graph::Resolver::submit) and
+/// [`CommandBuffer::submit_token`], and may be passed to
+/// [`Resolver::wait_on`](crate::graph::Resolver::wait_on).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SubmitToken {
+    pub(crate) semaphore: vk::Semaphore,
+    pub(crate) value: u64,
+}
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub struct CommandBufferInfo {
     pub queue_family_index: u32,
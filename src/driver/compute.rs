@@ -3,7 +3,10 @@
 use {
     super::{
         device::Device,
-        shader::{align_spriv, DescriptorBindingMap, PipelineDescriptorInfo, Shader},
+        shader::{
+            align_spriv, Descriptor, DescriptorBindingInfo, DescriptorBindingMap,
+            PipelineDescriptorInfo, Shader,
+        },
         DriverError,
     },
     ash::vk,
@@ -27,12 +30,14 @@ use {
 pub struct ComputePipeline {
     pub(crate) descriptor_bindings: DescriptorBindingMap,
     pub(crate) descriptor_info: PipelineDescriptorInfo,
-    device: Arc<Device>,
+    pub(crate) device: Arc<Device>,
     pub(crate) layout: vk::PipelineLayout,
 
     /// Information used to create this object.
     pub info: ComputePipelineInfo,
 
+    local_size: [u32; 3],
+
     /// A descriptive name used in debugging messages.
     pub name: Option<String>,
 
@@ -81,16 +86,16 @@ impl ComputePipeline {
         let device = Arc::clone(device);
         let info: ComputePipelineInfo = info.into();
         let shader = shader.into();
+        let local_size = shader.local_size();
 
         // Use SPIR-V reflection to get the types and counts of all descriptors
         let mut descriptor_bindings = shader.descriptor_bindings();
-        for (descriptor_info, _) in descriptor_bindings.values_mut() {
-            if descriptor_info.binding_count() == 0 {
-                descriptor_info.set_binding_count(info.bindless_descriptor_count);
-            }
-        }
-
-        let descriptor_info = PipelineDescriptorInfo::create(&device, &descriptor_bindings)?;
+        let descriptor_info = PipelineDescriptorInfo::create(
+            &device,
+            &mut descriptor_bindings,
+            info.bindless_descriptor_count,
+            info.push_descriptor_set,
+        )?;
         let descriptor_set_layouts = descriptor_info
             .layouts
             .values()
@@ -161,6 +166,7 @@ impl ComputePipeline {
                 device,
                 info,
                 layout,
+                local_size,
                 name: None,
                 pipeline,
                 push_constants,
@@ -168,6 +174,47 @@ impl ComputePipeline {
         }
     }
 
+    /// Returns the reflected information of a descriptor binding, if it is used by this pipeline.
+    pub fn descriptor_binding(
+        this: &Self,
+        descriptor: impl Into<Descriptor>,
+    ) -> Option<DescriptorBindingInfo> {
+        this.descriptor_bindings
+            .get(&descriptor.into())
+            .map(|&(descriptor_info, stage_flags)| {
+                DescriptorBindingInfo::new(descriptor_info, stage_flags)
+            })
+    }
+
+    /// Returns the reflected information of all descriptor bindings used by this pipeline, in no
+    /// particular order.
+    pub fn descriptor_bindings(
+        this: &Self,
+    ) -> impl Iterator<Item = (Descriptor, DescriptorBindingInfo)> + '_ {
+        this.descriptor_bindings
+            .iter()
+            .map(|(&descriptor, &(descriptor_info, stage_flags))| {
+                (
+                    descriptor,
+                    DescriptorBindingInfo::new(descriptor_info, stage_flags),
+                )
+            })
+    }
+
+    /// Returns the reflected local workgroup size of the compute shader.
+    ///
+    /// Workgroup sizes which use specialization constants (_such as `local_size_x_id` in GLSL_) are
+    /// reflected when the shader declares them using the `LocalSizeId` execution mode, which is
+    /// emitted for SPIR-V 1.6 and later.
+    pub fn local_size(this: &Self) -> [u32; 3] {
+        this.local_size
+    }
+
+    /// Returns the reflected push constant range, if the compute shader uses push constants.
+    pub fn push_constant_range(this: &Self) -> Option<vk::PushConstantRange> {
+        this.push_constants
+    }
+
     /// Sets the debugging name assigned to this pipeline.
     pub fn with_name(mut this: Self, name: impl Into<String>) -> Self {
         this.name = Some(name.into());
@@ -213,6 +260,11 @@ pub struct ComputePipelineInfo {
     /// The number of descriptors to allocate for a given binding when using bindless (unbounded)
     /// syntax.
     ///
+    /// Individual elements are accessed by a render pass using array descriptors such as
+    /// `(0, 0, [42])`; elements which are not accessed remain unbound. When supported by the
+    /// device, bindless bindings are created as update-after-bind and are subject to the larger
+    /// `maxDescriptorSetUpdateAfterBind*` limits.
+    ///
     /// The default is `8192`.
     ///
     /// # Examples
@@ -234,6 +286,21 @@ pub struct ComputePipelineInfo {
     /// ```
     #[builder(default = "8192")]
     pub bindless_descriptor_count: u32,
+
+    /// The descriptor set which is pushed by render passes using `push_buffer_descriptor` and
+    /// `push_image_descriptor` instead of being allocated from a descriptor pool.
+    ///
+    /// Uses the `VK_KHR_push_descriptor` extension when available; see
+    /// [`PhysicalDevice::push_descriptor_properties`](super::physical_device::PhysicalDevice::push_descriptor_properties).
+    /// On other devices each pushed descriptor allocates, writes and binds a new regular
+    /// descriptor set, which is slower but otherwise behaves the same.
+    ///
+    /// The push descriptor set must be the last descriptor set of the pipeline and must not contain
+    /// dynamic or bindless bindings.
+    ///
+    /// The default value is `None`.
+    #[builder(default, setter(strip_option))]
+    pub push_descriptor_set: Option<u32>,
 }
 
 impl ComputePipelineInfo {
@@ -242,6 +309,7 @@ impl ComputePipelineInfo {
     pub fn to_builder(self) -> ComputePipelineInfoBuilder {
         ComputePipelineInfoBuilder {
             bindless_descriptor_count: Some(self.bindless_descriptor_count),
+            push_descriptor_set: Some(self.push_descriptor_set),
         }
     }
 }
@@ -250,6 +318,7 @@ impl Default for ComputePipelineInfo {
     fn default() -> Self {
         Self {
             bindless_descriptor_count: 8192,
+            push_descriptor_set: None,
         }
     }
 }
@@ -123,10 +123,16 @@ impl DescriptorPool {
             pool_size_count += 1;
         }
 
+        let mut flags = vk::DescriptorPoolCreateFlags::FREE_DESCRIPTOR_SET;
+
+        if info.update_after_bind {
+            flags |= vk::DescriptorPoolCreateFlags::UPDATE_AFTER_BIND;
+        }
+
         let descriptor_pool = unsafe {
             device.create_descriptor_pool(
                 &vk::DescriptorPoolCreateInfo::default()
-                    .flags(vk::DescriptorPoolCreateFlags::FREE_DESCRIPTOR_SET)
+                    .flags(flags)
                     .max_sets(info.max_sets)
                     .pool_sizes(&pool_sizes[0..pool_size_count]),
                 None,
@@ -230,9 +236,57 @@ pub struct DescriptorPoolInfo {
     pub uniform_buffer_count: u32,
     pub uniform_buffer_dynamic_count: u32,
     pub uniform_texel_buffer_count: u32,
+    pub update_after_bind: bool,
 }
 
 impl DescriptorPoolInfo {
+    /// Adds `descriptor_count` descriptors of the given type to the size of the pool.
+    pub(crate) fn add_descriptor_count(
+        &mut self,
+        descriptor_ty: vk::DescriptorType,
+        descriptor_count: u32,
+    ) {
+        match descriptor_ty {
+            vk::DescriptorType::ACCELERATION_STRUCTURE_KHR => {
+                self.acceleration_structure_count += descriptor_count;
+            }
+            vk::DescriptorType::COMBINED_IMAGE_SAMPLER => {
+                self.combined_image_sampler_count += descriptor_count;
+            }
+            vk::DescriptorType::INPUT_ATTACHMENT => {
+                self.input_attachment_count += descriptor_count;
+            }
+            vk::DescriptorType::SAMPLED_IMAGE => {
+                self.sampled_image_count += descriptor_count;
+            }
+            vk::DescriptorType::SAMPLER => {
+                self.sampler_count += descriptor_count;
+            }
+            vk::DescriptorType::STORAGE_BUFFER => {
+                self.storage_buffer_count += descriptor_count;
+            }
+            vk::DescriptorType::STORAGE_BUFFER_DYNAMIC => {
+                self.storage_buffer_dynamic_count += descriptor_count;
+            }
+            vk::DescriptorType::STORAGE_IMAGE => {
+                self.storage_image_count += descriptor_count;
+            }
+            vk::DescriptorType::STORAGE_TEXEL_BUFFER => {
+                self.storage_texel_buffer_count += descriptor_count;
+            }
+            vk::DescriptorType::UNIFORM_BUFFER => {
+                self.uniform_buffer_count += descriptor_count;
+            }
+            vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC => {
+                self.uniform_buffer_dynamic_count += descriptor_count;
+            }
+            vk::DescriptorType::UNIFORM_TEXEL_BUFFER => {
+                self.uniform_texel_buffer_count += descriptor_count;
+            }
+            _ => unimplemented!("{descriptor_ty:?}"),
+        };
+    }
+
     pub fn is_empty(&self) -> bool {
         self.acceleration_structure_count
             + self.combined_image_sampler_count
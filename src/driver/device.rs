@@ -1,23 +1,33 @@
 //! Logical device resource types
 
 use {
-    super::{physical_device::PhysicalDevice, DriverError, Instance},
+    super::{
+        cmd_buf::SubmitToken,
+        image::{Image, SparseImageBinding},
+        physical_device::PhysicalDevice,
+        shader::SamplerInfo,
+        DriverError, Instance,
+    },
     ash::{ext, khr, vk},
     ash_window::enumerate_required_extensions,
     derive_builder::{Builder, UninitializedFieldError},
     gpu_allocator::{
-        vulkan::{Allocator, AllocatorCreateDesc},
-        AllocatorDebugSettings,
+        vulkan::{
+            Allocation, AllocationCreateDesc, AllocationScheme, Allocator, AllocatorCreateDesc,
+        },
+        AllocatorDebugSettings, MemoryLocation,
     },
     log::{error, trace, warn},
     raw_window_handle::HasDisplayHandle,
     std::{
         cmp::Ordering,
+        collections::HashSet,
         ffi::CStr,
         fmt::{Debug, Formatter},
         iter::{empty, repeat},
-        mem::{forget, ManuallyDrop},
+        mem::{forget, transmute, ManuallyDrop},
         ops::Deref,
+        slice::from_ref,
         thread::panicking,
         time::Instant,
     },
@@ -32,14 +42,84 @@ use std::sync::Mutex;
 /// Function type for selection of physical devices.
 pub type SelectPhysicalDeviceFn = dyn FnOnce(&[PhysicalDevice]) -> usize;
 
+/// Optional device capabilities which are enabled during device creation whenever the physical
+/// device supports them.
+///
+/// See [`Device::supports`] and [`DeviceInfo::required_features`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum DeviceFeature {
+    /// `VK_KHR_acceleration_structure`, required to create acceleration structures.
+    AccelerationStructure,
+
+    /// The `bufferDeviceAddress` feature, required by buffers created with
+    /// `vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS`.
+    BufferDeviceAddress,
+
+    /// `VK_EXT_conditional_rendering`.
+    ConditionalRendering,
+
+    /// The `hostQueryReset` feature, required by
+    /// [`QueryPool::reset`](super::query::QueryPool::reset).
+    HostQueryReset,
+
+    /// `VK_EXT_index_type_uint8`.
+    IndexTypeUint8,
+
+    /// `VK_EXT_memory_budget`, used by [`Device::memory_budget`].
+    MemoryBudget,
+
+    /// The `occlusionQueryPrecise` feature, required to begin precise occlusion queries.
+    OcclusionQueryPrecise,
+
+    /// The `pipelineStatisticsQuery` feature, required to create
+    /// [`vk::QueryType::PIPELINE_STATISTICS`] query pools.
+    PipelineStatisticsQuery,
+
+    /// `VK_KHR_push_descriptor`.
+    PushDescriptor,
+
+    /// `VK_KHR_ray_query`, required to use `rayQueryEXT` in shaders.
+    RayQuery,
+
+    /// `VK_KHR_ray_tracing_pipeline`, required to create ray trace pipelines.
+    RayTracingPipeline,
+
+    /// `VK_EXT_vertex_attribute_divisor`, required to use
+    /// [`ShaderBuilder::vertex_binding_divisor`](super::shader::ShaderBuilder::vertex_binding_divisor).
+    VertexAttributeDivisor,
+}
+
+const DEVICE_FEATURES: [DeviceFeature; 12] = [
+    DeviceFeature::AccelerationStructure,
+    DeviceFeature::BufferDeviceAddress,
+    DeviceFeature::ConditionalRendering,
+    DeviceFeature::HostQueryReset,
+    DeviceFeature::IndexTypeUint8,
+    DeviceFeature::MemoryBudget,
+    DeviceFeature::OcclusionQueryPrecise,
+    DeviceFeature::PipelineStatisticsQuery,
+    DeviceFeature::PushDescriptor,
+    DeviceFeature::RayQuery,
+    DeviceFeature::RayTracingPipeline,
+    DeviceFeature::VertexAttributeDivisor,
+];
+
 /// Opaque handle to a device object.
 pub struct Device {
     accel_struct_ext: Option<khr::acceleration_structure::Device>,
 
     pub(super) allocator: ManuallyDrop<Mutex<Allocator>>,
 
+    pub(crate) conditional_rendering_ext: Option<ext::conditional_rendering::DeviceFn>,
+
+    default_anisotropy: Option<f32>,
+    default_mip_lod_bias: Option<f32>,
+
     device: ash::Device,
 
+    enabled_features: HashSet<DeviceFeature>,
+
     /// Vulkan instance pointer, which includes useful functions.
     instance: Instance,
 
@@ -48,8 +128,10 @@ pub struct Device {
     /// The physical device, which contains useful data about features, properties, and limits.
     pub physical_device: PhysicalDevice,
 
+    pub(crate) push_descriptor_ext: Option<khr::push_descriptor::Device>,
+
     /// The physical execution queues which all work will be submitted to.
-    pub(crate) queues: Vec<Vec<vk::Queue>>,
+    pub(crate) queues: Vec<Vec<Mutex<Queue>>>,
 
     pub(crate) ray_trace_ext: Option<khr::ray_tracing_pipeline::Device>,
 
@@ -75,7 +157,7 @@ impl Device {
     where
         F: FnOnce(vk::DeviceCreateInfo) -> ash::prelude::VkResult<ash::Device>,
     {
-        let mut enabled_ext_names = Vec::with_capacity(6);
+        let mut enabled_ext_names = Vec::with_capacity(8);
 
         if display_window {
             enabled_ext_names.push(khr::swapchain::NAME.as_ptr());
@@ -94,10 +176,32 @@ impl Device {
             enabled_ext_names.push(khr::ray_tracing_pipeline::NAME.as_ptr());
         }
 
+        if physical_device
+            .conditional_rendering_features
+            .conditional_rendering
+        {
+            enabled_ext_names.push(ext::conditional_rendering::NAME.as_ptr());
+        }
+
         if physical_device.index_type_uint8_features.index_type_uint8 {
             enabled_ext_names.push(ext::index_type_uint8::NAME.as_ptr());
         }
 
+        if physical_device.supports_memory_budget {
+            enabled_ext_names.push(ext::memory_budget::NAME.as_ptr());
+        }
+
+        if physical_device
+            .vertex_attribute_divisor_features
+            .vertex_attribute_instance_rate_divisor
+        {
+            enabled_ext_names.push(ext::vertex_attribute_divisor::NAME.as_ptr());
+        }
+
+        if physical_device.push_descriptor_properties.is_some() {
+            enabled_ext_names.push(khr::push_descriptor::NAME.as_ptr());
+        }
+
         let priorities = repeat(1.0)
             .take(
                 physical_device
@@ -131,9 +235,13 @@ impl Device {
         let mut features_v1_2 = vk::PhysicalDeviceVulkan12Features::default();
         let mut acceleration_structure_features =
             vk::PhysicalDeviceAccelerationStructureFeaturesKHR::default();
+        let mut conditional_rendering_features =
+            vk::PhysicalDeviceConditionalRenderingFeaturesEXT::default();
         let mut index_type_uint8_features = vk::PhysicalDeviceIndexTypeUint8FeaturesEXT::default();
         let mut ray_query_features = vk::PhysicalDeviceRayQueryFeaturesKHR::default();
         let mut ray_trace_features = vk::PhysicalDeviceRayTracingPipelineFeaturesKHR::default();
+        let mut vertex_attribute_divisor_features =
+            vk::PhysicalDeviceVertexAttributeDivisorFeaturesEXT::default();
         let mut features = vk::PhysicalDeviceFeatures2::default()
             .push_next(&mut features_v1_1)
             .push_next(&mut features_v1_2);
@@ -150,10 +258,24 @@ impl Device {
             features = features.push_next(&mut ray_trace_features);
         }
 
+        if physical_device
+            .conditional_rendering_features
+            .conditional_rendering
+        {
+            features = features.push_next(&mut conditional_rendering_features);
+        }
+
         if physical_device.index_type_uint8_features.index_type_uint8 {
             features = features.push_next(&mut index_type_uint8_features);
         }
 
+        if physical_device
+            .vertex_attribute_divisor_features
+            .vertex_attribute_instance_rate_divisor
+        {
+            features = features.push_next(&mut vertex_attribute_divisor_features);
+        }
+
         unsafe { get_physical_device_features2(**physical_device, &mut features) };
 
         let device_create_info = vk::DeviceCreateInfo::default()
@@ -167,9 +289,16 @@ impl Device {
     #[profiling::function]
     fn create(
         instance: Instance,
-        select_physical_device: Box<SelectPhysicalDeviceFn>,
+        info: DeviceInfo,
         display_window: bool,
     ) -> Result<Self, DriverError> {
+        let DeviceInfo {
+            default_anisotropy,
+            default_mip_lod_bias,
+            required_features,
+            select_physical_device,
+            ..
+        } = info;
         let mut physical_devices = Instance::physical_devices(&instance)?;
 
         if physical_devices.is_empty() {
@@ -188,6 +317,42 @@ impl Device {
 
         let physical_device = physical_devices.remove(phyical_device_idx);
 
+        for feature in required_features {
+            if !Self::physical_device_supports(&physical_device, feature) {
+                error!("required feature {feature:?} not supported");
+
+                return Err(DriverError::FeatureNotEnabled(feature));
+            }
+        }
+
+        let default_anisotropy = default_anisotropy.and_then(|max_anisotropy| {
+            if !physical_device.features_v1_0.sampler_anisotropy {
+                warn!("sampler anisotropy not supported");
+
+                return None;
+            }
+
+            let max_sampler_anisotropy = physical_device
+                .properties_v1_0
+                .limits
+                .max_sampler_anisotropy;
+
+            if max_anisotropy > max_sampler_anisotropy {
+                warn!("default anisotropy clamped to {max_sampler_anisotropy}");
+            }
+
+            Some(max_anisotropy.clamp(1.0, max_sampler_anisotropy))
+        });
+        let default_mip_lod_bias = default_mip_lod_bias.map(|mip_lod_bias| {
+            let max_sampler_lod_bias = physical_device.properties_v1_0.limits.max_sampler_lod_bias;
+
+            if mip_lod_bias.abs() > max_sampler_lod_bias {
+                warn!("default mip LOD bias clamped to {max_sampler_lod_bias}");
+            }
+
+            mip_lod_bias.clamp(-max_sampler_lod_bias, max_sampler_lod_bias)
+        });
+
         let device = unsafe {
             Self::create_ash_device(
                 &instance,
@@ -204,19 +369,290 @@ impl Device {
             DriverError::Unsupported
         })?;
 
-        Self::load(instance, physical_device, device, display_window)
+        let enabled_features = Self::supported_features(&physical_device);
+        let mut device = Self::load(
+            instance,
+            physical_device,
+            device,
+            display_window,
+            enabled_features,
+        )?;
+        device.default_anisotropy = default_anisotropy;
+        device.default_mip_lod_bias = default_mip_lod_bias;
+
+        Ok(device)
+    }
+
+    /// Binds or unbinds memory for pages of a sparse image.
+    ///
+    /// Each bound page is backed by a separate allocation which is freed when the page is unbound or
+    /// the image is dropped. Binding a resident page or unbinding a non-resident page has no
+    /// effect. Pages within the mip tail of the image are not supported.
+    ///
+    /// This function waits for the sparse binding operation to complete. Pages must not be unbound
+    /// while they are in use by a pending command buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DriverError::InvalidData`] if `image` was not created using
+    /// [`vk::ImageCreateFlags::SPARSE_BINDING`] or a page is outside of the image.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::image::{Image, ImageInfo, SparseImageBinding, SparseImagePage};
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::default())?);
+    /// let info = ImageInfo::image_2d(16_384, 16_384, vk::Format::R8G8B8A8_UNORM, vk::ImageUsageFlags::SAMPLED)
+    ///     .sparse_residency();
+    /// let image = Image::create(&device, info)?;
+    ///
+    /// Device::bind_sparse(&device, &image, &[SparseImageBinding::Bind(SparseImagePage {
+    ///     array_layer: 0,
+    ///     aspect_mask: vk::ImageAspectFlags::COLOR,
+    ///     mip_level: 0,
+    ///     x: 0,
+    ///     y: 0,
+    ///     z: 0,
+    /// })])?;
+    /// # Ok(()) }
+    /// ```
+    #[profiling::function]
+    pub fn bind_sparse(
+        this: &Self,
+        image: &Image,
+        bindings: &[SparseImageBinding],
+    ) -> Result<(), DriverError> {
+        let Some(sparse_pages) = image.sparse_pages.as_ref() else {
+            warn!("image not created with sparse binding");
+
+            return Err(DriverError::InvalidData);
+        };
+        let queue_family_index = this
+            .physical_device
+            .queue_families
+            .iter()
+            .position(|properties| {
+                properties.queue_count > 0
+                    && properties
+                        .queue_flags
+                        .contains(vk::QueueFlags::SPARSE_BINDING)
+            })
+            .ok_or_else(|| {
+                warn!("sparse binding queue not supported");
+
+                DriverError::Unsupported
+            })?;
+        let requirements = Image::sparse_requirements(image).unwrap();
+
+        // Validate all pages before allocating any memory
+        let mut binds = Vec::with_capacity(bindings.len());
+        for binding in bindings.iter().copied() {
+            let (page, is_resident) = match binding {
+                SparseImageBinding::Bind(page) => (page, true),
+                SparseImageBinding::Unbind(page) => (page, false),
+            };
+            let aspect = requirements
+                .aspects
+                .iter()
+                .find(|aspect| {
+                    aspect
+                        .format_properties
+                        .aspect_mask
+                        .contains(page.aspect_mask)
+                })
+                .ok_or_else(|| {
+                    warn!("unsupported sparse image aspect {:?}", page.aspect_mask);
+
+                    DriverError::InvalidData
+                })?;
+
+            if page.mip_level >= aspect.image_mip_tail_first_lod {
+                warn!("unsupported sparse image mip tail level {}", page.mip_level);
+
+                return Err(DriverError::InvalidData);
+            }
+
+            let granularity = aspect.format_properties.image_granularity;
+            let mip_extent = |extent: u32| (extent >> page.mip_level).max(1);
+            let (width, height, depth) = (
+                mip_extent(image.info.width),
+                mip_extent(image.info.height),
+                mip_extent(image.info.depth),
+            );
+            let (x, y, z) = (
+                page.x * granularity.width,
+                page.y * granularity.height,
+                page.z * granularity.depth,
+            );
+
+            if x >= width || y >= height || z >= depth {
+                warn!("sparse image page out of bounds: {page:?}");
+
+                return Err(DriverError::InvalidData);
+            }
+
+            binds.push((
+                page,
+                is_resident,
+                vk::SparseImageMemoryBind {
+                    subresource: vk::ImageSubresource {
+                        aspect_mask: page.aspect_mask,
+                        mip_level: page.mip_level,
+                        array_layer: page.array_layer,
+                    },
+                    offset: vk::Offset3D {
+                        x: x as _,
+                        y: y as _,
+                        z: z as _,
+                    },
+                    extent: vk::Extent3D {
+                        width: granularity.width.min(width - x),
+                        height: granularity.height.min(height - y),
+                        depth: granularity.depth.min(depth - z),
+                    },
+                    ..Default::default()
+                },
+            ));
+        }
+
+        #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+        let mut sparse_pages = sparse_pages.lock();
+
+        #[cfg(not(feature = "parking_lot"))]
+        let mut sparse_pages = sparse_pages.unwrap();
+
+        #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+        let mut allocator = this.allocator.lock();
+
+        #[cfg(not(feature = "parking_lot"))]
+        let mut allocator = allocator.unwrap();
+
+        let mut bound_pages = Vec::<(_, Allocation)>::new();
+        let mut unbound_pages = vec![];
+        let mut memory_binds = Vec::with_capacity(binds.len());
+        for (page, is_resident, mut bind) in binds {
+            if is_resident {
+                if sparse_pages.contains_key(&page)
+                    || bound_pages
+                        .iter()
+                        .any(|(bound_page, _)| *bound_page == page)
+                {
+                    continue;
+                }
+
+                let allocation = match allocator.allocate(&AllocationCreateDesc {
+                    name: "sparse image page",
+                    requirements: vk::MemoryRequirements {
+                        size: requirements.memory_requirements.alignment,
+                        ..requirements.memory_requirements
+                    },
+                    location: MemoryLocation::GpuOnly,
+                    linear: false,
+                    allocation_scheme: AllocationScheme::GpuAllocatorManaged,
+                }) {
+                    Ok(allocation) => allocation,
+                    Err(err) => {
+                        warn!("{err}");
+
+                        for (_, allocation) in bound_pages {
+                            allocator.free(allocation).unwrap_or_else(|_| {
+                                warn!("Unable to free sparse image allocation")
+                            });
+                        }
+
+                        return Err(DriverError::OutOfMemory);
+                    }
+                };
+
+                bind.memory = unsafe { allocation.memory() };
+                bind.memory_offset = allocation.offset();
+                bound_pages.push((page, allocation));
+            } else if sparse_pages.contains_key(&page) && !unbound_pages.contains(&page) {
+                unbound_pages.push(page);
+            } else {
+                continue;
+            }
+
+            memory_binds.push(bind);
+        }
+
+        // Other resources may be allocated while we wait for the binding operation
+        drop(allocator);
+
+        if memory_binds.is_empty() {
+            return Ok(());
+        }
+
+        let image_bind = vk::SparseImageMemoryBindInfo::default()
+            .image(**image)
+            .binds(&memory_binds);
+        let bind_info = vk::BindSparseInfo::default().image_binds(from_ref(&image_bind));
+        let fence = Device::create_fence(this, false)?;
+        let res = {
+            let queue = this.queues[queue_family_index][0].lock();
+
+            #[cfg(not(feature = "parking_lot"))]
+            let queue = queue.unwrap();
+
+            unsafe { this.queue_bind_sparse(queue.queue, from_ref(&bind_info), fence) }
+        }
+        .map_err(|err| {
+            warn!("{err}");
+
+            DriverError::OutOfMemory
+        })
+        .and_then(|_| Device::wait_for_fence(this, &fence));
+
+        unsafe {
+            this.destroy_fence(fence, None);
+        }
+
+        // Memory which failed to bind is unused; memory which was unbound is no longer used
+        let unused_allocations = if res.is_ok() {
+            sparse_pages.extend(bound_pages);
+
+            unbound_pages
+                .iter()
+                .filter_map(|page| sparse_pages.remove(page))
+                .collect()
+        } else {
+            bound_pages
+                .into_iter()
+                .map(|(_, allocation)| allocation)
+                .collect::<Vec<_>>()
+        };
+
+        if !unused_allocations.is_empty() {
+            #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+            let mut allocator = this.allocator.lock();
+
+            #[cfg(not(feature = "parking_lot"))]
+            let mut allocator = allocator.unwrap();
+
+            for allocation in unused_allocations {
+                allocator
+                    .free(allocation)
+                    .unwrap_or_else(|_| warn!("Unable to free sparse image allocation"));
+            }
+        }
+
+        res
     }
 
     /// Constructs a new device using the given configuration.
     #[profiling::function]
     pub fn create_headless(info: impl Into<DeviceInfo>) -> Result<Self, DriverError> {
-        let DeviceInfo {
-            debug,
-            select_physical_device,
-        } = info.into();
-        let instance = Instance::create(debug, empty())?;
+        let info = info.into();
+        let instance = Instance::create(info.debug, empty())?;
 
-        Self::create(instance, select_physical_device, false)
+        Self::create(instance, info, false)
     }
 
     /// Constructs a new device using the given configuration.
@@ -225,10 +661,7 @@ impl Device {
         info: impl Into<DeviceInfo>,
         display_handle: &impl HasDisplayHandle,
     ) -> Result<Self, DriverError> {
-        let DeviceInfo {
-            debug,
-            select_physical_device,
-        } = info.into();
+        let info = info.into();
         let display_handle = display_handle.display_handle().map_err(|err| {
             warn!("{err}");
 
@@ -242,9 +675,9 @@ impl Device {
             })?
             .iter()
             .map(|ext| unsafe { CStr::from_ptr(*ext as *const _) });
-        let instance = Instance::create(debug, required_extensions)?;
+        let instance = Instance::create(info.debug, required_extensions)?;
 
-        Self::create(instance, select_physical_device, true)
+        Self::create(instance, info, true)
     }
 
     pub(crate) fn create_fence(this: &Self, signaled: bool) -> Result<vk::Fence, DriverError> {
@@ -275,6 +708,22 @@ impl Device {
         })
     }
 
+    /// Applies the device-level default anisotropy and mip LOD bias, if any, to sampler
+    /// information which was not manually specified.
+    pub(crate) fn default_sampler_info(this: &Self, mut info: SamplerInfo) -> SamplerInfo {
+        if info.anisotropy_enable {
+            if let Some(max_anisotropy) = this.default_anisotropy {
+                info.max_anisotropy = max_anisotropy.into();
+            }
+        }
+
+        if let Some(mip_lod_bias) = this.default_mip_lod_bias {
+            info.mip_lod_bias = mip_lod_bias.into();
+        }
+
+        info
+    }
+
     /// Helper for times when you already know that the device supports the acceleration
     /// structure extension.
     ///
@@ -287,14 +736,144 @@ impl Device {
             .expect("VK_KHR_acceleration_structure")
     }
 
+    fn physical_device_supports(physical_device: &PhysicalDevice, feature: DeviceFeature) -> bool {
+        match feature {
+            DeviceFeature::AccelerationStructure => {
+                physical_device.accel_struct_properties.is_some()
+            }
+            DeviceFeature::BufferDeviceAddress => {
+                physical_device.features_v1_2.buffer_device_address
+            }
+            DeviceFeature::ConditionalRendering => {
+                physical_device
+                    .conditional_rendering_features
+                    .conditional_rendering
+            }
+            DeviceFeature::HostQueryReset => physical_device.features_v1_2.host_query_reset,
+            DeviceFeature::IndexTypeUint8 => {
+                physical_device.index_type_uint8_features.index_type_uint8
+            }
+            DeviceFeature::MemoryBudget => physical_device.supports_memory_budget,
+            DeviceFeature::OcclusionQueryPrecise => {
+                physical_device.features_v1_0.occlusion_query_precise
+            }
+            DeviceFeature::PipelineStatisticsQuery => {
+                physical_device.features_v1_0.pipeline_statistics_query
+            }
+            DeviceFeature::PushDescriptor => physical_device.push_descriptor_properties.is_some(),
+            DeviceFeature::RayQuery => physical_device.ray_query_features.ray_query,
+            DeviceFeature::RayTracingPipeline => {
+                physical_device.ray_trace_features.ray_tracing_pipeline
+            }
+            DeviceFeature::VertexAttributeDivisor => {
+                physical_device
+                    .vertex_attribute_divisor_features
+                    .vertex_attribute_instance_rate_divisor
+            }
+        }
+    }
+
+    /// Returns the number of live memory allocations made by this device.
+    #[profiling::function]
+    pub fn allocation_count(this: &Self) -> usize {
+        let allocator = this.allocator.lock();
+
+        #[cfg(not(feature = "parking_lot"))]
+        let allocator = allocator.unwrap();
+
+        allocator.generate_report().allocations.len()
+    }
+
+    /// Returns the size, budget, and usage of each memory heap of this device.
+    ///
+    /// Budget and usage are reported using `VK_EXT_memory_budget` and include memory allocated by
+    /// other processes. If the extension is not supported the budget of each heap is its size and
+    /// the usage is not known.
+    #[profiling::function]
+    pub fn memory_budget(this: &Self) -> MemoryBudget {
+        let heap_count = this.physical_device.memory_properties.memory_heap_count as usize;
+        let heaps = &this.physical_device.memory_properties.memory_heaps[..heap_count];
+
+        if !Self::supports(this, DeviceFeature::MemoryBudget) {
+            return MemoryBudget {
+                heaps: heaps
+                    .iter()
+                    .map(|heap| MemoryHeapBudget {
+                        budget: heap.size,
+                        flags: heap.flags,
+                        size: heap.size,
+                        usage: None,
+                    })
+                    .collect(),
+            };
+        }
+
+        let mut budget_properties = vk::PhysicalDeviceMemoryBudgetPropertiesEXT::default();
+        let mut memory_properties =
+            vk::PhysicalDeviceMemoryProperties2::default().push_next(&mut budget_properties);
+
+        unsafe {
+            this.instance.get_physical_device_memory_properties2(
+                *this.physical_device,
+                &mut memory_properties,
+            );
+        }
+
+        MemoryBudget {
+            heaps: heaps
+                .iter()
+                .enumerate()
+                .map(|(heap_idx, heap)| MemoryHeapBudget {
+                    budget: budget_properties.heap_budget[heap_idx],
+                    flags: heap.flags,
+                    size: heap.size,
+                    usage: Some(budget_properties.heap_usage[heap_idx]),
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns `true` if the given feature was enabled when this device was created or loaded.
+    ///
+    /// Functions which require a feature that is not enabled return
+    /// [`DriverError::FeatureNotEnabled`].
+    pub fn supports(this: &Self, feature: DeviceFeature) -> bool {
+        this.enabled_features.contains(&feature)
+    }
+
+    /// Returns the optional features supported by the given physical device, which are the
+    /// features enabled by [`Device::create_ash_device`].
+    pub fn supported_features(physical_device: &PhysicalDevice) -> Vec<DeviceFeature> {
+        DEVICE_FEATURES
+            .into_iter()
+            .filter(|&feature| Self::physical_device_supports(physical_device, feature))
+            .collect()
+    }
+
     /// Loads and existing `ash` Vulkan device that may have been created by other means.
+    ///
+    /// `enabled_features` must list the optional features which were enabled when `device` was
+    /// created. For devices created using [`Device::create_ash_device`] these are the
+    /// [`Device::supported_features`] of `physical_device`.
     #[profiling::function]
     pub fn load(
         instance: Instance,
         physical_device: PhysicalDevice,
         device: ash::Device,
         display_window: bool,
+        enabled_features: impl IntoIterator<Item = DeviceFeature>,
     ) -> Result<Self, DriverError> {
+        let mut features = HashSet::new();
+
+        for feature in enabled_features {
+            if Self::physical_device_supports(&physical_device, feature) {
+                features.insert(feature);
+            } else {
+                warn!("enabled feature {feature:?} not supported");
+            }
+        }
+
+        let enabled_features = features;
         let debug = Instance::is_debug(&instance);
         let allocator = Allocator::new(&AllocatorCreateDesc {
             instance: (*instance).clone(),
@@ -306,7 +885,7 @@ impl Device {
                 log_allocations: debug,
                 ..Default::default()
             },
-            buffer_device_address: true,
+            buffer_device_address: enabled_features.contains(&DeviceFeature::BufferDeviceAddress),
             allocation_sizes: Default::default(),
         })
         .map_err(|err| {
@@ -321,8 +900,11 @@ impl Device {
             let mut queue_family = Vec::with_capacity(properties.queue_count as _);
 
             for queue_index in 0..properties.queue_count {
-                queue_family
-                    .push(unsafe { device.get_device_queue(queue_family_index as _, queue_index) });
+                queue_family.push(Mutex::new(Queue {
+                    queue: unsafe { device.get_device_queue(queue_family_index as _, queue_index) },
+                    timeline: Queue::create_timeline_semaphore(&device)?,
+                    timeline_value: 0,
+                }));
             }
 
             queues.push(queue_family);
@@ -331,13 +913,21 @@ impl Device {
         let surface_ext = display_window
             .then(|| khr::surface::Instance::new(Instance::entry(&instance), &instance));
         let swapchain_ext = display_window.then(|| khr::swapchain::Device::new(&instance, &device));
-        let accel_struct_ext = physical_device
-            .accel_struct_properties
-            .is_some()
+        let accel_struct_ext = enabled_features
+            .contains(&DeviceFeature::AccelerationStructure)
             .then(|| khr::acceleration_structure::Device::new(&instance, &device));
-        let ray_trace_ext = physical_device
-            .ray_trace_features
-            .ray_tracing_pipeline
+        let conditional_rendering_ext = enabled_features
+            .contains(&DeviceFeature::ConditionalRendering)
+            .then(|| {
+                ext::conditional_rendering::DeviceFn::load(|name| unsafe {
+                    transmute(instance.get_device_proc_addr(device.handle(), name.as_ptr()))
+                })
+            });
+        let push_descriptor_ext = enabled_features
+            .contains(&DeviceFeature::PushDescriptor)
+            .then(|| khr::push_descriptor::Device::new(&instance, &device));
+        let ray_trace_ext = enabled_features
+            .contains(&DeviceFeature::RayTracingPipeline)
             .then(|| khr::ray_tracing_pipeline::Device::new(&instance, &device));
 
         let pipeline_cache =
@@ -351,10 +941,15 @@ impl Device {
         Ok(Self {
             accel_struct_ext,
             allocator: ManuallyDrop::new(Mutex::new(allocator)),
+            conditional_rendering_ext,
+            default_anisotropy: None,
+            default_mip_lod_bias: None,
             device,
+            enabled_features,
             instance,
             pipeline_cache,
             physical_device,
+            push_descriptor_ext,
             queues,
             ray_trace_ext,
             surface_ext,
@@ -414,10 +1009,102 @@ impl Device {
         this.pipeline_cache
     }
 
+    /// Submits a command buffer to a queue of this device.
+    ///
+    /// Each submission signals the timeline semaphore of the queue with a new value, which is
+    /// returned as a token. `waits` are binary semaphores (_with a value of zero_) or timeline
+    /// semaphore tokens which must be signalled before the given stages execute.
     #[profiling::function]
-    pub(crate) fn wait_for_fence(this: &Self, fence: &vk::Fence) -> Result<(), DriverError> {
-        use std::slice::from_ref;
+    pub(crate) fn queue_submit(
+        this: &Self,
+        queue_family_index: usize,
+        queue_index: usize,
+        cmd_buf: vk::CommandBuffer,
+        fence: vk::Fence,
+        waits: &[(SubmitToken, vk::PipelineStageFlags)],
+        signal_semaphores: &[vk::Semaphore],
+    ) -> Result<SubmitToken, DriverError> {
+        #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+        let mut queue = this.queues[queue_family_index][queue_index].lock();
+
+        #[cfg(not(feature = "parking_lot"))]
+        let mut queue = queue.unwrap();
+
+        queue.timeline_value += 1;
+
+        let token = SubmitToken {
+            semaphore: queue.timeline,
+            value: queue.timeline_value,
+        };
+        let wait_semaphores = waits
+            .iter()
+            .map(|(token, _)| token.semaphore)
+            .collect::<Box<_>>();
+        let wait_values = waits
+            .iter()
+            .map(|(token, _)| token.value)
+            .collect::<Box<_>>();
+        let wait_stages = waits.iter().map(|(_, stage)| *stage).collect::<Box<_>>();
+        let signal_semaphores = signal_semaphores
+            .iter()
+            .copied()
+            .chain(Some(token.semaphore))
+            .collect::<Box<_>>();
+        let signal_values = repeat(0)
+            .take(signal_semaphores.len() - 1)
+            .chain(Some(token.value))
+            .collect::<Box<_>>();
+        let mut timeline_submit_info = vk::TimelineSemaphoreSubmitInfo::default()
+            .wait_semaphore_values(&wait_values)
+            .signal_semaphore_values(&signal_values);
 
+        unsafe {
+            this.queue_submit(
+                queue.queue,
+                from_ref(
+                    &vk::SubmitInfo::default()
+                        .command_buffers(from_ref(&cmd_buf))
+                        .wait_semaphores(&wait_semaphores)
+                        .wait_dst_stage_mask(&wait_stages)
+                        .signal_semaphores(&signal_semaphores)
+                        .push_next(&mut timeline_submit_info),
+                ),
+                fence,
+            )
+        }
+        .map_err(|err| {
+            warn!("{err}");
+
+            // The value was not signalled, so later submissions must not skip over it
+            queue.timeline_value -= 1;
+
+            DriverError::OutOfMemory
+        })?;
+
+        Ok(token)
+    }
+
+    /// Blocks until all work submitted to all queues of this device has finished executing.
+    ///
+    /// This is intended for use during shutdown, before dropping resources which may still be
+    /// in use by the GPU. Prefer waiting on individual command buffers during normal operation.
+    #[profiling::function]
+    pub fn wait_idle(this: &Self) -> Result<(), DriverError> {
+        unsafe { this.device.device_wait_idle() }.map_err(|err| {
+            if err == vk::Result::ERROR_DEVICE_LOST {
+                error!("Device lost");
+
+                DriverError::InvalidData
+            } else {
+                warn!("{err}");
+
+                DriverError::OutOfMemory
+            }
+        })
+    }
+
+    #[profiling::function]
+    pub(crate) fn wait_for_fence(this: &Self, fence: &vk::Fence) -> Result<(), DriverError> {
         Device::wait_for_fences(this, from_ref(fence))
     }
 
@@ -494,6 +1181,15 @@ impl Drop for Device {
         }
 
         unsafe {
+            for queue in self.queues.iter().flatten() {
+                let queue = queue.lock();
+
+                #[cfg(not(feature = "parking_lot"))]
+                let queue = queue.unwrap();
+
+                self.device.destroy_semaphore(queue.timeline, None);
+            }
+
             self.device
                 .destroy_pipeline_cache(self.pipeline_cache, None);
 
@@ -506,6 +1202,51 @@ impl Drop for Device {
     }
 }
 
+/// The memory heaps of a device, as reported by [`Device::memory_budget`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct MemoryBudget {
+    /// The budget of each memory heap, indexed by heap index.
+    pub heaps: Vec<MemoryHeapBudget>,
+}
+
+impl MemoryBudget {
+    /// Returns the total memory available to be allocated from device-local heaps.
+    pub fn device_local_available(&self) -> vk::DeviceSize {
+        self.heaps
+            .iter()
+            .filter(|heap| heap.flags.contains(vk::MemoryHeapFlags::DEVICE_LOCAL))
+            .map(MemoryHeapBudget::available)
+            .sum()
+    }
+}
+
+/// The size, budget, and usage of one memory heap.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct MemoryHeapBudget {
+    /// The amount of memory which this process may allocate from the heap before allocations
+    /// are likely to fail or cause performance degradation.
+    pub budget: vk::DeviceSize,
+
+    /// The flags of the heap, such as `DEVICE_LOCAL`.
+    pub flags: vk::MemoryHeapFlags,
+
+    /// The total size of the heap.
+    pub size: vk::DeviceSize,
+
+    /// The amount of memory currently used by this process, or `None` if `VK_EXT_memory_budget`
+    /// is not supported.
+    pub usage: Option<vk::DeviceSize>,
+}
+
+impl MemoryHeapBudget {
+    /// Returns the remaining budget of the heap.
+    pub fn available(&self) -> vk::DeviceSize {
+        self.budget.saturating_sub(self.usage.unwrap_or_default())
+    }
+}
+
 /// Information used to create a [`Device`] instance.
 #[derive(Builder)]
 #[builder(
@@ -530,8 +1271,39 @@ pub struct DeviceInfo {
     #[builder(default)]
     pub debug: bool,
 
+    /// The maximum anisotropy applied to all linearly-filtered samplers which are not manually
+    /// specified, including those described using binding name suffixes.
+    ///
+    /// The value is clamped to `max_sampler_anisotropy` of the physical device limits and is
+    /// ignored if the `sampler_anisotropy` feature is not supported.
+    ///
+    /// The default value is `None`, which leaves the anisotropy of each sampler unchanged.
+    #[builder(default, setter(strip_option))]
+    pub default_anisotropy: Option<f32>,
+
+    /// The mip LOD bias applied to all samplers which are not manually specified, including those
+    /// described using binding name suffixes.
+    ///
+    /// The value is clamped to `max_sampler_lod_bias` of the physical device limits.
+    ///
+    /// The default value is `None`, which leaves the mip LOD bias of each sampler unchanged.
+    #[builder(default, setter(strip_option))]
+    pub default_mip_lod_bias: Option<f32>,
+
+    /// Features which the selected physical device must support.
+    ///
+    /// Supported features are always enabled; device creation returns
+    /// [`DriverError::FeatureNotEnabled`] if a required feature is not supported. See
+    /// [`DeviceInfoBuilder::ray_tracing`] and related functions.
+    ///
+    /// The default value is empty.
+    #[builder(default, setter(into))]
+    pub required_features: Vec<DeviceFeature>,
+
     /// Callback function used to select a [`PhysicalDevice`] from the available devices. The
     /// callback must return the index of the selected device.
+    ///
+    /// The default value is [`DeviceInfo::discrete_gpu`]; see also [`DeviceInfo::integrated_gpu`].
     #[builder(default = "Box::new(DeviceInfo::discrete_gpu)")]
     pub select_physical_device: Box<SelectPhysicalDeviceFn>,
 }
@@ -545,6 +1317,17 @@ impl DeviceInfo {
         Default::default()
     }
 
+    /// Returns the total size of the device-local memory heaps of a physical device.
+    fn device_local_memory_size(physical_device: &PhysicalDevice) -> vk::DeviceSize {
+        let memory_properties = &physical_device.memory_properties;
+
+        memory_properties.memory_heaps[..memory_properties.memory_heap_count as usize]
+            .iter()
+            .filter(|heap| heap.flags.contains(vk::MemoryHeapFlags::DEVICE_LOCAL))
+            .map(|heap| heap.size)
+            .sum()
+    }
+
     /// A builtin [`DeviceInfo::select_physical_device`] function which prioritizes selection of
     /// lower-power integrated GPU devices.
     #[profiling::function]
@@ -576,9 +1359,8 @@ impl DeviceInfo {
                 return device_ty;
             }
 
-            // TODO: Select the device with the most memory
-
-            Ordering::Equal
+            // Select the device with the most memory
+            Self::device_local_memory_size(rhs).cmp(&Self::device_local_memory_size(lhs))
         });
 
         let (idx, _) = physical_devices[0];
@@ -617,9 +1399,8 @@ impl DeviceInfo {
                 return device_ty;
             }
 
-            // TODO: Select the device with the most memory
-
-            Ordering::Equal
+            // Select the device with the most memory
+            Self::device_local_memory_size(rhs).cmp(&Self::device_local_memory_size(lhs))
         });
 
         let (idx, _) = physical_devices[0];
@@ -632,6 +1413,9 @@ impl DeviceInfo {
     pub fn to_builder(self) -> DeviceInfoBuilder {
         DeviceInfoBuilder {
             debug: Some(self.debug),
+            default_anisotropy: Some(self.default_anisotropy),
+            default_mip_lod_bias: Some(self.default_mip_lod_bias),
+            required_features: Some(self.required_features),
             select_physical_device: Some(self.select_physical_device),
         }
     }
@@ -641,6 +1425,9 @@ impl Debug for DeviceInfo {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("DeviceInfo")
             .field("debug", &self.debug)
+            .field("default_anisotropy", &self.default_anisotropy)
+            .field("default_mip_lod_bias", &self.default_mip_lod_bias)
+            .field("required_features", &self.required_features)
             .field("select_physical_device", &"fn")
             .finish()
     }
@@ -650,6 +1437,9 @@ impl Default for DeviceInfo {
     fn default() -> Self {
         Self {
             debug: false,
+            default_anisotropy: None,
+            default_mip_lod_bias: None,
+            required_features: vec![],
             select_physical_device: Box::new(DeviceInfo::discrete_gpu),
         }
     }
@@ -662,6 +1452,36 @@ impl From<DeviceInfoBuilder> for DeviceInfo {
 }
 
 impl DeviceInfoBuilder {
+    /// Requires the `bufferDeviceAddress` feature.
+    pub fn buffer_device_address(self) -> Self {
+        self.require(DeviceFeature::BufferDeviceAddress)
+    }
+
+    /// Requires the features used by inline ray tracing (`rayQueryEXT`).
+    pub fn ray_query(self) -> Self {
+        self.require(DeviceFeature::AccelerationStructure)
+            .require(DeviceFeature::BufferDeviceAddress)
+            .require(DeviceFeature::RayQuery)
+    }
+
+    /// Requires the features used by ray trace pipelines.
+    pub fn ray_tracing(self) -> Self {
+        self.require(DeviceFeature::AccelerationStructure)
+            .require(DeviceFeature::BufferDeviceAddress)
+            .require(DeviceFeature::RayTracingPipeline)
+    }
+
+    /// Requires a feature which the selected physical device must support.
+    pub fn require(mut self, feature: DeviceFeature) -> Self {
+        let required_features = self.required_features.get_or_insert_with(Vec::new);
+
+        if !required_features.contains(&feature) {
+            required_features.push(feature);
+        }
+
+        self
+    }
+
     /// Builds a new `DeviceInfo`.
     #[inline(always)]
     pub fn build(self) -> DeviceInfo {
@@ -677,6 +1497,30 @@ impl DeviceInfoBuilder {
     }
 }
 
+/// A device queue and the timeline semaphore which is signalled by each submission to it.
+#[derive(Debug)]
+pub(crate) struct Queue {
+    pub(crate) queue: vk::Queue,
+    timeline: vk::Semaphore,
+    timeline_value: u64,
+}
+
+impl Queue {
+    fn create_timeline_semaphore(device: &ash::Device) -> Result<vk::Semaphore, DriverError> {
+        let mut type_create_info = vk::SemaphoreTypeCreateInfo::default()
+            .semaphore_type(vk::SemaphoreType::TIMELINE)
+            .initial_value(0);
+        let create_info = vk::SemaphoreCreateInfo::default().push_next(&mut type_create_info);
+        let allocation_callbacks = None;
+
+        unsafe { device.create_semaphore(&create_info, allocation_callbacks) }.map_err(|err| {
+            warn!("{err}");
+
+            DriverError::OutOfMemory
+        })
+    }
+}
+
 #[derive(Debug)]
 struct DeviceInfoBuilderError;
 
@@ -686,6 +1530,17 @@ impl From<UninitializedFieldError> for DeviceInfoBuilderError {
     }
 }
 
+/// Creates a headless device for tests which record and submit commands.
+///
+/// Returns `None` when the host does not have a Vulkan driver, in which case the test should pass
+/// without checking anything.
+#[cfg(test)]
+pub(crate) fn test_device() -> Option<std::sync::Arc<Device>> {
+    Device::create_headless(DeviceInfo::default())
+        .map(std::sync::Arc::new)
+        .ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -702,4 +1557,170 @@ mod tests {
     pub fn device_info_builder() {
         Builder::default().build();
     }
+
+    #[test]
+    pub fn device_info_sampler_defaults() {
+        let info = Builder::default()
+            .default_anisotropy(8.0)
+            .default_mip_lod_bias(-0.5)
+            .build()
+            .to_builder()
+            .build();
+
+        assert_eq!(info.default_anisotropy, Some(8.0));
+        assert_eq!(info.default_mip_lod_bias, Some(-0.5));
+    }
+
+    #[test]
+    pub fn device_info_required_features() {
+        let info = Builder::default()
+            .ray_tracing()
+            .ray_query()
+            .build()
+            .to_builder()
+            .build();
+
+        assert_eq!(
+            info.required_features,
+            [
+                DeviceFeature::AccelerationStructure,
+                DeviceFeature::BufferDeviceAddress,
+                DeviceFeature::RayTracingPipeline,
+                DeviceFeature::RayQuery,
+            ]
+        );
+    }
+
+    #[test]
+    pub fn bind_sparse() {
+        use crate::driver::image::{ImageInfo, SparseImagePage};
+
+        let Some(device) = test_device() else {
+            return;
+        };
+
+        let features = &device.physical_device.features_v1_0;
+        if !features.sparse_binding || !features.sparse_residency_image2_d {
+            return;
+        }
+
+        let image = Image::create(
+            &device,
+            ImageInfo::image_2d(
+                4096,
+                4096,
+                vk::Format::R8G8B8A8_UNORM,
+                vk::ImageUsageFlags::SAMPLED,
+            )
+            .sparse_residency(),
+        )
+        .unwrap();
+        let page_size = Image::sparse_requirements(&image)
+            .unwrap()
+            .memory_requirements
+            .alignment;
+        let page = SparseImagePage {
+            array_layer: 0,
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            mip_level: 0,
+            x: 0,
+            y: 0,
+            z: 0,
+        };
+
+        assert_eq!(Image::memory_size(&image), 0);
+
+        Device::bind_sparse(&device, &image, &[SparseImageBinding::Bind(page)]).unwrap();
+
+        assert_eq!(Image::memory_size(&image), page_size);
+
+        // Binding a resident page has no effect
+        Device::bind_sparse(
+            &device,
+            &image,
+            &[
+                SparseImageBinding::Bind(page),
+                SparseImageBinding::Bind(page),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(Image::memory_size(&image), page_size);
+
+        Device::bind_sparse(&device, &image, &[SparseImageBinding::Unbind(page)]).unwrap();
+
+        assert_eq!(Image::memory_size(&image), 0);
+
+        // Unbinding a non-resident page has no effect
+        Device::bind_sparse(&device, &image, &[SparseImageBinding::Unbind(page)]).unwrap();
+
+        assert_eq!(Image::memory_size(&image), 0);
+    }
+
+    #[test]
+    pub fn bind_sparse_invalid() {
+        use crate::driver::image::{ImageInfo, SparseImagePage};
+
+        let Some(device) = test_device() else {
+            return;
+        };
+
+        let page = SparseImagePage {
+            array_layer: 0,
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            mip_level: 0,
+            x: 0,
+            y: 0,
+            z: 0,
+        };
+        let info = ImageInfo::image_2d(
+            4096,
+            4096,
+            vk::Format::R8G8B8A8_UNORM,
+            vk::ImageUsageFlags::SAMPLED,
+        );
+
+        // Images must be created with sparse binding
+        let image = Image::create(&device, info).unwrap();
+
+        assert!(matches!(
+            Device::bind_sparse(&device, &image, &[SparseImageBinding::Bind(page)]),
+            Err(DriverError::InvalidData)
+        ));
+
+        let features = &device.physical_device.features_v1_0;
+        if !features.sparse_binding || !features.sparse_residency_image2_d {
+            return;
+        }
+
+        // Pages must be within the image
+        let image = Image::create(&device, info.sparse_residency()).unwrap();
+
+        assert!(matches!(
+            Device::bind_sparse(
+                &device,
+                &image,
+                &[SparseImageBinding::Bind(SparseImagePage {
+                    x: 4096,
+                    ..page
+                })],
+            ),
+            Err(DriverError::InvalidData)
+        ));
+        assert_eq!(Image::memory_size(&image), 0);
+    }
+
+    #[test]
+    pub fn supports_enabled_features() {
+        let Some(device) = test_device() else {
+            return;
+        };
+
+        for feature in DEVICE_FEATURES {
+            assert_eq!(
+                Device::supports(&device, feature),
+                Device::physical_device_supports(&device.physical_device, feature)
+            );
+        }
+    }
 }
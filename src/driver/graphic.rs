@@ -2,11 +2,14 @@
 
 use {
     super::{
-        device::Device,
+        device::{Device, DeviceFeature},
+        format_aspect_mask,
         image::SampleCount,
         merge_push_constant_ranges,
+        render_pass::{AttachmentInfo, AttachmentRef, RenderPass, RenderPassInfo, SubpassInfo},
         shader::{
-            align_spriv, DescriptorBindingMap, PipelineDescriptorInfo, Shader, SpecializationInfo,
+            align_spriv, Descriptor, DescriptorBindingInfo, DescriptorBindingMap,
+            PipelineDescriptorInfo, Shader, SpecializationInfo,
         },
         DriverError,
     },
@@ -353,7 +356,7 @@ impl From<UninitializedFieldError> for DepthStencilModeBuilderError {
 pub struct GraphicPipeline {
     pub(crate) descriptor_bindings: DescriptorBindingMap,
     pub(crate) descriptor_info: PipelineDescriptorInfo,
-    device: Arc<Device>,
+    pub(crate) device: Arc<Device>,
 
     /// Information used to create this object.
     pub info: GraphicPipelineInfo,
@@ -416,6 +419,33 @@ impl GraphicPipeline {
 
         let device = Arc::clone(device);
         let info = info.into();
+
+        if info.viewport_count > 1 && !device.physical_device.features_v1_0.multi_viewport {
+            warn!("unsupported multi viewport feature");
+
+            return Err(DriverError::Unsupported);
+        }
+
+        if info.sample_shading.is_some()
+            && !device.physical_device.features_v1_0.sample_rate_shading
+        {
+            warn!("unsupported sample rate shading feature");
+
+            return Err(DriverError::Unsupported);
+        }
+
+        if !is_valid_sample_shading(info.sample_shading) {
+            warn!("sample shading must be within 0.0..=1.0");
+
+            return Err(DriverError::InvalidData);
+        }
+
+        if info.primitive_restart && !is_strip_or_fan(info.topology) {
+            warn!("primitive restart requires strip or fan topology");
+
+            return Err(DriverError::InvalidData);
+        }
+
         let shaders = shaders
             .into_iter()
             .map(|shader| shader.into())
@@ -427,6 +457,30 @@ impl GraphicPipeline {
             .expect("vertex shader not found")
             .vertex_input();
 
+        if !vertex_input.vertex_binding_divisors.is_empty()
+            && !Device::supports(&device, DeviceFeature::VertexAttributeDivisor)
+        {
+            warn!("vertex attribute divisor feature not enabled");
+
+            return Err(DriverError::FeatureNotEnabled(
+                DeviceFeature::VertexAttributeDivisor,
+            ));
+        }
+
+        validate_vertex_binding_divisors(
+            &vertex_input,
+            device
+                .physical_device
+                .vertex_attribute_divisor_features
+                .vertex_attribute_instance_rate_zero_divisor,
+            device
+                .physical_device
+                .vertex_attribute_divisor_properties
+                .as_ref()
+                .map(|properties| properties.max_vertex_attrib_divisor)
+                .unwrap_or_default(),
+        )?;
+
         // Check for proper stages because vulkan may not complain but this is bad
         let has_fragment_stage = shaders
             .iter()
@@ -449,16 +503,21 @@ impl GraphicPipeline {
             "invalid shader stage combination"
         );
 
+        if has_tesselation_stage != info.patch_control_points.is_some() {
+            warn!("patch control points must be specified for tessellation stages only");
+
+            return Err(DriverError::InvalidData);
+        }
+
         let mut descriptor_bindings = Shader::merge_descriptor_bindings(
             shaders.iter().map(|shader| shader.descriptor_bindings()),
         );
-        for (descriptor_info, _) in descriptor_bindings.values_mut() {
-            if descriptor_info.binding_count() == 0 {
-                descriptor_info.set_binding_count(info.bindless_descriptor_count);
-            }
-        }
-
-        let descriptor_info = PipelineDescriptorInfo::create(&device, &descriptor_bindings)?;
+        let descriptor_info = PipelineDescriptorInfo::create(
+            &device,
+            &mut descriptor_bindings,
+            info.bindless_descriptor_count,
+            info.push_descriptor_set,
+        )?;
         let descriptor_sets_layouts = descriptor_info
             .layouts
             .values()
@@ -511,9 +570,13 @@ impl GraphicPipeline {
 
                     DriverError::Unsupported
                 })?;
-            let shader_info = shaders
-                .into_iter()
-                .map(|shader| {
+            // Stages which share SPIR-V code (a module with multiple entry points) share a module
+            let mut shader_modules = vec![];
+            let mut stages: Vec<Stage> = vec![];
+            for (shader, module_idx) in shaders.iter().zip(shader_module_indices(&shaders)) {
+                let shader_module = if module_idx < stages.len() {
+                    stages[module_idx].module
+                } else {
                     let shader_module = device
                         .create_shader_module(
                             &vk::ShaderModuleCreateInfo::default()
@@ -525,27 +588,24 @@ impl GraphicPipeline {
 
                             DriverError::Unsupported
                         })?;
-                    let shader_stage = Stage {
-                        flags: shader.stage,
-                        module: shader_module,
-                        name: CString::new(shader.entry_name.as_str()).unwrap(),
-                        specialization_info: shader.specialization_info,
-                    };
-
-                    Result::<_, DriverError>::Ok((shader_module, shader_stage))
-                })
-                .collect::<Result<Vec<_>, _>>()?;
-            let mut shader_modules = vec![];
-            let mut stages = vec![];
-            shader_info
-                .into_iter()
-                .for_each(|(shader_module, shader_stage)| {
                     shader_modules.push(shader_module);
-                    stages.push(shader_stage);
+
+                    shader_module
+                };
+
+                stages.push(Stage {
+                    flags: shader.stage,
+                    module: shader_module,
+                    name: CString::new(shader.entry_name.as_str()).unwrap(),
+                    specialization_info: shader.specialization_info.clone(),
                 });
+            }
 
             let multisample = MultisampleState {
+                alpha_to_coverage_enable: info.alpha_to_coverage,
+                min_sample_shading: info.sample_shading.map(|value| *value).unwrap_or_default(),
                 rasterization_samples: info.samples,
+                sample_shading_enable: info.sample_shading.is_some(),
                 ..Default::default()
             };
 
@@ -571,6 +631,122 @@ impl GraphicPipeline {
         }
     }
 
+    /// Creates a new graphic pipeline and compiles it for a render pass with the given attachment
+    /// formats.
+    ///
+    /// Pipelines are otherwise compiled the first time a render graph uses them, which is also when
+    /// any incompatibility with the attachments is reported. Compiling ahead of time validates the
+    /// pipeline and stores the result in the pipeline cache of the device, so that later use with
+    /// attachments of the same formats is faster.
+    ///
+    /// `color_formats` are the formats of color attachments zero and up, and `depth_stencil` is the
+    /// format and mode of the depth/stencil attachment, if any. All attachments use the sample
+    /// count of `info`.
+    ///
+    /// _NOTE:_ Pipelines which read input attachments are not supported.
+    #[profiling::function]
+    pub fn create_for_formats<S>(
+        device: &Arc<Device>,
+        info: impl Into<GraphicPipelineInfo>,
+        shaders: impl IntoIterator<Item = S>,
+        color_formats: &[vk::Format],
+        depth_stencil: Option<(vk::Format, DepthStencilMode)>,
+    ) -> Result<Self, DriverError>
+    where
+        S: Into<Shader>,
+    {
+        let pipeline = Self::create(device, info, shaders)?;
+
+        debug_assert!(
+            pipeline.input_attachments.is_empty(),
+            "input attachments are not supported"
+        );
+
+        let attachment_count = color_formats.len() + depth_stencil.is_some() as usize;
+        let mut render_pass_info = RenderPassInfo {
+            attachments: Vec::with_capacity(attachment_count),
+            subpasses: Vec::with_capacity(1),
+            dependencies: vec![],
+        };
+        let mut subpass = SubpassInfo::with_capacity(color_formats.len());
+
+        for &fmt in color_formats {
+            subpass.color_attachments.push(AttachmentRef {
+                attachment: render_pass_info.attachments.len() as _,
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                layout: vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+            });
+            render_pass_info.attachments.push(AttachmentInfo {
+                fmt,
+                sample_count: pipeline.info.samples,
+                initial_layout: vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                final_layout: vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                ..Default::default()
+            });
+        }
+
+        if let Some((fmt, _)) = depth_stencil {
+            subpass.depth_stencil_attachment = Some(AttachmentRef {
+                attachment: render_pass_info.attachments.len() as _,
+                aspect_mask: format_aspect_mask(fmt),
+                layout: vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+            });
+            render_pass_info.attachments.push(AttachmentInfo {
+                fmt,
+                sample_count: pipeline.info.samples,
+                initial_layout: vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+                final_layout: vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+                ..Default::default()
+            });
+        }
+
+        render_pass_info.subpasses.push(subpass);
+
+        // The render pass and its compiled pipeline are destroyed here but the pipeline cache
+        // retains the result
+        let mut render_pass = RenderPass::create(device, render_pass_info)?;
+        RenderPass::graphic_pipeline(
+            &mut render_pass,
+            &pipeline,
+            depth_stencil.map(|(_, depth_stencil)| depth_stencil),
+            0,
+        )?;
+
+        Ok(pipeline)
+    }
+
+    /// Returns the reflected information of a descriptor binding, if it is used by this pipeline.
+    pub fn descriptor_binding(
+        this: &Self,
+        descriptor: impl Into<Descriptor>,
+    ) -> Option<DescriptorBindingInfo> {
+        this.descriptor_bindings
+            .get(&descriptor.into())
+            .map(|&(descriptor_info, stage_flags)| {
+                DescriptorBindingInfo::new(descriptor_info, stage_flags)
+            })
+    }
+
+    /// Returns the reflected information of all descriptor bindings used by this pipeline, in no
+    /// particular order.
+    pub fn descriptor_bindings(
+        this: &Self,
+    ) -> impl Iterator<Item = (Descriptor, DescriptorBindingInfo)> + '_ {
+        this.descriptor_bindings
+            .iter()
+            .map(|(&descriptor, &(descriptor_info, stage_flags))| {
+                (
+                    descriptor,
+                    DescriptorBindingInfo::new(descriptor_info, stage_flags),
+                )
+            })
+    }
+
+    /// Returns the reflected push constant ranges of all shader stages.
+    pub fn push_constant_ranges(this: &Self) -> &[vk::PushConstantRange] {
+        &this.push_constants
+    }
+
     /// Sets the debugging name assigned to this pipeline.
     pub fn with_name(mut this: Self, name: impl Into<String>) -> Self {
         this.name = Some(name.into());
@@ -578,6 +754,20 @@ impl GraphicPipeline {
     }
 }
 
+/// Returns, for each shader, the index of the first shader which has identical SPIR-V code.
+fn shader_module_indices(shaders: &[Shader]) -> Vec<usize> {
+    shaders
+        .iter()
+        .enumerate()
+        .map(|(idx, shader)| {
+            shaders[..idx]
+                .iter()
+                .position(|other| other.spirv == shader.spirv)
+                .unwrap_or(idx)
+        })
+        .collect()
+}
+
 impl Drop for GraphicPipeline {
     #[profiling::function]
     fn drop(&mut self) {
@@ -610,9 +800,21 @@ impl Drop for GraphicPipeline {
 )]
 #[non_exhaustive]
 pub struct GraphicPipelineInfo {
+    /// Controls whether a temporary coverage value is generated based on the alpha component of
+    /// the fragment's first color output.
+    ///
+    /// The default value is `false`.
+    #[builder(default)]
+    pub alpha_to_coverage: bool,
+
     /// The number of descriptors to allocate for a given binding when using bindless (unbounded)
     /// syntax.
     ///
+    /// Individual elements are accessed by a render pass using array descriptors such as
+    /// `(0, 0, [42])`; elements which are not accessed remain unbound. When supported by the
+    /// device, bindless bindings are created as update-after-bind and are subject to the larger
+    /// `maxDescriptorSetUpdateAfterBind*` limits.
+    ///
     /// The default is `8192`.
     ///
     /// # Examples
@@ -638,28 +840,91 @@ pub struct GraphicPipelineInfo {
     /// Specifies color blend state used when rasterization is enabled for any color attachments
     /// accessed during rendering.
     ///
+    /// Individual color attachments may use a different blend state by specifying
+    /// [`GraphicPipelineInfo::blend_attachments`].
+    ///
     /// The default value is [`BlendMode::REPLACE`].
     #[builder(default)]
     pub blend: BlendMode,
 
+    /// Specifies color blend state for individual color attachments, indexed by attachment.
+    ///
+    /// Color attachments which do not have a blend state specified here use
+    /// [`GraphicPipelineInfo::blend`]. See [`GraphicPipelineInfoBuilder::blend_attachment`].
+    ///
+    /// The default value is `None` for all attachments.
+    #[builder(default)]
+    pub blend_attachments: [Option<BlendMode>; 8],
+
     /// Bitmask controlling triangle culling.
     ///
     /// The default value is `vk::CullModeFlags::BACK`.
     #[builder(default = "vk::CullModeFlags::BACK")]
     pub cull_mode: vk::CullModeFlags,
 
+    /// Controls whether depth bias is applied to fragment depth values.
+    ///
+    /// Depth bias values are specified by render passes using `set_depth_bias`.
+    ///
+    /// The default value is `false`.
+    #[builder(default)]
+    pub depth_bias: bool,
+
+    /// Specifies the depth/stencil state used when this pipeline is bound, unless a render pass
+    /// specifies a different mode using `set_depth_stencil`.
+    ///
+    /// The default value is `None`.
+    #[builder(default, setter(strip_option))]
+    pub depth_stencil: Option<DepthStencilMode>,
+
     /// Interpret polygon front-facing orientation.
     ///
     /// The default value is `vk::FrontFace::COUNTER_CLOCKWISE`.
     #[builder(default = "vk::FrontFace::COUNTER_CLOCKWISE")]
     pub front_face: vk::FrontFace,
 
+    /// The number of control points per patch used by tessellation stages.
+    ///
+    /// Must be specified if, and only if, the pipeline contains tessellation control and
+    /// tessellation evaluation shaders. Tessellation pipelines should use
+    /// `vk::PrimitiveTopology::PATCH_LIST`.
+    ///
+    /// The default value is `None`.
+    #[builder(default, setter(strip_option))]
+    pub patch_control_points: Option<u32>,
+
     /// Control polygon rasterization mode.
     ///
     /// The default value is `vk::PolygonMode::FILL`.
     #[builder(default = "vk::PolygonMode::FILL")]
     pub polygon_mode: vk::PolygonMode,
 
+    /// Controls whether a special vertex index value is treated as restarting the assembly of
+    /// primitives.
+    ///
+    /// Primitive restart may only be enabled for strip and fan topologies. The special index value
+    /// is `0xFFFFFFFF` for `vk::IndexType::UINT32` indices and `0xFFFF` for `vk::IndexType::UINT16`
+    /// indices.
+    ///
+    /// The default value is `false`.
+    #[builder(default)]
+    pub primitive_restart: bool,
+
+    /// The descriptor set which is pushed by render passes using `push_buffer_descriptor` and
+    /// `push_image_descriptor` instead of being allocated from a descriptor pool.
+    ///
+    /// Uses the `VK_KHR_push_descriptor` extension when available; see
+    /// [`PhysicalDevice::push_descriptor_properties`](super::physical_device::PhysicalDevice::push_descriptor_properties).
+    /// On other devices each pushed descriptor allocates, writes and binds a new regular
+    /// descriptor set, which is slower but otherwise behaves the same.
+    ///
+    /// The push descriptor set must be the last descriptor set of the pipeline and must not contain
+    /// dynamic or bindless bindings.
+    ///
+    /// The default value is `None`.
+    #[builder(default, setter(strip_option))]
+    pub push_descriptor_set: Option<u32>,
+
     /// Input primitive topology.
     ///
     /// The default value is `vk::PrimitiveTopology::TRIANGLE_LIST`.
@@ -668,11 +933,36 @@ pub struct GraphicPipelineInfo {
 
     /// Multisampling antialias mode.
     ///
+    /// Render passes which use multisampled attachments create the pipeline using the sample
+    /// count of those attachments, logging a warning if it differs from this value; this value is
+    /// used for passes without attachments.
+    ///
     /// The default value is `SampleCount::Type1`.
     ///
     /// See [multisampling](https://registry.khronos.org/vulkan/specs/1.3-extensions/html/vkspec.html#primsrast-multisampling).
     #[builder(default = "SampleCount::Type1")]
     pub samples: SampleCount,
+
+    // Note: Using setter(into) so caller does not need our version of OrderedFloat
+    /// Enables [sample shading] using the given minimum fraction of samples to be shaded
+    /// individually.
+    ///
+    /// Requires the `sampleRateShading` device feature.
+    ///
+    /// The default value is `None`.
+    ///
+    /// [sample shading]: https://registry.khronos.org/vulkan/specs/1.3-extensions/html/vkspec.html#primsrast-sampleshading
+    #[builder(default, setter(into, strip_option))]
+    pub sample_shading: Option<OrderedFloat<f32>>,
+
+    /// The number of viewports and scissors used by this pipeline.
+    ///
+    /// Values greater than `1` require the `multiViewport` device feature. Render passes which
+    /// use more than one viewport must set all of them using `set_viewports` and `set_scissors`.
+    ///
+    /// The default value is `1`.
+    #[builder(default = "1")]
+    pub viewport_count: u32,
 }
 
 impl GraphicPipelineInfo {
@@ -682,17 +972,43 @@ impl GraphicPipelineInfo {
         Default::default()
     }
 
+    /// Returns the blend state of the given color attachment.
+    pub(crate) fn attachment_blend(&self, attachment_idx: usize) -> BlendMode {
+        self.blend_attachments
+            .get(attachment_idx)
+            .copied()
+            .flatten()
+            .unwrap_or(self.blend)
+    }
+
+    /// Returns the depth/stencil state of a render pass, which may not have specified one.
+    pub(crate) fn depth_stencil_mode(
+        &self,
+        depth_stencil: Option<DepthStencilMode>,
+    ) -> Option<DepthStencilMode> {
+        depth_stencil.or(self.depth_stencil)
+    }
+
     /// Converts a `GraphicPipelineInfo` into a `GraphicPipelineInfoBuilder`.
     #[inline(always)]
     pub fn to_builder(self) -> GraphicPipelineInfoBuilder {
         GraphicPipelineInfoBuilder {
+            alpha_to_coverage: Some(self.alpha_to_coverage),
             bindless_descriptor_count: Some(self.bindless_descriptor_count),
             blend: Some(self.blend),
+            blend_attachments: Some(self.blend_attachments),
             cull_mode: Some(self.cull_mode),
+            depth_bias: Some(self.depth_bias),
+            depth_stencil: Some(self.depth_stencil),
             front_face: Some(self.front_face),
+            patch_control_points: Some(self.patch_control_points),
             polygon_mode: Some(self.polygon_mode),
+            primitive_restart: Some(self.primitive_restart),
+            push_descriptor_set: Some(self.push_descriptor_set),
             topology: Some(self.topology),
             samples: Some(self.samples),
+            sample_shading: Some(self.sample_shading),
+            viewport_count: Some(self.viewport_count),
         }
     }
 }
@@ -700,13 +1016,22 @@ impl GraphicPipelineInfo {
 impl Default for GraphicPipelineInfo {
     fn default() -> Self {
         Self {
+            alpha_to_coverage: false,
             bindless_descriptor_count: 8192,
             blend: BlendMode::REPLACE,
+            blend_attachments: Default::default(),
             cull_mode: vk::CullModeFlags::BACK,
+            depth_bias: false,
+            depth_stencil: None,
             front_face: vk::FrontFace::COUNTER_CLOCKWISE,
+            patch_control_points: None,
             polygon_mode: vk::PolygonMode::FILL,
+            primitive_restart: false,
+            push_descriptor_set: None,
             topology: vk::PrimitiveTopology::TRIANGLE_LIST,
             samples: SampleCount::Type1,
+            sample_shading: None,
+            viewport_count: 1,
         }
     }
 }
@@ -718,7 +1043,23 @@ impl From<GraphicPipelineInfoBuilder> for GraphicPipelineInfo {
 }
 
 impl GraphicPipelineInfoBuilder {
+    /// Specifies color blend state for one color attachment, overriding the value of
+    /// [`GraphicPipelineInfo::blend`] for that attachment.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `attachment_idx` is eight or more.
+    pub fn blend_attachment(mut self, attachment_idx: u32, blend: BlendMode) -> Self {
+        self.blend_attachments.get_or_insert_with(Default::default)[attachment_idx as usize] =
+            Some(blend);
+        self
+    }
+
     /// Builds a new `GraphicPipelineInfo`.
+    ///
+    /// # Panics
+    ///
+    /// If `sample_shading` is not within `0.0..=1.0` this function will panic.
     #[inline(always)]
     pub fn build(self) -> GraphicPipelineInfo {
         let res = self.fallible_build();
@@ -729,6 +1070,11 @@ impl GraphicPipelineInfoBuilder {
         #[cfg(not(test))]
         let res = unsafe { res.unwrap_unchecked() };
 
+        assert!(
+            is_valid_sample_shading(res.sample_shading),
+            "Sample shading must be within 0.0..=1.0"
+        );
+
         res
     }
 }
@@ -832,9 +1178,66 @@ impl Default for StencilMode {
 #[derive(Clone, Debug, Default)]
 pub(super) struct VertexInputState {
     pub vertex_binding_descriptions: Vec<vk::VertexInputBindingDescription>,
+    pub vertex_binding_divisors: Vec<vk::VertexInputBindingDivisorDescriptionEXT>,
     pub vertex_attribute_descriptions: Vec<vk::VertexInputAttributeDescription>,
 }
 
+fn is_valid_sample_shading(sample_shading: Option<OrderedFloat<f32>>) -> bool {
+    match sample_shading {
+        Some(value) => (0.0..=1.0).contains(&*value),
+        None => true,
+    }
+}
+
+/// Checks that each vertex binding divisor applies to an instance-rate binding and is supported by
+/// the device.
+fn validate_vertex_binding_divisors(
+    vertex_input: &VertexInputState,
+    supports_zero_divisor: bool,
+    max_divisor: u32,
+) -> Result<(), DriverError> {
+    for &vk::VertexInputBindingDivisorDescriptionEXT { binding, divisor } in
+        &vertex_input.vertex_binding_divisors
+    {
+        let input_rate = vertex_input
+            .vertex_binding_descriptions
+            .iter()
+            .find(|description| description.binding == binding)
+            .map(|description| description.input_rate);
+
+        if input_rate != Some(vk::VertexInputRate::INSTANCE) {
+            warn!("vertex binding {binding} divisor requires instance input rate");
+
+            return Err(DriverError::InvalidData);
+        }
+
+        if divisor == 0 && !supports_zero_divisor {
+            warn!("unsupported vertex attribute instance rate zero divisor feature");
+
+            return Err(DriverError::Unsupported);
+        }
+
+        if divisor > max_divisor {
+            warn!("vertex binding {binding} divisor {divisor} exceeds {max_divisor}");
+
+            return Err(DriverError::InvalidData);
+        }
+    }
+
+    Ok(())
+}
+
+const fn is_strip_or_fan(topology: vk::PrimitiveTopology) -> bool {
+    matches!(
+        topology,
+        vk::PrimitiveTopology::LINE_STRIP
+            | vk::PrimitiveTopology::LINE_STRIP_WITH_ADJACENCY
+            | vk::PrimitiveTopology::TRIANGLE_FAN
+            | vk::PrimitiveTopology::TRIANGLE_STRIP
+            | vk::PrimitiveTopology::TRIANGLE_STRIP_WITH_ADJACENCY
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -842,6 +1245,88 @@ mod tests {
     type Info = GraphicPipelineInfo;
     type Builder = GraphicPipelineInfoBuilder;
 
+    // A single module containing empty "vs_main" vertex and "fs_main" fragment entry points
+    const VERTEX_FRAGMENT_SPIRV: [u32; 46] = [
+        0x07230203, 0x00010000, 0x00000000, 0x00000007, 0x00000000, // Header
+        0x00020011, 0x00000001, // OpCapability Shader
+        0x0003000e, 0x00000000, 0x00000001, // OpMemoryModel Logical GLSL450
+        0x0005000f, 0x00000000, 0x00000003, 0x6d5f7376, 0x006e6961, // OpEntryPoint Vertex
+        0x0005000f, 0x00000004, 0x00000004, 0x6d5f7366, 0x006e6961, // OpEntryPoint Fragment
+        0x00030010, 0x00000004, 0x00000007, // OpExecutionMode OriginUpperLeft
+        0x00020013, 0x00000001, // OpTypeVoid
+        0x00030021, 0x00000002, 0x00000001, // OpTypeFunction
+        0x00050036, 0x00000001, 0x00000003, 0x00000000, 0x00000002, // OpFunction (vs_main)
+        0x000200f8, 0x00000005, // OpLabel
+        0x000100fd, // OpReturn
+        0x00010038, // OpFunctionEnd
+        0x00050036, 0x00000001, 0x00000004, 0x00000000, 0x00000002, // OpFunction (fs_main)
+        0x000200f8, 0x00000006, // OpLabel
+        0x000100fd, // OpReturn
+        0x00010038, // OpFunctionEnd
+    ];
+
+    #[test]
+    pub fn vertex_binding_divisors() {
+        let vertex_input = |input_rate, divisor| VertexInputState {
+            vertex_binding_descriptions: vec![vk::VertexInputBindingDescription {
+                binding: 1,
+                stride: 16,
+                input_rate,
+            }],
+            vertex_binding_divisors: vec![vk::VertexInputBindingDivisorDescriptionEXT {
+                binding: 1,
+                divisor,
+            }],
+            vertex_attribute_descriptions: vec![],
+        };
+
+        assert!(validate_vertex_binding_divisors(
+            &vertex_input(vk::VertexInputRate::INSTANCE, 4),
+            false,
+            4
+        )
+        .is_ok());
+        assert!(matches!(
+            validate_vertex_binding_divisors(
+                &vertex_input(vk::VertexInputRate::VERTEX, 4),
+                false,
+                4
+            ),
+            Err(DriverError::InvalidData)
+        ));
+        assert!(matches!(
+            validate_vertex_binding_divisors(
+                &vertex_input(vk::VertexInputRate::INSTANCE, 5),
+                false,
+                4
+            ),
+            Err(DriverError::InvalidData)
+        ));
+        assert!(matches!(
+            validate_vertex_binding_divisors(
+                &vertex_input(vk::VertexInputRate::INSTANCE, 0),
+                false,
+                4
+            ),
+            Err(DriverError::Unsupported)
+        ));
+        assert!(validate_vertex_binding_divisors(
+            &vertex_input(vk::VertexInputRate::INSTANCE, 0),
+            true,
+            4
+        )
+        .is_ok());
+
+        // Divisors must apply to a binding of the vertex input layout
+        let mut missing_binding = vertex_input(vk::VertexInputRate::INSTANCE, 4);
+        missing_binding.vertex_binding_divisors[0].binding = 0;
+
+        assert!(matches!(
+            validate_vertex_binding_divisors(&missing_binding, false, 4),
+            Err(DriverError::InvalidData)
+        ));
+    }
+
     #[test]
     pub fn graphic_pipeline_info() {
         let info = Info::default();
@@ -857,4 +1342,78 @@ mod tests {
 
         assert_eq!(info, builder);
     }
+
+    #[test]
+    pub fn graphic_pipeline_info_builder_sample_shading() {
+        let info = Builder::default().sample_shading(0.5).build();
+
+        assert_eq!(info.sample_shading, Some(OrderedFloat(0.5)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Sample shading must be within 0.0..=1.0")]
+    pub fn graphic_pipeline_info_builder_sample_shading_1_5() {
+        Builder::default().sample_shading(1.5).build();
+    }
+
+    #[test]
+    pub fn shared_shader_module() {
+        let vert = Shader::new_vertex(VERTEX_FRAGMENT_SPIRV.as_slice())
+            .entry_name("vs_main".to_owned())
+            .build();
+        let frag = Shader::new_fragment(VERTEX_FRAGMENT_SPIRV.as_slice())
+            .entry_name("fs_main".to_owned())
+            .build();
+
+        // Same code with a larger id bound
+        let mut other_spirv = VERTEX_FRAGMENT_SPIRV;
+        other_spirv[3] = 8;
+        let other_frag = Shader::new_fragment(other_spirv.as_slice())
+            .entry_name("fs_main".to_owned())
+            .build();
+
+        assert_eq!(shader_module_indices(&[vert.clone(), frag.clone()]), [0, 0]);
+        assert_eq!(shader_module_indices(&[vert, other_frag, frag]), [0, 1, 0]);
+    }
+
+    #[test]
+    pub fn graphic_pipeline_info_blend_attachment() {
+        let info = Builder::default()
+            .blend(BlendMode::ALPHA)
+            .blend_attachment(1, BlendMode::REPLACE)
+            .build();
+
+        assert_eq!(info.attachment_blend(0), BlendMode::ALPHA);
+        assert_eq!(info.attachment_blend(1), BlendMode::REPLACE);
+        assert_eq!(info.attachment_blend(2), BlendMode::ALPHA);
+        assert_eq!(info.attachment_blend(8), BlendMode::ALPHA);
+    }
+
+    #[test]
+    pub fn graphic_pipeline_info_depth_stencil() {
+        let info = Builder::default()
+            .depth_stencil(DepthStencilMode::DEPTH_WRITE)
+            .build();
+
+        // Passes which do not call set_depth_stencil use the pipeline depth/stencil mode
+        let depth_stencil = info.depth_stencil_mode(None).unwrap();
+
+        assert!(depth_stencil.depth_test);
+        assert!(depth_stencil.depth_write);
+        assert_eq!(
+            info.depth_stencil_mode(Some(DepthStencilMode::DEPTH_READ)),
+            Some(DepthStencilMode::DEPTH_READ)
+        );
+        assert_eq!(Info::default().depth_stencil_mode(None), None);
+    }
+
+    #[test]
+    pub fn primitive_restart_topology() {
+        assert!(is_strip_or_fan(vk::PrimitiveTopology::LINE_STRIP));
+        assert!(is_strip_or_fan(vk::PrimitiveTopology::TRIANGLE_FAN));
+        assert!(is_strip_or_fan(vk::PrimitiveTopology::TRIANGLE_STRIP));
+        assert!(!is_strip_or_fan(vk::PrimitiveTopology::POINT_LIST));
+        assert!(!is_strip_or_fan(vk::PrimitiveTopology::TRIANGLE_LIST));
+        assert!(!is_strip_or_fan(vk::PrimitiveTopology::PATCH_LIST));
+    }
 }
@@ -2,7 +2,11 @@
 
 use {
     super::{
-        access_type_from_u8, access_type_into_u8, device::Device, format_aspect_mask, DriverError,
+        access_type_from_u8, access_type_into_u8,
+        device::Device,
+        format_aspect_mask,
+        shader::{SamplerYcbcrConversion, YcbcrConversionInfo},
+        DriverError,
     },
     ash::vk,
     derive_builder::{Builder, UninitializedFieldError},
@@ -61,7 +65,7 @@ use std::sync::Mutex;
 /// [fully qualified syntax]: https://doc.rust-lang.org/book/ch19-03-advanced-traits.html#fully-qualified-syntax-for-disambiguation-calling-methods-with-the-same-name
 pub struct Image {
     allocation: Option<Allocation>, // None when we don't own the image (Swapchain images)
-    pub(super) device: Arc<Device>,
+    pub(crate) device: Arc<Device>,
     image: vk::Image,
     #[allow(clippy::type_complexity)]
     image_view_cache: Mutex<HashMap<ImageViewInfo, ImageView>>,
@@ -73,11 +77,18 @@ pub struct Image {
     pub name: Option<String>,
 
     prev_access: AtomicU8,
+
+    // Some when created with the SPARSE_BINDING flag; each page owns a separate allocation
+    #[allow(clippy::type_complexity)]
+    pub(super) sparse_pages: Option<Mutex<HashMap<SparseImagePage, Allocation>>>,
 }
 
 impl Image {
     /// Creates a new image on the given device.
     ///
+    /// Images created using [`vk::ImageCreateFlags::SPARSE_BINDING`] are not bound to any memory;
+    /// use [`Device::bind_sparse`] to bind individual pages.
+    ///
     /// # Examples
     ///
     /// Basic usage:
@@ -111,6 +122,33 @@ impl Image {
             info.usage
         );
 
+        let is_sparse = info.flags.contains(vk::ImageCreateFlags::SPARSE_BINDING);
+
+        if is_sparse {
+            let features = &device.physical_device.features_v1_0;
+
+            if !features.sparse_binding {
+                warn!("sparse binding feature not supported");
+
+                return Err(DriverError::Unsupported);
+            }
+
+            if info.flags.contains(vk::ImageCreateFlags::SPARSE_RESIDENCY)
+                && !match info.ty {
+                    ImageType::Texture3D => features.sparse_residency_image3_d,
+                    ImageType::Texture1D | ImageType::TextureArray1D => false,
+                    _ => {
+                        features.sparse_residency_image2_d
+                            && info.sample_count == SampleCount::Type1
+                    }
+                }
+            {
+                warn!("sparse residency feature not supported for {:?}", info.ty);
+
+                return Err(DriverError::Unsupported);
+            }
+        }
+
         let device = Arc::clone(device);
         let create_info = info
             .image_create_info()
@@ -122,6 +160,20 @@ impl Image {
                 DriverError::Unsupported
             })?
         };
+
+        if is_sparse {
+            return Ok(Self {
+                allocation: None,
+                device,
+                image,
+                image_view_cache: Mutex::new(Default::default()),
+                info,
+                name: None,
+                prev_access: AtomicU8::new(access_type_into_u8(AccessType::Nothing)),
+                sparse_pages: Some(Mutex::new(Default::default())),
+            });
+        }
+
         let requirements = unsafe { device.get_image_memory_requirements(image) };
         let allocation = {
             profiling::scope!("allocate");
@@ -165,6 +217,7 @@ impl Image {
             info,
             name: None,
             prev_access: AtomicU8::new(access_type_into_u8(AccessType::Nothing)),
+            sparse_pages: None,
         })
     }
 
@@ -238,11 +291,12 @@ impl Image {
             info,
             name: this.name.clone(),
             prev_access: AtomicU8::new(access_type_into_u8(AccessType::Nothing)),
+            sparse_pages: None,
         }
     }
 
     #[profiling::function]
-    fn drop_allocation(this: &Self, allocation: Allocation) {
+    fn drop_allocations(this: &Self, allocations: impl IntoIterator<Item = Allocation>) {
         {
             profiling::scope!("views");
 
@@ -268,9 +322,12 @@ impl Image {
             #[cfg(not(feature = "parking_lot"))]
             let mut allocator = allocator.unwrap();
 
-            allocator.free(allocation)
+            for allocation in allocations {
+                allocator
+                    .free(allocation)
+                    .unwrap_or_else(|_| warn!("Unable to free image allocation"));
+            }
         }
-        .unwrap_or_else(|_| warn!("Unable to free image allocation"));
     }
 
     /// Consumes a Vulkan image created by some other library.
@@ -290,7 +347,52 @@ impl Image {
             info,
             name: None,
             prev_access: AtomicU8::new(access_type_into_u8(AccessType::Nothing)),
+            sparse_pages: None,
+        }
+    }
+
+    /// Returns the size, in bytes, of the device memory allocated for this object, including any
+    /// bound sparse pages.
+    pub(crate) fn memory_size(this: &Self) -> vk::DeviceSize {
+        let mut size = this
+            .allocation
+            .as_ref()
+            .map(Allocation::size)
+            .unwrap_or_default();
+
+        if let Some(sparse_pages) = &this.sparse_pages {
+            let sparse_pages = sparse_pages.lock();
+
+            #[cfg(not(feature = "parking_lot"))]
+            let sparse_pages = sparse_pages.unwrap();
+
+            size += sparse_pages
+                .values()
+                .map(Allocation::size)
+                .sum::<vk::DeviceSize>();
         }
+
+        size
+    }
+
+    /// Returns the memory requirements and page granularity of a sparse image.
+    ///
+    /// Returns `None` if the image was not created using [`vk::ImageCreateFlags::SPARSE_BINDING`].
+    #[profiling::function]
+    pub fn sparse_requirements(this: &Self) -> Option<SparseImageRequirements> {
+        this.sparse_pages.as_ref()?;
+
+        let (aspects, memory_requirements) = unsafe {
+            (
+                this.device.get_image_sparse_memory_requirements(this.image),
+                this.device.get_image_memory_requirements(this.image),
+            )
+        };
+
+        Some(SparseImageRequirements {
+            aspects,
+            memory_requirements,
+        })
     }
 
     #[profiling::function]
@@ -331,7 +433,7 @@ impl Deref for Image {
 }
 
 impl Drop for Image {
-    // This function is not profiled because drop_allocation is
+    // This function is not profiled because drop_allocations is
     fn drop(&mut self) {
         if panicking() {
             return;
@@ -340,7 +442,15 @@ impl Drop for Image {
         // When our allocation is some we allocated ourself; otherwise somebody
         // else owns this image and we should not destroy it. Usually it's the swapchain...
         if let Some(allocation) = self.allocation.take() {
-            Self::drop_allocation(self, allocation);
+            Self::drop_allocations(self, [allocation]);
+        } else if let Some(sparse_pages) = self.sparse_pages.take() {
+            #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+            let sparse_pages = sparse_pages.into_inner();
+
+            #[cfg(not(feature = "parking_lot"))]
+            let sparse_pages = sparse_pages.unwrap_or_else(|err| err.into_inner());
+
+            Self::drop_allocations(self, sparse_pages.into_values());
         }
     }
 }
@@ -406,12 +516,58 @@ pub struct ImageInfo {
 }
 
 impl ImageInfo {
+    /// Returns the number of Vulkan array layers of the image.
+    ///
+    /// Cube images contain six layers for each array element, one for each face.
+    pub fn array_layer_count(self) -> u32 {
+        match self.ty {
+            ImageType::Texture1D | ImageType::Texture3D => 1,
+            ImageType::Texture2D => {
+                if self.flags.contains(vk::ImageCreateFlags::CUBE_COMPATIBLE) {
+                    self.array_elements
+                } else {
+                    1
+                }
+            }
+            ImageType::TextureArray1D | ImageType::TextureArray2D => self.array_elements,
+            ImageType::Cube => 6,
+            ImageType::CubeArray => 6 * self.array_elements,
+        }
+    }
+
     /// Specifies a cube image.
+    ///
+    /// The image has six layers, one for each face, and is created using
+    /// [`vk::ImageCreateFlags::CUBE_COMPATIBLE`]. The default view of the image is a cube view;
+    /// individual faces may be rendered to by using a two-dimensional view of a single layer, see
+    /// [`ImageViewInfo::with_array_layers`].
     #[inline(always)]
     pub const fn cube(size: u32, fmt: vk::Format, usage: vk::ImageUsageFlags) -> ImageInfo {
         Self::new(ImageType::Cube, size, size, 1, 1, fmt, usage)
     }
 
+    /// Specifies a cube image array with six layers for each of the `array_elements` cubes.
+    ///
+    /// Cube array views require the
+    /// [`image_cube_array`](super::physical_device::Vulkan10Features::image_cube_array) feature.
+    #[inline(always)]
+    pub const fn cube_array(
+        size: u32,
+        array_elements: u32,
+        fmt: vk::Format,
+        usage: vk::ImageUsageFlags,
+    ) -> ImageInfo {
+        Self::new(
+            ImageType::CubeArray,
+            size,
+            size,
+            1,
+            array_elements,
+            fmt,
+            usage,
+        )
+    }
+
     /// Specifies a one-dimensional image.
     #[inline(always)]
     pub const fn image_1d(size: u32, fmt: vk::Format, usage: vk::ImageUsageFlags) -> ImageInfo {
@@ -450,6 +606,9 @@ impl ImageInfo {
     }
 
     /// Specifies a three-dimensional image.
+    ///
+    /// The default view of the image is a [`vk::ImageViewType::TYPE_3D`] view, which may be used
+    /// as a `sampler3D` or `image3D` shader descriptor.
     #[inline(always)]
     pub const fn image_3d(
         width: u32,
@@ -544,46 +703,24 @@ impl ImageInfo {
     }
 
     fn image_create_info<'a>(self) -> vk::ImageCreateInfo<'a> {
-        let (ty, extent, array_layers) = match self.ty {
-            ImageType::Texture1D => (
+        let (ty, extent, flags) = match self.ty {
+            ImageType::Texture1D | ImageType::TextureArray1D => (
                 vk::ImageType::TYPE_1D,
                 vk::Extent3D {
                     width: self.width,
                     height: 1,
                     depth: 1,
                 },
-                1,
+                self.flags,
             ),
-            ImageType::TextureArray1D => (
-                vk::ImageType::TYPE_1D,
-                vk::Extent3D {
-                    width: self.width,
-                    height: 1,
-                    depth: 1,
-                },
-                self.array_elements,
-            ),
-            ImageType::Texture2D => (
-                vk::ImageType::TYPE_2D,
-                vk::Extent3D {
-                    width: self.width,
-                    height: self.height,
-                    depth: 1,
-                },
-                if self.flags.contains(vk::ImageCreateFlags::CUBE_COMPATIBLE) {
-                    self.array_elements
-                } else {
-                    1
-                },
-            ),
-            ImageType::TextureArray2D => (
+            ImageType::Texture2D | ImageType::TextureArray2D => (
                 vk::ImageType::TYPE_2D,
                 vk::Extent3D {
                     width: self.width,
                     height: self.height,
                     depth: 1,
                 },
-                self.array_elements,
+                self.flags,
             ),
             ImageType::Texture3D => (
                 vk::ImageType::TYPE_3D,
@@ -592,35 +729,26 @@ impl ImageInfo {
                     height: self.height,
                     depth: self.depth,
                 },
-                1,
+                self.flags,
             ),
-            ImageType::Cube => (
+            ImageType::Cube | ImageType::CubeArray => (
                 vk::ImageType::TYPE_2D,
                 vk::Extent3D {
                     width: self.width,
                     height: self.height,
                     depth: 1,
                 },
-                6,
-            ),
-            ImageType::CubeArray => (
-                vk::ImageType::TYPE_2D,
-                vk::Extent3D {
-                    width: self.width,
-                    height: self.height,
-                    depth: 1,
-                },
-                6 * self.array_elements,
+                self.flags | vk::ImageCreateFlags::CUBE_COMPATIBLE,
             ),
         };
 
         vk::ImageCreateInfo::default()
-            .flags(self.flags)
+            .flags(flags)
             .image_type(ty)
             .format(self.fmt)
             .extent(extent)
             .mip_levels(self.mip_level_count)
-            .array_layers(array_layers)
+            .array_layers(self.array_layer_count())
             .samples(self.sample_count.into())
             .tiling(self.tiling)
             .usage(self.usage)
@@ -628,6 +756,17 @@ impl ImageInfo {
             .initial_layout(vk::ImageLayout::UNDEFINED)
     }
 
+    /// Adds the [`vk::ImageCreateFlags::SPARSE_BINDING`] and
+    /// [`vk::ImageCreateFlags::SPARSE_RESIDENCY`] flags, allowing pages of the image to be bound to
+    /// memory on demand.
+    ///
+    /// See [`Device::bind_sparse`].
+    #[inline(always)]
+    pub fn sparse_residency(mut self) -> Self {
+        self.flags |= vk::ImageCreateFlags::SPARSE_BINDING | vk::ImageCreateFlags::SPARSE_RESIDENCY;
+        self
+    }
+
     /// Converts an `ImageInfo` into an `ImageInfoBuilder`.
     #[inline(always)]
     pub fn to_builder(self) -> ImageInfoBuilder {
@@ -683,6 +822,66 @@ impl From<UninitializedFieldError> for ImageInfoBuilderError {
     }
 }
 
+/// Describes a page of a sparse image.
+///
+/// Page coordinates are given in units of the image granularity of the aspect, see
+/// [`SparseImageRequirements::granularity`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SparseImagePage {
+    /// The array layer of the page.
+    pub array_layer: u32,
+
+    /// The portion of the image which the page belongs to.
+    pub aspect_mask: vk::ImageAspectFlags,
+
+    /// The mip level of the page.
+    ///
+    /// Must be less than the first mip level of the mip tail.
+    pub mip_level: u32,
+
+    /// Page coordinate of the X axis.
+    pub x: u32,
+
+    /// Page coordinate of the Y axis.
+    pub y: u32,
+
+    /// Page coordinate of the Z axis.
+    pub z: u32,
+}
+
+/// Specifies an operation on a page of a sparse image.
+///
+/// See [`Device::bind_sparse`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SparseImageBinding {
+    /// Binds newly allocated memory to a page, making it resident.
+    Bind(SparseImagePage),
+
+    /// Unbinds and frees the memory of a page.
+    Unbind(SparseImagePage),
+}
+
+/// Memory requirements of a sparse image.
+#[derive(Clone, Debug)]
+pub struct SparseImageRequirements {
+    /// Sparse memory requirements of each aspect of the image, including the image granularity and
+    /// mip tail information.
+    pub aspects: Vec<vk::SparseImageMemoryRequirements>,
+
+    /// Memory requirements of the image; the alignment is the size of each page.
+    pub memory_requirements: vk::MemoryRequirements,
+}
+
+impl SparseImageRequirements {
+    /// Returns the size, in texels, of one page of the given aspect.
+    pub fn granularity(&self, aspect_mask: vk::ImageAspectFlags) -> Option<vk::Extent3D> {
+        self.aspects
+            .iter()
+            .find(|aspect| aspect.format_properties.aspect_mask.contains(aspect_mask))
+            .map(|aspect| aspect.format_properties.image_granularity)
+    }
+}
+
 /// Describes a subset of an image.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct ImageSubresource {
@@ -718,6 +917,20 @@ impl ImageSubresource {
     }
 }
 
+impl From<vk::ImageSubresourceRange> for ImageSubresource {
+    fn from(range: vk::ImageSubresourceRange) -> Self {
+        Self {
+            aspect_mask: range.aspect_mask,
+            base_mip_level: range.base_mip_level,
+            base_array_layer: range.base_array_layer,
+            array_layer_count: (range.layer_count != vk::REMAINING_ARRAY_LAYERS)
+                .then_some(range.layer_count),
+            mip_level_count: (range.level_count != vk::REMAINING_MIP_LEVELS)
+                .then_some(range.level_count),
+        }
+    }
+}
+
 impl From<ImageViewInfo> for ImageSubresource {
     fn from(info: ImageViewInfo) -> Self {
         Self {
@@ -780,6 +993,7 @@ impl From<ImageType> for vk::ImageType {
 struct ImageView {
     device: Arc<Device>,
     image_view: vk::ImageView,
+    _ycbcr_conversion: Option<SamplerYcbcrConversion>,
 }
 
 impl ImageView {
@@ -790,8 +1004,21 @@ impl ImageView {
         image: vk::Image,
     ) -> Result<Self, DriverError> {
         let info = info.into();
+
+        if info.ty == ImageType::CubeArray && !device.physical_device.features_v1_0.image_cube_array
+        {
+            warn!("image cube array feature not supported");
+
+            return Err(DriverError::Unsupported);
+        }
+
         let device = Arc::clone(device);
-        let create_info = vk::ImageViewCreateInfo::default()
+        let ycbcr_conversion = info
+            .ycbcr_conversion
+            .map(|ycbcr_conversion| SamplerYcbcrConversion::create(&device, ycbcr_conversion))
+            .transpose()?;
+        let mut ycbcr_conversion_info = vk::SamplerYcbcrConversionInfo::default();
+        let mut create_info = vk::ImageViewCreateInfo::default()
             .view_type(info.ty.into_vk())
             .format(info.fmt)
             .components(vk::ComponentMapping {
@@ -809,6 +1036,11 @@ impl ImageView {
                 layer_count: info.array_layer_count.unwrap_or(vk::REMAINING_ARRAY_LAYERS),
             });
 
+        if let Some(ycbcr_conversion) = &ycbcr_conversion {
+            ycbcr_conversion_info = ycbcr_conversion_info.conversion(**ycbcr_conversion);
+            create_info = create_info.push_next(&mut ycbcr_conversion_info);
+        }
+
         let image_view =
             unsafe { device.create_image_view(&create_info, None) }.map_err(|err| {
                 warn!("{err}");
@@ -816,7 +1048,11 @@ impl ImageView {
                 DriverError::Unsupported
             })?;
 
-        Ok(Self { device, image_view })
+        Ok(Self {
+            device,
+            image_view,
+            _ycbcr_conversion: ycbcr_conversion,
+        })
     }
 }
 
@@ -870,6 +1106,14 @@ pub struct ImageViewInfo {
 
     /// The basic dimensionality of the view.
     pub ty: ImageType,
+
+    /// Enables sampler Y′CBCR conversion, which is required in order to sample multi-planar
+    /// formats.
+    ///
+    /// The view must be sampled using an immutable combined image sampler with an identical
+    /// [`SamplerInfo::ycbcr_conversion`](super::shader::SamplerInfo::ycbcr_conversion) value.
+    #[builder(default, setter(strip_option))]
+    pub ycbcr_conversion: Option<YcbcrConversionInfo>,
 }
 
 impl ImageViewInfo {
@@ -888,6 +1132,7 @@ impl ImageViewInfo {
             fmt,
             mip_level_count: None,
             ty,
+            ycbcr_conversion: None,
         }
     }
 
@@ -902,9 +1147,24 @@ impl ImageViewInfo {
             fmt: Some(self.fmt),
             mip_level_count: Some(self.mip_level_count),
             ty: Some(self.ty),
+            ycbcr_conversion: Some(self.ycbcr_conversion),
         }
     }
 
+    /// Takes this instance and returns it with a newly specified range of array layers.
+    pub fn with_array_layers(mut self, base_array_layer: u32, array_layer_count: u32) -> Self {
+        self.base_array_layer = base_array_layer;
+        self.array_layer_count = Some(array_layer_count);
+        self
+    }
+
+    /// Takes this instance and returns it with a newly specified range of mip levels.
+    pub fn with_mip_levels(mut self, base_mip_level: u32, mip_level_count: u32) -> Self {
+        self.base_mip_level = base_mip_level;
+        self.mip_level_count = Some(mip_level_count);
+        self
+    }
+
     /// Takes this instance and returns it with a newly specified `ImageType`.
     pub fn with_ty(mut self, ty: ImageType) -> Self {
         self.ty = ty;
@@ -915,13 +1175,14 @@ impl ImageViewInfo {
 impl From<ImageInfo> for ImageViewInfo {
     fn from(info: ImageInfo) -> Self {
         Self {
-            array_layer_count: Some(info.array_elements),
+            array_layer_count: Some(info.array_layer_count()),
             aspect_mask: format_aspect_mask(info.fmt),
             base_array_layer: 0,
             base_mip_level: 0,
             fmt: info.fmt,
             mip_level_count: Some(info.mip_level_count),
             ty: info.ty,
+            ycbcr_conversion: None,
         }
     }
 }
@@ -1055,6 +1316,28 @@ mod tests {
         assert_eq!(info, builder);
     }
 
+    #[test]
+    pub fn image_info_cube_array() {
+        let info =
+            ImageInfo::cube_array(42, 3, vk::Format::R32_SFLOAT, vk::ImageUsageFlags::empty());
+        let builder = info.to_builder().build();
+
+        assert_eq!(info, builder);
+        assert_eq!(info.array_layer_count(), 18);
+    }
+
+    #[test]
+    pub fn image_info_cube_create_info() {
+        let info = ImageInfo::cube(42, vk::Format::R32_SFLOAT, vk::ImageUsageFlags::SAMPLED);
+        let create_info = info.image_create_info();
+
+        assert!(create_info
+            .flags
+            .contains(vk::ImageCreateFlags::CUBE_COMPATIBLE));
+        assert_eq!(create_info.array_layers, 6);
+        assert_eq!(info.default_view_info().array_layer_count, Some(6));
+    }
+
     #[test]
     pub fn image_info_image_1d() {
         let info = ImageInfo::image_1d(42, vk::Format::R32_SFLOAT, vk::ImageUsageFlags::empty());
@@ -1212,6 +1495,17 @@ mod tests {
             .build();
     }
 
+    #[test]
+    pub fn image_info_sparse_residency() {
+        let info =
+            ImageInfo::image_2d(42, 84, vk::Format::R32_SFLOAT, vk::ImageUsageFlags::empty())
+                .sparse_residency();
+
+        assert!(info.flags.contains(vk::ImageCreateFlags::SPARSE_BINDING));
+        assert!(info.flags.contains(vk::ImageCreateFlags::SPARSE_RESIDENCY));
+        assert_eq!(info, info.to_builder().build());
+    }
+
     #[test]
     pub fn image_view_info() {
         let info = ImageViewInfo::new(vk::Format::default(), ImageType::Texture1D);
@@ -1232,6 +1526,32 @@ mod tests {
         assert_eq!(info, builder);
     }
 
+    #[test]
+    pub fn image_view_info_single_layer_mip() {
+        let info = ImageInfo::image_2d_array(
+            64,
+            64,
+            4,
+            vk::Format::R8G8B8A8_UNORM,
+            vk::ImageUsageFlags::SAMPLED,
+        )
+        .to_builder()
+        .mip_level_count(5)
+        .build();
+        let view_info = info
+            .default_view_info()
+            .with_ty(ImageType::Texture2D)
+            .with_array_layers(2, 1)
+            .with_mip_levels(3, 1);
+        let subresource = ImageSubresource::from(view_info);
+
+        assert_eq!(subresource.base_array_layer, 2);
+        assert_eq!(subresource.array_layer_count, Some(1));
+        assert_eq!(subresource.base_mip_level, 3);
+        assert_eq!(subresource.mip_level_count, Some(1));
+        assert_ne!(view_info, info.default_view_info());
+    }
+
     #[test]
     #[should_panic(expected = "Field not initialized: aspect_mask")]
     pub fn image_view_info_builder_uninit_aspect_mask() {
@@ -1254,4 +1574,26 @@ mod tests {
             .fmt(vk::Format::default())
             .build();
     }
+
+    #[test]
+    pub fn image_subresource_range() {
+        let range = vk::ImageSubresourceRange {
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            base_mip_level: 1,
+            level_count: 2,
+            base_array_layer: 3,
+            layer_count: vk::REMAINING_ARRAY_LAYERS,
+        };
+        let subresource = ImageSubresource::from(range);
+
+        assert_eq!(subresource.array_layer_count, None);
+        assert_eq!(subresource.mip_level_count, Some(2));
+
+        let range = subresource.into_vk();
+
+        assert_eq!(range.base_mip_level, 1);
+        assert_eq!(range.level_count, 2);
+        assert_eq!(range.base_array_layer, 3);
+        assert_eq!(range.layer_count, vk::REMAINING_ARRAY_LAYERS);
+    }
 }
@@ -1,6 +1,6 @@
 use {
     super::{physical_device::PhysicalDevice, DriverError},
-    ash::{ext, vk, Entry},
+    ash::{ext, khr, vk, Entry},
     log::{debug, error, info, logger, trace, warn, Level, Metadata},
     std::{
         env::var,
@@ -91,6 +91,7 @@ pub struct Instance {
     debug_utils: Option<ext::debug_utils::Instance>,
     entry: Entry,
     instance: ash::Instance,
+    swapchain_colorspace: bool,
 }
 
 impl Instance {
@@ -117,10 +118,21 @@ impl Instance {
         let entry = ash_molten::load();
 
         let required_extensions = required_extensions.collect::<Vec<_>>();
+
+        // Wide-gamut and HDR color spaces are enabled whenever a surface may be presented
+        let swapchain_colorspace = required_extensions.contains(&khr::surface::NAME)
+            && unsafe { entry.enumerate_instance_extension_properties(None) }
+                .unwrap_or_default()
+                .iter()
+                .any(|property| {
+                    property.extension_name_as_c_str() == Ok(ext::swapchain_colorspace::NAME)
+                });
+
         let instance_extensions = required_extensions
             .iter()
             .map(|ext| ext.as_ptr())
             .chain(unsafe { Self::extension_names(debug).into_iter() })
+            .chain(swapchain_colorspace.then_some(ext::swapchain_colorspace::NAME.as_ptr()))
             .collect::<Box<[_]>>();
         let layer_names = Self::layer_names(debug);
         let layer_names: Vec<*const i8> = layer_names
@@ -187,6 +199,7 @@ impl Instance {
             debug_utils,
             entry,
             instance,
+            swapchain_colorspace,
         })
     }
 
@@ -208,6 +221,7 @@ impl Instance {
             debug_utils: None,
             entry,
             instance,
+            swapchain_colorspace: false,
         })
     }
 
@@ -233,6 +247,14 @@ impl Instance {
         this.debug_utils.is_some()
     }
 
+    /// Returns `true` if this instance was created with the `VK_EXT_swapchain_colorspace`
+    /// extension enabled.
+    ///
+    /// Instances loaded using [`Instance::load`] always return `false`.
+    pub fn is_swapchain_colorspace_enabled(this: &Self) -> bool {
+        this.swapchain_colorspace
+    }
+
     fn layer_names(debug: bool) -> Vec<CString> {
         let mut res = Vec::new();
 
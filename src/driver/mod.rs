@@ -33,6 +33,7 @@ pub mod device;
 pub mod graphic;
 pub mod image;
 pub mod physical_device;
+pub mod query;
 pub mod ray_trace;
 pub mod render_pass;
 pub mod shader;
@@ -45,9 +46,12 @@ mod descriptor_set_layout;
 mod instance;
 
 pub use {
-    self::{cmd_buf::CommandBuffer, instance::Instance},
+    self::{
+        cmd_buf::{CommandBuffer, SubmitToken},
+        instance::Instance,
+    },
     ash::{self},
-    vk_sync::AccessType,
+    vk_sync::{self, AccessType},
 };
 
 /// Specifying depth and stencil resolve modes.
@@ -233,20 +237,46 @@ pub(super) fn buffer_copy_subresources(
 #[allow(clippy::reversed_empty_ranges)]
 #[profiling::function]
 pub(super) fn buffer_image_copy_subresource(
+    fmt: vk::Format,
     regions: &[vk::BufferImageCopy],
 ) -> Range<vk::DeviceSize> {
     debug_assert!(!regions.is_empty());
 
+    let (block_width, block_height) = format_texel_block_extent(fmt);
+
     let mut res = vk::DeviceSize::MAX..vk::DeviceSize::MIN;
     for region in regions.iter() {
-        debug_assert_ne!(0, region.buffer_row_length);
-        debug_assert_ne!(0, region.buffer_image_height);
+        // Unknown formats are assumed to use the largest uncompressed texel size
+        let block_size = format_texel_block_size(fmt, region.image_subresource.aspect_mask)
+            .unwrap_or(16) as vk::DeviceSize;
+
+        // Zero row length or image height means the buffer is tightly packed
+        let row_length = if region.buffer_row_length == 0 {
+            region.image_extent.width
+        } else {
+            region.buffer_row_length
+        };
+        let image_height = if region.buffer_image_height == 0 {
+            region.image_extent.height
+        } else {
+            region.buffer_image_height
+        };
+
+        let row_size = row_length.div_ceil(block_width) as vk::DeviceSize * block_size;
+        let slice_size = image_height.div_ceil(block_height) as vk::DeviceSize * row_size;
+        let slice_count = region.image_extent.depth.max(1) as vk::DeviceSize
+            * region.image_subresource.layer_count.max(1) as vk::DeviceSize;
+        let last_row_size =
+            region.image_extent.width.div_ceil(block_width) as vk::DeviceSize * block_size;
+        let last_slice_size = (region.image_extent.height.div_ceil(block_height).max(1) - 1)
+            as vk::DeviceSize
+            * row_size
+            + last_row_size;
 
         res.start = res.start.min(region.buffer_offset);
-        res.end = res.end.max(
-            region.buffer_offset
-                + (region.buffer_row_length * region.buffer_image_height) as vk::DeviceSize,
-        );
+        res.end = res
+            .end
+            .max(region.buffer_offset + (slice_count - 1) * slice_size + last_slice_size);
     }
 
     debug_assert!(res.end > res.start);
@@ -273,6 +303,288 @@ pub(super) const fn format_aspect_mask(fmt: vk::Format) -> vk::ImageAspectFlags
     }
 }
 
+/// Returns the number of planes of the given format, which is one for all formats except the
+/// multi-planar Y′CBCR formats.
+pub(crate) const fn format_plane_count(fmt: vk::Format) -> u32 {
+    match fmt {
+        vk::Format::G8_B8R8_2PLANE_420_UNORM
+        | vk::Format::G8_B8R8_2PLANE_422_UNORM
+        | vk::Format::G8_B8R8_2PLANE_444_UNORM
+        | vk::Format::G10X6_B10X6R10X6_2PLANE_420_UNORM_3PACK16
+        | vk::Format::G10X6_B10X6R10X6_2PLANE_422_UNORM_3PACK16
+        | vk::Format::G10X6_B10X6R10X6_2PLANE_444_UNORM_3PACK16
+        | vk::Format::G12X4_B12X4R12X4_2PLANE_420_UNORM_3PACK16
+        | vk::Format::G12X4_B12X4R12X4_2PLANE_422_UNORM_3PACK16
+        | vk::Format::G12X4_B12X4R12X4_2PLANE_444_UNORM_3PACK16
+        | vk::Format::G16_B16R16_2PLANE_420_UNORM
+        | vk::Format::G16_B16R16_2PLANE_422_UNORM
+        | vk::Format::G16_B16R16_2PLANE_444_UNORM => 2,
+        vk::Format::G8_B8_R8_3PLANE_420_UNORM
+        | vk::Format::G8_B8_R8_3PLANE_422_UNORM
+        | vk::Format::G8_B8_R8_3PLANE_444_UNORM
+        | vk::Format::G10X6_B10X6_R10X6_3PLANE_420_UNORM_3PACK16
+        | vk::Format::G10X6_B10X6_R10X6_3PLANE_422_UNORM_3PACK16
+        | vk::Format::G10X6_B10X6_R10X6_3PLANE_444_UNORM_3PACK16
+        | vk::Format::G12X4_B12X4_R12X4_3PLANE_420_UNORM_3PACK16
+        | vk::Format::G12X4_B12X4_R12X4_3PLANE_422_UNORM_3PACK16
+        | vk::Format::G12X4_B12X4_R12X4_3PLANE_444_UNORM_3PACK16
+        | vk::Format::G16_B16_R16_3PLANE_420_UNORM
+        | vk::Format::G16_B16_R16_3PLANE_422_UNORM
+        | vk::Format::G16_B16_R16_3PLANE_444_UNORM => 3,
+        _ => 1,
+    }
+}
+
+/// Returns the width and height, in texels, of one texel block of the given format.
+pub const fn format_texel_block_extent(fmt: vk::Format) -> (u32, u32) {
+    match fmt {
+        vk::Format::BC1_RGB_UNORM_BLOCK
+        | vk::Format::BC1_RGB_SRGB_BLOCK
+        | vk::Format::BC1_RGBA_UNORM_BLOCK
+        | vk::Format::BC1_RGBA_SRGB_BLOCK
+        | vk::Format::BC2_UNORM_BLOCK
+        | vk::Format::BC2_SRGB_BLOCK
+        | vk::Format::BC3_UNORM_BLOCK
+        | vk::Format::BC3_SRGB_BLOCK
+        | vk::Format::BC4_UNORM_BLOCK
+        | vk::Format::BC4_SNORM_BLOCK
+        | vk::Format::BC5_UNORM_BLOCK
+        | vk::Format::BC5_SNORM_BLOCK
+        | vk::Format::BC6H_UFLOAT_BLOCK
+        | vk::Format::BC6H_SFLOAT_BLOCK
+        | vk::Format::BC7_UNORM_BLOCK
+        | vk::Format::BC7_SRGB_BLOCK
+        | vk::Format::ETC2_R8G8B8_UNORM_BLOCK
+        | vk::Format::ETC2_R8G8B8_SRGB_BLOCK
+        | vk::Format::ETC2_R8G8B8A1_UNORM_BLOCK
+        | vk::Format::ETC2_R8G8B8A1_SRGB_BLOCK
+        | vk::Format::ETC2_R8G8B8A8_UNORM_BLOCK
+        | vk::Format::ETC2_R8G8B8A8_SRGB_BLOCK
+        | vk::Format::EAC_R11_UNORM_BLOCK
+        | vk::Format::EAC_R11_SNORM_BLOCK
+        | vk::Format::EAC_R11G11_UNORM_BLOCK
+        | vk::Format::EAC_R11G11_SNORM_BLOCK
+        | vk::Format::ASTC_4X4_UNORM_BLOCK
+        | vk::Format::ASTC_4X4_SRGB_BLOCK => (4, 4),
+        vk::Format::ASTC_5X4_UNORM_BLOCK | vk::Format::ASTC_5X4_SRGB_BLOCK => (5, 4),
+        vk::Format::ASTC_5X5_UNORM_BLOCK | vk::Format::ASTC_5X5_SRGB_BLOCK => (5, 5),
+        vk::Format::ASTC_6X5_UNORM_BLOCK | vk::Format::ASTC_6X5_SRGB_BLOCK => (6, 5),
+        vk::Format::ASTC_6X6_UNORM_BLOCK | vk::Format::ASTC_6X6_SRGB_BLOCK => (6, 6),
+        vk::Format::ASTC_8X5_UNORM_BLOCK | vk::Format::ASTC_8X5_SRGB_BLOCK => (8, 5),
+        vk::Format::ASTC_8X6_UNORM_BLOCK | vk::Format::ASTC_8X6_SRGB_BLOCK => (8, 6),
+        vk::Format::ASTC_8X8_UNORM_BLOCK | vk::Format::ASTC_8X8_SRGB_BLOCK => (8, 8),
+        vk::Format::ASTC_10X5_UNORM_BLOCK | vk::Format::ASTC_10X5_SRGB_BLOCK => (10, 5),
+        vk::Format::ASTC_10X6_UNORM_BLOCK | vk::Format::ASTC_10X6_SRGB_BLOCK => (10, 6),
+        vk::Format::ASTC_10X8_UNORM_BLOCK | vk::Format::ASTC_10X8_SRGB_BLOCK => (10, 8),
+        vk::Format::ASTC_10X10_UNORM_BLOCK | vk::Format::ASTC_10X10_SRGB_BLOCK => (10, 10),
+        vk::Format::ASTC_12X10_UNORM_BLOCK | vk::Format::ASTC_12X10_SRGB_BLOCK => (12, 10),
+        vk::Format::ASTC_12X12_UNORM_BLOCK | vk::Format::ASTC_12X12_SRGB_BLOCK => (12, 12),
+        _ => (1, 1),
+    }
+}
+
+/// Returns the size, in bytes, of one texel block of the given format when copied to or from a
+/// buffer using `aspect_mask`.
+///
+/// Returns `None` for formats which are not known.
+pub const fn format_texel_block_size(
+    fmt: vk::Format,
+    aspect_mask: vk::ImageAspectFlags,
+) -> Option<u32> {
+    // Depth/stencil formats are copied one aspect at a time
+    if aspect_mask.as_raw() == vk::ImageAspectFlags::STENCIL.as_raw() {
+        return Some(1);
+    }
+
+    Some(match fmt {
+        vk::Format::R4G4_UNORM_PACK8
+        | vk::Format::R8_UNORM
+        | vk::Format::R8_SNORM
+        | vk::Format::R8_USCALED
+        | vk::Format::R8_SSCALED
+        | vk::Format::R8_UINT
+        | vk::Format::R8_SINT
+        | vk::Format::R8_SRGB
+        | vk::Format::S8_UINT => 1,
+        vk::Format::R4G4B4A4_UNORM_PACK16
+        | vk::Format::B4G4R4A4_UNORM_PACK16
+        | vk::Format::R5G6B5_UNORM_PACK16
+        | vk::Format::B5G6R5_UNORM_PACK16
+        | vk::Format::R5G5B5A1_UNORM_PACK16
+        | vk::Format::B5G5R5A1_UNORM_PACK16
+        | vk::Format::A1R5G5B5_UNORM_PACK16
+        | vk::Format::R8G8_UNORM
+        | vk::Format::R8G8_SNORM
+        | vk::Format::R8G8_USCALED
+        | vk::Format::R8G8_SSCALED
+        | vk::Format::R8G8_UINT
+        | vk::Format::R8G8_SINT
+        | vk::Format::R8G8_SRGB
+        | vk::Format::R16_UNORM
+        | vk::Format::R16_SNORM
+        | vk::Format::R16_USCALED
+        | vk::Format::R16_SSCALED
+        | vk::Format::R16_UINT
+        | vk::Format::R16_SINT
+        | vk::Format::R16_SFLOAT
+        | vk::Format::D16_UNORM
+        | vk::Format::D16_UNORM_S8_UINT => 2,
+        vk::Format::R8G8B8_UNORM
+        | vk::Format::R8G8B8_SNORM
+        | vk::Format::R8G8B8_USCALED
+        | vk::Format::R8G8B8_SSCALED
+        | vk::Format::R8G8B8_UINT
+        | vk::Format::R8G8B8_SINT
+        | vk::Format::R8G8B8_SRGB
+        | vk::Format::B8G8R8_UNORM
+        | vk::Format::B8G8R8_SNORM
+        | vk::Format::B8G8R8_USCALED
+        | vk::Format::B8G8R8_SSCALED
+        | vk::Format::B8G8R8_UINT
+        | vk::Format::B8G8R8_SINT
+        | vk::Format::B8G8R8_SRGB => 3,
+        vk::Format::R8G8B8A8_UNORM
+        | vk::Format::R8G8B8A8_SNORM
+        | vk::Format::R8G8B8A8_USCALED
+        | vk::Format::R8G8B8A8_SSCALED
+        | vk::Format::R8G8B8A8_UINT
+        | vk::Format::R8G8B8A8_SINT
+        | vk::Format::R8G8B8A8_SRGB
+        | vk::Format::B8G8R8A8_UNORM
+        | vk::Format::B8G8R8A8_SNORM
+        | vk::Format::B8G8R8A8_USCALED
+        | vk::Format::B8G8R8A8_SSCALED
+        | vk::Format::B8G8R8A8_UINT
+        | vk::Format::B8G8R8A8_SINT
+        | vk::Format::B8G8R8A8_SRGB
+        | vk::Format::A8B8G8R8_UNORM_PACK32
+        | vk::Format::A8B8G8R8_SNORM_PACK32
+        | vk::Format::A8B8G8R8_USCALED_PACK32
+        | vk::Format::A8B8G8R8_SSCALED_PACK32
+        | vk::Format::A8B8G8R8_UINT_PACK32
+        | vk::Format::A8B8G8R8_SINT_PACK32
+        | vk::Format::A8B8G8R8_SRGB_PACK32
+        | vk::Format::A2R10G10B10_UNORM_PACK32
+        | vk::Format::A2R10G10B10_SNORM_PACK32
+        | vk::Format::A2R10G10B10_USCALED_PACK32
+        | vk::Format::A2R10G10B10_SSCALED_PACK32
+        | vk::Format::A2R10G10B10_UINT_PACK32
+        | vk::Format::A2R10G10B10_SINT_PACK32
+        | vk::Format::A2B10G10R10_UNORM_PACK32
+        | vk::Format::A2B10G10R10_SNORM_PACK32
+        | vk::Format::A2B10G10R10_USCALED_PACK32
+        | vk::Format::A2B10G10R10_SSCALED_PACK32
+        | vk::Format::A2B10G10R10_UINT_PACK32
+        | vk::Format::A2B10G10R10_SINT_PACK32
+        | vk::Format::R16G16_UNORM
+        | vk::Format::R16G16_SNORM
+        | vk::Format::R16G16_USCALED
+        | vk::Format::R16G16_SSCALED
+        | vk::Format::R16G16_UINT
+        | vk::Format::R16G16_SINT
+        | vk::Format::R16G16_SFLOAT
+        | vk::Format::R32_UINT
+        | vk::Format::R32_SINT
+        | vk::Format::R32_SFLOAT
+        | vk::Format::B10G11R11_UFLOAT_PACK32
+        | vk::Format::E5B9G9R9_UFLOAT_PACK32
+        | vk::Format::X8_D24_UNORM_PACK32
+        | vk::Format::D32_SFLOAT
+        | vk::Format::D24_UNORM_S8_UINT
+        | vk::Format::D32_SFLOAT_S8_UINT => 4,
+        vk::Format::R16G16B16_UNORM
+        | vk::Format::R16G16B16_SNORM
+        | vk::Format::R16G16B16_USCALED
+        | vk::Format::R16G16B16_SSCALED
+        | vk::Format::R16G16B16_UINT
+        | vk::Format::R16G16B16_SINT
+        | vk::Format::R16G16B16_SFLOAT => 6,
+        vk::Format::R16G16B16A16_UNORM
+        | vk::Format::R16G16B16A16_SNORM
+        | vk::Format::R16G16B16A16_USCALED
+        | vk::Format::R16G16B16A16_SSCALED
+        | vk::Format::R16G16B16A16_UINT
+        | vk::Format::R16G16B16A16_SINT
+        | vk::Format::R16G16B16A16_SFLOAT
+        | vk::Format::R32G32_UINT
+        | vk::Format::R32G32_SINT
+        | vk::Format::R32G32_SFLOAT
+        | vk::Format::R64_UINT
+        | vk::Format::R64_SINT
+        | vk::Format::R64_SFLOAT
+        | vk::Format::BC1_RGB_UNORM_BLOCK
+        | vk::Format::BC1_RGB_SRGB_BLOCK
+        | vk::Format::BC1_RGBA_UNORM_BLOCK
+        | vk::Format::BC1_RGBA_SRGB_BLOCK
+        | vk::Format::BC4_UNORM_BLOCK
+        | vk::Format::BC4_SNORM_BLOCK
+        | vk::Format::ETC2_R8G8B8_UNORM_BLOCK
+        | vk::Format::ETC2_R8G8B8_SRGB_BLOCK
+        | vk::Format::ETC2_R8G8B8A1_UNORM_BLOCK
+        | vk::Format::ETC2_R8G8B8A1_SRGB_BLOCK
+        | vk::Format::EAC_R11_UNORM_BLOCK
+        | vk::Format::EAC_R11_SNORM_BLOCK => 8,
+        vk::Format::R32G32B32_UINT | vk::Format::R32G32B32_SINT | vk::Format::R32G32B32_SFLOAT => {
+            12
+        }
+        vk::Format::R32G32B32A32_UINT
+        | vk::Format::R32G32B32A32_SINT
+        | vk::Format::R32G32B32A32_SFLOAT
+        | vk::Format::R64G64_UINT
+        | vk::Format::R64G64_SINT
+        | vk::Format::R64G64_SFLOAT
+        | vk::Format::BC2_UNORM_BLOCK
+        | vk::Format::BC2_SRGB_BLOCK
+        | vk::Format::BC3_UNORM_BLOCK
+        | vk::Format::BC3_SRGB_BLOCK
+        | vk::Format::BC5_UNORM_BLOCK
+        | vk::Format::BC5_SNORM_BLOCK
+        | vk::Format::BC6H_UFLOAT_BLOCK
+        | vk::Format::BC6H_SFLOAT_BLOCK
+        | vk::Format::BC7_UNORM_BLOCK
+        | vk::Format::BC7_SRGB_BLOCK
+        | vk::Format::ETC2_R8G8B8A8_UNORM_BLOCK
+        | vk::Format::ETC2_R8G8B8A8_SRGB_BLOCK
+        | vk::Format::EAC_R11G11_UNORM_BLOCK
+        | vk::Format::EAC_R11G11_SNORM_BLOCK
+        | vk::Format::ASTC_4X4_UNORM_BLOCK
+        | vk::Format::ASTC_4X4_SRGB_BLOCK
+        | vk::Format::ASTC_5X4_UNORM_BLOCK
+        | vk::Format::ASTC_5X4_SRGB_BLOCK
+        | vk::Format::ASTC_5X5_UNORM_BLOCK
+        | vk::Format::ASTC_5X5_SRGB_BLOCK
+        | vk::Format::ASTC_6X5_UNORM_BLOCK
+        | vk::Format::ASTC_6X5_SRGB_BLOCK
+        | vk::Format::ASTC_6X6_UNORM_BLOCK
+        | vk::Format::ASTC_6X6_SRGB_BLOCK
+        | vk::Format::ASTC_8X5_UNORM_BLOCK
+        | vk::Format::ASTC_8X5_SRGB_BLOCK
+        | vk::Format::ASTC_8X6_UNORM_BLOCK
+        | vk::Format::ASTC_8X6_SRGB_BLOCK
+        | vk::Format::ASTC_8X8_UNORM_BLOCK
+        | vk::Format::ASTC_8X8_SRGB_BLOCK
+        | vk::Format::ASTC_10X5_UNORM_BLOCK
+        | vk::Format::ASTC_10X5_SRGB_BLOCK
+        | vk::Format::ASTC_10X6_UNORM_BLOCK
+        | vk::Format::ASTC_10X6_SRGB_BLOCK
+        | vk::Format::ASTC_10X8_UNORM_BLOCK
+        | vk::Format::ASTC_10X8_SRGB_BLOCK
+        | vk::Format::ASTC_10X10_UNORM_BLOCK
+        | vk::Format::ASTC_10X10_SRGB_BLOCK
+        | vk::Format::ASTC_12X10_UNORM_BLOCK
+        | vk::Format::ASTC_12X10_SRGB_BLOCK
+        | vk::Format::ASTC_12X12_UNORM_BLOCK
+        | vk::Format::ASTC_12X12_SRGB_BLOCK => 16,
+        vk::Format::R64G64B64_UINT | vk::Format::R64G64B64_SINT | vk::Format::R64G64B64_SFLOAT => {
+            24
+        }
+        vk::Format::R64G64B64A64_UINT
+        | vk::Format::R64G64B64A64_SINT
+        | vk::Format::R64G64B64A64_SFLOAT => 32,
+        _ => return None,
+    })
+}
+
 pub(super) const fn image_access_layout(access: AccessType) -> ImageLayout {
     if matches!(access, AccessType::Present | AccessType::ComputeShaderWrite) {
         ImageLayout::General
@@ -660,6 +972,11 @@ pub enum DriverError {
     ///
     /// Many drivers return this value for generic or unhandled error conditions.
     OutOfMemory,
+
+    /// The operation requires a device feature which is not enabled.
+    ///
+    /// See [`Device::supports`](device::Device::supports).
+    FeatureNotEnabled(device::DeviceFeature),
 }
 
 impl Display for DriverError {
@@ -672,7 +989,13 @@ impl Error for DriverError {}
 
 #[cfg(test)]
 mod tests {
-    use {super::merge_push_constant_ranges, ash::vk};
+    use {
+        super::{
+            buffer_image_copy_subresource, merge_push_constant_ranges, pipeline_stage_access_flags,
+            AccessType,
+        },
+        ash::vk,
+    };
 
     macro_rules! assert_pcr_eq {
         ($lhs: expr, $rhs: expr,) => {
@@ -985,4 +1308,90 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    pub fn buffer_image_copy_subresource_3d() {
+        let region = vk::BufferImageCopy {
+            buffer_offset: 64,
+            buffer_row_length: 4,
+            buffer_image_height: 4,
+            image_subresource: vk::ImageSubresourceLayers {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                mip_level: 0,
+                base_array_layer: 0,
+                layer_count: 1,
+            },
+            image_offset: vk::Offset3D { x: 0, y: 0, z: 0 },
+            image_extent: vk::Extent3D {
+                width: 4,
+                height: 4,
+                depth: 4,
+            },
+        };
+        let range = buffer_image_copy_subresource(vk::Format::R8G8B8A8_UNORM, &[region]);
+
+        assert_eq!(range, 64..64 + 4 * 4 * 4 * 4);
+    }
+
+    #[test]
+    pub fn buffer_image_copy_subresource_packed_compressed() {
+        let region = vk::BufferImageCopy {
+            buffer_offset: 0,
+            buffer_row_length: 0,
+            buffer_image_height: 0,
+            image_subresource: vk::ImageSubresourceLayers {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                mip_level: 0,
+                base_array_layer: 0,
+                layer_count: 2,
+            },
+            image_offset: vk::Offset3D { x: 0, y: 0, z: 0 },
+            image_extent: vk::Extent3D {
+                width: 6,
+                height: 6,
+                depth: 1,
+            },
+        };
+        let range = buffer_image_copy_subresource(vk::Format::BC1_RGBA_UNORM_BLOCK, &[region]);
+
+        // 2x2 blocks of 8 bytes for each of the two layers
+        assert_eq!(range, 0..2 * 2 * 2 * 8);
+    }
+
+    #[test]
+    pub fn buffer_image_copy_subresource_stencil() {
+        let region = vk::BufferImageCopy {
+            buffer_offset: 0,
+            buffer_row_length: 8,
+            buffer_image_height: 4,
+            image_subresource: vk::ImageSubresourceLayers {
+                aspect_mask: vk::ImageAspectFlags::STENCIL,
+                mip_level: 0,
+                base_array_layer: 0,
+                layer_count: 1,
+            },
+            image_offset: vk::Offset3D { x: 0, y: 0, z: 0 },
+            image_extent: vk::Extent3D {
+                width: 4,
+                height: 4,
+                depth: 1,
+            },
+        };
+        let range = buffer_image_copy_subresource(vk::Format::D24_UNORM_S8_UINT, &[region]);
+
+        assert_eq!(range, 0..3 * 8 + 4);
+    }
+
+    #[test]
+    pub fn pipeline_stage_access_flags_vertex_input() {
+        let (stage, access) = pipeline_stage_access_flags(AccessType::VertexBuffer);
+
+        assert_eq!(stage, vk::PipelineStageFlags::VERTEX_INPUT);
+        assert_eq!(access, vk::AccessFlags::VERTEX_ATTRIBUTE_READ);
+
+        let (stage, access) = pipeline_stage_access_flags(AccessType::IndexBuffer);
+
+        assert_eq!(stage, vk::PipelineStageFlags::VERTEX_INPUT);
+        assert_eq!(access, vk::AccessFlags::INDEX_READ);
+    }
 }
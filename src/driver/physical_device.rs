@@ -73,6 +73,30 @@ impl From<vk::PhysicalDeviceAccelerationStructurePropertiesKHR<'_>>
     }
 }
 
+/// Features of the physical device for conditional rendering.
+///
+/// See
+/// [`VkPhysicalDeviceConditionalRenderingFeaturesEXT`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDeviceConditionalRenderingFeaturesEXT.html)
+/// manual page.
+#[derive(Debug, Default)]
+pub struct ConditionalRenderingFeatures {
+    /// Indicates whether the implementation supports conditional rendering.
+    pub conditional_rendering: bool,
+
+    /// Indicates whether a secondary command buffer can be executed while conditional rendering
+    /// is active in the primary command buffer.
+    pub inherited_conditional_rendering: bool,
+}
+
+impl From<vk::PhysicalDeviceConditionalRenderingFeaturesEXT<'_>> for ConditionalRenderingFeatures {
+    fn from(features: vk::PhysicalDeviceConditionalRenderingFeaturesEXT<'_>) -> Self {
+        Self {
+            conditional_rendering: features.conditional_rendering == vk::TRUE,
+            inherited_conditional_rendering: features.inherited_conditional_rendering == vk::TRUE,
+        }
+    }
+}
+
 /// Structure describing depth/stencil resolve properties that can be supported by an
 /// implementation.
 ///
@@ -138,12 +162,66 @@ impl From<vk::PhysicalDeviceIndexTypeUint8FeaturesEXT<'_>> for IndexTypeUint8Fea
     }
 }
 
+/// Features of the physical device for instanced rendering.
+///
+/// See
+/// [`VkPhysicalDeviceVertexAttributeDivisorFeaturesEXT`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDeviceVertexAttributeDivisorFeaturesEXT.html)
+/// manual page.
+#[derive(Debug, Default)]
+pub struct VertexAttributeDivisorFeatures {
+    /// Indicates that vertex attribute fetching may be repeated in case of instanced rendering.
+    pub vertex_attribute_instance_rate_divisor: bool,
+
+    /// Indicates that a divisor of zero is supported, which repeats the first vertex attribute
+    /// for all instances.
+    pub vertex_attribute_instance_rate_zero_divisor: bool,
+}
+
+impl From<vk::PhysicalDeviceVertexAttributeDivisorFeaturesEXT<'_>>
+    for VertexAttributeDivisorFeatures
+{
+    fn from(features: vk::PhysicalDeviceVertexAttributeDivisorFeaturesEXT<'_>) -> Self {
+        Self {
+            vertex_attribute_instance_rate_divisor: features.vertex_attribute_instance_rate_divisor
+                == vk::TRUE,
+            vertex_attribute_instance_rate_zero_divisor: features
+                .vertex_attribute_instance_rate_zero_divisor
+                == vk::TRUE,
+        }
+    }
+}
+
+/// Properties of the physical device for instanced rendering.
+///
+/// See
+/// [`VkPhysicalDeviceVertexAttributeDivisorPropertiesEXT`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDeviceVertexAttributeDivisorPropertiesEXT.html)
+/// manual page.
+#[derive(Debug)]
+pub struct VertexAttributeDivisorProperties {
+    /// The maximum value of the number of instances that will repeat the value of vertex attribute
+    /// data when instanced rendering is enabled.
+    pub max_vertex_attrib_divisor: u32,
+}
+
+impl From<vk::PhysicalDeviceVertexAttributeDivisorPropertiesEXT<'_>>
+    for VertexAttributeDivisorProperties
+{
+    fn from(properties: vk::PhysicalDeviceVertexAttributeDivisorPropertiesEXT<'_>) -> Self {
+        Self {
+            max_vertex_attrib_divisor: properties.max_vertex_attrib_divisor,
+        }
+    }
+}
+
 /// Structure which holds data about the physical hardware selected by the current device.
 pub struct PhysicalDevice {
     /// Describes the properties of the device which relate to acceleration structures, if
     /// available.
     pub accel_struct_properties: Option<AccelerationStructureProperties>,
 
+    /// Describes the features of the device which relate to conditional rendering, if available.
+    pub conditional_rendering_features: ConditionalRenderingFeatures,
+
     /// Describes the properties of the device which relate to depth/stencil resolve operations.
     pub depth_stencil_resolve_properties: DepthStencilResolveProperties,
 
@@ -162,6 +240,8 @@ pub struct PhysicalDevice {
     /// Memory properties of the physical device.
     pub memory_properties: vk::PhysicalDeviceMemoryProperties,
 
+    pub(crate) supports_memory_budget: bool,
+
     /// Device properties of the physical device which are part of the Vulkan 1.0 base feature set.
     pub properties_v1_0: Vulkan10Properties,
 
@@ -175,6 +255,9 @@ pub struct PhysicalDevice {
 
     physical_device: vk::PhysicalDevice,
 
+    /// Describes the properties of the device which relate to push descriptors, if available.
+    pub push_descriptor_properties: Option<PushDescriptorProperties>,
+
     /// Describes the queues offered by this physical device.
     pub queue_families: Box<[vk::QueueFamilyProperties]>,
 
@@ -191,6 +274,12 @@ pub struct PhysicalDevice {
 
     /// Describes the properties of the device which relate to min/max sampler filtering.
     pub sampler_filter_minmax_properties: SamplerFilterMinmaxProperties,
+
+    /// Describes the features of the device which relate to instanced rendering, if available.
+    pub vertex_attribute_divisor_features: VertexAttributeDivisorFeatures,
+
+    /// Describes the properties of the device which relate to instanced rendering, if available.
+    pub vertex_attribute_divisor_properties: Option<VertexAttributeDivisorProperties>,
 }
 
 impl PhysicalDevice {
@@ -230,16 +319,22 @@ impl PhysicalDevice {
         let mut features_v1_2 = vk::PhysicalDeviceVulkan12Features::default();
         let mut acceleration_structure_features =
             vk::PhysicalDeviceAccelerationStructureFeaturesKHR::default();
+        let mut conditional_rendering_features =
+            vk::PhysicalDeviceConditionalRenderingFeaturesEXT::default();
         let mut index_type_u8_features = vk::PhysicalDeviceIndexTypeUint8FeaturesEXT::default();
         let mut ray_query_features = vk::PhysicalDeviceRayQueryFeaturesKHR::default();
         let mut ray_trace_features = vk::PhysicalDeviceRayTracingPipelineFeaturesKHR::default();
+        let mut vertex_attribute_divisor_features =
+            vk::PhysicalDeviceVertexAttributeDivisorFeaturesEXT::default();
         let mut features = vk::PhysicalDeviceFeatures2::default()
             .push_next(&mut features_v1_1)
             .push_next(&mut features_v1_2)
             .push_next(&mut acceleration_structure_features)
+            .push_next(&mut conditional_rendering_features)
             .push_next(&mut index_type_u8_features)
             .push_next(&mut ray_query_features)
-            .push_next(&mut ray_trace_features);
+            .push_next(&mut ray_trace_features)
+            .push_next(&mut vertex_attribute_divisor_features);
         unsafe {
             get_physical_device_features2(physical_device, &mut features);
         }
@@ -254,16 +349,22 @@ impl PhysicalDevice {
             vk::PhysicalDeviceAccelerationStructurePropertiesKHR::default();
         let mut depth_stencil_resolve_properties =
             vk::PhysicalDeviceDepthStencilResolveProperties::default();
+        let mut push_descriptor_properties =
+            vk::PhysicalDevicePushDescriptorPropertiesKHR::default();
         let mut ray_trace_properties = vk::PhysicalDeviceRayTracingPipelinePropertiesKHR::default();
         let mut sampler_filter_minmax_properties =
             vk::PhysicalDeviceSamplerFilterMinmaxProperties::default();
+        let mut vertex_attribute_divisor_properties =
+            vk::PhysicalDeviceVertexAttributeDivisorPropertiesEXT::default();
         let mut properties = vk::PhysicalDeviceProperties2::default()
             .push_next(&mut properties_v1_1)
             .push_next(&mut properties_v1_2)
             .push_next(&mut accel_struct_properties)
             .push_next(&mut depth_stencil_resolve_properties)
+            .push_next(&mut push_descriptor_properties)
             .push_next(&mut ray_trace_properties)
-            .push_next(&mut sampler_filter_minmax_properties);
+            .push_next(&mut sampler_filter_minmax_properties)
+            .push_next(&mut vertex_attribute_divisor_properties);
         unsafe {
             get_physical_device_properties2(physical_device, &mut properties);
         }
@@ -306,11 +407,19 @@ impl PhysicalDevice {
             .collect::<HashSet<_>>();
         let supports_accel_struct = extensions.contains(khr::acceleration_structure::NAME)
             && extensions.contains(khr::deferred_host_operations::NAME);
+        let supports_conditional_rendering = extensions.contains(ext::conditional_rendering::NAME);
         let supports_index_type_uint8 = extensions.contains(ext::index_type_uint8::NAME);
+        let supports_memory_budget = extensions.contains(ext::memory_budget::NAME);
+        let supports_push_descriptor = extensions.contains(khr::push_descriptor::NAME);
         let supports_ray_query = extensions.contains(khr::ray_query::NAME);
         let supports_ray_trace = extensions.contains(khr::ray_tracing_pipeline::NAME);
+        let supports_vertex_attribute_divisor =
+            extensions.contains(ext::vertex_attribute_divisor::NAME);
 
         // Gather optional features and properties of the physical device
+        let conditional_rendering_features = supports_conditional_rendering
+            .then(|| conditional_rendering_features.into())
+            .unwrap_or_default();
         let index_type_uint8_features = supports_index_type_uint8
             .then(|| index_type_u8_features.into())
             .unwrap_or_default();
@@ -320,11 +429,19 @@ impl PhysicalDevice {
         let ray_trace_features = supports_ray_trace
             .then(|| ray_trace_features.into())
             .unwrap_or_default();
+        let vertex_attribute_divisor_features = supports_vertex_attribute_divisor
+            .then(|| vertex_attribute_divisor_features.into())
+            .unwrap_or_default();
         let accel_struct_properties = supports_accel_struct.then(|| accel_struct_properties.into());
         let ray_trace_properties = supports_ray_trace.then(|| ray_trace_properties.into());
+        let push_descriptor_properties =
+            supports_push_descriptor.then(|| push_descriptor_properties.into());
+        let vertex_attribute_divisor_properties =
+            supports_vertex_attribute_divisor.then(|| vertex_attribute_divisor_properties.into());
 
         Ok(Self {
             accel_struct_properties,
+            conditional_rendering_features,
             depth_stencil_resolve_properties,
             features_v1_0,
             features_v1_1,
@@ -335,12 +452,16 @@ impl PhysicalDevice {
             properties_v1_0,
             properties_v1_1,
             properties_v1_2,
+            push_descriptor_properties,
             queue_families,
             queue_family_indices,
             ray_query_features,
             ray_trace_features,
             ray_trace_properties,
             sampler_filter_minmax_properties,
+            supports_memory_budget,
+            vertex_attribute_divisor_features,
+            vertex_attribute_divisor_properties,
         })
     }
 }
@@ -363,6 +484,26 @@ impl Deref for PhysicalDevice {
     }
 }
 
+/// Properties of the physical device for push descriptors.
+///
+/// See
+/// [`VkPhysicalDevicePushDescriptorPropertiesKHR`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDevicePushDescriptorPropertiesKHR.html)
+/// manual page.
+#[derive(Debug)]
+pub struct PushDescriptorProperties {
+    /// The maximum number of descriptors that can be used in a descriptor set layout created with
+    /// `VK_DESCRIPTOR_SET_LAYOUT_CREATE_PUSH_DESCRIPTOR_BIT_KHR` set.
+    pub max_push_descriptors: u32,
+}
+
+impl From<vk::PhysicalDevicePushDescriptorPropertiesKHR<'_>> for PushDescriptorProperties {
+    fn from(properties: vk::PhysicalDevicePushDescriptorPropertiesKHR<'_>) -> Self {
+        Self {
+            max_push_descriptors: properties.max_push_descriptors,
+        }
+    }
+}
+
 /// Features of the physical device for ray query.
 ///
 /// See
@@ -791,6 +932,18 @@ pub struct Vulkan10Features {
     /// used to check for supported properties of individual formats as normal.
     pub texture_compression_bc: bool,
 
+    /// Specifies whether occlusion queries returning actual sample counts are supported.
+    ///
+    /// If this feature is not enabled, `vk::QueryControlFlags::PRECISE` must not be used when
+    /// beginning occlusion queries.
+    pub occlusion_query_precise: bool,
+
+    /// Specifies whether the pipeline statistics queries are supported.
+    ///
+    /// If this feature is not enabled, query pools of type
+    /// [`vk::QueryType::PIPELINE_STATISTICS`] must not be created.
+    pub pipeline_statistics_query: bool,
+
     /// Specifies whether storage buffers and images support stores and atomic operations in the
     /// vertex, tessellation, and geometry shader stages.
     ///
@@ -997,6 +1150,27 @@ pub struct Vulkan10Features {
     /// This also specifies whether shader modules can declare the `MinLod` capability.
     pub shader_resource_min_lod: bool,
 
+    /// Specifies whether resource memory can be managed at opaque sparse block level instead of at
+    /// the object level.
+    ///
+    /// If this feature is not enabled, resource memory must be bound only on a per-object basis
+    /// and images must not be created using [`vk::ImageCreateFlags::SPARSE_BINDING`].
+    pub sparse_binding: bool,
+
+    /// Specifies whether the device can access partially resident 2D images with 1 sample per
+    /// pixel.
+    ///
+    /// If this feature is not enabled, images with an image type of `VK_IMAGE_TYPE_2D` and
+    /// `samples` set to `VK_SAMPLE_COUNT_1_BIT` must not be created with
+    /// [`vk::ImageCreateFlags::SPARSE_RESIDENCY`].
+    pub sparse_residency_image2_d: bool,
+
+    /// Specifies whether the device can access partially resident 3D images.
+    ///
+    /// If this feature is not enabled, images with an image type of `VK_IMAGE_TYPE_3D` must not be
+    /// created with [`vk::ImageCreateFlags::SPARSE_RESIDENCY`].
+    pub sparse_residency_image3_d: bool,
+
     /// Specifies whether all pipelines that will be bound to a command buffer during a subpass
     /// which uses no attachments must have the same value for
     /// `VkPipelineMultisampleStateCreateInfo::rasterizationSamples`.
@@ -1010,16 +1184,11 @@ pub struct Vulkan10Features {
     /// This has no effect in situations where a subpass uses any attachments.
     pub variable_multisample_rate: bool,
     // Unsupported (queries):
-    // pub occlusion_query_precise: bool,
-    // pub pipeline_statistics_query: bool,
     // pub inherited_queries: bool,
 
     // Unsupported (sparse residency):
     // pub shader_resource_residency: bool,
-    // pub sparse_binding: bool,
     // pub sparse_residency_buffer: bool,
-    // pub sparse_residency_image2_d: bool,
-    // pub sparse_residency_image3_d: bool,
     // pub sparse_residency2_samples: bool,
     // pub sparse_residency4_samples: bool,
     // pub sparse_residency8_samples: bool,
@@ -1053,6 +1222,8 @@ impl From<vk::PhysicalDeviceFeatures> for Vulkan10Features {
             texture_compression_etc2: features.texture_compression_etc2 == vk::TRUE,
             texture_compression_astc_ldr: features.texture_compression_astc_ldr == vk::TRUE,
             texture_compression_bc: features.texture_compression_bc == vk::TRUE,
+            occlusion_query_precise: features.occlusion_query_precise == vk::TRUE,
+            pipeline_statistics_query: features.pipeline_statistics_query == vk::TRUE,
             vertex_pipeline_stores_and_atomics: features.vertex_pipeline_stores_and_atomics
                 == vk::TRUE,
             fragment_stores_and_atomics: features.fragment_stores_and_atomics == vk::TRUE,
@@ -1087,6 +1258,9 @@ impl From<vk::PhysicalDeviceFeatures> for Vulkan10Features {
             shader_int64: features.shader_int64 == vk::TRUE,
             shader_int16: features.shader_int16 == vk::TRUE,
             shader_resource_min_lod: features.shader_resource_min_lod == vk::TRUE,
+            sparse_binding: features.sparse_binding == vk::TRUE,
+            sparse_residency_image2_d: features.sparse_residency_image2_d == vk::TRUE,
+            sparse_residency_image3_d: features.sparse_residency_image3_d == vk::TRUE,
             variable_multisample_rate: features.variable_multisample_rate == vk::TRUE,
         }
     }
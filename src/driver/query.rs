@@ -0,0 +1,519 @@
+//! Query pool resource types
+
+use {
+    super::{
+        device::{Device, DeviceFeature},
+        DriverError,
+    },
+    ash::vk,
+    derive_builder::{Builder, UninitializedFieldError},
+    log::warn,
+    std::{mem::size_of, ops::Deref, sync::Arc, thread::panicking},
+};
+
+/// Smart pointer handle to a [query pool] object.
+///
+/// Also contains information about the object.
+///
+/// ## `Deref` behavior
+///
+/// `QueryPool` automatically dereferences to [`vk::QueryPool`] (via the [`Deref`] trait), so you
+/// can call `vk::QueryPool`'s methods on a value of type `QueryPool`. To avoid name clashes with
+/// `vk::QueryPool`'s methods, the methods of `QueryPool` itself are associated functions, called
+/// using [fully qualified syntax]:
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use ash::vk;
+/// # use screen_13::driver::DriverError;
+/// # use screen_13::driver::device::{Device, DeviceInfo};
+/// # use screen_13::driver::query::{QueryPool, QueryPoolInfo};
+/// # fn main() -> Result<(), DriverError> {
+/// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+/// # let info = QueryPoolInfo::new(vk::QueryType::TIMESTAMP, 2);
+/// # let my_query_pool = QueryPool::create(&device, info)?;
+/// let mut results = [0u64; 2];
+/// QueryPool::results(&my_query_pool, 0, &mut results)?;
+/// # Ok(()) }
+/// ```
+///
+/// [query pool]: https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkQueryPool.html
+/// [deref]: core::ops::Deref
+/// [fully qualified syntax]: https://doc.rust-lang.org/book/ch19-03-advanced-traits.html#fully-qualified-syntax-for-disambiguation-calling-methods-with-the-same-name
+#[derive(Debug)]
+pub struct QueryPool {
+    device: Arc<Device>,
+
+    /// Information used to create this object.
+    pub info: QueryPoolInfo,
+
+    query_pool: vk::QueryPool,
+}
+
+impl QueryPool {
+    /// Creates a new query pool on the given device.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::query::{QueryPool, QueryPoolInfo};
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// let info = QueryPoolInfo::new(vk::QueryType::TIMESTAMP, 2);
+    /// let query_pool = QueryPool::create(&device, info)?;
+    ///
+    /// assert_ne!(*query_pool, vk::QueryPool::null());
+    /// assert_eq!(query_pool.info.query_count, 2);
+    /// # Ok(()) }
+    /// ```
+    #[profiling::function]
+    pub fn create(
+        device: &Arc<Device>,
+        info: impl Into<QueryPoolInfo>,
+    ) -> Result<Self, DriverError> {
+        let device = Arc::clone(device);
+        let info = info.into();
+
+        if info.ty == vk::QueryType::PIPELINE_STATISTICS
+            && !Device::supports(&device, DeviceFeature::PipelineStatisticsQuery)
+        {
+            warn!("pipeline statistics query feature not enabled");
+
+            return Err(DriverError::FeatureNotEnabled(
+                DeviceFeature::PipelineStatisticsQuery,
+            ));
+        }
+
+        let query_pool = unsafe {
+            device.create_query_pool(
+                &vk::QueryPoolCreateInfo::default()
+                    .query_type(info.ty)
+                    .query_count(info.query_count)
+                    .pipeline_statistics(info.pipeline_statistics),
+                None,
+            )
+        }
+        .map_err(|err| {
+            warn!("{err}");
+
+            DriverError::OutOfMemory
+        })?;
+
+        Ok(Self {
+            device,
+            info,
+            query_pool,
+        })
+    }
+
+    /// Reads the result of a [`vk::QueryType::PIPELINE_STATISTICS`] query.
+    ///
+    /// This function blocks until the query result is available. Only statistics enabled by
+    /// [`QueryPoolInfo::pipeline_statistics`] are returned.
+    #[profiling::function]
+    pub fn pipeline_statistics(this: &Self, query: u32) -> Result<PipelineStatistics, DriverError> {
+        debug_assert_eq!(this.info.ty, vk::QueryType::PIPELINE_STATISTICS);
+        debug_assert!(
+            query < this.info.query_count,
+            "query exceeds query pool size"
+        );
+
+        // Each enabled statistic is written as a separate value
+        let mut results = [0u64; PipelineStatistics::STATISTIC_COUNT];
+        let result_count = this.info.pipeline_statistics.as_raw().count_ones() as usize;
+        let stride = (result_count * size_of::<u64>()) as vk::DeviceSize;
+
+        unsafe {
+            (this.device.fp_v1_0().get_query_pool_results)(
+                this.device.handle(),
+                this.query_pool,
+                query,
+                1,
+                stride as _,
+                results.as_mut_ptr() as *mut _,
+                stride,
+                vk::QueryResultFlags::TYPE_64 | vk::QueryResultFlags::WAIT,
+            )
+        }
+        .result()
+        .map_err(|err| {
+            warn!("{err}");
+
+            match err {
+                vk::Result::ERROR_DEVICE_LOST => DriverError::InvalidData,
+                _ => DriverError::OutOfMemory,
+            }
+        })?;
+
+        Ok(PipelineStatistics::from_results(
+            this.info.pipeline_statistics,
+            &results[0..result_count],
+        ))
+    }
+
+    /// Resets `query_count` queries starting at `first_query` from the host.
+    ///
+    /// Queries must be reset before they are written and must not be in use by any pending
+    /// command buffer.
+    ///
+    /// Returns [`DriverError::FeatureNotEnabled`] if the device does not support
+    /// [`DeviceFeature::HostQueryReset`]; in that case queries must be reset using
+    /// `vkCmdResetQueryPool` outside of a render pass instead.
+    #[profiling::function]
+    pub fn reset(this: &Self, first_query: u32, query_count: u32) -> Result<(), DriverError> {
+        debug_assert!(
+            first_query + query_count <= this.info.query_count,
+            "query range exceeds query pool size"
+        );
+
+        if !Device::supports(&this.device, DeviceFeature::HostQueryReset) {
+            warn!("host query reset not supported");
+
+            return Err(DriverError::FeatureNotEnabled(
+                DeviceFeature::HostQueryReset,
+            ));
+        }
+
+        unsafe {
+            this.device
+                .reset_query_pool(this.query_pool, first_query, query_count);
+        }
+
+        Ok(())
+    }
+
+    /// Reads the 64-bit results of `results.len()` queries starting at `first_query`.
+    ///
+    /// This function blocks until all requested query results are available; the commands which
+    /// write the queries must have been submitted before calling this function.
+    #[profiling::function]
+    pub fn results(this: &Self, first_query: u32, results: &mut [u64]) -> Result<(), DriverError> {
+        debug_assert!(
+            first_query as usize + results.len() <= this.info.query_count as usize,
+            "query range exceeds query pool size"
+        );
+
+        unsafe {
+            this.device.get_query_pool_results(
+                this.query_pool,
+                first_query,
+                results,
+                vk::QueryResultFlags::TYPE_64 | vk::QueryResultFlags::WAIT,
+            )
+        }
+        .map_err(|err| {
+            warn!("{err}");
+
+            match err {
+                vk::Result::ERROR_DEVICE_LOST => DriverError::InvalidData,
+                _ => DriverError::OutOfMemory,
+            }
+        })
+    }
+
+    /// Reads the 64-bit results of `results.len()` queries starting at `first_query` without
+    /// blocking.
+    ///
+    /// Queries which are not yet available are returned as `None`.
+    #[profiling::function]
+    pub fn try_results(
+        this: &Self,
+        first_query: u32,
+        results: &mut [Option<u64>],
+    ) -> Result<(), DriverError> {
+        debug_assert!(
+            first_query as usize + results.len() <= this.info.query_count as usize,
+            "query range exceeds query pool size"
+        );
+
+        // Each result is followed by an availability value
+        let mut data = vec![[0u64; 2]; results.len()];
+
+        match unsafe {
+            this.device.get_query_pool_results(
+                this.query_pool,
+                first_query,
+                &mut data,
+                vk::QueryResultFlags::TYPE_64 | vk::QueryResultFlags::WITH_AVAILABILITY,
+            )
+        } {
+            Ok(_) | Err(vk::Result::NOT_READY) => (),
+            Err(err) => {
+                warn!("{err}");
+
+                return Err(match err {
+                    vk::Result::ERROR_DEVICE_LOST => DriverError::InvalidData,
+                    _ => DriverError::OutOfMemory,
+                });
+            }
+        }
+
+        for (result, [value, available]) in results.iter_mut().zip(data) {
+            *result = (available != 0).then_some(value);
+        }
+
+        Ok(())
+    }
+}
+
+impl Deref for QueryPool {
+    type Target = vk::QueryPool;
+
+    fn deref(&self) -> &Self::Target {
+        &self.query_pool
+    }
+}
+
+impl Drop for QueryPool {
+    #[profiling::function]
+    fn drop(&mut self) {
+        if panicking() {
+            return;
+        }
+
+        unsafe {
+            self.device.destroy_query_pool(self.query_pool, None);
+        }
+    }
+}
+
+/// Information used to create a [`QueryPool`] instance.
+#[derive(Builder, Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[builder(
+    build_fn(private, name = "fallible_build", error = "QueryPoolInfoBuilderError"),
+    derive(Clone, Copy, Debug),
+    pattern = "owned"
+)]
+#[non_exhaustive]
+pub struct QueryPoolInfo {
+    /// The statistics counted by queries of a [`vk::QueryType::PIPELINE_STATISTICS`] pool.
+    ///
+    /// Ignored for other query types.
+    #[builder(default)]
+    pub pipeline_statistics: vk::QueryPipelineStatisticFlags,
+
+    /// The number of queries managed by the pool.
+    #[builder(default = "1")]
+    pub query_count: u32,
+
+    /// The type of queries managed by the pool.
+    #[builder(default = "vk::QueryType::TIMESTAMP")]
+    pub ty: vk::QueryType,
+}
+
+impl QueryPoolInfo {
+    /// Specifies a query pool of the given type and number of queries.
+    #[inline(always)]
+    pub const fn new(ty: vk::QueryType, query_count: u32) -> Self {
+        Self {
+            pipeline_statistics: vk::QueryPipelineStatisticFlags::empty(),
+            query_count,
+            ty,
+        }
+    }
+
+    /// Specifies an [`vk::QueryType::OCCLUSION`] query pool with the given number of queries.
+    #[inline(always)]
+    pub const fn occlusion(query_count: u32) -> Self {
+        Self::new(vk::QueryType::OCCLUSION, query_count)
+    }
+
+    /// Specifies a [`vk::QueryType::PIPELINE_STATISTICS`] query pool with the given number of
+    /// queries, each counting `pipeline_statistics`.
+    #[inline(always)]
+    pub const fn pipeline_statistics(
+        query_count: u32,
+        pipeline_statistics: vk::QueryPipelineStatisticFlags,
+    ) -> Self {
+        Self {
+            pipeline_statistics,
+            query_count,
+            ty: vk::QueryType::PIPELINE_STATISTICS,
+        }
+    }
+
+    /// Converts a `QueryPoolInfo` into a `QueryPoolInfoBuilder`.
+    #[inline(always)]
+    pub fn to_builder(self) -> QueryPoolInfoBuilder {
+        QueryPoolInfoBuilder {
+            pipeline_statistics: Some(self.pipeline_statistics),
+            query_count: Some(self.query_count),
+            ty: Some(self.ty),
+        }
+    }
+}
+
+impl Default for QueryPoolInfo {
+    fn default() -> Self {
+        Self::new(vk::QueryType::TIMESTAMP, 1)
+    }
+}
+
+impl From<QueryPoolInfoBuilder> for QueryPoolInfo {
+    fn from(info: QueryPoolInfoBuilder) -> Self {
+        info.build()
+    }
+}
+
+impl QueryPoolInfoBuilder {
+    /// Builds a new `QueryPoolInfo`.
+    #[inline(always)]
+    pub fn build(self) -> QueryPoolInfo {
+        let res = self.fallible_build();
+
+        #[cfg(test)]
+        let res = res.unwrap();
+
+        #[cfg(not(test))]
+        let res = unsafe { res.unwrap_unchecked() };
+
+        res
+    }
+}
+
+#[derive(Debug)]
+struct QueryPoolInfoBuilderError;
+
+impl From<UninitializedFieldError> for QueryPoolInfoBuilderError {
+    fn from(_: UninitializedFieldError) -> Self {
+        Self
+    }
+}
+
+/// The results of a [`vk::QueryType::PIPELINE_STATISTICS`] query.
+///
+/// Statistics which were not enabled by [`QueryPoolInfo::pipeline_statistics`] are `None`.
+///
+/// See [`QueryPool::pipeline_statistics`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PipelineStatistics {
+    /// Number of vertices processed by the primitive assembly stage.
+    pub input_assembly_vertices: Option<u64>,
+
+    /// Number of primitives processed by the primitive assembly stage.
+    pub input_assembly_primitives: Option<u64>,
+
+    /// Number of vertex shader invocations.
+    pub vertex_shader_invocations: Option<u64>,
+
+    /// Number of geometry shader invocations.
+    pub geometry_shader_invocations: Option<u64>,
+
+    /// Number of primitives generated by geometry shader invocations.
+    pub geometry_shader_primitives: Option<u64>,
+
+    /// Number of primitives processed by the primitive clipping stage.
+    pub clipping_invocations: Option<u64>,
+
+    /// Number of primitives output by the primitive clipping stage.
+    pub clipping_primitives: Option<u64>,
+
+    /// Number of fragment shader invocations.
+    pub fragment_shader_invocations: Option<u64>,
+
+    /// Number of patches processed by the tessellation control shader.
+    pub tessellation_control_shader_patches: Option<u64>,
+
+    /// Number of tessellation evaluation shader invocations.
+    pub tessellation_evaluation_shader_invocations: Option<u64>,
+
+    /// Number of compute shader invocations.
+    pub compute_shader_invocations: Option<u64>,
+}
+
+impl PipelineStatistics {
+    const STATISTIC_COUNT: usize = 11;
+
+    fn from_results(flags: vk::QueryPipelineStatisticFlags, results: &[u64]) -> Self {
+        use vk::QueryPipelineStatisticFlags as F;
+
+        // Results are written in ascending bit order, so fields must be initialized in that order
+        let mut results = results.iter().copied();
+        let mut next = |flag| flags.contains(flag).then(|| results.next()).flatten();
+
+        Self {
+            input_assembly_vertices: next(F::INPUT_ASSEMBLY_VERTICES),
+            input_assembly_primitives: next(F::INPUT_ASSEMBLY_PRIMITIVES),
+            vertex_shader_invocations: next(F::VERTEX_SHADER_INVOCATIONS),
+            geometry_shader_invocations: next(F::GEOMETRY_SHADER_INVOCATIONS),
+            geometry_shader_primitives: next(F::GEOMETRY_SHADER_PRIMITIVES),
+            clipping_invocations: next(F::CLIPPING_INVOCATIONS),
+            clipping_primitives: next(F::CLIPPING_PRIMITIVES),
+            fragment_shader_invocations: next(F::FRAGMENT_SHADER_INVOCATIONS),
+            tessellation_control_shader_patches: next(F::TESSELLATION_CONTROL_SHADER_PATCHES),
+            tessellation_evaluation_shader_invocations: next(
+                F::TESSELLATION_EVALUATION_SHADER_INVOCATIONS,
+            ),
+            compute_shader_invocations: next(F::COMPUTE_SHADER_INVOCATIONS),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::driver::device::test_device};
+
+    type Info = QueryPoolInfo;
+    type Builder = QueryPoolInfoBuilder;
+
+    #[test]
+    pub fn query_pool_info() {
+        let info = Info::new(vk::QueryType::OCCLUSION, 4);
+        let builder = info.to_builder().build();
+
+        assert_eq!(info, builder);
+    }
+
+    #[test]
+    pub fn query_pool_info_builder() {
+        let info = Info::default();
+        let builder = Builder::default().build();
+
+        assert_eq!(info, builder);
+    }
+
+    #[test]
+    pub fn pipeline_statistics_from_results() {
+        let flags = vk::QueryPipelineStatisticFlags::INPUT_ASSEMBLY_VERTICES
+            | vk::QueryPipelineStatisticFlags::CLIPPING_PRIMITIVES
+            | vk::QueryPipelineStatisticFlags::FRAGMENT_SHADER_INVOCATIONS;
+        let statistics = PipelineStatistics::from_results(flags, &[3, 1, 42]);
+
+        assert_eq!(
+            statistics,
+            PipelineStatistics {
+                input_assembly_vertices: Some(3),
+                clipping_primitives: Some(1),
+                fragment_shader_invocations: Some(42),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    pub fn pipeline_statistics_feature() {
+        let Some(device) = test_device() else {
+            return;
+        };
+
+        let query_pool =
+            QueryPool::create(&device, Info::new(vk::QueryType::PIPELINE_STATISTICS, 1));
+
+        if Device::supports(&device, DeviceFeature::PipelineStatisticsQuery) {
+            assert!(query_pool.is_ok());
+        } else {
+            assert!(matches!(
+                query_pool,
+                Err(DriverError::FeatureNotEnabled(
+                    DeviceFeature::PipelineStatisticsQuery
+                ))
+            ));
+        }
+    }
+}
@@ -2,12 +2,17 @@
 
 use {
     super::{
-        device::Device,
+        buffer::{Buffer, BufferInfo},
+        device::{Device, DeviceFeature},
         merge_push_constant_ranges,
         physical_device::RayTraceProperties,
-        shader::{align_spriv, DescriptorBindingMap, PipelineDescriptorInfo, Shader},
+        shader::{
+            align_spriv, Descriptor, DescriptorBindingInfo, DescriptorBindingMap,
+            PipelineDescriptorInfo, Shader,
+        },
         DriverError,
     },
+    crate::pool::{Lease, Pool},
     ash::vk,
     derive_builder::{Builder, UninitializedFieldError},
     log::warn,
@@ -46,6 +51,7 @@ pub struct RayTracePipeline {
     pipeline: vk::Pipeline,
     shader_modules: Vec<vk::ShaderModule>,
     shader_group_handles: Vec<u8>,
+    shader_group_regions: Vec<ShaderGroupRegion>,
 }
 
 impl RayTracePipeline {
@@ -111,17 +117,32 @@ impl RayTracePipeline {
     where
         S: Into<Shader>,
     {
+        if !Device::supports(device, DeviceFeature::RayTracingPipeline) {
+            warn!("ray tracing pipeline feature not enabled");
+
+            return Err(DriverError::FeatureNotEnabled(
+                DeviceFeature::RayTracingPipeline,
+            ));
+        }
+
         let info = info.into();
-        let shader_groups = shader_groups
-            .into_iter()
-            .map(|shader_group| shader_group.into())
-            .collect::<Vec<_>>();
+        let shader_groups = shader_groups.into_iter().collect::<Vec<_>>();
         let group_count = shader_groups.len();
 
         let shaders = shaders
             .into_iter()
             .map(|shader| shader.into())
             .collect::<Vec<Shader>>();
+
+        // Remember which shader binding table region each group belongs to
+        let shader_group_regions = shader_groups
+            .iter()
+            .map(|shader_group| ShaderGroupRegion::new(shader_group, &shaders))
+            .collect::<Result<Vec<_>, _>>()?;
+        let shader_groups = shader_groups
+            .into_iter()
+            .map(|shader_group| shader_group.into())
+            .collect::<Vec<vk::RayTracingShaderGroupCreateInfoKHR>>();
         let push_constants = shaders
             .iter()
             .map(|shader| shader.push_constant_range())
@@ -132,13 +153,12 @@ impl RayTracePipeline {
         let mut descriptor_bindings = Shader::merge_descriptor_bindings(
             shaders.iter().map(|shader| shader.descriptor_bindings()),
         );
-        for (descriptor_info, _) in descriptor_bindings.values_mut() {
-            if descriptor_info.binding_count() == 0 {
-                descriptor_info.set_binding_count(info.bindless_descriptor_count);
-            }
-        }
-
-        let descriptor_info = PipelineDescriptorInfo::create(device, &descriptor_bindings)?;
+        let descriptor_info = PipelineDescriptorInfo::create(
+            device,
+            &mut descriptor_bindings,
+            info.bindless_descriptor_count,
+            None,
+        )?;
         let descriptor_set_layout_handles = descriptor_info
             .layouts
             .values()
@@ -304,10 +324,170 @@ impl RayTracePipeline {
                 pipeline,
                 shader_modules,
                 shader_group_handles,
+                shader_group_regions,
             })
         }
     }
 
+    /// Creates a shader binding table containing a record for each shader group of this pipeline.
+    ///
+    /// Records are placed in the region of their shader group type (ray generation, miss, hit, or
+    /// callable) in the order the groups were provided to [`RayTracePipeline::create`], using the
+    /// handle size and alignments of the physical device. The ray generation region refers to the
+    /// first ray generation group only.
+    ///
+    /// The returned regions may be passed directly to
+    /// [`RayTrace::trace_rays`](crate::graph::pass_ref::RayTrace::trace_rays); the buffer must be
+    /// kept alive until the trace has executed.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::ray_trace::{RayTracePipeline, RayTracePipelineInfo, RayTraceShaderGroup};
+    /// # use screen_13::driver::shader::Shader;
+    /// # use screen_13::pool::lazy::LazyPool;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let mut pool = LazyPool::new(&device);
+    /// # let my_rgen_code = [0u8; 1];
+    /// # let my_chit_code = [0u8; 1];
+    /// # let my_miss_code = [0u8; 1];
+    /// let pipeline = RayTracePipeline::create(
+    ///     &device,
+    ///     RayTracePipelineInfo::default(),
+    ///     [
+    ///         Shader::new_ray_gen(my_rgen_code.as_slice()),
+    ///         Shader::new_closest_hit(my_chit_code.as_slice()),
+    ///         Shader::new_miss(my_miss_code.as_slice()),
+    ///     ],
+    ///     [
+    ///         RayTraceShaderGroup::new_general(0),
+    ///         RayTraceShaderGroup::new_triangles(1, None),
+    ///         RayTraceShaderGroup::new_general(2),
+    ///     ],
+    /// )?;
+    /// let sbt = RayTracePipeline::create_shader_binding_table(&pipeline, &mut pool)?;
+    ///
+    /// assert_eq!(sbt.callable.size, 0);
+    /// # Ok(()) }
+    /// ```
+    #[profiling::function]
+    pub fn create_shader_binding_table<P>(
+        this: &Self,
+        pool: &mut P,
+    ) -> Result<ShaderBindingTable, DriverError>
+    where
+        P: Pool<BufferInfo, Buffer> + ?Sized,
+    {
+        let properties = this
+            .device
+            .physical_device
+            .ray_trace_properties
+            .as_ref()
+            .ok_or(DriverError::Unsupported)?;
+        let group_count = |region: ShaderGroupRegion| {
+            this.shader_group_regions
+                .iter()
+                .filter(|group_region| **group_region == region)
+                .count()
+        };
+        let [raygen_count, miss_count, hit_count, callable_count] = [
+            ShaderGroupRegion::RayGen,
+            ShaderGroupRegion::Miss,
+            ShaderGroupRegion::Hit,
+            ShaderGroupRegion::Callable,
+        ]
+        .map(group_count);
+        let (mut regions, size) = shader_binding_table_regions(
+            [raygen_count.min(1), miss_count, hit_count, callable_count],
+            properties,
+        );
+
+        let mut buffer = pool.lease(
+            BufferInfo::host_mem(
+                size,
+                vk::BufferUsageFlags::SHADER_BINDING_TABLE_KHR
+                    | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS,
+            )
+            .to_builder()
+            .alignment(properties.shader_group_base_alignment as _)
+            .build(),
+        )?;
+
+        {
+            let data = Buffer::mapped_slice_mut(&mut buffer);
+            let mut region_counts: [vk::DeviceSize; 4] = [0; 4];
+
+            for (group_idx, group_region) in this.shader_group_regions.iter().enumerate() {
+                let region_idx = *group_region as usize;
+                let region = regions[region_idx];
+                let record_idx = region_counts[region_idx];
+
+                // Only the first ray generation group fits in the ray generation region
+                if region.size == 0 || record_idx * region.stride >= region.size {
+                    continue;
+                }
+
+                let handle = Self::group_handle(this, group_idx)?;
+                let start = (region.device_address + record_idx * region.stride) as usize;
+                data[start..start + handle.len()].copy_from_slice(handle);
+
+                region_counts[region_idx] += 1;
+            }
+        }
+
+        let device_address = Buffer::device_address(&buffer);
+        for region in &mut regions {
+            if region.size > 0 {
+                region.device_address += device_address;
+            } else {
+                *region = vk::StridedDeviceAddressRegionKHR::default();
+            }
+        }
+
+        let [raygen, miss, hit, callable] = regions;
+
+        Ok(ShaderBindingTable {
+            buffer: Arc::new(buffer),
+            callable,
+            hit,
+            miss,
+            raygen,
+        })
+    }
+
+    /// Returns the reflected information of a descriptor binding, if it is used by this pipeline.
+    pub fn descriptor_binding(
+        this: &Self,
+        descriptor: impl Into<Descriptor>,
+    ) -> Option<DescriptorBindingInfo> {
+        this.descriptor_bindings
+            .get(&descriptor.into())
+            .map(|&(descriptor_info, stage_flags)| {
+                DescriptorBindingInfo::new(descriptor_info, stage_flags)
+            })
+    }
+
+    /// Returns the reflected information of all descriptor bindings used by this pipeline, in no
+    /// particular order.
+    pub fn descriptor_bindings(
+        this: &Self,
+    ) -> impl Iterator<Item = (Descriptor, DescriptorBindingInfo)> + '_ {
+        this.descriptor_bindings
+            .iter()
+            .map(|(&descriptor, &(descriptor_info, stage_flags))| {
+                (
+                    descriptor,
+                    DescriptorBindingInfo::new(descriptor_info, stage_flags),
+                )
+            })
+    }
+
     /// Function returning a handle to a shader group of this pipeline.
     /// This can be used to construct a sbt.
     ///
@@ -352,6 +532,11 @@ impl RayTracePipeline {
         }
     }
 
+    /// Returns the reflected push constant ranges of all shader stages.
+    pub fn push_constant_ranges(this: &Self) -> &[vk::PushConstantRange] {
+        &this.push_constants
+    }
+
     /// Sets the debugging name assigned to this pipeline.
     pub fn with_name(mut this: Self, name: impl Into<String>) -> Self {
         this.name = Some(name.into());
@@ -403,6 +588,11 @@ pub struct RayTracePipelineInfo {
     /// The number of descriptors to allocate for a given binding when using bindless (unbounded)
     /// syntax.
     ///
+    /// Individual elements are accessed by a render pass using array descriptors such as
+    /// `(0, 0, [42])`; elements which are not accessed remain unbound. When supported by the
+    /// device, bindless bindings are created as update-after-bind and are subject to the larger
+    /// `maxDescriptorSetUpdateAfterBind*` limits.
+    ///
     /// The default is `8192`.
     ///
     /// # Examples
@@ -501,6 +691,99 @@ impl From<UninitializedFieldError> for RayTracePipelineInfoBuilderError {
     }
 }
 
+/// Describes the type of region a shader group belongs to within a shader binding table.
+///
+/// Discriminants index the arrays of [`shader_binding_table_regions`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ShaderGroupRegion {
+    RayGen = 0,
+    Miss = 1,
+    Hit = 2,
+    Callable = 3,
+}
+
+impl ShaderGroupRegion {
+    fn new(shader_group: &RayTraceShaderGroup, shaders: &[Shader]) -> Result<Self, DriverError> {
+        Ok(match shader_group.ty {
+            RayTraceShaderGroupType::General => {
+                let stage = shader_group
+                    .general_shader
+                    .and_then(|idx| shaders.get(idx as usize))
+                    .map(|shader| shader.stage)
+                    .unwrap_or_default();
+
+                match stage {
+                    vk::ShaderStageFlags::RAYGEN_KHR => Self::RayGen,
+                    vk::ShaderStageFlags::MISS_KHR => Self::Miss,
+                    vk::ShaderStageFlags::CALLABLE_KHR => Self::Callable,
+                    _ => {
+                        warn!("invalid general shader group stage: {stage:?}");
+
+                        return Err(DriverError::InvalidData);
+                    }
+                }
+            }
+            RayTraceShaderGroupType::ProceduralHitGroup
+            | RayTraceShaderGroupType::TrianglesHitGroup => Self::Hit,
+        })
+    }
+}
+
+/// A buffer of shader group handle records and the regions of it which are used by each type of
+/// shader group.
+///
+/// See [`RayTracePipeline::create_shader_binding_table`].
+#[derive(Clone, Debug)]
+pub struct ShaderBindingTable {
+    /// The host-visible buffer which contains the shader group handle records.
+    pub buffer: Arc<Lease<Buffer>>,
+
+    /// The region of callable shader group records, or a zero-sized region if the pipeline has no
+    /// callable shader groups.
+    pub callable: vk::StridedDeviceAddressRegionKHR,
+
+    /// The region of hit shader group records, or a zero-sized region if the pipeline has no hit
+    /// shader groups.
+    pub hit: vk::StridedDeviceAddressRegionKHR,
+
+    /// The region of miss shader group records, or a zero-sized region if the pipeline has no
+    /// miss shader groups.
+    pub miss: vk::StridedDeviceAddressRegionKHR,
+
+    /// The region of the ray generation shader group record.
+    pub raygen: vk::StridedDeviceAddressRegionKHR,
+}
+
+/// Returns the regions, with device addresses relative to the start of the table, and the total
+/// size of a shader binding table containing the given number of ray generation, miss, hit, and
+/// callable records.
+fn shader_binding_table_regions(
+    group_counts: [usize; 4],
+    properties: &RayTraceProperties,
+) -> ([vk::StridedDeviceAddressRegionKHR; 4], vk::DeviceSize) {
+    let base_alignment = properties.shader_group_base_alignment as vk::DeviceSize;
+    let stride = (properties.shader_group_handle_size as vk::DeviceSize)
+        .next_multiple_of(properties.shader_group_handle_alignment as _);
+
+    let mut offset = 0;
+    let regions = group_counts.map(|group_count| {
+        let device_address = offset.next_multiple_of(base_alignment);
+        let size = group_count as vk::DeviceSize * stride;
+
+        if size > 0 {
+            offset = device_address + size;
+        }
+
+        vk::StridedDeviceAddressRegionKHR {
+            device_address,
+            stride,
+            size,
+        }
+    });
+
+    (regions, offset.max(1))
+}
+
 /// Describes the set of the shader stages to be included in each shader group in the ray trace
 /// pipeline.
 ///
@@ -661,4 +944,36 @@ mod tests {
 
         assert_eq!(info, builder);
     }
+
+    #[test]
+    pub fn shader_binding_table_alignment() {
+        let properties = RayTraceProperties {
+            shader_group_handle_size: 24,
+            max_ray_recursion_depth: 1,
+            max_shader_group_stride: 4096,
+            shader_group_base_alignment: 64,
+            shader_group_handle_capture_replay_size: 32,
+            max_ray_dispatch_invocation_count: 1 << 30,
+            shader_group_handle_alignment: 16,
+            max_ray_hit_attribute_size: 32,
+        };
+
+        let ([raygen, miss, hit, callable], size) =
+            shader_binding_table_regions([1, 3, 2, 0], &properties);
+
+        // Records are padded to the handle alignment
+        assert_eq!(raygen.stride, 32);
+        assert_eq!(miss.stride, 32);
+        assert_eq!(hit.stride, 32);
+
+        // Regions start at the base alignment
+        assert_eq!(raygen.device_address, 0);
+        assert_eq!(raygen.size, 32);
+        assert_eq!(miss.device_address, 64);
+        assert_eq!(miss.size, 96);
+        assert_eq!(hit.device_address, 192);
+        assert_eq!(hit.size, 64);
+        assert_eq!(callable.size, 0);
+        assert_eq!(size, 256);
+    }
 }
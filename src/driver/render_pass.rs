@@ -25,6 +25,29 @@ pub(crate) struct AttachmentInfo {
     pub final_layout: vk::ImageLayout,
 }
 
+impl AttachmentInfo {
+    /// Sets the load operation of the depth and/or stencil aspects included in `aspect_mask`.
+    pub fn set_depth_stencil_load_op(
+        &mut self,
+        aspect_mask: vk::ImageAspectFlags,
+        load_op: vk::AttachmentLoadOp,
+    ) {
+        if aspect_mask.contains(vk::ImageAspectFlags::DEPTH) {
+            self.load_op = load_op;
+        }
+
+        if aspect_mask.contains(vk::ImageAspectFlags::STENCIL) {
+            self.stencil_load_op = load_op;
+        }
+    }
+
+    /// Returns `true` if any aspect of this attachment is loaded or cleared.
+    pub fn is_loaded(&self) -> bool {
+        self.load_op != vk::AttachmentLoadOp::DONT_CARE
+            || self.stencil_load_op != vk::AttachmentLoadOp::DONT_CARE
+    }
+}
+
 impl From<AttachmentInfo> for vk::AttachmentDescription2<'_> {
     fn from(value: AttachmentInfo) -> Self {
         vk::AttachmentDescription2::default()
@@ -317,7 +340,7 @@ impl RenderPass {
     #[profiling::function]
     pub fn graphic_pipeline(
         this: &mut Self,
-        pipeline: &Arc<GraphicPipeline>,
+        pipeline: &GraphicPipeline,
         depth_stencil: Option<DepthStencilMode>,
         subpass_idx: u32,
     ) -> Result<vk::Pipeline, DriverError> {
@@ -338,14 +361,38 @@ impl RenderPass {
             _ => unreachable!(),
         };
 
+        // Multisampled attachments determine the rasterization sample count of the pipeline
+        let subpass = &this.info.subpasses[subpass_idx as usize];
+        let rasterization_samples = subpass
+            .color_attachments
+            .iter()
+            .chain(subpass.depth_stencil_attachment.iter())
+            .filter_map(|attachment| this.info.attachments.get(attachment.attachment as usize))
+            .map(|attachment| attachment.sample_count)
+            .next()
+            .unwrap_or(pipeline.state.multisample.rasterization_samples);
+
+        if rasterization_samples != pipeline.state.multisample.rasterization_samples {
+            warn!(
+                "graphic pipeline samples {:?} overridden by render pass attachment samples {:?}",
+                pipeline.state.multisample.rasterization_samples, rasterization_samples
+            );
+        }
+
         let color_blend_attachment_states = this.info.subpasses[subpass_idx as usize]
             .color_attachments
             .iter()
-            .map(|_| pipeline.info.blend.into_vk())
+            .enumerate()
+            .map(|(attachment_idx, _)| pipeline.info.attachment_blend(attachment_idx).into_vk())
             .collect::<Box<[_]>>();
         let color_blend_state = vk::PipelineColorBlendStateCreateInfo::default()
             .attachments(&color_blend_attachment_states);
-        let dynamic_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
+        let dynamic_states = [
+            vk::DynamicState::VIEWPORT,
+            vk::DynamicState::SCISSOR,
+            vk::DynamicState::LINE_WIDTH,
+            vk::DynamicState::DEPTH_BIAS,
+        ];
         let dynamic_state =
             vk::PipelineDynamicStateCreateInfo::default().dynamic_states(&dynamic_states);
         let multisample_state = vk::PipelineMultisampleStateCreateInfo::default()
@@ -353,7 +400,7 @@ impl RenderPass {
             .alpha_to_one_enable(pipeline.state.multisample.alpha_to_one_enable)
             .flags(pipeline.state.multisample.flags)
             .min_sample_shading(pipeline.state.multisample.min_sample_shading)
-            .rasterization_samples(pipeline.state.multisample.rasterization_samples.into())
+            .rasterization_samples(rasterization_samples.into())
             .sample_shading_enable(pipeline.state.multisample.sample_shading_enable)
             .sample_mask(&pipeline.state.multisample.sample_mask);
         let specializations = pipeline
@@ -389,15 +436,28 @@ impl RenderPass {
                 info
             })
             .collect::<Box<[_]>>();
-        let vertex_input_state = vk::PipelineVertexInputStateCreateInfo::default()
+        let mut vertex_input_divisor_state =
+            vk::PipelineVertexInputDivisorStateCreateInfoEXT::default()
+                .vertex_binding_divisors(&pipeline.state.vertex_input.vertex_binding_divisors);
+        let mut vertex_input_state = vk::PipelineVertexInputStateCreateInfo::default()
             .vertex_attribute_descriptions(
                 &pipeline.state.vertex_input.vertex_attribute_descriptions,
             )
             .vertex_binding_descriptions(&pipeline.state.vertex_input.vertex_binding_descriptions);
+
+        if !pipeline
+            .state
+            .vertex_input
+            .vertex_binding_divisors
+            .is_empty()
+        {
+            vertex_input_state = vertex_input_state.push_next(&mut vertex_input_divisor_state);
+        }
         let viewport_state = vk::PipelineViewportStateCreateInfo::default()
-            .viewport_count(1)
-            .scissor_count(1);
+            .viewport_count(pipeline.info.viewport_count)
+            .scissor_count(pipeline.info.viewport_count);
         let input_assembly_state = vk::PipelineInputAssemblyStateCreateInfo {
+            primitive_restart_enable: pipeline.info.primitive_restart as _,
             topology: pipeline.info.topology,
             ..Default::default()
         };
@@ -405,13 +465,21 @@ impl RenderPass {
             .map(|depth_stencil| depth_stencil.into_vk())
             .unwrap_or_default();
         let rasterization_state = vk::PipelineRasterizationStateCreateInfo {
+            depth_bias_enable: pipeline.info.depth_bias as _,
             front_face: pipeline.info.front_face,
             line_width: 1.0,
             polygon_mode: pipeline.info.polygon_mode,
             cull_mode: pipeline.info.cull_mode,
             ..Default::default()
         };
-        let graphic_pipeline_info = vk::GraphicsPipelineCreateInfo::default()
+        let tessellation_state = pipeline
+            .info
+            .patch_control_points
+            .map(|patch_control_points| {
+                vk::PipelineTessellationStateCreateInfo::default()
+                    .patch_control_points(patch_control_points)
+            });
+        let mut graphic_pipeline_info = vk::GraphicsPipelineCreateInfo::default()
             .color_blend_state(&color_blend_state)
             .depth_stencil_state(&depth_stencil)
             .dynamic_state(&dynamic_state)
@@ -425,6 +493,10 @@ impl RenderPass {
             .vertex_input_state(&vertex_input_state)
             .viewport_state(&viewport_state);
 
+        if let Some(tessellation_state) = &tessellation_state {
+            graphic_pipeline_info = graphic_pipeline_info.tessellation_state(tessellation_state);
+        }
+
         let pipeline = unsafe {
             this.device.create_graphics_pipelines(
                 Device::pipeline_cache(&this.device),
@@ -568,3 +640,35 @@ impl SubpassInfo {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn attachment_info_depth_stencil_clear() {
+        let mut depth_stencil = AttachmentInfo::default();
+        depth_stencil.set_depth_stencil_load_op(
+            vk::ImageAspectFlags::DEPTH | vk::ImageAspectFlags::STENCIL,
+            vk::AttachmentLoadOp::CLEAR,
+        );
+
+        assert_eq!(depth_stencil.load_op, vk::AttachmentLoadOp::CLEAR);
+        assert_eq!(depth_stencil.stencil_load_op, vk::AttachmentLoadOp::CLEAR);
+
+        let mut depth = AttachmentInfo::default();
+        depth.set_depth_stencil_load_op(vk::ImageAspectFlags::DEPTH, vk::AttachmentLoadOp::CLEAR);
+
+        assert_eq!(depth.load_op, vk::AttachmentLoadOp::CLEAR);
+        assert_eq!(depth.stencil_load_op, vk::AttachmentLoadOp::DONT_CARE);
+
+        let mut stencil = AttachmentInfo::default();
+        stencil
+            .set_depth_stencil_load_op(vk::ImageAspectFlags::STENCIL, vk::AttachmentLoadOp::CLEAR);
+
+        assert_eq!(stencil.load_op, vk::AttachmentLoadOp::DONT_CARE);
+        assert_eq!(stencil.stencil_load_op, vk::AttachmentLoadOp::CLEAR);
+        assert!(stencil.is_loaded());
+        assert!(!AttachmentInfo::default().is_loaded());
+    }
+}
@@ -1,22 +1,26 @@
 //! Shader resource types
 
 use {
-    super::{device::Device, DescriptorSetLayout, DriverError, VertexInputState},
+    super::{
+        device::Device, format_plane_count, DescriptorSetLayout, DriverError, VertexInputState,
+    },
     ash::vk,
     derive_builder::{Builder, UninitializedFieldError},
     log::{debug, error, trace, warn},
     ordered_float::OrderedFloat,
     spirq::{
+        constant::ConstantValue,
         entry_point::EntryPoint,
+        spirv::ExecutionMode,
         ty::{DescriptorType, ScalarType, SpirvType, Type},
         var::Variable,
         ReflectConfig,
     },
     std::{
-        collections::{BTreeMap, HashMap},
+        collections::{BTreeMap, HashMap, HashSet},
         fmt::{Debug, Formatter},
         iter::repeat,
-        mem::size_of_val,
+        mem::{size_of, size_of_val},
         ops::Deref,
         sync::Arc,
         thread::panicking,
@@ -41,8 +45,15 @@ pub(crate) fn align_spriv(code: &[u8]) -> Result<&[u32], DriverError> {
 fn guess_immutable_sampler(binding_name: &str) -> SamplerInfo {
     const INVALID_ERR: &str = "Invalid sampler specification";
 
-    let (texel_filter, mipmap_mode, address_modes) = if binding_name.contains("_sampler_") {
-        let spec = &binding_name[binding_name.len() - 3..];
+    let (texel_filter, mipmap_mode, address_modes, border_color, compare_op) = if let Some(idx) =
+        binding_name.rfind("_sampler_")
+    {
+        let spec = &binding_name[idx + 9..];
+        let spec = if (3..=5).contains(&spec.len()) {
+            spec
+        } else {
+            &binding_name[binding_name.len() - 3..]
+        };
         let texel_filter = match &spec[0..1] {
             "n" => vk::Filter::NEAREST,
             "l" => vk::Filter::LINEAR,
@@ -63,7 +74,35 @@ fn guess_immutable_sampler(binding_name: &str) -> SamplerInfo {
             _ => panic!("{INVALID_ERR}: {}", &spec[2..3]),
         };
 
-        (texel_filter, mipmap_mode, address_modes)
+        // Optional trailing border color and compare operator characters
+        let mut border_color = None;
+        let mut compare_op = None;
+        for c in spec[3..].chars() {
+            match c {
+                'k' if border_color.is_none() => {
+                    border_color = Some(vk::BorderColor::FLOAT_OPAQUE_BLACK)
+                }
+                't' if border_color.is_none() => {
+                    border_color = Some(vk::BorderColor::FLOAT_TRANSPARENT_BLACK)
+                }
+                'w' if border_color.is_none() => {
+                    border_color = Some(vk::BorderColor::FLOAT_OPAQUE_WHITE)
+                }
+                'l' if compare_op.is_none() => compare_op = Some(vk::CompareOp::LESS),
+                'L' if compare_op.is_none() => compare_op = Some(vk::CompareOp::LESS_OR_EQUAL),
+                'g' if compare_op.is_none() => compare_op = Some(vk::CompareOp::GREATER),
+                'G' if compare_op.is_none() => compare_op = Some(vk::CompareOp::GREATER_OR_EQUAL),
+                _ => panic!("{INVALID_ERR}: {c}"),
+            }
+        }
+
+        (
+            texel_filter,
+            mipmap_mode,
+            address_modes,
+            border_color,
+            compare_op,
+        )
     } else {
         debug!("image binding {binding_name} using default sampler");
 
@@ -71,6 +110,8 @@ fn guess_immutable_sampler(binding_name: &str) -> SamplerInfo {
             vk::Filter::LINEAR,
             vk::SamplerMipmapMode::LINEAR,
             vk::SamplerAddressMode::REPEAT,
+            None,
+            None,
         )
     };
     let anisotropy_enable = texel_filter == vk::Filter::LINEAR;
@@ -88,9 +129,42 @@ fn guess_immutable_sampler(binding_name: &str) -> SamplerInfo {
         info = info.max_anisotropy(16.0);
     }
 
+    if let Some(border_color) = border_color {
+        info = info.border_color(border_color);
+    }
+
+    if let Some(compare_op) = compare_op {
+        info = info.compare(compare_op);
+    }
+
     info.build()
 }
 
+/// Reflected information about a pipeline descriptor binding.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct DescriptorBindingInfo {
+    /// The number of descriptors in the binding; bindless arrays use the
+    /// `bindless_descriptor_count` value of the pipeline information.
+    pub descriptor_count: u32,
+
+    /// The type of descriptors in the binding.
+    pub descriptor_type: vk::DescriptorType,
+
+    /// The shader stages which access the binding.
+    pub stage_flags: vk::ShaderStageFlags,
+}
+
+impl DescriptorBindingInfo {
+    pub(super) fn new(descriptor_info: DescriptorInfo, stage_flags: vk::ShaderStageFlags) -> Self {
+        Self {
+            descriptor_count: descriptor_info.binding_count(),
+            descriptor_type: descriptor_info.descriptor_type(),
+            stage_flags,
+        }
+    }
+}
+
 /// Tuple of descriptor set index and binding index.
 ///
 /// This is a generic representation of the descriptor binding point within the shader and not a
@@ -124,9 +198,11 @@ pub(crate) enum DescriptorInfo {
     SampledImage(u32),
     Sampler(u32, SamplerInfo, bool), //count, sampler, is-manually-defined?
     StorageBuffer(u32),
+    StorageBufferDynamic(u32),
     StorageImage(u32),
     StorageTexelBuffer(u32),
     UniformBuffer(u32),
+    UniformBufferDynamic(u32),
     UniformTexelBuffer(u32),
 }
 
@@ -139,9 +215,11 @@ impl DescriptorInfo {
             Self::SampledImage(binding_count) => binding_count,
             Self::Sampler(binding_count, ..) => binding_count,
             Self::StorageBuffer(binding_count) => binding_count,
+            Self::StorageBufferDynamic(binding_count) => binding_count,
             Self::StorageImage(binding_count) => binding_count,
             Self::StorageTexelBuffer(binding_count) => binding_count,
             Self::UniformBuffer(binding_count) => binding_count,
+            Self::UniformBufferDynamic(binding_count) => binding_count,
             Self::UniformTexelBuffer(binding_count) => binding_count,
         }
     }
@@ -154,17 +232,43 @@ impl DescriptorInfo {
             Self::SampledImage(_) => vk::DescriptorType::SAMPLED_IMAGE,
             Self::Sampler(..) => vk::DescriptorType::SAMPLER,
             Self::StorageBuffer(_) => vk::DescriptorType::STORAGE_BUFFER,
+            Self::StorageBufferDynamic(_) => vk::DescriptorType::STORAGE_BUFFER_DYNAMIC,
             Self::StorageImage(_) => vk::DescriptorType::STORAGE_IMAGE,
             Self::StorageTexelBuffer(_) => vk::DescriptorType::STORAGE_TEXEL_BUFFER,
             Self::UniformBuffer(_) => vk::DescriptorType::UNIFORM_BUFFER,
+            Self::UniformBufferDynamic(_) => vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC,
             Self::UniformTexelBuffer(_) => vk::DescriptorType::UNIFORM_TEXEL_BUFFER,
         }
     }
 
-    fn sampler_info(self) -> Option<SamplerInfo> {
+    pub fn is_dynamic(self) -> bool {
+        matches!(
+            self,
+            Self::StorageBufferDynamic(_) | Self::UniformBufferDynamic(_)
+        )
+    }
+
+    /// Returns the number of descriptors consumed by each element of this binding.
+    ///
+    /// Combined image samplers using Y′CBCR conversion of multi-planar formats may consume up to
+    /// one descriptor per plane.
+    fn descriptor_count_per_binding(self) -> u32 {
+        match self {
+            Self::CombinedImageSampler(_, sampler_info, _) => sampler_info
+                .ycbcr_conversion
+                .map(|ycbcr_conversion| format_plane_count(ycbcr_conversion.fmt))
+                .unwrap_or(1),
+            _ => 1,
+        }
+    }
+
+    fn sampler_info(self, device: &Device) -> Option<SamplerInfo> {
         match self {
-            Self::CombinedImageSampler(_, sampler_info, _) | Self::Sampler(_, sampler_info, _) => {
-                Some(sampler_info)
+            Self::CombinedImageSampler(_, sampler_info, true)
+            | Self::Sampler(_, sampler_info, true) => Some(sampler_info),
+            Self::CombinedImageSampler(_, sampler_info, false)
+            | Self::Sampler(_, sampler_info, false) => {
+                Some(Device::default_sampler_info(device, sampler_info))
             }
             _ => None,
         }
@@ -178,9 +282,11 @@ impl DescriptorInfo {
             Self::SampledImage(binding_count) => binding_count,
             Self::Sampler(binding_count, ..) => binding_count,
             Self::StorageBuffer(binding_count) => binding_count,
+            Self::StorageBufferDynamic(binding_count) => binding_count,
             Self::StorageImage(binding_count) => binding_count,
             Self::StorageTexelBuffer(binding_count) => binding_count,
             Self::UniformBuffer(binding_count) => binding_count,
+            Self::UniformBufferDynamic(binding_count) => binding_count,
             Self::UniformTexelBuffer(binding_count) => binding_count,
         } = binding_count;
     }
@@ -191,16 +297,44 @@ pub(crate) struct PipelineDescriptorInfo {
     pub layouts: BTreeMap<u32, DescriptorSetLayout>,
     pub pool_sizes: HashMap<u32, HashMap<vk::DescriptorType, u32>>,
 
+    /// The descriptor set which is pushed using `vkCmdPushDescriptorSetKHR`, if any. This set is
+    /// not allocated from the descriptor pool of a pass and has no pool sizes.
+    pub push_descriptor_set: Option<u32>,
+
+    /// `true` if any layout requires a descriptor pool created with
+    /// `VK_DESCRIPTOR_POOL_CREATE_UPDATE_AFTER_BIND_BIT`.
+    pub update_after_bind: bool,
+
     #[allow(dead_code)]
     samplers: Box<[Sampler]>,
 }
 
 impl PipelineDescriptorInfo {
+    /// Creates descriptor set layouts for the given bindings.
+    ///
+    /// Runtime-sized (_bindless_) array bindings, which reflect with a binding count of zero, are
+    /// given `bindless_descriptor_count` elements and are created as update-after-bind when
+    /// supported by the device.
+    ///
+    /// If specified, `push_descriptor_set` must be the last descriptor set and may not contain
+    /// dynamic or bindless bindings. On devices without `VK_KHR_push_descriptor` the set is
+    /// created as a regular descriptor set layout.
     #[profiling::function]
     pub fn create(
         device: &Arc<Device>,
-        descriptor_bindings: &DescriptorBindingMap,
+        descriptor_bindings: &mut DescriptorBindingMap,
+        bindless_descriptor_count: u32,
+        push_descriptor_set: Option<u32>,
     ) -> Result<Self, DriverError> {
+        let mut bindless_descriptors = HashSet::new();
+        for (descriptor, (descriptor_info, _)) in descriptor_bindings.iter_mut() {
+            if descriptor_info.binding_count() == 0 {
+                descriptor_info.set_binding_count(bindless_descriptor_count);
+                bindless_descriptors.insert(*descriptor);
+            }
+        }
+
+        let descriptor_bindings = &*descriptor_bindings;
         let descriptor_set_count = descriptor_bindings
             .keys()
             .map(|descriptor| descriptor.set)
@@ -210,12 +344,60 @@ impl PipelineDescriptorInfo {
         let mut layouts = BTreeMap::new();
         let mut pool_sizes = HashMap::new();
 
+        if let Some(push_descriptor_set) = push_descriptor_set {
+            if push_descriptor_set + 1 != descriptor_set_count {
+                warn!("push descriptor set {push_descriptor_set} must be the last set");
+
+                return Err(DriverError::InvalidData);
+            }
+
+            let mut push_descriptor_count = 0;
+            for (descriptor, (descriptor_info, _)) in descriptor_bindings
+                .iter()
+                .filter(|(descriptor, _)| descriptor.set == push_descriptor_set)
+            {
+                if descriptor_info.is_dynamic() || bindless_descriptors.contains(descriptor) {
+                    warn!("push descriptor {descriptor:?} must not be dynamic or bindless");
+
+                    return Err(DriverError::InvalidData);
+                }
+
+                push_descriptor_count += descriptor_info.binding_count();
+            }
+
+            // Without the extension the set is a regular set written by the recorder instead
+            if let Some(push_descriptor_properties) =
+                device.physical_device.push_descriptor_properties.as_ref()
+            {
+                if push_descriptor_count > push_descriptor_properties.max_push_descriptors {
+                    warn!(
+                        "push descriptor count {push_descriptor_count} exceeds {}",
+                        push_descriptor_properties.max_push_descriptors
+                    );
+
+                    return Err(DriverError::InvalidData);
+                }
+            }
+        }
+
+        for (descriptor, (descriptor_info, _)) in descriptor_bindings.iter() {
+            if let DescriptorInfo::Sampler(_, sampler_info, _) = descriptor_info {
+                if sampler_info.ycbcr_conversion.is_some() {
+                    warn!(
+                        "Y′CBCR conversion sampler {descriptor:?} must be a combined image sampler"
+                    );
+
+                    return Err(DriverError::InvalidData);
+                }
+            }
+        }
+
         //trace!("descriptor_bindings: {:#?}", &descriptor_bindings);
 
         let mut sampler_info_binding_count = HashMap::<_, u32>::with_capacity(
             descriptor_bindings
                 .values()
-                .filter(|(descriptor_info, _)| descriptor_info.sampler_info().is_some())
+                .filter(|(descriptor_info, _)| descriptor_info.sampler_info(device).is_some())
                 .count(),
         );
 
@@ -224,7 +406,7 @@ impl PipelineDescriptorInfo {
                 .values()
                 .filter_map(|(descriptor_info, _)| {
                     descriptor_info
-                        .sampler_info()
+                        .sampler_info(device)
                         .map(|sampler_info| (sampler_info, descriptor_info.binding_count()))
                 })
         {
@@ -255,8 +437,12 @@ impl PipelineDescriptorInfo {
             })
             .collect::<HashMap<_, _>>();
 
+        let features = &device.physical_device.features_v1_2;
+        let mut update_after_bind = false;
+
         for descriptor_set_idx in 0..descriptor_set_count {
             let mut binding_counts = HashMap::<vk::DescriptorType, u32>::new();
+            let mut binding_flags = vec![];
             let mut bindings = vec![];
 
             for (descriptor, (descriptor_info, stage_flags)) in descriptor_bindings
@@ -264,8 +450,9 @@ impl PipelineDescriptorInfo {
                 .filter(|(descriptor, _)| descriptor.set == descriptor_set_idx)
             {
                 let descriptor_ty = descriptor_info.descriptor_type();
-                *binding_counts.entry(descriptor_ty).or_default() +=
-                    descriptor_info.binding_count();
+                *binding_counts.entry(descriptor_ty).or_default() += descriptor_info
+                    .binding_count()
+                    * descriptor_info.descriptor_count_per_binding();
                 let mut binding = vk::DescriptorSetLayoutBinding::default()
                     .binding(descriptor.binding)
                     .descriptor_count(descriptor_info.binding_count())
@@ -273,7 +460,7 @@ impl PipelineDescriptorInfo {
                     .stage_flags(*stage_flags);
 
                 if let Some(immutable_samplers) =
-                    descriptor_info.sampler_info().map(|sampler_info| {
+                    descriptor_info.sampler_info(device).map(|sampler_info| {
                         &immutable_samplers[&sampler_info]
                             [0..descriptor_info.binding_count() as usize]
                     })
@@ -281,39 +468,84 @@ impl PipelineDescriptorInfo {
                     binding = binding.immutable_samplers(immutable_samplers);
                 }
 
+                let mut flags = vk::DescriptorBindingFlags::empty();
+
+                // Bindless arrays may leave elements unwritten
+                if features.descriptor_binding_partially_bound {
+                    flags |= vk::DescriptorBindingFlags::PARTIALLY_BOUND;
+                }
+
+                // Bindless arrays use the (much larger) update-after-bind descriptor limits
+                if bindless_descriptors.contains(descriptor)
+                    && match descriptor_ty {
+                        vk::DescriptorType::COMBINED_IMAGE_SAMPLER
+                        | vk::DescriptorType::SAMPLED_IMAGE
+                        | vk::DescriptorType::SAMPLER => {
+                            features.descriptor_binding_sampled_image_update_after_bind
+                        }
+                        vk::DescriptorType::STORAGE_BUFFER => {
+                            features.descriptor_binding_storage_buffer_update_after_bind
+                        }
+                        vk::DescriptorType::STORAGE_IMAGE => {
+                            features.descriptor_binding_storage_image_update_after_bind
+                        }
+                        vk::DescriptorType::STORAGE_TEXEL_BUFFER => {
+                            features.descriptor_binding_storage_texel_buffer_update_after_bind
+                        }
+                        vk::DescriptorType::UNIFORM_BUFFER => {
+                            features.descriptor_binding_uniform_buffer_update_after_bind
+                        }
+                        vk::DescriptorType::UNIFORM_TEXEL_BUFFER => {
+                            features.descriptor_binding_uniform_texel_buffer_update_after_bind
+                        }
+                        _ => false,
+                    }
+                {
+                    flags |= vk::DescriptorBindingFlags::UPDATE_AFTER_BIND;
+                }
+
+                binding_flags.push(flags);
                 bindings.push(binding);
             }
 
-            let pool_size = pool_sizes
-                .entry(descriptor_set_idx)
-                .or_insert_with(HashMap::new);
+            let is_push_descriptor_set = push_descriptor_set == Some(descriptor_set_idx);
+
+            // Push descriptor sets are not allocated from a pool
+            if !is_push_descriptor_set {
+                let pool_size = pool_sizes
+                    .entry(descriptor_set_idx)
+                    .or_insert_with(HashMap::new);
 
-            for (descriptor_ty, binding_count) in binding_counts.into_iter() {
-                *pool_size.entry(descriptor_ty).or_default() += binding_count;
+                for (descriptor_ty, binding_count) in binding_counts.into_iter() {
+                    *pool_size.entry(descriptor_ty).or_default() += binding_count;
+                }
             }
 
             //trace!("bindings: {:#?}", &bindings);
 
             let mut create_info = vk::DescriptorSetLayoutCreateInfo::default().bindings(&bindings);
 
-            // The bindless flags have to be created for every descriptor set layout binding.
+            // The binding flags have to be specified for every descriptor set layout binding.
             // [vulkan spec](https://www.khronos.org/registry/vulkan/specs/1.3-extensions/man/html/VkDescriptorSetLayoutBindingFlagsCreateInfo.html)
-            // Maybe using one vector and updating it would be more efficient.
-            let bindless_flags = vec![vk::DescriptorBindingFlags::PARTIALLY_BOUND; bindings.len()];
-            let mut bindless_flags = if device
-                .physical_device
-                .features_v1_2
-                .descriptor_binding_partially_bound
+            let mut binding_flags_info = vk::DescriptorSetLayoutBindingFlagsCreateInfo::default()
+                .binding_flags(&binding_flags);
+
+            if binding_flags.iter().any(|flags| !flags.is_empty()) {
+                create_info = create_info.push_next(&mut binding_flags_info);
+            }
+
+            if binding_flags
+                .iter()
+                .any(|flags| flags.contains(vk::DescriptorBindingFlags::UPDATE_AFTER_BIND))
             {
-                let bindless_flags = vk::DescriptorSetLayoutBindingFlagsCreateInfo::default()
-                    .binding_flags(&bindless_flags);
-                Some(bindless_flags)
-            } else {
-                None
-            };
+                create_info =
+                    create_info.flags(vk::DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL);
+                update_after_bind = true;
+            }
 
-            if let Some(bindless_flags) = bindless_flags.as_mut() {
-                create_info = create_info.push_next(bindless_flags);
+            if is_push_descriptor_set && device.push_descriptor_ext.is_some() {
+                create_info =
+                    create_info.flags(vk::DescriptorSetLayoutCreateFlags::PUSH_DESCRIPTOR_KHR);
             }
 
             layouts.insert(
@@ -333,7 +565,9 @@ impl PipelineDescriptorInfo {
         Ok(Self {
             layouts,
             pool_sizes,
+            push_descriptor_set,
             samplers,
+            update_after_bind,
         })
     }
 }
@@ -341,38 +575,171 @@ impl PipelineDescriptorInfo {
 pub(crate) struct Sampler {
     device: Arc<Device>,
     sampler: vk::Sampler,
+    _ycbcr_conversion: Option<SamplerYcbcrConversion>,
 }
 
 impl Sampler {
     #[profiling::function]
     pub fn create(device: &Arc<Device>, info: impl Into<SamplerInfo>) -> Result<Self, DriverError> {
         let device = Arc::clone(device);
-        let info = info.into();
+        let mut info: SamplerInfo = info.into();
+
+        debug_assert!(
+            info.min_lod <= info.max_lod,
+            "min_lod must be less than or equal to max_lod"
+        );
+
+        let limits = &device.physical_device.properties_v1_0.limits;
+
+        if info.anisotropy_enable {
+            // Immutable samplers guessed from binding names request anisotropy by default
+            if !device.physical_device.features_v1_0.sampler_anisotropy {
+                warn!("sampler anisotropy feature not supported");
+
+                info.anisotropy_enable = false;
+            } else if info.max_anisotropy.0 > limits.max_sampler_anisotropy {
+                warn!(
+                    "max anisotropy clamped to {}",
+                    limits.max_sampler_anisotropy
+                );
+
+                info.max_anisotropy = limits.max_sampler_anisotropy.into();
+            }
+
+            info.max_anisotropy = info.max_anisotropy.max(OrderedFloat(1.0));
+        }
+
+        if info.ycbcr_conversion.is_some()
+            && (info.address_mode_u != vk::SamplerAddressMode::CLAMP_TO_EDGE
+                || info.address_mode_v != vk::SamplerAddressMode::CLAMP_TO_EDGE
+                || info.address_mode_w != vk::SamplerAddressMode::CLAMP_TO_EDGE
+                || info.anisotropy_enable
+                || info.unnormalized_coordinates)
+        {
+            warn!("Y′CBCR conversion samplers must clamp to edge without anisotropy");
+
+            return Err(DriverError::InvalidData);
+        }
+
+        if info.mip_lod_bias.0.abs() > limits.max_sampler_lod_bias {
+            warn!("mip LOD bias clamped to {}", limits.max_sampler_lod_bias);
+
+            info.mip_lod_bias = info
+                .mip_lod_bias
+                .0
+                .clamp(-limits.max_sampler_lod_bias, limits.max_sampler_lod_bias)
+                .into();
+        }
+
+        let ycbcr_conversion = info
+            .ycbcr_conversion
+            .map(|ycbcr_conversion| SamplerYcbcrConversion::create(&device, ycbcr_conversion))
+            .transpose()?;
+        let mut reduction_mode_info =
+            vk::SamplerReductionModeCreateInfo::default().reduction_mode(info.reduction_mode);
+        let mut ycbcr_conversion_info = vk::SamplerYcbcrConversionInfo::default();
+        let mut create_info = vk::SamplerCreateInfo::default()
+            .flags(info.flags)
+            .mag_filter(info.mag_filter)
+            .min_filter(info.min_filter)
+            .mipmap_mode(info.mipmap_mode)
+            .address_mode_u(info.address_mode_u)
+            .address_mode_v(info.address_mode_v)
+            .address_mode_w(info.address_mode_w)
+            .mip_lod_bias(info.mip_lod_bias.0)
+            .anisotropy_enable(info.anisotropy_enable)
+            .max_anisotropy(info.max_anisotropy.0)
+            .compare_enable(info.compare_enable)
+            .compare_op(info.compare_op)
+            .min_lod(info.min_lod.0)
+            .max_lod(info.max_lod.0)
+            .border_color(info.border_color)
+            .unnormalized_coordinates(info.unnormalized_coordinates)
+            .push_next(&mut reduction_mode_info);
+
+        if let Some(ycbcr_conversion) = &ycbcr_conversion {
+            ycbcr_conversion_info = ycbcr_conversion_info.conversion(**ycbcr_conversion);
+            create_info = create_info.push_next(&mut ycbcr_conversion_info);
+        }
 
         let sampler = unsafe {
+            device.create_sampler(&create_info, None).map_err(|err| {
+                warn!("{err}");
+
+                match err {
+                    vk::Result::ERROR_OUT_OF_HOST_MEMORY
+                    | vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => DriverError::OutOfMemory,
+                    _ => DriverError::Unsupported,
+                }
+            })?
+        };
+
+        Ok(Self {
+            device,
+            sampler,
+            _ycbcr_conversion: ycbcr_conversion,
+        })
+    }
+}
+
+impl Debug for Sampler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.sampler)
+    }
+}
+
+impl Deref for Sampler {
+    type Target = vk::Sampler;
+
+    fn deref(&self) -> &Self::Target {
+        &self.sampler
+    }
+}
+
+impl Drop for Sampler {
+    #[profiling::function]
+    fn drop(&mut self) {
+        if panicking() {
+            return;
+        }
+
+        unsafe {
+            self.device.destroy_sampler(self.sampler, None);
+        }
+    }
+}
+
+pub(crate) struct SamplerYcbcrConversion {
+    device: Arc<Device>,
+    conversion: vk::SamplerYcbcrConversion,
+}
+
+impl SamplerYcbcrConversion {
+    #[profiling::function]
+    pub fn create(device: &Arc<Device>, info: YcbcrConversionInfo) -> Result<Self, DriverError> {
+        if !device
+            .physical_device
+            .features_v1_1
+            .sampler_ycbcr_conversion
+        {
+            warn!("sampler Y′CBCR conversion feature not supported");
+
+            return Err(DriverError::Unsupported);
+        }
+
+        let device = Arc::clone(device);
+        let conversion = unsafe {
             device
-                .create_sampler(
-                    &vk::SamplerCreateInfo::default()
-                        .flags(info.flags)
-                        .mag_filter(info.mag_filter)
-                        .min_filter(info.min_filter)
-                        .mipmap_mode(info.mipmap_mode)
-                        .address_mode_u(info.address_mode_u)
-                        .address_mode_v(info.address_mode_v)
-                        .address_mode_w(info.address_mode_w)
-                        .mip_lod_bias(info.mip_lod_bias.0)
-                        .anisotropy_enable(info.anisotropy_enable)
-                        .max_anisotropy(info.max_anisotropy.0)
-                        .compare_enable(info.compare_enable)
-                        .compare_op(info.compare_op)
-                        .min_lod(info.min_lod.0)
-                        .max_lod(info.max_lod.0)
-                        .border_color(info.border_color)
-                        .unnormalized_coordinates(info.unnormalized_coordinates)
-                        .push_next(
-                            &mut vk::SamplerReductionModeCreateInfo::default()
-                                .reduction_mode(info.reduction_mode),
-                        ),
+                .create_sampler_ycbcr_conversion(
+                    &vk::SamplerYcbcrConversionCreateInfo::default()
+                        .format(info.fmt)
+                        .ycbcr_model(info.ycbcr_model)
+                        .ycbcr_range(info.ycbcr_range)
+                        .components(info.components)
+                        .x_chroma_offset(info.x_chroma_offset)
+                        .y_chroma_offset(info.y_chroma_offset)
+                        .chroma_filter(info.chroma_filter)
+                        .force_explicit_reconstruction(info.force_explicit_reconstruction),
                     None,
                 )
                 .map_err(|err| {
@@ -386,25 +753,25 @@ impl Sampler {
                 })?
         };
 
-        Ok(Self { device, sampler })
+        Ok(Self { device, conversion })
     }
 }
 
-impl Debug for Sampler {
+impl Debug for SamplerYcbcrConversion {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self.sampler)
+        write!(f, "{:?}", self.conversion)
     }
 }
 
-impl Deref for Sampler {
-    type Target = vk::Sampler;
+impl Deref for SamplerYcbcrConversion {
+    type Target = vk::SamplerYcbcrConversion;
 
     fn deref(&self) -> &Self::Target {
-        &self.sampler
+        &self.conversion
     }
 }
 
-impl Drop for Sampler {
+impl Drop for SamplerYcbcrConversion {
     #[profiling::function]
     fn drop(&mut self) {
         if panicking() {
@@ -412,7 +779,8 @@ impl Drop for Sampler {
         }
 
         unsafe {
-            self.device.destroy_sampler(self.sampler, None);
+            self.device
+                .destroy_sampler_ycbcr_conversion(self.conversion, None);
         }
     }
 }
@@ -538,6 +906,21 @@ pub struct SamplerInfo {
     /// [requirements](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkSamplerCreateInfo.html).
     #[builder(default)]
     pub reduction_mode: vk::SamplerReductionMode,
+
+    /// Enables sampler Y′CBCR conversion, which is required in order to sample multi-planar
+    /// formats such as `vk::Format::G8_B8R8_2PLANE_420_UNORM`.
+    ///
+    /// Image views sampled using this sampler must specify an identical
+    /// [`ImageViewInfo::ycbcr_conversion`](super::image::ImageViewInfo::ycbcr_conversion) value
+    /// and this sampler must be an immutable combined image sampler (_see
+    /// [`ShaderBuilder::image_sampler`]_). Address modes must be `CLAMP_TO_EDGE` and anisotropy
+    /// must be disabled.
+    ///
+    /// Requires the
+    /// [`sampler_ycbcr_conversion`](super::physical_device::Vulkan11Features::sampler_ycbcr_conversion)
+    /// feature.
+    #[builder(default, setter(strip_option))]
+    pub ycbcr_conversion: Option<YcbcrConversionInfo>,
 }
 
 impl SamplerInfo {
@@ -560,6 +943,7 @@ impl SamplerInfo {
         border_color: None,
         unnormalized_coordinates: None,
         reduction_mode: None,
+        ycbcr_conversion: None,
     };
 
     /// Default sampler information with `mag_filter`, `min_filter` and `mipmap_mode` set to
@@ -582,6 +966,7 @@ impl SamplerInfo {
         border_color: None,
         unnormalized_coordinates: None,
         reduction_mode: None,
+        ycbcr_conversion: None,
     };
 
     /// Creates a default `SamplerInfoBuilder`.
@@ -613,6 +998,7 @@ impl SamplerInfo {
             border_color: Some(self.border_color),
             unnormalized_coordinates: Some(self.unnormalized_coordinates),
             reduction_mode: Some(self.reduction_mode),
+            ycbcr_conversion: Some(self.ycbcr_conversion),
         }
     }
 }
@@ -637,11 +1023,37 @@ impl Default for SamplerInfo {
             border_color: vk::BorderColor::FLOAT_TRANSPARENT_BLACK,
             unnormalized_coordinates: false,
             reduction_mode: vk::SamplerReductionMode::WEIGHTED_AVERAGE,
+            ycbcr_conversion: None,
         }
     }
 }
 
 impl SamplerInfoBuilder {
+    /// Enables anisotropic filtering using the given maximum anisotropy.
+    ///
+    /// Anisotropy is disabled if the
+    /// [`sampler_anisotropy`](super::physical_device::Vulkan10Features::sampler_anisotropy)
+    /// feature is not supported. Values greater than the `max_sampler_anisotropy` device limit are
+    /// clamped.
+    pub fn anisotropy(self, max_anisotropy: f32) -> Self {
+        self.anisotropy_enable(true).max_anisotropy(max_anisotropy)
+    }
+
+    /// Enables depth comparison using the given operator, as used by shadow map samplers.
+    ///
+    /// Sampled depth values are compared against a reference value and the result of the
+    /// comparison is filtered instead of the depth value itself.
+    pub fn compare(self, compare_op: vk::CompareOp) -> Self {
+        self.compare_enable(true).compare_op(compare_op)
+    }
+
+    /// Sets the minimum and maximum values used to clamp the computed LOD value.
+    ///
+    /// Use `vk::LOD_CLAMP_NONE` as `max_lod` to avoid clamping the maximum value.
+    pub fn lod_range(self, min_lod: f32, max_lod: f32) -> Self {
+        self.min_lod(min_lod).max_lod(max_lod)
+    }
+
     /// Builds a new `SamplerInfo`.
     #[inline(always)]
     pub fn build(self) -> SamplerInfo {
@@ -672,6 +1084,162 @@ impl From<UninitializedFieldError> for SamplerInfoBuilderError {
     }
 }
 
+/// Information used to create a
+/// [`vk::SamplerYcbcrConversion`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkSamplerYcbcrConversion.html)
+/// instance.
+///
+/// Sampler Y′CBCR conversion is used to sample multi-planar formats, such as those produced by
+/// hardware video decoders and cameras, as RGB. See [`SamplerInfo::ycbcr_conversion`] and
+/// [`ImageViewInfo::ycbcr_conversion`](super::image::ImageViewInfo::ycbcr_conversion).
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// # use ash::vk;
+/// # use screen_13::driver::image::{ImageType, ImageViewInfo};
+/// # use screen_13::driver::shader::{SamplerInfo, YcbcrConversionInfo};
+/// let fmt = vk::Format::G8_B8R8_2PLANE_420_UNORM;
+/// let ycbcr_conversion = YcbcrConversionInfo::new(fmt);
+/// let sampler_info = SamplerInfo::LINEAR
+///     .address_mode_u(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+///     .address_mode_v(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+///     .address_mode_w(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+///     .ycbcr_conversion(ycbcr_conversion)
+///     .build();
+/// let view_info = ImageViewInfo::new(fmt, ImageType::Texture2D)
+///     .to_builder()
+///     .ycbcr_conversion(ycbcr_conversion)
+///     .build();
+/// ```
+#[derive(Builder, Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[builder(
+    build_fn(
+        private,
+        name = "fallible_build",
+        error = "YcbcrConversionInfoBuilderError"
+    ),
+    derive(Clone, Copy, Debug),
+    pattern = "owned"
+)]
+#[non_exhaustive]
+pub struct YcbcrConversionInfo {
+    /// The format of the image from which color information will be retrieved.
+    pub fmt: vk::Format,
+
+    /// The color matrix used for conversion between color models.
+    ///
+    /// The default value is [`vk::SamplerYcbcrModelConversion::YCBCR_709`].
+    #[builder(default = "vk::SamplerYcbcrModelConversion::YCBCR_709")]
+    pub ycbcr_model: vk::SamplerYcbcrModelConversion,
+
+    /// Specifies whether the encoded values have headroom and foot room, or whether the encoding
+    /// uses the full numerical range.
+    ///
+    /// The default value is [`vk::SamplerYcbcrRange::ITU_NARROW`].
+    #[builder(default = "vk::SamplerYcbcrRange::ITU_NARROW")]
+    pub ycbcr_range: vk::SamplerYcbcrRange,
+
+    /// Applies a swizzle based on `vk::ComponentSwizzle` enums prior to range expansion and color
+    /// model conversion.
+    #[builder(default)]
+    pub components: vk::ComponentMapping,
+
+    /// Describes the sample location associated with downsampled chroma components in the x
+    /// dimension.
+    ///
+    /// The default value is [`vk::ChromaLocation::COSITED_EVEN`].
+    #[builder(default)]
+    pub x_chroma_offset: vk::ChromaLocation,
+
+    /// Describes the sample location associated with downsampled chroma components in the y
+    /// dimension.
+    ///
+    /// The default value is [`vk::ChromaLocation::COSITED_EVEN`].
+    #[builder(default)]
+    pub y_chroma_offset: vk::ChromaLocation,
+
+    /// The filter for chroma reconstruction.
+    ///
+    /// The default value is [`vk::Filter::NEAREST`].
+    #[builder(default)]
+    pub chroma_filter: vk::Filter,
+
+    /// Can be used to ensure that reconstruction is done explicitly, if supported.
+    #[builder(default)]
+    pub force_explicit_reconstruction: bool,
+}
+
+impl YcbcrConversionInfo {
+    /// Specifies a default conversion for the given multi-planar `fmt` value.
+    #[inline(always)]
+    pub const fn new(fmt: vk::Format) -> Self {
+        Self {
+            fmt,
+            ycbcr_model: vk::SamplerYcbcrModelConversion::YCBCR_709,
+            ycbcr_range: vk::SamplerYcbcrRange::ITU_NARROW,
+            components: vk::ComponentMapping {
+                r: vk::ComponentSwizzle::IDENTITY,
+                g: vk::ComponentSwizzle::IDENTITY,
+                b: vk::ComponentSwizzle::IDENTITY,
+                a: vk::ComponentSwizzle::IDENTITY,
+            },
+            x_chroma_offset: vk::ChromaLocation::COSITED_EVEN,
+            y_chroma_offset: vk::ChromaLocation::COSITED_EVEN,
+            chroma_filter: vk::Filter::NEAREST,
+            force_explicit_reconstruction: false,
+        }
+    }
+
+    /// Converts a `YcbcrConversionInfo` into a `YcbcrConversionInfoBuilder`.
+    #[inline(always)]
+    pub fn to_builder(self) -> YcbcrConversionInfoBuilder {
+        YcbcrConversionInfoBuilder {
+            fmt: Some(self.fmt),
+            ycbcr_model: Some(self.ycbcr_model),
+            ycbcr_range: Some(self.ycbcr_range),
+            components: Some(self.components),
+            x_chroma_offset: Some(self.x_chroma_offset),
+            y_chroma_offset: Some(self.y_chroma_offset),
+            chroma_filter: Some(self.chroma_filter),
+            force_explicit_reconstruction: Some(self.force_explicit_reconstruction),
+        }
+    }
+}
+
+impl From<YcbcrConversionInfoBuilder> for YcbcrConversionInfo {
+    fn from(info: YcbcrConversionInfoBuilder) -> Self {
+        info.build()
+    }
+}
+
+impl YcbcrConversionInfoBuilder {
+    /// Builds a new `YcbcrConversionInfo`.
+    ///
+    /// # Panics
+    ///
+    /// If any of the following values have not been set this function will panic:
+    ///
+    /// * `fmt`
+    #[inline(always)]
+    pub fn build(self) -> YcbcrConversionInfo {
+        match self.fallible_build() {
+            Err(YcbcrConversionInfoBuilderError(err)) => panic!("{err}"),
+            Ok(info) => info,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct YcbcrConversionInfoBuilderError(UninitializedFieldError);
+
+impl From<UninitializedFieldError> for YcbcrConversionInfoBuilderError {
+    fn from(err: UninitializedFieldError) -> Self {
+        Self(err)
+    }
+}
+
 /// Describes a shader program which runs on some pipeline stage.
 #[allow(missing_docs)]
 #[derive(Builder, Clone)]
@@ -683,6 +1251,9 @@ impl From<UninitializedFieldError> for SamplerInfoBuilderError {
 pub struct Shader {
     /// The name of the entry point which will be executed by this shader.
     ///
+    /// SPIR-V modules may contain multiple entry points; stages of the same pipeline may use the
+    /// same shader code with different entry names and a single shader module will be created.
+    ///
     /// The default value is `main`.
     #[builder(default = "\"main\".to_owned()")]
     pub entry_name: String,
@@ -746,11 +1317,17 @@ pub struct Shader {
     #[builder(private)]
     entry_point: EntryPoint,
 
+    #[builder(default, private)]
+    dynamic_buffers: HashSet<Descriptor>,
+
     #[builder(default, private)]
     image_samplers: HashMap<Descriptor, SamplerInfo>,
 
     #[builder(default, private, setter(strip_option))]
     vertex_input_state: Option<VertexInputState>,
+
+    #[builder(default, private)]
+    vertex_binding_divisors: Vec<vk::VertexInputBindingDivisorDescriptionEXT>,
 }
 
 impl Shader {
@@ -969,7 +1546,11 @@ impl Shader {
                     DescriptorInfo::Sampler(binding_count, sampler_info, is_manually_defined)
                 }
                 DescriptorType::StorageBuffer(_access_ty) => {
-                    DescriptorInfo::StorageBuffer(binding_count)
+                    if self.dynamic_buffers.contains(&descriptor) {
+                        DescriptorInfo::StorageBufferDynamic(binding_count)
+                    } else {
+                        DescriptorInfo::StorageBuffer(binding_count)
+                    }
                 }
                 DescriptorType::StorageImage(_access_ty) => {
                     DescriptorInfo::StorageImage(binding_count)
@@ -977,7 +1558,13 @@ impl Shader {
                 DescriptorType::StorageTexelBuffer(_access_ty) => {
                     DescriptorInfo::StorageTexelBuffer(binding_count)
                 }
-                DescriptorType::UniformBuffer() => DescriptorInfo::UniformBuffer(binding_count),
+                DescriptorType::UniformBuffer() => {
+                    if self.dynamic_buffers.contains(&descriptor) {
+                        DescriptorInfo::UniformBufferDynamic(binding_count)
+                    } else {
+                        DescriptorInfo::UniformBuffer(binding_count)
+                    }
+                }
                 DescriptorType::UniformTexelBuffer() => {
                     DescriptorInfo::UniformTexelBuffer(binding_count)
                 }
@@ -1001,6 +1588,25 @@ impl Shader {
         descriptor_bindings: impl IntoIterator<Item = DescriptorBindingMap>,
     ) -> DescriptorBindingMap {
         fn merge_info(lhs: &mut DescriptorInfo, rhs: DescriptorInfo) -> bool {
+            // A buffer specified as dynamic by any one stage is dynamic for the whole pipeline
+            let rhs = match (*lhs, rhs) {
+                (DescriptorInfo::StorageBuffer(count), DescriptorInfo::StorageBufferDynamic(_)) => {
+                    *lhs = DescriptorInfo::StorageBufferDynamic(count);
+                    rhs
+                }
+                (DescriptorInfo::StorageBufferDynamic(_), DescriptorInfo::StorageBuffer(count)) => {
+                    DescriptorInfo::StorageBufferDynamic(count)
+                }
+                (DescriptorInfo::UniformBuffer(count), DescriptorInfo::UniformBufferDynamic(_)) => {
+                    *lhs = DescriptorInfo::UniformBufferDynamic(count);
+                    rhs
+                }
+                (DescriptorInfo::UniformBufferDynamic(_), DescriptorInfo::UniformBuffer(count)) => {
+                    DescriptorInfo::UniformBufferDynamic(count)
+                }
+                _ => rhs,
+            };
+
             let (lhs_count, rhs_count) = match lhs {
                 DescriptorInfo::AccelerationStructure(lhs) => {
                     if let DescriptorInfo::AccelerationStructure(rhs) = rhs {
@@ -1068,6 +1674,13 @@ impl Shader {
                         return false;
                     }
                 }
+                DescriptorInfo::StorageBufferDynamic(lhs) => {
+                    if let DescriptorInfo::StorageBufferDynamic(rhs) = rhs {
+                        (lhs, rhs)
+                    } else {
+                        return false;
+                    }
+                }
                 DescriptorInfo::StorageImage(lhs) => {
                     if let DescriptorInfo::StorageImage(rhs) = rhs {
                         (lhs, rhs)
@@ -1089,6 +1702,13 @@ impl Shader {
                         return false;
                     }
                 }
+                DescriptorInfo::UniformBufferDynamic(lhs) => {
+                    if let DescriptorInfo::UniformBufferDynamic(rhs) = rhs {
+                        (lhs, rhs)
+                    } else {
+                        return false;
+                    }
+                }
                 DescriptorInfo::UniformTexelBuffer(lhs) => {
                     if let DescriptorInfo::UniformTexelBuffer(rhs) = rhs {
                         (lhs, rhs)
@@ -1157,6 +1777,36 @@ impl Shader {
             })
     }
 
+    /// Returns the `LocalSizeId` or `LocalSize` execution mode of the entry point of a compute
+    /// shader.
+    ///
+    /// The entry point is reflected using the specialization info of this shader, so any
+    /// specialization constants used by `LocalSizeId` have already been applied.
+    pub(super) fn local_size(&self) -> [u32; 3] {
+        let mut res = [1; 3];
+
+        // LocalSizeId overrides LocalSize when both are declared
+        if let Some(exec_mode) = [ExecutionMode::LocalSizeId, ExecutionMode::LocalSize]
+            .into_iter()
+            .find_map(|mode| {
+                self.entry_point
+                    .exec_modes
+                    .iter()
+                    .find(|exec_mode| exec_mode.exec_mode == mode)
+            })
+        {
+            for (size, operand) in res.iter_mut().zip(&exec_mode.operands) {
+                match operand.value {
+                    ConstantValue::S32(value) => *size = value as _,
+                    ConstantValue::U32(value) => *size = value,
+                    _ => (),
+                }
+            }
+        }
+
+        res
+    }
+
     #[profiling::function]
     fn reflect_entry_point(
         entry_name: &str,
@@ -1196,7 +1846,10 @@ impl Shader {
     pub(super) fn vertex_input(&self) -> VertexInputState {
         // Check for manually-specified vertex layout descriptions
         if let Some(vertex_input) = &self.vertex_input_state {
-            return vertex_input.clone();
+            return VertexInputState {
+                vertex_binding_divisors: self.vertex_binding_divisors.clone(),
+                ..vertex_input.clone()
+            };
         }
 
         fn scalar_format(ty: &ScalarType, byte_len: u32) -> vk::Format {
@@ -1320,6 +1973,7 @@ impl Shader {
         VertexInputState {
             vertex_attribute_descriptions,
             vertex_binding_descriptions,
+            vertex_binding_divisors: self.vertex_binding_divisors.clone(),
         }
     }
 }
@@ -1364,6 +2018,24 @@ impl ShaderBuilder {
             .expect("All required fields set at initialization")
     }
 
+    /// Specifies that a uniform or storage buffer descriptor uses a dynamic offset.
+    ///
+    /// SPIR-V does not distinguish dynamic buffers from regular buffers, so reflection alone treats
+    /// all buffers as regular. Dynamic buffers are bound once per pass and then offset for each
+    /// draw or dispatch using `set_dynamic_offset` on the pass recorder.
+    ///
+    /// Descriptor bindings may be specified as `(1, 2)` for descriptor set index `1` and binding
+    /// index `2`, or if the descriptor set index is `0` simply specify `2` for the same case.
+    ///
+    /// _NOTE:_ A buffer specified as dynamic in any stage of a pipeline is dynamic in all stages.
+    pub fn dynamic_buffer(mut self, descriptor: impl Into<Descriptor>) -> Self {
+        self.dynamic_buffers
+            .get_or_insert_with(Default::default)
+            .insert(descriptor.into());
+
+        self
+    }
+
     /// Specifies a manually-defined image sampler.
     ///
     /// Sampled images, by default, use reflection to automatically assign image samplers. Each
@@ -1423,10 +2095,46 @@ impl ShaderBuilder {
     ) -> Self {
         self.vertex_input_state = Some(Some(VertexInputState {
             vertex_binding_descriptions: bindings.to_vec(),
+            vertex_binding_divisors: vec![],
             vertex_attribute_descriptions: attributes.to_vec(),
         }));
         self
     }
+
+    /// Specifies that an instance-rate vertex buffer binding advances once every `divisor`
+    /// instances, instead of once per instance.
+    ///
+    /// Applies to both manually-defined and automatic vertex input layouts. A `divisor` of zero
+    /// repeats the first value for all instances and requires the
+    /// `vertex_attribute_instance_rate_zero_divisor` feature.
+    ///
+    /// Requires [`DeviceFeature::VertexAttributeDivisor`](super::device::DeviceFeature). Graphic
+    /// pipelines return [`DriverError::InvalidData`] if `binding` is not an instance-rate binding
+    /// or `divisor` exceeds `max_vertex_attrib_divisor` of
+    /// [`VertexAttributeDivisorProperties`](super::physical_device::VertexAttributeDivisorProperties),
+    /// and [`DriverError::Unsupported`] for an unsupported zero divisor.
+    pub fn vertex_binding_divisor(mut self, binding: u32, divisor: u32) -> Self {
+        let divisors = self
+            .vertex_binding_divisors
+            .get_or_insert_with(Default::default);
+        divisors.retain(|vertex_binding_divisor| vertex_binding_divisor.binding != binding);
+        divisors.push(vk::VertexInputBindingDivisorDescriptionEXT { binding, divisor });
+        self
+    }
+
+    /// Specifies a manually-defined vertex input layout using a single vertex-rate binding `0` of
+    /// type `V`.
+    ///
+    /// See [`Vertex`] for layouts which use more than one binding.
+    pub fn vertex_layout<V>(self) -> Self
+    where
+        V: Vertex,
+    {
+        self.vertex_input(
+            &[V::binding(0, vk::VertexInputRate::VERTEX)],
+            &V::attributes(0),
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -1482,7 +2190,7 @@ impl ShaderCode for Vec<u32> {
 }
 
 /// Describes specialized constant values.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct SpecializationInfo {
     /// A buffer of data which holds the constant values.
     pub data: Vec<u8>,
@@ -1502,6 +2210,137 @@ impl SpecializationInfo {
             map_entries: map_entries.into(),
         }
     }
+
+    /// Specifies specialized constant values one at a time.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// # use screen_13::driver::shader::SpecializationInfo;
+    /// let info = SpecializationInfo::builder()
+    ///     .constant(0, 64u32)
+    ///     .constant(1, 0.5f32)
+    ///     .constant(2, true)
+    ///     .build();
+    ///
+    /// assert_eq!(info.map_entries.len(), 3);
+    /// assert_eq!(info.data.len(), 12);
+    /// ```
+    pub fn builder() -> SpecializationBuilder {
+        Default::default()
+    }
+}
+
+impl From<SpecializationBuilder> for SpecializationInfo {
+    fn from(builder: SpecializationBuilder) -> Self {
+        builder.build()
+    }
+}
+
+/// Builder for [`SpecializationInfo`] which lays out the data and map entries of each constant.
+#[derive(Clone, Debug, Default)]
+pub struct SpecializationBuilder {
+    info: SpecializationInfo,
+}
+
+impl SpecializationBuilder {
+    /// Specifies the value of the specialization constant with the given `constant_id`.
+    ///
+    /// Specifying the same `constant_id` again replaces the previous value.
+    pub fn constant(mut self, constant_id: u32, value: impl SpecializationConstant) -> Self {
+        let data = value.to_ne_bytes();
+
+        if let Some(map_entry) = self
+            .info
+            .map_entries
+            .iter()
+            .find(|map_entry| map_entry.constant_id == constant_id)
+        {
+            let offset = map_entry.offset as usize;
+            self.info.data[offset..offset + data.len()].copy_from_slice(&data);
+        } else {
+            self.info.map_entries.push(vk::SpecializationMapEntry {
+                constant_id,
+                offset: self.info.data.len() as _,
+                size: data.len(),
+            });
+            self.info.data.extend_from_slice(&data);
+        }
+
+        self
+    }
+
+    /// Builds a new `SpecializationInfo`.
+    pub fn build(self) -> SpecializationInfo {
+        self.info
+    }
+}
+
+/// A value which may be used as a shader specialization constant.
+///
+/// Booleans are encoded as 32-bit `VkBool32` values.
+pub trait SpecializationConstant: Copy {
+    /// Returns the value as native-endian bytes.
+    fn to_ne_bytes(self) -> [u8; 4];
+}
+
+impl SpecializationConstant for bool {
+    fn to_ne_bytes(self) -> [u8; 4] {
+        if self { vk::TRUE } else { vk::FALSE }.to_ne_bytes()
+    }
+}
+
+impl SpecializationConstant for f32 {
+    fn to_ne_bytes(self) -> [u8; 4] {
+        f32::to_ne_bytes(self)
+    }
+}
+
+impl SpecializationConstant for i32 {
+    fn to_ne_bytes(self) -> [u8; 4] {
+        i32::to_ne_bytes(self)
+    }
+}
+
+impl SpecializationConstant for u32 {
+    fn to_ne_bytes(self) -> [u8; 4] {
+        u32::to_ne_bytes(self)
+    }
+}
+
+/// A `#[repr(C)]` type which may be read from a vertex buffer.
+///
+/// Implementations are usually generated using `#[derive(Vertex)]` from the `screen-13-macros`
+/// contrib crate. Layouts which use more than one binding may combine the results of multiple
+/// types:
+///
+/// ```no_run
+/// # use screen_13::driver::ash::vk;
+/// # use screen_13::driver::shader::{Shader, Vertex};
+/// # fn f<Position: Vertex, Instance: Vertex>(spirv: &[u8]) {
+/// let shader = Shader::new_vertex(spirv).vertex_input(
+///     &[
+///         Position::binding(0, vk::VertexInputRate::VERTEX),
+///         Instance::binding(1, vk::VertexInputRate::INSTANCE),
+///     ],
+///     &[Position::attributes(0), Instance::attributes(1)].concat(),
+/// );
+/// # }
+/// ```
+pub trait Vertex: Sized {
+    /// Returns the attribute descriptions of each field of this type, read from `binding`.
+    fn attributes(binding: u32) -> Vec<vk::VertexInputAttributeDescription>;
+
+    /// Returns a binding description with a stride of the size of this type.
+    fn binding(binding: u32, input_rate: vk::VertexInputRate) -> vk::VertexInputBindingDescription {
+        vk::VertexInputBindingDescription {
+            binding,
+            stride: size_of::<Self>() as _,
+            input_rate,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1526,4 +2365,295 @@ mod tests {
 
         assert_eq!(info, builder);
     }
+
+    #[test]
+    pub fn sampler_info_anisotropy_lod_range() {
+        let info = Builder::default()
+            .anisotropy(16.0)
+            .mip_lod_bias(-0.5)
+            .lod_range(1.0, vk::LOD_CLAMP_NONE)
+            .build();
+
+        assert!(info.anisotropy_enable);
+        assert_eq!(info.max_anisotropy, OrderedFloat(16.0));
+        assert_eq!(info.mip_lod_bias, OrderedFloat(-0.5));
+        assert_eq!(info.min_lod, OrderedFloat(1.0));
+        assert_eq!(info.max_lod, OrderedFloat(vk::LOD_CLAMP_NONE));
+    }
+
+    #[test]
+    pub fn sampler_info_compare() {
+        let info = Builder::default().compare(vk::CompareOp::LESS).build();
+
+        assert!(info.compare_enable);
+        assert_eq!(info.compare_op, vk::CompareOp::LESS);
+
+        let info = guess_immutable_sampler("shadow_sampler_lleG");
+
+        assert!(info.compare_enable);
+        assert_eq!(info.compare_op, vk::CompareOp::GREATER_OR_EQUAL);
+        assert_eq!(info.address_mode_u, vk::SamplerAddressMode::CLAMP_TO_EDGE);
+
+        let info = guess_immutable_sampler("pages_sampler_nnr");
+
+        assert!(!info.compare_enable);
+        assert_eq!(info.mag_filter, vk::Filter::NEAREST);
+    }
+
+    #[test]
+    pub fn sampler_info_border_color() {
+        let info = guess_immutable_sampler("post_sampler_llbk");
+
+        assert_eq!(info.address_mode_u, vk::SamplerAddressMode::CLAMP_TO_BORDER);
+        assert_eq!(info.border_color, vk::BorderColor::FLOAT_OPAQUE_BLACK);
+        assert!(!info.compare_enable);
+
+        let info = guess_immutable_sampler("shadow_sampler_llbwL");
+
+        assert_eq!(info.border_color, vk::BorderColor::FLOAT_OPAQUE_WHITE);
+        assert!(info.compare_enable);
+        assert_eq!(info.compare_op, vk::CompareOp::LESS_OR_EQUAL);
+
+        let info = guess_immutable_sampler("pages_sampler_nnb");
+
+        assert_eq!(info.border_color, vk::BorderColor::FLOAT_TRANSPARENT_BLACK);
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn sampler_info_border_color_invalid() {
+        guess_immutable_sampler("post_sampler_llbx");
+    }
+
+    #[test]
+    pub fn ycbcr_conversion_info() {
+        let fmt = vk::Format::G8_B8R8_2PLANE_420_UNORM;
+        let info = YcbcrConversionInfo::new(fmt);
+        let builder = info.to_builder().build();
+
+        assert_eq!(info, builder);
+        assert_eq!(info.ycbcr_model, vk::SamplerYcbcrModelConversion::YCBCR_709);
+
+        let sampler_info = Builder::default()
+            .address_mode_u(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+            .address_mode_v(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+            .address_mode_w(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+            .ycbcr_conversion(info)
+            .build();
+
+        assert_eq!(sampler_info.ycbcr_conversion, Some(info));
+        assert_eq!(sampler_info.to_builder().build(), sampler_info);
+        assert_eq!(
+            DescriptorInfo::CombinedImageSampler(1, sampler_info, true)
+                .descriptor_count_per_binding(),
+            2
+        );
+        assert_eq!(
+            DescriptorInfo::CombinedImageSampler(1, Info::default(), true)
+                .descriptor_count_per_binding(),
+            1
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn ycbcr_conversion_info_builder_uninit() {
+        YcbcrConversionInfoBuilder::default().build();
+    }
+
+    #[test]
+    pub fn specialization_builder() {
+        let info = SpecializationInfo::builder()
+            .constant(3, 42u32)
+            .constant(0, true)
+            .constant(1, -1i32)
+            .constant(2, 1.5f32)
+            .constant(3, 7u32)
+            .build();
+
+        assert_eq!(info.map_entries.len(), 4);
+        assert_eq!(info.data.len(), 16);
+
+        for (map_entry, (constant_id, offset)) in
+            info.map_entries
+                .iter()
+                .zip([(3, 0), (0, 4), (1, 8), (2, 12)])
+        {
+            assert_eq!(map_entry.constant_id, constant_id);
+            assert_eq!(map_entry.offset, offset);
+            assert_eq!(map_entry.size, 4);
+        }
+
+        assert_eq!(info.data[0..4], 7u32.to_ne_bytes());
+        assert_eq!(info.data[4..8], vk::TRUE.to_ne_bytes());
+        assert_eq!(info.data[8..12], (-1i32).to_ne_bytes());
+        assert_eq!(info.data[12..16], 1.5f32.to_ne_bytes());
+    }
+
+    #[test]
+    pub fn local_size() {
+        let shader = Shader::new_compute(inline_spirv::inline_spirv!(
+            r#"
+            #version 450
+
+            layout(local_size_x = 8, local_size_y = 4) in;
+
+            void main() {}
+            "#,
+            comp
+        ))
+        .build();
+
+        assert_eq!(shader.local_size(), [8, 4, 1]);
+    }
+
+    #[test]
+    pub fn local_size_id() {
+        // SPIR-V 1.6 declares specialized workgroup sizes using the LocalSizeId execution mode
+        let spirv = inline_spirv::inline_spirv!(
+            r#"
+            #version 460
+
+            layout(local_size_x_id = 0, local_size_y = 4) in;
+
+            void main() {}
+            "#,
+            comp,
+            vulkan1_3
+        );
+
+        let shader = Shader::new_compute(spirv).build();
+
+        assert_eq!(shader.local_size(), [1, 4, 1]);
+
+        let shader = Shader::new_compute(spirv)
+            .specialization_info(SpecializationInfo::builder().constant(0, 64u32).build())
+            .build();
+
+        assert_eq!(shader.local_size(), [64, 4, 1]);
+    }
+
+    #[test]
+    pub fn vertex_input_binding_divisors() {
+        let spirv = inline_spirv::inline_spirv!(
+            r#"
+            #version 450
+
+            layout(location = 0) in vec4 offset_ibind0;
+
+            void main() {
+                gl_Position = offset_ibind0;
+            }
+            "#,
+            vert
+        );
+
+        // Automatic vertex input layouts keep the last divisor of each binding
+        let vertex_input = Shader::new_vertex(spirv)
+            .vertex_binding_divisor(0, 2)
+            .vertex_binding_divisor(0, 4)
+            .build()
+            .vertex_input();
+
+        assert_eq!(vertex_input.vertex_binding_descriptions.len(), 1);
+        assert_eq!(
+            vertex_input.vertex_binding_descriptions[0].input_rate,
+            vk::VertexInputRate::INSTANCE
+        );
+        assert_eq!(vertex_input.vertex_binding_divisors.len(), 1);
+        assert_eq!(vertex_input.vertex_binding_divisors[0].binding, 0);
+        assert_eq!(vertex_input.vertex_binding_divisors[0].divisor, 4);
+
+        // Manual vertex input layouts keep divisors specified before the layout
+        let vertex_input = Shader::new_vertex(spirv)
+            .vertex_binding_divisor(1, 3)
+            .vertex_input(
+                &[
+                    vk::VertexInputBindingDescription {
+                        binding: 0,
+                        stride: 16,
+                        input_rate: vk::VertexInputRate::VERTEX,
+                    },
+                    vk::VertexInputBindingDescription {
+                        binding: 1,
+                        stride: 16,
+                        input_rate: vk::VertexInputRate::INSTANCE,
+                    },
+                ],
+                &[vk::VertexInputAttributeDescription {
+                    location: 0,
+                    binding: 1,
+                    format: vk::Format::R32G32B32A32_SFLOAT,
+                    offset: 0,
+                }],
+            )
+            .build()
+            .vertex_input();
+
+        assert_eq!(vertex_input.vertex_binding_descriptions.len(), 2);
+        assert_eq!(vertex_input.vertex_binding_divisors.len(), 1);
+        assert_eq!(vertex_input.vertex_binding_divisors[0].binding, 1);
+        assert_eq!(vertex_input.vertex_binding_divisors[0].divisor, 3);
+    }
+
+    #[test]
+    pub fn vertex_layout() {
+        #[allow(dead_code)]
+        #[repr(C)]
+        struct TestVertex {
+            position: [f32; 3],
+            color: [u8; 4],
+        }
+
+        impl Vertex for TestVertex {
+            fn attributes(binding: u32) -> Vec<vk::VertexInputAttributeDescription> {
+                vec![
+                    vk::VertexInputAttributeDescription {
+                        location: 0,
+                        binding,
+                        format: vk::Format::R32G32B32_SFLOAT,
+                        offset: 0,
+                    },
+                    vk::VertexInputAttributeDescription {
+                        location: 1,
+                        binding,
+                        format: vk::Format::R8G8B8A8_UNORM,
+                        offset: 12,
+                    },
+                ]
+            }
+        }
+
+        let spirv = inline_spirv::inline_spirv!(
+            r#"
+            #version 450
+
+            layout(location = 0) in vec3 position;
+            layout(location = 1) in vec4 color;
+
+            layout(location = 0) out vec4 color_out;
+
+            void main() {
+                gl_Position = vec4(position, 1.0);
+                color_out = color;
+            }
+            "#,
+            vert
+        );
+
+        let vertex_input = Shader::new_vertex(spirv)
+            .vertex_layout::<TestVertex>()
+            .build()
+            .vertex_input();
+
+        assert_eq!(vertex_input.vertex_binding_descriptions.len(), 1);
+        assert_eq!(vertex_input.vertex_binding_descriptions[0].binding, 0);
+        assert_eq!(vertex_input.vertex_binding_descriptions[0].stride, 16);
+        assert_eq!(
+            vertex_input.vertex_binding_descriptions[0].input_rate,
+            vk::VertexInputRate::VERTEX
+        );
+        assert_eq!(vertex_input.vertex_attribute_descriptions.len(), 2);
+        assert_eq!(vertex_input.vertex_attribute_descriptions[1].offset, 12);
+    }
 }
@@ -60,6 +60,40 @@ impl Surface {
         Ok(Self { device, surface })
     }
 
+    /// Helper function to select the first format which matches the given predicate, if any.
+    ///
+    /// This may be used to select wide-gamut or HDR color spaces, which require the
+    /// `VK_EXT_swapchain_colorspace` extension. See [`Surface::is_swapchain_colorspace_enabled`].
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// # use ash::vk;
+    /// # use screen_13::driver::surface::Surface;
+    /// # let formats = [vk::SurfaceFormatKHR::default()];
+    /// // formats is the result of Surface::formats(&my_surface)
+    /// let hdr10 = Surface::find_format(&formats, |format| {
+    ///     format.color_space == vk::ColorSpaceKHR::HDR10_ST2084_EXT
+    /// });
+    /// let format = hdr10.unwrap_or_else(|| Surface::linear_or_default(&formats));
+    /// ```
+    #[profiling::function]
+    pub fn find_format(
+        formats: &[vk::SurfaceFormatKHR],
+        mut predicate: impl FnMut(&vk::SurfaceFormatKHR) -> bool,
+    ) -> Option<vk::SurfaceFormatKHR> {
+        formats.iter().copied().find(|format| predicate(format))
+    }
+
+    /// Returns `true` if the `VK_EXT_swapchain_colorspace` instance extension was enabled, which
+    /// is required in order to present using color spaces other than
+    /// [`vk::ColorSpaceKHR::SRGB_NONLINEAR`].
+    pub fn is_swapchain_colorspace_enabled(this: &Self) -> bool {
+        Instance::is_swapchain_colorspace_enabled(Device::instance(&this.device))
+    }
+
     /// Lists the supported surface formats.
     #[profiling::function]
     pub fn formats(this: &Self) -> Result<Vec<vk::SurfaceFormatKHR>, DriverError> {
@@ -51,11 +51,26 @@ impl Swapchain {
 
     /// Gets the next available swapchain image which should be rendered to and then presented using
     /// [`present_image`][Self::present_image].
+    ///
+    /// If the swapchain is out of date or suboptimal, for example after the window has been
+    /// resized, it is recreated using the current surface dimensions and the image is acquired
+    /// again once. [`SwapchainError::Suboptimal`] is returned only if that also fails.
     #[profiling::function]
     pub fn acquire_next_image(&mut self) -> Result<SwapchainImage, SwapchainError> {
+        match self.try_acquire_next_image() {
+            Err(SwapchainError::Suboptimal) => {
+                debug!("Retrying swapchain image acquisition");
+
+                self.try_acquire_next_image()
+            }
+            res => res,
+        }
+    }
+
+    fn try_acquire_next_image(&mut self) -> Result<SwapchainImage, SwapchainError> {
         if self.suboptimal {
             self.recreate_swapchain()
-                .map_err(|_| SwapchainError::SurfaceLost)?;
+                .map_err(|_| SwapchainError::Suboptimal)?;
             self.suboptimal = false;
         }
 
@@ -216,7 +231,7 @@ impl Swapchain {
 
     /// Gets information about this swapchain.
     pub fn info(&self) -> SwapchainInfo {
-        self.info
+        self.info.clone()
     }
 
     /// Presents an image which has been previously acquired using
@@ -247,11 +262,13 @@ impl Swapchain {
             .swapchains(slice::from_ref(&self.swapchain))
             .image_indices(slice::from_ref(&image.image_idx));
 
+        let queue = self.device.queues[queue_family_index][queue_index].lock();
+
+        #[cfg(not(feature = "parking_lot"))]
+        let queue = queue.unwrap();
+
         unsafe {
-            match swapchain_ext.queue_present(
-                self.device.queues[queue_family_index][queue_index],
-                &present_info,
-            ) {
+            match swapchain_ext.queue_present(queue.queue, &present_info) {
                 Ok(_) => (),
                 Err(err)
                     if err == vk::Result::ERROR_DEVICE_LOST
@@ -375,9 +392,9 @@ impl Swapchain {
         }
 
         let present_mode_preference = if self.info.sync_display {
-            vec![vk::PresentModeKHR::FIFO_RELAXED, vk::PresentModeKHR::FIFO]
+            [vk::PresentModeKHR::FIFO_RELAXED, vk::PresentModeKHR::FIFO]
         } else {
-            vec![vk::PresentModeKHR::MAILBOX, vk::PresentModeKHR::IMMEDIATE]
+            [vk::PresentModeKHR::MAILBOX, vk::PresentModeKHR::IMMEDIATE]
         };
 
         let present_modes = unsafe {
@@ -392,9 +409,21 @@ impl Swapchain {
             DriverError::Unsupported
         })?;
 
-        let present_mode = present_mode_preference
-            .into_iter()
+        let present_mode = self
+            .info
+            .present_modes
+            .iter()
+            .copied()
             .find(|mode| present_modes.contains(mode))
+            .or_else(|| {
+                if !self.info.present_modes.is_empty() {
+                    warn!("Unsupported present modes: {:?}", &self.info.present_modes);
+                }
+
+                present_mode_preference
+                    .into_iter()
+                    .find(|mode| present_modes.contains(mode))
+            })
             .unwrap_or(vk::PresentModeKHR::FIFO);
 
         let pre_transform = if surface_capabilities
@@ -417,6 +446,15 @@ impl Swapchain {
             DriverError::Unsupported
         })?;
 
+        if self.info.surface.color_space != vk::ColorSpaceKHR::SRGB_NONLINEAR
+            && !Surface::is_swapchain_colorspace_enabled(&self.surface)
+        {
+            warn!(
+                "{:?} requires VK_EXT_swapchain_colorspace",
+                self.info.surface.color_space
+            );
+        }
+
         let swapchain_create_info = vk::SwapchainCreateInfoKHR::default()
             .surface(*self.surface)
             .min_image_count(desired_image_count)
@@ -576,7 +614,8 @@ pub enum SwapchainError {
     /// This frame is lost but more may be acquired later.
     DeviceLost,
 
-    /// This frame is not lost but there may be a delay while the next frame is recreated.
+    /// This frame is lost because the swapchain could not be recreated, such as while the window is
+    /// minimized, but more may be acquired later.
     Suboptimal,
 
     /// The surface was lost and must be recreated, which includes any operating system window.
@@ -584,10 +623,10 @@ pub enum SwapchainError {
 }
 
 /// Information used to create a [`Swapchain`] instance.
-#[derive(Builder, Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Builder, Clone, Debug, Eq, Hash, PartialEq)]
 #[builder(
     build_fn(private, name = "fallible_build", error = "SwapchainInfoBuilderError"),
-    derive(Clone, Copy, Debug),
+    derive(Clone, Debug),
     pattern = "owned"
 )]
 #[non_exhaustive]
@@ -601,7 +640,21 @@ pub struct SwapchainInfo {
     /// The initial height of the surface.
     pub height: u32,
 
+    /// A prioritized list of the desired presentation modes.
+    ///
+    /// The first supported mode is used. If empty, or if none of the modes are supported, the
+    /// presentation mode is chosen based on [`sync_display`](Self::sync_display).
+    ///
+    /// See
+    /// [VkPresentModeKHR](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPresentModeKHR.html).
+    #[builder(default)]
+    pub present_modes: Vec<vk::PresentModeKHR>,
+
     /// The format and color space of the surface.
+    ///
+    /// Color spaces other than [`vk::ColorSpaceKHR::SRGB_NONLINEAR`], such as
+    /// [`vk::ColorSpaceKHR::HDR10_ST2084_EXT`], require the `VK_EXT_swapchain_colorspace`
+    /// extension. See [`Surface::find_format`] and [`Surface::is_swapchain_colorspace_enabled`].
     pub surface: vk::SurfaceFormatKHR,
 
     /// Determines if frames will be submitted to the display in a synchronous fashion or if they
@@ -624,6 +677,7 @@ impl SwapchainInfo {
             height,
             surface,
             desired_image_count: 3,
+            present_modes: Vec::new(),
             sync_display: true,
         }
     }
@@ -634,6 +688,7 @@ impl SwapchainInfo {
         SwapchainInfoBuilder {
             desired_image_count: Some(self.desired_image_count),
             height: Some(self.height),
+            present_modes: Some(self.present_modes),
             surface: Some(self.surface),
             sync_display: Some(self.sync_display),
             width: Some(self.width),
@@ -700,7 +755,7 @@ mod tests {
     #[test]
     pub fn swapchain_info() {
         let info = Info::new(20, 24, vk::SurfaceFormatKHR::default());
-        let builder = info.to_builder().build();
+        let builder = info.clone().to_builder().build();
 
         assert_eq!(info, builder);
     }
@@ -717,6 +772,45 @@ mod tests {
         assert_eq!(info, builder);
     }
 
+    #[test]
+    pub fn swapchain_info_hdr_color_space() {
+        let formats = [
+            vk::SurfaceFormatKHR {
+                format: vk::Format::B8G8R8A8_UNORM,
+                color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
+            },
+            vk::SurfaceFormatKHR {
+                format: vk::Format::A2B10G10R10_UNORM_PACK32,
+                color_space: vk::ColorSpaceKHR::HDR10_ST2084_EXT,
+            },
+        ];
+        let surface = Surface::find_format(&formats, |format| {
+            format.color_space == vk::ColorSpaceKHR::HDR10_ST2084_EXT
+        })
+        .unwrap();
+        let info = Info::new(32, 32, surface).to_builder().build();
+
+        assert_eq!(info.surface.format, vk::Format::A2B10G10R10_UNORM_PACK32);
+        assert_eq!(
+            info.surface.color_space,
+            vk::ColorSpaceKHR::HDR10_ST2084_EXT
+        );
+    }
+
+    #[test]
+    pub fn swapchain_info_present_modes() {
+        let info = Builder::default()
+            .width(23)
+            .height(64)
+            .surface(vk::SurfaceFormatKHR::default())
+            .present_modes(vec![vk::PresentModeKHR::MAILBOX])
+            .build();
+        let builder = info.clone().to_builder().build();
+
+        assert_eq!(info, builder);
+        assert_eq!(info.present_modes, [vk::PresentModeKHR::MAILBOX]);
+    }
+
     #[test]
     #[should_panic(expected = "Field not initialized: height")]
     pub fn accel_struct_info_builder_uninit_height() {
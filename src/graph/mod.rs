@@ -13,7 +13,7 @@ mod swapchain;
 
 pub use self::{
     binding::{Bind, Unbind},
-    resolver::Resolver,
+    resolver::{PassBarrier, PassInfo, PassTimestamps, Resolver},
 };
 
 use {
@@ -27,35 +27,46 @@ use {
             AnyAccelerationStructureNode, AnyBufferNode, AnyImageNode, BufferLeaseNode, BufferNode,
             ImageLeaseNode, ImageNode, SwapchainImageNode,
         },
-        pass_ref::{AttachmentIndex, Bindings, Descriptor, PassRef, SubresourceAccess, ViewType},
+        pass_ref::{
+            AttachmentIndex, Bindings, Descriptor, PassRef, QueueClass, SubresourceAccess, ViewType,
+        },
     },
-    crate::driver::{
-        buffer::Buffer,
-        buffer_copy_subresources, buffer_image_copy_subresource,
-        compute::ComputePipeline,
-        device::Device,
-        format_aspect_mask,
-        graphic::{DepthStencilMode, GraphicPipeline},
-        image::{ImageType, ImageViewInfo, SampleCount},
-        is_write_access,
-        ray_trace::RayTracePipeline,
-        render_pass::ResolveMode,
-        shader::PipelineDescriptorInfo,
-        DescriptorBindingMap,
+    crate::{
+        driver::{
+            accel_struct::{AccelerationStructureGeometry, AccelerationStructureGeometryInfo},
+            buffer::{Buffer, BufferInfo, BufferSubresource},
+            buffer_copy_subresources, buffer_image_copy_subresource,
+            compute::ComputePipeline,
+            device::Device,
+            format_aspect_mask,
+            graphic::{DepthStencilMode, GraphicPipeline},
+            image::{ImageType, ImageViewInfo, SampleCount},
+            is_write_access,
+            query::QueryPool,
+            ray_trace::RayTracePipeline,
+            render_pass::ResolveMode,
+            shader::PipelineDescriptorInfo,
+            CommandBuffer, CommandBufferInfo, DescriptorBindingMap, DescriptorPool,
+            DescriptorPoolInfo, DescriptorSet, DriverError, RenderPass, RenderPassInfo,
+        },
+        pool::Pool,
     },
     ash::vk,
+    log::warn,
     std::{
+        cell::RefCell,
         cmp::Ord,
-        collections::{BTreeMap, HashMap},
+        collections::{BTreeMap, HashMap, HashSet},
         fmt::{Debug, Formatter},
         ops::Range,
         sync::Arc,
     },
-    vk_sync::AccessType,
+    vk_sync::{cmd::pipeline_barrier, AccessType, ImageBarrier, ImageLayout},
 };
 
 type ExecFn = Box<dyn FnOnce(&Device, vk::CommandBuffer, Bindings<'_>) + Send>;
 type NodeIndex = usize;
+type PassObserverFn = Box<dyn FnMut(PassInfo) + Send>;
 
 #[derive(Clone, Copy, Debug)]
 struct Area {
@@ -151,13 +162,39 @@ impl From<[u8; 4]> for ClearColorValue {
     }
 }
 
+/// The source stages, source access, destination stages, and destination access of the by-region
+/// subpass self-dependency which allows draws to read color attachments as subpass input.
+const SUBPASS_SELF_DEPENDENCY: (
+    vk::PipelineStageFlags,
+    vk::AccessFlags,
+    vk::PipelineStageFlags,
+    vk::AccessFlags,
+) = (
+    vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+    vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+    vk::PipelineStageFlags::FRAGMENT_SHADER,
+    vk::AccessFlags::INPUT_ATTACHMENT_READ,
+);
+
 #[derive(Default)]
 struct Execution {
     accesses: HashMap<NodeIndex, [SubresourceAccess; 2]>,
     bindings: BTreeMap<Descriptor, (NodeIndex, Option<ViewType>)>,
 
+    /// Buffers which source a conditional rendering predicate, which cannot be told apart from
+    /// other general accesses.
+    conditional_buffers: HashSet<NodeIndex>,
+
     correlated_view_mask: u32,
     depth_stencil: Option<DepthStencilMode>,
+    descriptor_sets: Vec<vk::DescriptorSet>,
+
+    /// Descriptor sets written by recorders in place of push descriptors on devices without
+    /// `VK_KHR_push_descriptor`, along with the pool each group of sets was allocated from; the
+    /// most recent set is last.
+    push_descriptor_sets: RefCell<Vec<(Vec<DescriptorSet>, DescriptorPool)>>,
+
+    self_dependency: bool,
     view_mask: u32,
 
     color_attachments: HashMap<AttachmentIndex, Attachment>,
@@ -187,7 +224,9 @@ impl Debug for Execution {
         f.debug_struct("Execution")
             .field("accesses", &self.accesses)
             .field("bindings", &self.bindings)
+            .field("conditional_buffers", &self.conditional_buffers)
             .field("depth_stencil", &self.depth_stencil)
+            .field("descriptor_sets", &self.descriptor_sets)
             .field("color_attachments", &self.color_attachments)
             .field("color_clears", &self.color_clears)
             .field("color_loads", &self.color_loads)
@@ -199,12 +238,22 @@ impl Debug for Execution {
             .field("depth_stencil_resolve", &self.depth_stencil_resolve)
             .field("depth_stencil_store", &self.depth_stencil_store)
             .field("pipeline", &self.pipeline)
+            .field("push_descriptor_sets", &self.push_descriptor_sets)
+            .field("self_dependency", &self.self_dependency)
             .finish()
     }
 }
 
 struct ExecutionFunction(ExecFn);
 
+struct PassObserver(PassObserverFn);
+
+impl Debug for PassObserver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PassObserver")
+    }
+}
+
 #[derive(Debug)]
 enum ExecutionPipeline {
     Compute(Arc<ComputePipeline>),
@@ -266,8 +315,13 @@ impl Clone for ExecutionPipeline {
 
 #[derive(Debug)]
 struct Pass {
+    dependencies: Vec<usize>,
     execs: Vec<Execution>,
+    merged: bool,
     name: String,
+    pass_indices: Vec<usize>,
+    pinned: bool,
+    queue_class: QueueClass,
     render_area: Option<Area>,
 }
 
@@ -293,7 +347,10 @@ impl Pass {
 #[derive(Debug)]
 pub struct RenderGraph {
     bindings: Vec<Binding>,
+    pass_observer: Option<PassObserver>,
     passes: Vec<Pass>,
+    transients: HashSet<NodeIndex>,
+    validation: bool,
 
     /// Set to true (when in debug mode) in order to get a breakpoint hit where you want.
     #[cfg(debug_assertions)]
@@ -306,18 +363,62 @@ impl RenderGraph {
     pub fn new() -> Self {
         let bindings = vec![];
         let passes = vec![];
+        let transients = HashSet::new();
+        let validation = cfg!(debug_assertions);
 
         #[cfg(debug_assertions)]
         let debug = false;
 
         Self {
             bindings,
+            pass_observer: None,
             passes,
+            transients,
+            validation,
             #[cfg(debug_assertions)]
             debug,
         }
     }
 
+    /// Declares that the pass at index `pass_a` must execute before the pass at index `pass_b`.
+    ///
+    /// The resolver reorders passes in order to increase GPU utilization, but it only knows about
+    /// the dependencies between passes which access the same nodes. Use this function to declare
+    /// any other ordering constraint, such as a pass which uses data written outside of this graph
+    /// by a previous pass. Resolving `pass_b` also resolves `pass_a`.
+    ///
+    /// Pass indexes are returned by [`PassRef::index`]. `pass_a` must have been begun before
+    /// `pass_b`.
+    ///
+    /// _NOTE:_ Each dependency limits the passes which may be reordered; overuse of this function
+    /// (_or [`PassRef::pin_order`]_) defeats the scheduler and may reduce performance.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// # use screen_13::graph::RenderGraph;
+    /// let mut my_graph = RenderGraph::new();
+    /// let pass_a = my_graph.begin_pass("a").index();
+    /// let pass_b = my_graph.begin_pass("b").index();
+    /// my_graph.add_dependency(pass_a, pass_b);
+    /// ```
+    pub fn add_dependency(&mut self, pass_a: usize, pass_b: usize) -> &mut Self {
+        assert!(
+            pass_a < pass_b,
+            "pass {pass_a} must be begun before dependent pass {pass_b}"
+        );
+        assert!(pass_b < self.passes.len(), "invalid pass {pass_b}");
+
+        let dependencies = &mut self.passes[pass_b].dependencies;
+        if !dependencies.contains(&pass_a) {
+            dependencies.push(pass_a);
+        }
+
+        self
+    }
+
     /// Begins a new pass.
     pub fn begin_pass(&mut self, name: impl AsRef<str>) -> PassRef<'_> {
         PassRef::new(self, name.as_ref().to_string())
@@ -396,6 +497,11 @@ impl RenderGraph {
     }
 
     /// Copy regions of an image, potentially performing format conversion.
+    ///
+    /// The source image must have been created with `vk::ImageUsageFlags::TRANSFER_SRC` and the
+    /// destination image with `vk::ImageUsageFlags::TRANSFER_DST`. When `filter` is
+    /// `vk::Filter::LINEAR` the source format must support
+    /// `vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR`.
     #[profiling::function]
     pub fn blit_image_regions(
         &mut self,
@@ -406,13 +512,40 @@ impl RenderGraph {
     ) -> &mut Self {
         let src_node = src_node.into();
         let dst_node = dst_node.into();
-        let src_access_range = self.node_info(src_node).default_view_info();
-        let dst_access_range = self.node_info(dst_node).default_view_info();
+        let src_info = self.node_info(src_node);
+        let dst_info = self.node_info(dst_node);
+
+        debug_assert!(
+            src_info.usage.contains(vk::ImageUsageFlags::TRANSFER_SRC),
+            "blit source image requires TRANSFER_SRC usage"
+        );
+        debug_assert!(
+            dst_info.usage.contains(vk::ImageUsageFlags::TRANSFER_DST),
+            "blit destination image requires TRANSFER_DST usage"
+        );
+
+        let src_access_range = src_info.default_view_info();
+        let dst_access_range = dst_info.default_view_info();
 
         self.begin_pass("blit image")
             .access_node_subrange(src_node, AccessType::TransferRead, src_access_range)
             .access_node_subrange(dst_node, AccessType::TransferWrite, dst_access_range)
             .record_cmd_buf(move |device, cmd_buf, bindings| unsafe {
+                debug_assert!(
+                    filter != vk::Filter::LINEAR || {
+                        let format_properties = Device::format_properties(device, src_info.fmt);
+                        let format_features = if src_info.tiling == vk::ImageTiling::LINEAR {
+                            format_properties.linear_tiling_features
+                        } else {
+                            format_properties.optimal_tiling_features
+                        };
+
+                        format_features
+                            .contains(vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR)
+                    },
+                    "blit source format does not support linear filtering"
+                );
+
                 device.cmd_blit_image(
                     cmd_buf,
                     *bindings[src_node],
@@ -432,19 +565,41 @@ impl RenderGraph {
     }
 
     /// Clear a color image.
-    #[profiling::function]
     pub fn clear_color_image_value(
         &mut self,
         image_node: impl Into<AnyImageNode>,
         color_value: impl Into<ClearColorValue>,
     ) -> &mut Self {
-        let color_value = color_value.into();
         let image_node = image_node.into();
         let image_info = self.node_info(image_node);
-        let image_access_range = image_info.default_view_info();
+
+        self.clear_color_image_range(
+            image_node,
+            color_value,
+            vk::ImageSubresourceRange {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                level_count: image_info.mip_level_count,
+                layer_count: image_info.array_layer_count(),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Clear a range of mip levels and array layers of a color image.
+    ///
+    /// Subresources of the image outside of `range` are not modified.
+    #[profiling::function]
+    pub fn clear_color_image_range(
+        &mut self,
+        image_node: impl Into<AnyImageNode>,
+        color_value: impl Into<ClearColorValue>,
+        range: vk::ImageSubresourceRange,
+    ) -> &mut Self {
+        let color_value = color_value.into();
+        let image_node = image_node.into();
 
         self.begin_pass("clear color")
-            .access_node_subrange(image_node, AccessType::TransferWrite, image_access_range)
+            .access_node_subrange(image_node, AccessType::TransferWrite, range)
             .record_cmd_buf(move |device, cmd_buf, bindings| unsafe {
                 device.cmd_clear_color_image(
                     cmd_buf,
@@ -453,12 +608,7 @@ impl RenderGraph {
                     &vk::ClearColorValue {
                         float32: color_value.0,
                     },
-                    &[vk::ImageSubresourceRange {
-                        aspect_mask: vk::ImageAspectFlags::COLOR,
-                        level_count: image_info.mip_level_count,
-                        layer_count: image_info.array_elements,
-                        ..Default::default()
-                    }],
+                    &[range],
                 );
             })
             .submit_pass()
@@ -492,7 +642,7 @@ impl RenderGraph {
                     &[vk::ImageSubresourceRange {
                         aspect_mask: format_aspect_mask(image_info.fmt),
                         level_count: image_info.mip_level_count,
-                        layer_count: image_info.array_elements,
+                        layer_count: image_info.array_layer_count(),
                         ..Default::default()
                     }],
                 );
@@ -500,6 +650,38 @@ impl RenderGraph {
             .submit_pass()
     }
 
+    /// Copy an acceleration structure, optionally compacting it.
+    ///
+    /// Compaction is a two-phase process:
+    ///
+    /// 1. Build the source acceleration structure using
+    ///    [`vk::BuildAccelerationStructureFlagsKHR::ALLOW_COMPACTION`] and then call
+    ///    [`RenderGraph::write_compacted_size`] to record the compacted size into a query pool
+    /// 2. Once that graph has been submitted and has finished executing, read the size back using
+    ///    [`QueryPool::results`], create the destination using
+    ///    [`AccelerationStructure::create_compacted`] and call this function using
+    ///    [`vk::CopyAccelerationStructureModeKHR::COMPACT`]
+    ///
+    /// [`AccelerationStructure::create_compacted`]: crate::driver::accel_struct::AccelerationStructure::create_compacted
+    #[profiling::function]
+    pub fn copy_acceleration_structure(
+        &mut self,
+        src_node: impl Into<AnyAccelerationStructureNode>,
+        dst_node: impl Into<AnyAccelerationStructureNode>,
+        mode: vk::CopyAccelerationStructureModeKHR,
+    ) -> &mut Self {
+        let src_node = src_node.into();
+        let dst_node = dst_node.into();
+
+        self.begin_pass("copy acceleration structure")
+            .access_node(src_node, AccessType::AccelerationStructureBuildRead)
+            .access_node(dst_node, AccessType::AccelerationStructureBuildWrite)
+            .record_acceleration(move |acceleration, _| {
+                acceleration.copy_structure(src_node, dst_node, mode);
+            })
+            .submit_pass()
+    }
+
     /// Copy data between buffers
     pub fn copy_buffer(
         &mut self,
@@ -578,6 +760,103 @@ impl RenderGraph {
             .submit_pass()
     }
 
+    /// Copies a range of buffer data to host memory, resolving and submitting this render graph
+    /// and blocking until it has executed.
+    ///
+    /// The copy is submitted to the first queue of queue family zero after any passes of this
+    /// render graph which write the buffer. A host-visible staging buffer is leased from `pool`.
+    ///
+    /// Returns [`DriverError::InvalidData`] if the buffer was not created with `TRANSFER_SRC`
+    /// usage or if `range` is not within the buffer.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::buffer::{Buffer, BufferInfo};
+    /// # use screen_13::graph::RenderGraph;
+    /// # use screen_13::pool::hash::HashPool;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let info = BufferInfo::device_mem(8, vk::BufferUsageFlags::TRANSFER_SRC);
+    /// # let my_buf = Buffer::create(&device, info)?;
+    /// let mut render_graph = RenderGraph::new();
+    /// let my_buf = render_graph.bind_node(my_buf);
+    /// let mut pool = HashPool::new(&device);
+    /// let data = render_graph.copy_buffer_to_host(my_buf, 4..8, &mut pool)?;
+    ///
+    /// assert_eq!(data.len(), 4);
+    /// # Ok(()) }
+    /// ```
+    #[profiling::function]
+    pub fn copy_buffer_to_host<P>(
+        mut self,
+        buffer_node: impl Into<AnyBufferNode>,
+        range: impl Into<BufferSubresource>,
+        pool: &mut P,
+    ) -> Result<Vec<u8>, DriverError>
+    where
+        P: Pool<BufferInfo, Buffer>
+            + Pool<CommandBufferInfo, CommandBuffer>
+            + Pool<DescriptorPoolInfo, DescriptorPool>
+            + Pool<RenderPassInfo, RenderPass>,
+    {
+        let buffer_node = buffer_node.into();
+        let buffer_info = self.node_info(buffer_node);
+        let BufferSubresource { start, end } = range.into();
+
+        if start > end || end > buffer_info.size {
+            warn!(
+                "range {start}..{end} not within buffer of {} bytes",
+                buffer_info.size
+            );
+
+            return Err(DriverError::InvalidData);
+        }
+
+        if !buffer_info
+            .usage
+            .contains(vk::BufferUsageFlags::TRANSFER_SRC)
+        {
+            warn!("buffer usage must include TRANSFER_SRC");
+
+            return Err(DriverError::InvalidData);
+        }
+
+        let size = end - start;
+
+        if size == 0 {
+            return Ok(vec![]);
+        }
+
+        let dst_buf = self.bind_node(pool.lease(BufferInfo::host_mem(
+            size,
+            vk::BufferUsageFlags::TRANSFER_DST,
+        ))?);
+        self.copy_buffer_region(
+            buffer_node,
+            dst_buf,
+            vk::BufferCopy {
+                src_offset: start,
+                dst_offset: 0,
+                size,
+            },
+        );
+
+        let dst_buf = self.unbind_node(dst_buf);
+
+        self.resolve().submit(pool, 0, 0)?.0.wait_until_executed()?;
+
+        Buffer::invalidate_range(&dst_buf, 0, size)?;
+
+        Ok(Buffer::mapped_slice(&dst_buf)[..size as usize].to_vec())
+    }
+
     /// Copy data from a buffer into an image.
     pub fn copy_buffer_to_image(
         &mut self,
@@ -630,8 +909,10 @@ impl RenderGraph {
     ) -> &mut Self {
         let src_node = src_node.into();
         let dst_node = dst_node.into();
-        let dst_access_range = self.node_info(dst_node).default_view_info();
-        let src_access_range = buffer_image_copy_subresource(regions.as_ref());
+        let dst_info = self.node_info(dst_node);
+        let dst_access_range = dst_info.default_view_info();
+        let mut src_access_range = buffer_image_copy_subresource(dst_info.fmt, regions.as_ref());
+        src_access_range.end = src_access_range.end.min(self.node_info(src_node).size);
 
         self.begin_pass("copy buffer to image")
             .access_node_subrange(src_node, AccessType::TransferRead, src_access_range)
@@ -788,8 +1069,10 @@ impl RenderGraph {
     ) -> &mut Self {
         let src_node = src_node.into();
         let dst_node = dst_node.into();
-        let src_subresource = self.node_info(src_node).default_view_info();
-        let dst_subresource = buffer_image_copy_subresource(regions.as_ref());
+        let src_info = self.node_info(src_node);
+        let src_subresource = src_info.default_view_info();
+        let mut dst_subresource = buffer_image_copy_subresource(src_info.fmt, regions.as_ref());
+        dst_subresource.end = dst_subresource.end.min(self.node_info(dst_node).size);
 
         self.begin_pass("copy image to buffer")
             .access_node_subrange(src_node, AccessType::TransferRead, src_subresource)
@@ -806,7 +1089,9 @@ impl RenderGraph {
             .submit_pass()
     }
 
-    /// Fill a region of a buffer with a fixed value.
+    /// Fill a buffer with a fixed value.
+    ///
+    /// This is useful for resetting atomic counters between passes without host access.
     pub fn fill_buffer(&mut self, buffer_node: impl Into<AnyBufferNode>, data: u32) -> &mut Self {
         let buffer_node = buffer_node.into();
 
@@ -816,6 +1101,9 @@ impl RenderGraph {
     }
 
     /// Fill a region of a buffer with a fixed value.
+    ///
+    /// Note: The start of `region` must be a multiple of four; the length of `region` must be a
+    /// multiple of four unless it extends to the end of the buffer.
     #[profiling::function]
     pub fn fill_buffer_region(
         &mut self,
@@ -825,7 +1113,15 @@ impl RenderGraph {
     ) -> &mut Self {
         let buffer_node = buffer_node.into();
         let buffer_info = self.node_info(buffer_node);
-        let buffer_access_range = 0..buffer_info.size;
+
+        debug_assert!(
+            buffer_info.size >= region.end,
+            "buffer range end ({}) exceeds buffer size ({})",
+            region.end,
+            buffer_info.size
+        );
+
+        let buffer_access_range = region.clone();
 
         self.begin_pass("fill buffer")
             .access_node_subrange(buffer_node, AccessType::TransferWrite, buffer_access_range)
@@ -841,6 +1137,180 @@ impl RenderGraph {
             .submit_pass()
     }
 
+    /// Generates all mip levels of an image by progressively blitting each mip level into the
+    /// next using linear filtering.
+    ///
+    /// The first mip level must already contain image data and is accessed as
+    /// [`AccessType::TransferRead`]; the remaining mip levels are accessed as
+    /// [`AccessType::TransferWrite`]. Afterwards, all mip levels of the image are accessed as
+    /// [`AccessType::TransferRead`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DriverError::Unsupported`] if the image format does not support blitting with
+    /// linear filtering.
+    #[profiling::function]
+    pub fn generate_mipmaps(
+        &mut self,
+        image_node: impl Into<AnyImageNode>,
+    ) -> Result<&mut Self, DriverError> {
+        let image_node = image_node.into();
+        let image_info = self.node_info(image_node);
+
+        if image_info.mip_level_count <= 1 {
+            return Ok(self);
+        }
+
+        {
+            let image = self.bindings[image_node.index()]
+                .as_driver_image()
+                .expect("image");
+            let format_properties = Device::format_properties(&image.device, image_info.fmt);
+            let format_features = if image_info.tiling == vk::ImageTiling::LINEAR {
+                format_properties.linear_tiling_features
+            } else {
+                format_properties.optimal_tiling_features
+            };
+
+            if !format_features.contains(
+                vk::FormatFeatureFlags::BLIT_SRC
+                    | vk::FormatFeatureFlags::BLIT_DST
+                    | vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR,
+            ) {
+                warn!(
+                    "format {:?} does not support linear blit filtering",
+                    image_info.fmt
+                );
+
+                return Err(DriverError::Unsupported);
+            }
+        }
+
+        let image_access_range = image_info.default_view_info();
+        let first_mip_access_range = ImageViewInfo {
+            mip_level_count: Some(1),
+            ..image_access_range
+        };
+        let mip_chain_access_range = ImageViewInfo {
+            base_mip_level: 1,
+            mip_level_count: Some(image_info.mip_level_count - 1),
+            ..image_access_range
+        };
+
+        Ok(self
+            .begin_pass("generate mipmaps")
+            .access_node_subrange(image_node, AccessType::TransferRead, first_mip_access_range)
+            .access_node_subrange(
+                image_node,
+                AccessType::TransferWrite,
+                mip_chain_access_range,
+            )
+            .access_node_subrange(image_node, AccessType::TransferRead, image_access_range)
+            .record_cmd_buf(move |device, cmd_buf, bindings| {
+                use std::slice::from_ref;
+
+                let image = *bindings[image_node];
+                let aspect_mask = format_aspect_mask(image_info.fmt);
+                let mip_extent = |mip_level: u32| vk::Offset3D {
+                    x: (image_info.width >> mip_level).max(1) as _,
+                    y: (image_info.height >> mip_level).max(1) as _,
+                    z: (image_info.depth >> mip_level).max(1) as _,
+                };
+                let mip_range = |base_mip_level: u32, level_count: u32| vk::ImageSubresourceRange {
+                    aspect_mask,
+                    base_mip_level,
+                    level_count,
+                    base_array_layer: 0,
+                    layer_count: image_info.array_layer_count(),
+                };
+                let transfer_read_barrier = |mip_level: u32| ImageBarrier {
+                    previous_accesses: from_ref(&AccessType::TransferWrite),
+                    next_accesses: from_ref(&AccessType::TransferRead),
+                    previous_layout: ImageLayout::Optimal,
+                    next_layout: ImageLayout::Optimal,
+                    discard_contents: false,
+                    src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+                    dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+                    image,
+                    range: mip_range(mip_level, 1),
+                };
+
+                // Only the first mip level was barriered by the graph: the contents of the
+                // remaining mip levels are about to be overwritten so their layout is discarded
+                pipeline_barrier(
+                    device,
+                    cmd_buf,
+                    None,
+                    &[],
+                    &[ImageBarrier {
+                        previous_accesses: from_ref(&AccessType::General),
+                        next_accesses: from_ref(&AccessType::TransferWrite),
+                        previous_layout: ImageLayout::Optimal,
+                        next_layout: ImageLayout::Optimal,
+                        discard_contents: true,
+                        src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+                        dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+                        image,
+                        range: mip_range(1, image_info.mip_level_count - 1),
+                    }],
+                );
+
+                for mip_level in 1..image_info.mip_level_count {
+                    if mip_level > 1 {
+                        pipeline_barrier(
+                            device,
+                            cmd_buf,
+                            None,
+                            &[],
+                            &[transfer_read_barrier(mip_level - 1)],
+                        );
+                    }
+
+                    unsafe {
+                        device.cmd_blit_image(
+                            cmd_buf,
+                            image,
+                            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                            image,
+                            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                            &[vk::ImageBlit {
+                                src_subresource: vk::ImageSubresourceLayers {
+                                    aspect_mask,
+                                    mip_level: mip_level - 1,
+                                    base_array_layer: 0,
+                                    layer_count: image_info.array_layer_count(),
+                                },
+                                src_offsets: [
+                                    vk::Offset3D { x: 0, y: 0, z: 0 },
+                                    mip_extent(mip_level - 1),
+                                ],
+                                dst_subresource: vk::ImageSubresourceLayers {
+                                    aspect_mask,
+                                    mip_level,
+                                    base_array_layer: 0,
+                                    layer_count: image_info.array_layer_count(),
+                                },
+                                dst_offsets: [
+                                    vk::Offset3D { x: 0, y: 0, z: 0 },
+                                    mip_extent(mip_level),
+                                ],
+                            }],
+                            vk::Filter::LINEAR,
+                        );
+                    }
+                }
+
+                pipeline_barrier(
+                    device,
+                    cmd_buf,
+                    None,
+                    &[],
+                    &[transfer_read_barrier(image_info.mip_level_count - 1)],
+                );
+            })
+            .submit_pass())
+    }
+
     #[profiling::function]
     pub(super) fn last_write(&self, node: impl Node) -> Option<AccessType> {
         let node_idx = node.index();
@@ -876,6 +1346,28 @@ impl RenderGraph {
         None
     }
 
+    /// Hints that the contents of an image node are not required after the last pass which accesses
+    /// it.
+    ///
+    /// Render pass attachments of transient images are not written back to memory (_they use
+    /// [`vk::AttachmentStoreOp::DONT_CARE`]_) once no other pass of this graph accesses the image.
+    /// This saves bandwidth on tiled architectures, where G-buffer attachments which live only
+    /// within a frame may remain entirely in tile memory.
+    ///
+    /// This hint does not alias the device memory of transient images. To share memory between
+    /// images with non-overlapping lifetimes lease them from an
+    /// [`AliasPool`](crate::pool::alias::AliasPool): compatible requests are given the same image
+    /// and the render graph orders the passes which access it.
+    ///
+    /// **_NOTE:_** Reading a transient image using a later render graph returns undefined contents.
+    pub fn mark_transient(&mut self, image_node: impl Into<AnyImageNode>) -> &mut Self {
+        let image_node: AnyImageNode = image_node.into();
+
+        self.transients.insert(image_node.index());
+
+        self
+    }
+
     /// Returns the device address of a buffer node.
     ///
     /// # Panics
@@ -909,6 +1401,42 @@ impl RenderGraph {
         Resolver::new(self)
     }
 
+    /// Sets a callback which is invoked as each pass is recorded during resolution.
+    ///
+    /// The callback receives a [`PassInfo`] describing the name of the pass, the nodes it
+    /// accesses, whether it was merged with other passes into the subpasses of a single render
+    /// pass, and the pipeline barriers recorded for it. This is useful for debugging
+    /// synchronization issues or visualizing how a graph was scheduled.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// # use screen_13::graph::RenderGraph;
+    /// let mut my_graph = RenderGraph::new();
+    /// my_graph.set_pass_observer(Box::new(|info| {
+    ///     println!("{} ({} barriers)", info.name, info.barriers.len());
+    /// }));
+    /// ```
+    pub fn set_pass_observer(&mut self, observer: Box<dyn FnMut(PassInfo) + Send>) -> &mut Self {
+        self.pass_observer = Some(PassObserver(observer));
+        self
+    }
+
+    /// Enables or disables record-time validation of descriptor bindings.
+    ///
+    /// When enabled, each `access_descriptor`, `read_descriptor`, or `write_descriptor` call of a
+    /// pipeline pass is checked against the descriptor bindings reflected from the shader code of
+    /// the pipeline. A descriptive warning, naming the descriptor set, binding, and expected
+    /// descriptor type, is logged if the node or access type does not match the shader.
+    ///
+    /// Validation is enabled by default in debug builds.
+    pub fn set_validation(&mut self, enabled: bool) -> &mut Self {
+        self.validation = enabled;
+        self
+    }
+
     /// Removes a node from this graph.
     ///
     /// Future access to `node` on this graph will return invalid results.
@@ -920,6 +1448,74 @@ impl RenderGraph {
         node.unbind(self)
     }
 
+    /// Records an in-place update (refit) of an acceleration structure previously built using
+    /// `prev_info`.
+    ///
+    /// This is much cheaper than a full rebuild when only geometry positions or instance transforms
+    /// have changed. The source build must have used
+    /// [`vk::BuildAccelerationStructureFlagsKHR::ALLOW_UPDATE`] and `info` must describe the same
+    /// topology; see [`AccelerationStructureGeometryInfo::is_update_compatible`].
+    ///
+    /// `data_nodes` are the buffers which contain the geometry or instance data referenced by
+    /// `info`, and `scratch_buf_node` must be at least as large as the `update_size` returned by
+    /// [`AccelerationStructure::size_of`](crate::driver::accel_struct::AccelerationStructure::size_of).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DriverError::InvalidData`] if `info` changes the topology of the acceleration
+    /// structure.
+    #[profiling::function]
+    pub fn update_acceleration_structure(
+        &mut self,
+        prev_info: &AccelerationStructureGeometryInfo<(
+            AccelerationStructureGeometry,
+            vk::AccelerationStructureBuildRangeInfoKHR,
+        )>,
+        info: AccelerationStructureGeometryInfo<(
+            AccelerationStructureGeometry,
+            vk::AccelerationStructureBuildRangeInfoKHR,
+        )>,
+        accel_struct_node: impl Into<AnyAccelerationStructureNode>,
+        scratch_buf_node: impl Into<AnyBufferNode>,
+        data_nodes: &[AnyBufferNode],
+    ) -> Result<&mut Self, DriverError> {
+        if !prev_info.is_update_compatible(&info) {
+            warn!("acceleration structure topology changed: rebuild required");
+
+            return Err(DriverError::InvalidData);
+        }
+
+        let accel_struct_node = accel_struct_node.into();
+        let scratch_buf_node = scratch_buf_node.into();
+
+        let mut pass = self.begin_pass("update acceleration structure");
+
+        for data_node in data_nodes.iter().copied() {
+            pass.access_node_mut(data_node, AccessType::AccelerationStructureBuildRead);
+        }
+
+        Ok(pass
+            .access_node(
+                scratch_buf_node,
+                AccessType::AccelerationStructureBufferWrite,
+            )
+            .access_node(
+                accel_struct_node,
+                AccessType::AccelerationStructureBuildWrite,
+            )
+            .record_acceleration(move |acceleration, bindings| {
+                let scratch_addr = Buffer::device_address(&bindings[scratch_buf_node]);
+
+                acceleration.update_structure(
+                    &info,
+                    accel_struct_node,
+                    accel_struct_node,
+                    scratch_addr,
+                );
+            })
+            .submit_pass())
+    }
+
     /// Note: `data` must not exceed 65536 bytes.
     pub fn update_buffer(
         &mut self,
@@ -929,7 +1525,12 @@ impl RenderGraph {
         self.update_buffer_offset(buffer_node, 0, data)
     }
 
-    /// Note: `data` must not exceed 65536 bytes.
+    /// Note: `data` must not exceed 65536 bytes, and both `offset` and the length of `data` must
+    /// be multiples of four.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` exceeds 65536 bytes.
     #[profiling::function]
     pub fn update_buffer_offset(
         &mut self,
@@ -939,8 +1540,23 @@ impl RenderGraph {
     ) -> &mut Self {
         let buffer_node = buffer_node.into();
         let buffer_info = self.node_info(buffer_node);
-        let buffer_access_range = 0..buffer_info.size;
+        let data_len = data.as_ref().len() as vk::DeviceSize;
+        let buffer_access_range = offset..offset + data_len;
 
+        assert!(
+            data_len <= 65_536,
+            "update buffer data ({data_len} bytes) exceeds 65536 bytes"
+        );
+        debug_assert_eq!(
+            offset % 4,
+            0,
+            "update buffer offset must be a multiple of 4"
+        );
+        debug_assert_eq!(
+            data_len % 4,
+            0,
+            "update buffer data must be a multiple of 4 bytes"
+        );
         debug_assert!(
             buffer_info.size >= buffer_access_range.end,
             "buffer range end ({}) exceeds buffer size ({})",
@@ -955,4 +1571,290 @@ impl RenderGraph {
             })
             .submit_pass()
     }
+
+    /// Records the compacted size of an acceleration structure into `query` of the given query
+    /// pool.
+    ///
+    /// The acceleration structure must have been built by a previous pass using
+    /// [`vk::BuildAccelerationStructureFlagsKHR::ALLOW_COMPACTION`]. The query result must not be
+    /// read back until after this graph has been submitted and has finished executing.
+    ///
+    /// See [`RenderGraph::copy_acceleration_structure`].
+    #[profiling::function]
+    pub fn write_compacted_size(
+        &mut self,
+        accel_struct_node: impl Into<AnyAccelerationStructureNode>,
+        query_pool: &Arc<QueryPool>,
+        query: u32,
+    ) -> &mut Self {
+        let accel_struct_node = accel_struct_node.into();
+        let query_pool = Arc::clone(query_pool);
+
+        self.begin_pass("write compacted size")
+            .access_node(
+                accel_struct_node,
+                AccessType::AccelerationStructureBuildRead,
+            )
+            .record_acceleration(move |acceleration, _| {
+                acceleration.write_compacted_size(accel_struct_node, &query_pool, query);
+            })
+            .submit_pass()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            driver::{
+                buffer::BufferInfo,
+                compute::{ComputePipeline, ComputePipelineInfoBuilder},
+                device::test_device,
+                image::{Image, ImageInfo},
+                shader::Shader,
+            },
+            pool::lazy::LazyPool,
+        },
+    };
+
+    #[test]
+    pub fn copy_buffer_to_host() {
+        let Some(device) = test_device() else {
+            return;
+        };
+
+        let buf = Buffer::create(
+            &device,
+            BufferInfo::device_mem(
+                16,
+                vk::BufferUsageFlags::TRANSFER_DST | vk::BufferUsageFlags::TRANSFER_SRC,
+            ),
+        )
+        .unwrap();
+
+        let mut graph = RenderGraph::new();
+        let buf = graph.bind_node(buf);
+        graph
+            .fill_buffer(buf, 0x01010101)
+            .fill_buffer_region(buf, 0x02020202, 4..8);
+
+        let mut pool = LazyPool::new(&device);
+        let data = graph.copy_buffer_to_host(buf, 2..10, &mut pool).unwrap();
+
+        assert_eq!(data, [1, 1, 2, 2, 2, 2, 1, 1]);
+    }
+
+    #[test]
+    pub fn copy_buffer_to_host_out_of_range() {
+        let Some(device) = test_device() else {
+            return;
+        };
+
+        let buf = Arc::new(
+            Buffer::create(
+                &device,
+                BufferInfo::device_mem(16, vk::BufferUsageFlags::TRANSFER_SRC),
+            )
+            .unwrap(),
+        );
+        let mut pool = LazyPool::new(&device);
+
+        let mut graph = RenderGraph::new();
+        let buf_node = graph.bind_node(&buf);
+
+        assert!(matches!(
+            graph.copy_buffer_to_host(buf_node, 8..17, &mut pool),
+            Err(DriverError::InvalidData)
+        ));
+
+        let mut graph = RenderGraph::new();
+        let buf_node = graph.bind_node(&buf);
+
+        #[allow(clippy::reversed_empty_ranges)]
+        let res = graph.copy_buffer_to_host(buf_node, 8..4, &mut pool);
+
+        assert!(matches!(res, Err(DriverError::InvalidData)));
+    }
+
+    #[test]
+    pub fn generate_mipmaps() {
+        let Some(device) = test_device() else {
+            return;
+        };
+
+        let image = Image::create(
+            &device,
+            ImageInfo::image_2d(
+                4,
+                4,
+                vk::Format::R8G8B8A8_UNORM,
+                vk::ImageUsageFlags::TRANSFER_DST | vk::ImageUsageFlags::TRANSFER_SRC,
+            )
+            .to_builder()
+            .mip_level_count(3),
+        )
+        .unwrap();
+        let mut src_buf = Buffer::create(
+            &device,
+            BufferInfo::host_mem(64, vk::BufferUsageFlags::TRANSFER_SRC),
+        )
+        .unwrap();
+        Buffer::copy_from_slice(&mut src_buf, 0, [0xff; 64]);
+        let dst_buf = Arc::new(
+            Buffer::create(
+                &device,
+                BufferInfo::host_mem(4, vk::BufferUsageFlags::TRANSFER_DST),
+            )
+            .unwrap(),
+        );
+
+        let mut graph = RenderGraph::new();
+        let image = graph.bind_node(image);
+        let src_buf = graph.bind_node(src_buf);
+        let dst_buf_node = graph.bind_node(Arc::clone(&dst_buf));
+
+        graph
+            .clear_color_image(image)
+            .copy_buffer_to_image(src_buf, image)
+            .generate_mipmaps(image)
+            .unwrap()
+            .copy_image_to_buffer_region(
+                image,
+                dst_buf_node,
+                vk::BufferImageCopy {
+                    buffer_offset: 0,
+                    buffer_row_length: 1,
+                    buffer_image_height: 1,
+                    image_subresource: vk::ImageSubresourceLayers {
+                        aspect_mask: vk::ImageAspectFlags::COLOR,
+                        mip_level: 2,
+                        base_array_layer: 0,
+                        layer_count: 1,
+                    },
+                    image_offset: Default::default(),
+                    image_extent: vk::Extent3D {
+                        width: 1,
+                        height: 1,
+                        depth: 1,
+                    },
+                },
+            );
+
+        let mut pool = LazyPool::new(&device);
+        let (cmd_buf, _) = graph.resolve().submit(&mut pool, 0, 0).unwrap();
+        cmd_buf.wait_until_executed().unwrap();
+
+        // The last mip level is only correct if the first mip level was not discarded
+        assert_eq!(Buffer::mapped_slice(&dst_buf), [0xff; 4]);
+    }
+
+    #[test]
+    pub fn generate_mipmaps_unsupported_format() {
+        let Some(device) = test_device() else {
+            return;
+        };
+
+        let image = Image::create(
+            &device,
+            ImageInfo::image_2d(
+                4,
+                4,
+                vk::Format::R8G8B8A8_UINT,
+                vk::ImageUsageFlags::TRANSFER_DST | vk::ImageUsageFlags::TRANSFER_SRC,
+            )
+            .to_builder()
+            .mip_level_count(3),
+        )
+        .unwrap();
+
+        let mut graph = RenderGraph::new();
+        let image = graph.bind_node(image);
+
+        assert!(matches!(
+            graph.generate_mipmaps(image),
+            Err(DriverError::Unsupported)
+        ));
+    }
+
+    #[test]
+    pub fn push_descriptors() {
+        let Some(device) = test_device() else {
+            return;
+        };
+
+        let pipeline = Arc::new(
+            ComputePipeline::create(
+                &device,
+                ComputePipelineInfoBuilder::default().push_descriptor_set(0),
+                Shader::new_compute(
+                    inline_spirv::inline_spirv!(
+                        r#"
+                        #version 450
+
+                        layout(push_constant) uniform PushConstants {
+                            uint idx;
+                        } push_constants;
+
+                        layout(set = 0, binding = 0) buffer Data {
+                            uint data[];
+                        } data;
+
+                        void main() {
+                            data.data[push_constants.idx] = push_constants.idx + 1;
+                        }
+                        "#,
+                        comp
+                    )
+                    .as_slice(),
+                ),
+            )
+            .unwrap(),
+        );
+        let buf = Buffer::create(
+            &device,
+            BufferInfo::device_mem(
+                256,
+                vk::BufferUsageFlags::STORAGE_BUFFER | vk::BufferUsageFlags::TRANSFER_SRC,
+            ),
+        )
+        .unwrap();
+
+        let mut graph = RenderGraph::new();
+        let buf = graph.bind_node(buf);
+
+        // Enough pushes to fill more than one descriptor pool on devices without push descriptors
+        graph
+            .begin_pass("push descriptors")
+            .bind_pipeline(&pipeline)
+            .access_node(buf, AccessType::ComputeShaderWrite)
+            .record_compute(move |compute, _| {
+                for idx in 0..64u32 {
+                    compute
+                        .push_buffer_descriptor(0, buf, 0..256)
+                        .unwrap()
+                        .push_constants(&idx.to_ne_bytes())
+                        .dispatch(1, 1, 1);
+                }
+
+                assert!(matches!(
+                    compute.push_buffer_descriptor((0, 1), buf, 0..256),
+                    Err(DriverError::InvalidData)
+                ));
+                assert!(matches!(
+                    compute.push_buffer_descriptor((1, 0), buf, 0..256),
+                    Err(DriverError::InvalidData)
+                ));
+            });
+
+        let mut pool = LazyPool::new(&device);
+        let data = graph.copy_buffer_to_host(buf, 0..256, &mut pool).unwrap();
+
+        for (idx, value) in data.chunks_exact(4).enumerate() {
+            assert_eq!(
+                u32::from_ne_bytes(value.try_into().unwrap()),
+                idx as u32 + 1
+            );
+        }
+    }
 }
@@ -6,7 +6,7 @@ use {
         AnyBufferNode, AnyImageNode, Area, Attachment, Bind, Binding, BufferLeaseNode, BufferNode,
         ClearColorValue, Edge, Execution, ExecutionFunction, ExecutionPipeline, ImageLeaseNode,
         ImageNode, Information, Node, NodeIndex, Pass, RenderGraph, SampleCount,
-        SwapchainImageNode,
+        SwapchainImageNode, SUBPASS_SELF_DEPENDENCY,
     },
     crate::driver::{
         accel_struct::{
@@ -15,21 +15,28 @@ use {
         },
         buffer::{Buffer, BufferSubresource},
         compute::ComputePipeline,
-        device::Device,
+        device::{Device, DeviceFeature},
+        format_aspect_mask,
         graphic::{DepthStencilMode, GraphicPipeline},
         image::{Image, ImageSubresource, ImageViewInfo},
-        ray_trace::RayTracePipeline,
+        query::QueryPool,
+        ray_trace::{RayTracePipeline, ShaderBindingTable},
         render_pass::ResolveMode,
+        shader::PipelineDescriptorInfo,
+        DescriptorBindingMap, DescriptorPool, DescriptorPoolInfo, DriverError,
     },
     ash::vk,
-    log::trace,
+    bytemuck::{bytes_of, Pod},
+    log::{trace, warn},
     std::{
         cell::RefCell,
+        collections::BTreeMap,
         marker::PhantomData,
+        mem::size_of,
         ops::{Index, Range},
         sync::Arc,
     },
-    vk_sync::AccessType,
+    vk_sync::{cmd::pipeline_barrier, AccessType, GlobalBarrier, ImageBarrier},
 };
 
 /// Alias for the index of a framebuffer attachment.
@@ -44,6 +51,9 @@ pub type BindingOffset = u32;
 /// Alias for the descriptor set index of a shader descriptor.
 pub type DescriptorSetIndex = u32;
 
+// vk-sync does not offer a conditional rendering read so we use the most general access
+const CONDITIONAL_RENDERING_ACCESS: AccessType = AccessType::General;
+
 /// Recording interface for acceleration structure commands.
 ///
 /// This structure provides a strongly-typed set of methods which allow acceleration structures to
@@ -699,6 +709,75 @@ impl Acceleration<'_> {
 
         self
     }
+
+    /// Writes the compacted size of an acceleration structure into `query` of the given query
+    /// pool.
+    ///
+    /// The acceleration structure must have been built with
+    /// [`vk::BuildAccelerationStructureFlagsKHR::ALLOW_COMPACTION`] in a previous pass and must be
+    /// accessed using [`AccessType::AccelerationStructureBuildRead`] by this pass. The query pool
+    /// must have been created with a type of
+    /// [`vk::QueryType::ACCELERATION_STRUCTURE_COMPACTED_SIZE_KHR`].
+    ///
+    /// The query result is only available after the command buffer has finished executing; read it
+    /// back using [`QueryPool::results`] and then create the destination using
+    /// [`AccelerationStructure::create_compacted`].
+    pub fn write_compacted_size(
+        &self,
+        accel_struct: impl Into<AnyAccelerationStructureNode>,
+        query_pool: &QueryPool,
+        query: u32,
+    ) -> &Self {
+        use std::slice::from_ref;
+
+        debug_assert_eq!(
+            query_pool.info.ty,
+            vk::QueryType::ACCELERATION_STRUCTURE_COMPACTED_SIZE_KHR
+        );
+
+        let accel_struct = accel_struct.into();
+
+        unsafe {
+            self.device
+                .cmd_reset_query_pool(self.cmd_buf, **query_pool, query, 1);
+            Device::expect_accel_struct_ext(self.device)
+                .cmd_write_acceleration_structures_properties(
+                    self.cmd_buf,
+                    from_ref(&*self.bindings[accel_struct]),
+                    vk::QueryType::ACCELERATION_STRUCTURE_COMPACTED_SIZE_KHR,
+                    **query_pool,
+                    query,
+                );
+        }
+
+        self
+    }
+
+    /// Copies an acceleration structure, optionally compacting it.
+    ///
+    /// When `mode` is [`vk::CopyAccelerationStructureModeKHR::COMPACT`] the destination should be
+    /// created using [`AccelerationStructure::create_compacted`].
+    pub fn copy_structure(
+        &self,
+        src_accel_struct: impl Into<AnyAccelerationStructureNode>,
+        dst_accel_struct: impl Into<AnyAccelerationStructureNode>,
+        mode: vk::CopyAccelerationStructureModeKHR,
+    ) -> &Self {
+        let src_accel_struct = src_accel_struct.into();
+        let dst_accel_struct = dst_accel_struct.into();
+
+        unsafe {
+            Device::expect_accel_struct_ext(self.device).cmd_copy_acceleration_structure(
+                self.cmd_buf,
+                &vk::CopyAccelerationStructureInfoKHR::default()
+                    .src(*self.bindings[src_accel_struct])
+                    .dst(*self.bindings[dst_accel_struct])
+                    .mode(mode),
+            );
+        }
+
+        self
+    }
 }
 
 /// Specifies the information and data used to build an acceleration structure.
@@ -899,6 +978,14 @@ impl AccelerationStructureUpdateInfo {
 
 /// Associated type trait which enables default values for read and write methods.
 pub trait Access {
+    /// The `AccessType` used when shaders of this pipeline read an acceleration structure, either
+    /// by tracing rays or by using inline ray queries (`rayQueryEXT`).
+    ///
+    /// The default is [`AccessType::General`] because it is the only access type which includes
+    /// `vk::AccessFlags::ACCELERATION_STRUCTURE_READ_KHR` without requiring a ray tracing or
+    /// acceleration structure build stage.
+    const ACCELERATION_STRUCTURE_READ: AccessType = AccessType::General;
+
     /// The default `AccessType` for read operations, if not specified explicitly.
     const DEFAULT_READ: AccessType;
 
@@ -917,6 +1004,8 @@ impl Access for GraphicPipeline {
 }
 
 impl Access for RayTracePipeline {
+    const ACCELERATION_STRUCTURE_READ: AccessType =
+        AccessType::RayTracingShaderReadAccelerationStructure;
     const DEFAULT_READ: AccessType =
         AccessType::RayTracingShaderReadSampledImageOrUniformTexelBuffer;
     const DEFAULT_WRITE: AccessType = AccessType::AnyShaderWrite;
@@ -1008,12 +1097,24 @@ bind!(RayTrace);
 #[derive(Clone, Copy, Debug)]
 pub struct Bindings<'a> {
     bindings: &'a [Binding],
+    cmd_buf: vk::CommandBuffer,
+    device: &'a Device,
     exec: &'a Execution,
 }
 
 impl<'a> Bindings<'a> {
-    pub(super) fn new(bindings: &'a [Binding], exec: &'a Execution) -> Self {
-        Self { bindings, exec }
+    pub(super) fn new(
+        bindings: &'a [Binding],
+        cmd_buf: vk::CommandBuffer,
+        device: &'a Device,
+        exec: &'a Execution,
+    ) -> Self {
+        Self {
+            bindings,
+            cmd_buf,
+            device,
+            exec,
+        }
     }
 
     fn binding_ref(&self, node_idx: usize) -> &Binding {
@@ -1026,6 +1127,112 @@ impl<'a> Bindings<'a> {
 
         &self.bindings[node_idx]
     }
+
+    /// Records a pipeline barrier between two commands of this execution.
+    ///
+    /// Node accesses declared on the pass are synchronized automatically; this function is only
+    /// required when raw commands recorded using [`PassRef::record_cmd_buf`] depend on each other.
+    /// The accesses of all `global_barriers` are combined into a single memory barrier.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::{AccessType, DriverError};
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::buffer::{Buffer, BufferInfo};
+    /// # use screen_13::driver::vk_sync::GlobalBarrier;
+    /// # use screen_13::graph::RenderGraph;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let info = BufferInfo::device_mem(8, vk::BufferUsageFlags::TRANSFER_DST);
+    /// # let buffer = Buffer::create(&device, info)?;
+    /// # let mut my_graph = RenderGraph::new();
+    /// # let my_buf_node = my_graph.bind_node(buffer);
+    /// my_graph.begin_pass("custom vulkan commands")
+    ///         .write_node(my_buf_node)
+    ///         .record_cmd_buf(move |device, cmd_buf, bindings| {
+    ///             let my_buf = *bindings[my_buf_node];
+    ///
+    ///             unsafe {
+    ///                 device.cmd_fill_buffer(cmd_buf, my_buf, 0, 8, 0);
+    ///             }
+    ///
+    ///             bindings.pipeline_barrier(
+    ///                 &[GlobalBarrier {
+    ///                     previous_accesses: &[AccessType::TransferWrite],
+    ///                     next_accesses: &[AccessType::TransferWrite],
+    ///                 }],
+    ///                 &[],
+    ///             );
+    ///
+    ///             unsafe {
+    ///                 device.cmd_fill_buffer(cmd_buf, my_buf, 4, 4, 42);
+    ///             }
+    ///         });
+    /// # Ok(()) }
+    /// ```
+    #[profiling::function]
+    pub fn pipeline_barrier(
+        &self,
+        global_barriers: &[GlobalBarrier],
+        image_barriers: &[ImageBarrier],
+    ) -> &Self {
+        let mut next_accesses = vec![];
+        let mut previous_accesses = vec![];
+        for global_barrier in global_barriers {
+            next_accesses.extend_from_slice(global_barrier.next_accesses);
+            previous_accesses.extend_from_slice(global_barrier.previous_accesses);
+        }
+
+        let global_barrier = (!global_barriers.is_empty()).then_some(GlobalBarrier {
+            next_accesses: &next_accesses,
+            previous_accesses: &previous_accesses,
+        });
+
+        pipeline_barrier(
+            self.device,
+            self.cmd_buf,
+            global_barrier,
+            &[],
+            image_barriers,
+        );
+
+        self
+    }
+
+    fn is_conditional_buffer(&self, node: impl Node) -> bool {
+        self.exec.conditional_buffers.contains(&node.index())
+    }
+
+    /// Warns (_in debug builds_) if the given node was not accessed in a way which synchronizes
+    /// indirect parameter reads.
+    fn check_indirect_buffer(&self, node: impl Node) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        let is_indirect_buffer = self
+            .exec
+            .accesses
+            .get(&node.index())
+            .map(|accesses| {
+                accesses.iter().any(|access| {
+                    matches!(
+                        access.access,
+                        AccessType::IndirectBuffer | AccessType::General
+                    )
+                })
+            })
+            .unwrap_or_default();
+
+        if !is_indirect_buffer {
+            warn!("indirect buffer access required: call read_indirect_buffer first");
+        }
+    }
 }
 
 macro_rules! index {
@@ -1146,10 +1353,47 @@ pub struct Compute<'a> {
     bindings: Bindings<'a>,
     cmd_buf: vk::CommandBuffer,
     device: &'a Device,
+    dynamic_offsets: RefCell<DynamicOffsets>,
     pipeline: Arc<ComputePipeline>,
 }
 
 impl Compute<'_> {
+    /// Begins conditional rendering of the subsequent dispatch commands.
+    ///
+    /// The 32-bit value at `offset` of `buffer` is read when the commands execute: commands are
+    /// discarded if the value is zero, or if the value is non-zero and `inverted` is `true`. Call
+    /// [`Compute::end_conditional`] after the dispatch commands to be predicated.
+    ///
+    /// `buffer` must have been created with [`vk::BufferUsageFlags::CONDITIONAL_RENDERING_EXT`]
+    /// and must be read using [`PipelinePassRef::read_conditional_buffer`]. `offset` must be a
+    /// multiple of four.
+    ///
+    /// Returns [`DriverError::FeatureNotEnabled`] if the device does not support
+    /// [`DeviceFeature::ConditionalRendering`].
+    pub fn begin_conditional(
+        &self,
+        buffer: impl Into<AnyBufferNode>,
+        offset: vk::DeviceSize,
+        inverted: bool,
+    ) -> Result<&Self, DriverError> {
+        let buffer = buffer.into();
+
+        debug_assert!(
+            self.bindings.is_conditional_buffer(buffer),
+            "conditional buffer access required: call read_conditional_buffer first"
+        );
+
+        begin_conditional_rendering(
+            self.device,
+            self.cmd_buf,
+            *self.bindings[buffer],
+            offset,
+            inverted,
+        )?;
+
+        Ok(self)
+    }
+
     /// [Dispatch] compute work items.
     ///
     /// When the command is executed, a global workgroup consisting of
@@ -1248,6 +1492,75 @@ impl Compute<'_> {
         self
     }
 
+    /// [Dispatch] enough compute workgroups to cover `width × height × depth` work items.
+    ///
+    /// Each dimension is divided by the local workgroup size of the compute shader, rounding up,
+    /// so the shader must ignore any work items outside of the requested size. See
+    /// [`ComputePipeline::local_size`].
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// # inline_spirv::inline_spirv!(r#"
+    /// #version 450
+    ///
+    /// layout(local_size_x = 16, local_size_y = 16) in;
+    /// layout(set = 0, binding = 0, rgba8) writeonly uniform image2D my_image;
+    ///
+    /// void main()
+    /// {
+    ///     if (any(greaterThanEqual(gl_GlobalInvocationID.xy, imageSize(my_image)))) {
+    ///         return;
+    ///     }
+    ///
+    ///     imageStore(my_image, ivec2(gl_GlobalInvocationID.xy), vec4(1.0));
+    /// }
+    /// # "#, comp);
+    /// ```
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::image::{Image, ImageInfo};
+    /// # use screen_13::driver::compute::{ComputePipeline, ComputePipelineInfo};
+    /// # use screen_13::driver::shader::{Shader};
+    /// # use screen_13::graph::RenderGraph;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let info = ImageInfo::image_2d(1920, 1080, vk::Format::R8G8B8A8_UNORM, vk::ImageUsageFlags::STORAGE);
+    /// # let my_image = Image::create(&device, info)?;
+    /// # let info = ComputePipelineInfo::default();
+    /// # let shader = Shader::new_compute([0u8; 1].as_slice());
+    /// # let my_compute_pipeline = Arc::new(ComputePipeline::create(&device, info, shader)?);
+    /// # let mut my_graph = RenderGraph::new();
+    /// # let my_image_node = my_graph.bind_node(my_image);
+    /// my_graph.begin_pass("fill my_image_node")
+    ///         .bind_pipeline(&my_compute_pipeline)
+    ///         .write_descriptor(0, my_image_node)
+    ///         .record_compute(move |compute, bindings| {
+    ///             // Dispatches 120x68x1 workgroups
+    ///             compute.dispatch_elements(1920, 1080, 1);
+    ///         });
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [Dispatch]: https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCmdDispatch.html
+    #[profiling::function]
+    pub fn dispatch_elements(&self, width: u32, height: u32, depth: u32) -> &Self {
+        let [local_size_x, local_size_y, local_size_z] =
+            ComputePipeline::local_size(&self.pipeline);
+
+        self.dispatch(
+            width.div_ceil(local_size_x),
+            height.div_ceil(local_size_y),
+            depth.div_ceil(local_size_z),
+        )
+    }
+
     /// Dispatch compute work items with indirect parameters.
     ///
     /// `dispatch_indirect` behaves similarly to [`Compute::dispatch`] except that the parameters
@@ -1289,13 +1602,13 @@ impl Compute<'_> {
     ///     std::slice::from_raw_parts(&cmd as *const _ as *const _, CMD_SIZE)
     /// };
     ///
-    /// let args_buf_flags = vk::BufferUsageFlags::STORAGE_BUFFER;
+    /// let args_buf_flags = vk::BufferUsageFlags::INDIRECT_BUFFER;
     /// let args_buf = Buffer::create_from_slice(&device, args_buf_flags, cmd_data)?;
     /// let args_buf_node = my_graph.bind_node(args_buf);
     ///
     /// my_graph.begin_pass("fill my_buf_node with data")
     ///         .bind_pipeline(&my_compute_pipeline)
-    ///         .read_node(args_buf_node)
+    ///         .read_indirect_buffer(args_buf_node)
     ///         .write_descriptor(0, my_buf_node)
     ///         .record_compute(move |compute, bindings| {
     ///             compute.dispatch_indirect(args_buf_node, 0);
@@ -1313,6 +1626,8 @@ impl Compute<'_> {
     ) -> &Self {
         let args_buf = args_buf.into();
 
+        self.bindings.check_indirect_buffer(args_buf);
+
         unsafe {
             self.device
                 .cmd_dispatch_indirect(self.cmd_buf, *self.bindings[args_buf], args_offset);
@@ -1321,6 +1636,13 @@ impl Compute<'_> {
         self
     }
 
+    /// Ends conditional rendering previously begun using [`Compute::begin_conditional`].
+    pub fn end_conditional(&self) -> &Self {
+        end_conditional_rendering(self.device, self.cmd_buf);
+
+        self
+    }
+
     /// Updates push constants.
     ///
     /// Push constants represent a high speed path to modify constant data in pipelines that is
@@ -1467,7 +1789,7 @@ impl Compute<'_> {
                         self.cmd_buf,
                         self.pipeline.layout,
                         vk::ShaderStageFlags::COMPUTE,
-                        push_const.offset,
+                        start,
                         &data[(start - offset) as usize..(end - offset) as usize],
                     );
                 }
@@ -1476,6 +1798,500 @@ impl Compute<'_> {
 
         self
     }
+
+    /// Updates push constants using the bytes of `data`.
+    ///
+    /// Behaves similarly to [`Compute::push_constants`] except that the size of `T` is checked
+    /// against the push constant ranges reflected from the shader code of the currently bound
+    /// pipeline. A warning is logged if the sizes do not match, which usually means the layout of
+    /// `T` has drifted from the shader code.
+    pub fn push_constants_typed<T: Pod>(&self, data: &T) -> &Self {
+        check_push_constants_size::<T>(self.pipeline.push_constants.iter());
+
+        self.push_constants(bytes_of(data))
+    }
+
+    /// Pushes a buffer descriptor into the push descriptor set of the pipeline.
+    ///
+    /// The descriptor applies to subsequent commands. The pipeline must have been created with a
+    /// `push_descriptor_set` which contains `descriptor`, and the buffer must have been accessed
+    /// by this pass (_such as by using [`PipelinePassRef::access_node`]_).
+    ///
+    /// Returns [`DriverError::InvalidData`] if `descriptor` is not part of the push descriptor set.
+    #[profiling::function]
+    pub fn push_buffer_descriptor(
+        &self,
+        descriptor: impl Into<Descriptor>,
+        buffer_node: impl Into<AnyBufferNode>,
+        range: Range<vk::DeviceSize>,
+    ) -> Result<&Self, DriverError> {
+        let descriptor = descriptor.into();
+        let buffer = &self.bindings[buffer_node.into()];
+        let buffer_info = vk::DescriptorBufferInfo::default()
+            .buffer(**buffer)
+            .offset(range.start)
+            .range(range.end - range.start);
+
+        push_descriptor(
+            &self.bindings,
+            vk::PipelineBindPoint::COMPUTE,
+            &self.pipeline.device,
+            self.pipeline.layout,
+            &self.pipeline.descriptor_bindings,
+            &self.pipeline.descriptor_info,
+            descriptor,
+            vk::WriteDescriptorSet::default().buffer_info(std::slice::from_ref(&buffer_info)),
+        )?;
+
+        Ok(self)
+    }
+
+    /// Pushes an image descriptor into the push descriptor set of the pipeline.
+    ///
+    /// The descriptor applies to subsequent commands. The pipeline must have been created with a
+    /// `push_descriptor_set` which contains `descriptor`, and the image must have been accessed
+    /// by this pass (_such as by using [`PipelinePassRef::access_node`]_).
+    ///
+    /// Returns [`DriverError::InvalidData`] if `descriptor` is not part of the push descriptor set
+    /// or is not a sampled or storage image descriptor, or an error if the image view cannot be
+    /// created.
+    #[profiling::function]
+    pub fn push_image_descriptor(
+        &self,
+        descriptor: impl Into<Descriptor>,
+        image_node: impl Into<AnyImageNode>,
+        image_view_info: impl Into<ImageViewInfo>,
+    ) -> Result<&Self, DriverError> {
+        let descriptor = descriptor.into();
+        let image = &self.bindings[image_node.into()];
+        let mut image_view_info = image_view_info.into();
+
+        // Handle default views which did not specify a particular aspect
+        if image_view_info.aspect_mask.is_empty() {
+            image_view_info.aspect_mask = format_aspect_mask(image.info.fmt);
+        }
+
+        let descriptor_type = push_descriptor_type(
+            &self.pipeline.descriptor_bindings,
+            &self.pipeline.descriptor_info,
+            descriptor,
+        )?;
+        let Some(image_layout) =
+            image_descriptor_layout(descriptor_type, image_view_info.aspect_mask)
+        else {
+            warn!("unsupported push descriptor {descriptor:?} type {descriptor_type:?}");
+
+            return Err(DriverError::InvalidData);
+        };
+
+        let image_view = Image::view(image, image_view_info)?;
+        let image_info = vk::DescriptorImageInfo::default()
+            .image_layout(image_layout)
+            .image_view(image_view);
+
+        push_descriptor(
+            &self.bindings,
+            vk::PipelineBindPoint::COMPUTE,
+            &self.pipeline.device,
+            self.pipeline.layout,
+            &self.pipeline.descriptor_bindings,
+            &self.pipeline.descriptor_info,
+            descriptor,
+            vk::WriteDescriptorSet::default().image_info(std::slice::from_ref(&image_info)),
+        )?;
+
+        Ok(self)
+    }
+
+    /// Sets the dynamic offset of a dynamic uniform or storage buffer descriptor.
+    ///
+    /// The offset applies to subsequent commands and must be a multiple of the device
+    /// `minUniformBufferOffsetAlignment` or `minStorageBufferOffsetAlignment` limit. Descriptors
+    /// which have not been given an offset use zero. Array descriptors specify the offset of each
+    /// element separately, such as `(0, 1, [2])` for the third element of set `0` binding `1`.
+    ///
+    /// The buffer must have been accessed using [`PipelinePassRef::access_descriptor_dynamic`].
+    #[profiling::function]
+    pub fn set_dynamic_offset(&self, descriptor: impl Into<Descriptor>, offset: u32) -> &Self {
+        let descriptor = descriptor.into();
+        let set = descriptor.set();
+        let mut dynamic_offsets = self.dynamic_offsets.borrow_mut();
+        let dynamic_offsets =
+            dynamic_offsets.set(&self.pipeline.descriptor_bindings, descriptor, offset);
+
+        trace!("      set dynamic offsets {set}: {dynamic_offsets:?}");
+
+        unsafe {
+            self.device.cmd_bind_descriptor_sets(
+                self.cmd_buf,
+                vk::PipelineBindPoint::COMPUTE,
+                self.pipeline.layout,
+                set,
+                &[self.bindings.exec.descriptor_sets[set as usize]],
+                dynamic_offsets,
+            );
+        }
+
+        self
+    }
+}
+
+fn descriptor_type(
+    descriptor_bindings: &DescriptorBindingMap,
+    descriptor: Descriptor,
+) -> Option<vk::DescriptorType> {
+    let (set, binding, _) = descriptor.into_tuple();
+
+    descriptor_bindings
+        .iter()
+        .find(|(descriptor, _)| descriptor.set == set && descriptor.binding == binding)
+        .map(|(_, (descriptor_info, _))| descriptor_info.descriptor_type())
+}
+
+/// Returns the layout of an image while it is used as a descriptor of the given type, or `None`
+/// if the type is not a sampled or storage image.
+pub(super) fn image_descriptor_layout(
+    descriptor_type: vk::DescriptorType,
+    aspect_mask: vk::ImageAspectFlags,
+) -> Option<vk::ImageLayout> {
+    Some(match descriptor_type {
+        vk::DescriptorType::COMBINED_IMAGE_SAMPLER | vk::DescriptorType::SAMPLED_IMAGE => {
+            if aspect_mask.contains(vk::ImageAspectFlags::DEPTH | vk::ImageAspectFlags::STENCIL) {
+                vk::ImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL
+            } else if aspect_mask.contains(vk::ImageAspectFlags::DEPTH) {
+                vk::ImageLayout::DEPTH_READ_ONLY_OPTIMAL
+            } else if aspect_mask.contains(vk::ImageAspectFlags::STENCIL) {
+                vk::ImageLayout::STENCIL_READ_ONLY_OPTIMAL
+            } else {
+                vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL
+            }
+        }
+        vk::DescriptorType::STORAGE_IMAGE => vk::ImageLayout::GENERAL,
+        _ => return None,
+    })
+}
+
+fn begin_conditional_rendering(
+    device: &Device,
+    cmd_buf: vk::CommandBuffer,
+    buffer: vk::Buffer,
+    offset: vk::DeviceSize,
+    inverted: bool,
+) -> Result<(), DriverError> {
+    debug_assert_eq!(offset % 4, 0, "unaligned conditional rendering offset");
+
+    let Some(conditional_rendering_ext) = device
+        .conditional_rendering_ext
+        .as_ref()
+        .filter(|_| Device::supports(device, DeviceFeature::ConditionalRendering))
+    else {
+        warn!("conditional rendering feature not enabled");
+
+        return Err(DriverError::FeatureNotEnabled(
+            DeviceFeature::ConditionalRendering,
+        ));
+    };
+    let flags = if inverted {
+        vk::ConditionalRenderingFlagsEXT::INVERTED
+    } else {
+        vk::ConditionalRenderingFlagsEXT::empty()
+    };
+    let info = vk::ConditionalRenderingBeginInfoEXT::default()
+        .buffer(buffer)
+        .offset(offset)
+        .flags(flags);
+
+    unsafe {
+        (conditional_rendering_ext.cmd_begin_conditional_rendering_ext)(cmd_buf, &info);
+    }
+
+    Ok(())
+}
+
+fn end_conditional_rendering(device: &Device, cmd_buf: vk::CommandBuffer) {
+    // Conditional rendering cannot have begun without the extension
+    let Some(conditional_rendering_ext) = device.conditional_rendering_ext.as_ref() else {
+        return;
+    };
+
+    unsafe {
+        (conditional_rendering_ext.cmd_end_conditional_rendering_ext)(cmd_buf);
+    }
+}
+
+/// Logs a warning if the size of `T` does not match the overall size of the push constant ranges
+/// reflected from the shader code of a pipeline.
+fn check_push_constants_size<'a, T>(
+    push_constants: impl IntoIterator<Item = &'a vk::PushConstantRange>,
+) {
+    let size = size_of::<T>() as u32;
+    let push_constants_size = push_constants
+        .into_iter()
+        .map(|push_const| push_const.offset + push_const.size)
+        .max()
+        .unwrap_or_default();
+
+    if size != push_constants_size {
+        warn!(
+            "push constants size mismatch: {} is {size} bytes but the pipeline uses {push_constants_size} bytes",
+            std::any::type_name::<T>()
+        );
+    }
+}
+
+/// Logs a warning if `view_info` or `access` is not compatible with the reflected shader binding
+/// of `descriptor`.
+fn validate_descriptor(
+    descriptor_bindings: &DescriptorBindingMap,
+    descriptor: Descriptor,
+    view_info: &ViewType,
+    access: AccessType,
+) {
+    let (set, binding, _) = descriptor.into_tuple();
+    let Some((descriptor_info, stage_flags)) = descriptor_bindings
+        .iter()
+        .find(|(descriptor, _)| descriptor.set == set && descriptor.binding == binding)
+        .map(|(_, binding)| binding)
+    else {
+        warn!("descriptor {set}.{binding} was not discovered through shader reflection");
+
+        return;
+    };
+    let descriptor_type = descriptor_info.descriptor_type();
+    let (is_compatible, node_ty) = match view_info {
+        ViewType::AccelerationStructure => (
+            descriptor_type == vk::DescriptorType::ACCELERATION_STRUCTURE_KHR,
+            "an acceleration structure",
+        ),
+        ViewType::Buffer(_) => (
+            matches!(
+                descriptor_type,
+                vk::DescriptorType::STORAGE_BUFFER
+                    | vk::DescriptorType::STORAGE_BUFFER_DYNAMIC
+                    | vk::DescriptorType::STORAGE_TEXEL_BUFFER
+                    | vk::DescriptorType::UNIFORM_BUFFER
+                    | vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC
+                    | vk::DescriptorType::UNIFORM_TEXEL_BUFFER
+            ),
+            "a buffer",
+        ),
+        ViewType::Image(_) => (
+            matches!(
+                descriptor_type,
+                vk::DescriptorType::COMBINED_IMAGE_SAMPLER
+                    | vk::DescriptorType::INPUT_ATTACHMENT
+                    | vk::DescriptorType::SAMPLED_IMAGE
+                    | vk::DescriptorType::STORAGE_IMAGE
+            ),
+            "an image",
+        ),
+    };
+
+    if !is_compatible {
+        warn!("descriptor {set}.{binding} expects {descriptor_type:?} but {node_ty} was bound");
+    }
+
+    if let Some(access_stage_flags) = access_type_shader_stage(access) {
+        if !stage_flags.intersects(access_stage_flags) {
+            warn!(
+                "descriptor {set}.{binding} ({descriptor_type:?}) is used by {stage_flags:?} but \
+                was accessed using {access:?}"
+            );
+        }
+    }
+}
+
+/// Returns the shader stages of a stage-specific access type.
+fn access_type_shader_stage(access: AccessType) -> Option<vk::ShaderStageFlags> {
+    Some(match access {
+        AccessType::VertexShaderReadUniformBuffer
+        | AccessType::VertexShaderReadSampledImageOrUniformTexelBuffer
+        | AccessType::VertexShaderReadOther
+        | AccessType::VertexShaderWrite => vk::ShaderStageFlags::VERTEX,
+        AccessType::TessellationControlShaderReadUniformBuffer
+        | AccessType::TessellationControlShaderReadSampledImageOrUniformTexelBuffer
+        | AccessType::TessellationControlShaderReadOther
+        | AccessType::TessellationControlShaderWrite => vk::ShaderStageFlags::TESSELLATION_CONTROL,
+        AccessType::TessellationEvaluationShaderReadUniformBuffer
+        | AccessType::TessellationEvaluationShaderReadSampledImageOrUniformTexelBuffer
+        | AccessType::TessellationEvaluationShaderReadOther
+        | AccessType::TessellationEvaluationShaderWrite => {
+            vk::ShaderStageFlags::TESSELLATION_EVALUATION
+        }
+        AccessType::GeometryShaderReadUniformBuffer
+        | AccessType::GeometryShaderReadSampledImageOrUniformTexelBuffer
+        | AccessType::GeometryShaderReadOther
+        | AccessType::GeometryShaderWrite => vk::ShaderStageFlags::GEOMETRY,
+        AccessType::FragmentShaderReadUniformBuffer
+        | AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer
+        | AccessType::FragmentShaderReadColorInputAttachment
+        | AccessType::FragmentShaderReadDepthStencilInputAttachment
+        | AccessType::FragmentShaderReadOther
+        | AccessType::FragmentShaderWrite => vk::ShaderStageFlags::FRAGMENT,
+        AccessType::ComputeShaderReadUniformBuffer
+        | AccessType::ComputeShaderReadSampledImageOrUniformTexelBuffer
+        | AccessType::ComputeShaderReadOther
+        | AccessType::ComputeShaderWrite => vk::ShaderStageFlags::COMPUTE,
+        AccessType::RayTracingShaderReadSampledImageOrUniformTexelBuffer
+        | AccessType::RayTracingShaderReadColorInputAttachment
+        | AccessType::RayTracingShaderReadDepthStencilInputAttachment
+        | AccessType::RayTracingShaderReadAccelerationStructure
+        | AccessType::RayTracingShaderReadOther => {
+            vk::ShaderStageFlags::RAYGEN_KHR
+                | vk::ShaderStageFlags::ANY_HIT_KHR
+                | vk::ShaderStageFlags::CLOSEST_HIT_KHR
+                | vk::ShaderStageFlags::MISS_KHR
+                | vk::ShaderStageFlags::INTERSECTION_KHR
+                | vk::ShaderStageFlags::CALLABLE_KHR
+        }
+        _ => return None,
+    })
+}
+
+/// The number of descriptor sets of the first descriptor pool an execution allocates in place of
+/// push descriptors.
+const PUSH_DESCRIPTOR_POOL_SETS: u32 = 16;
+
+/// Records one descriptor of the push descriptor set of a pipeline.
+///
+/// Devices without `VK_KHR_push_descriptor` instead allocate a new regular descriptor set from the
+/// descriptor pools of the execution; the set receives a copy of the previously pushed descriptors
+/// before the write, and is then bound.
+#[allow(clippy::too_many_arguments)]
+fn push_descriptor(
+    bindings: &Bindings,
+    pipeline_bind_point: vk::PipelineBindPoint,
+    device: &Arc<Device>,
+    layout: vk::PipelineLayout,
+    descriptor_bindings: &DescriptorBindingMap,
+    descriptor_info: &PipelineDescriptorInfo,
+    descriptor: Descriptor,
+    mut write: vk::WriteDescriptorSet<'_>,
+) -> Result<(), DriverError> {
+    let (set, binding, binding_offset) = descriptor.into_tuple();
+
+    write.descriptor_count = 1;
+    write.descriptor_type = push_descriptor_type(descriptor_bindings, descriptor_info, descriptor)?;
+    write.dst_array_element = binding_offset;
+    write.dst_binding = binding;
+
+    if let Some(push_descriptor_ext) = device.push_descriptor_ext.as_ref() {
+        trace!("      push descriptor {descriptor:?}");
+
+        unsafe {
+            push_descriptor_ext.cmd_push_descriptor_set(
+                bindings.cmd_buf,
+                pipeline_bind_point,
+                layout,
+                set,
+                std::slice::from_ref(&write),
+            );
+        }
+
+        return Ok(());
+    }
+
+    trace!("      write push descriptor {descriptor:?}");
+
+    let set_bindings = || {
+        descriptor_bindings
+            .iter()
+            .filter(move |(descriptor, _)| descriptor.set == set)
+    };
+
+    let mut push_descriptor_sets = bindings.exec.push_descriptor_sets.borrow_mut();
+
+    // Each pool holds twice as many sets as the previous pool so that passes which record many
+    // small draws or dispatches only create a few pools
+    let has_free_set =
+        push_descriptor_sets
+            .last()
+            .is_some_and(|(descriptor_sets, descriptor_pool)| {
+                descriptor_sets.len() < descriptor_pool.info.max_sets as usize
+            });
+
+    if !has_free_set {
+        let max_sets = push_descriptor_sets
+            .last()
+            .map(|(_, descriptor_pool)| descriptor_pool.info.max_sets * 2)
+            .unwrap_or(PUSH_DESCRIPTOR_POOL_SETS);
+        let mut pool_info = DescriptorPoolInfo {
+            max_sets,
+            ..Default::default()
+        };
+
+        for (_, (binding_info, _)) in set_bindings() {
+            pool_info.add_descriptor_count(
+                binding_info.descriptor_type(),
+                binding_info.binding_count() * max_sets,
+            );
+        }
+
+        push_descriptor_sets.push((vec![], DescriptorPool::create(device, pool_info)?));
+    }
+
+    let (_, descriptor_pool) = push_descriptor_sets.last().unwrap();
+    let descriptor_set =
+        DescriptorPool::allocate_descriptor_set(descriptor_pool, &descriptor_info.layouts[&set])?;
+
+    // Descriptors pushed by previous commands remain in the set
+    let copies = push_descriptor_sets
+        .iter()
+        .rev()
+        .find_map(|(descriptor_sets, _)| descriptor_sets.last())
+        .map(|prev_descriptor_set| {
+            set_bindings()
+                .map(|(descriptor, (binding_info, _))| {
+                    vk::CopyDescriptorSet::default()
+                        .src_set(**prev_descriptor_set)
+                        .src_binding(descriptor.binding)
+                        .dst_set(*descriptor_set)
+                        .dst_binding(descriptor.binding)
+                        .descriptor_count(binding_info.binding_count())
+                })
+                .collect::<Box<_>>()
+        })
+        .unwrap_or_default();
+
+    write.dst_set = *descriptor_set;
+
+    unsafe {
+        device.update_descriptor_sets(std::slice::from_ref(&write), &copies);
+        device.cmd_bind_descriptor_sets(
+            bindings.cmd_buf,
+            pipeline_bind_point,
+            layout,
+            set,
+            std::slice::from_ref(&*descriptor_set),
+            &[],
+        );
+    }
+
+    let (descriptor_sets, _) = push_descriptor_sets.last_mut().unwrap();
+    descriptor_sets.push(descriptor_set);
+
+    Ok(())
+}
+
+/// Returns the type of a descriptor of the push descriptor set of a pipeline.
+fn push_descriptor_type(
+    descriptor_bindings: &DescriptorBindingMap,
+    descriptor_info: &PipelineDescriptorInfo,
+    descriptor: Descriptor,
+) -> Result<vk::DescriptorType, DriverError> {
+    if descriptor_info.push_descriptor_set != Some(descriptor.set()) {
+        warn!("descriptor {descriptor:?} is not part of the push descriptor set");
+
+        return Err(DriverError::InvalidData);
+    }
+
+    let Some(descriptor_type) = descriptor_type(descriptor_bindings, descriptor) else {
+        warn!("descriptor {descriptor:?} was not discovered through shader reflection");
+
+        return Err(DriverError::InvalidData);
+    };
+
+    Ok(descriptor_type)
 }
 
 /// Describes the SPIR-V binding index, and optionally a specific descriptor set
@@ -1544,6 +2360,64 @@ impl From<(DescriptorSetIndex, BindingIndex, [BindingOffset; 1])> for Descriptor
     }
 }
 
+#[derive(Default)]
+struct DynamicOffsets {
+    offsets: BTreeMap<(DescriptorSetIndex, BindingIndex, BindingOffset), u32>,
+    set_offsets: Vec<u32>,
+}
+
+impl DynamicOffsets {
+    /// Stores the offset of one descriptor and returns the offsets of every dynamic descriptor in
+    /// the same set, in binding order, as required by `vkCmdBindDescriptorSets`.
+    fn set(
+        &mut self,
+        descriptor_bindings: &DescriptorBindingMap,
+        descriptor: Descriptor,
+        offset: u32,
+    ) -> &[u32] {
+        let (set, binding, _) = descriptor.into_tuple();
+
+        debug_assert!(
+            descriptor_bindings
+                .iter()
+                .any(|(descriptor, (descriptor_info, _))| {
+                    descriptor.set == set
+                        && descriptor.binding == binding
+                        && descriptor_info.is_dynamic()
+                }),
+            "descriptor {descriptor:?} is not a dynamic buffer"
+        );
+
+        self.offsets.insert(descriptor.into_tuple(), offset);
+
+        let mut descriptors = descriptor_bindings
+            .iter()
+            .filter(|(descriptor, (descriptor_info, _))| {
+                descriptor.set == set && descriptor_info.is_dynamic()
+            })
+            .map(|(descriptor, (descriptor_info, _))| {
+                (descriptor.binding, descriptor_info.binding_count())
+            })
+            .collect::<Vec<_>>();
+        descriptors.sort_unstable();
+
+        self.set_offsets.clear();
+
+        for (binding, binding_count) in descriptors {
+            for element in 0..binding_count {
+                self.set_offsets.push(
+                    self.offsets
+                        .get(&(set, binding, element))
+                        .copied()
+                        .unwrap_or_default(),
+                );
+            }
+        }
+
+        &self.set_offsets
+    }
+}
+
 /// Recording interface for drawing commands.
 ///
 /// This structure provides a strongly-typed set of methods which allow rasterization shader code to
@@ -1587,10 +2461,97 @@ pub struct Draw<'a> {
     bindings: Bindings<'a>,
     cmd_buf: vk::CommandBuffer,
     device: &'a Device,
+    dynamic_offsets: RefCell<DynamicOffsets>,
     pipeline: Arc<GraphicPipeline>,
 }
 
 impl Draw<'_> {
+    /// Begins conditional rendering of the subsequent draw commands.
+    ///
+    /// The 32-bit value at `offset` of `buffer` is read when the commands execute: commands are
+    /// discarded if the value is zero, or if the value is non-zero and `inverted` is `true`. Call
+    /// [`Draw::end_conditional`] after the draw commands to be predicated.
+    ///
+    /// `buffer` must have been created with [`vk::BufferUsageFlags::CONDITIONAL_RENDERING_EXT`]
+    /// and must be read using [`PipelinePassRef::read_conditional_buffer`]. `offset` must be a
+    /// multiple of four.
+    ///
+    /// Returns [`DriverError::FeatureNotEnabled`] if the device does not support
+    /// [`DeviceFeature::ConditionalRendering`].
+    pub fn begin_conditional(
+        &self,
+        buffer: impl Into<AnyBufferNode>,
+        offset: vk::DeviceSize,
+        inverted: bool,
+    ) -> Result<&Self, DriverError> {
+        let buffer = buffer.into();
+
+        debug_assert!(
+            self.bindings.is_conditional_buffer(buffer),
+            "conditional buffer access required: call read_conditional_buffer first"
+        );
+
+        begin_conditional_rendering(
+            self.device,
+            self.cmd_buf,
+            *self.bindings[buffer],
+            offset,
+            inverted,
+        )?;
+
+        Ok(self)
+    }
+
+    /// Begins a query which wraps subsequent draw commands.
+    ///
+    /// The query pool must be of type [`vk::QueryType::OCCLUSION`] or
+    /// [`vk::QueryType::PIPELINE_STATISTICS`] and `query` must have been reset using
+    /// [`QueryPool::reset`] before the command buffer is submitted, or using `vkCmdResetQueryPool`
+    /// in a previous pass if host query reset is not supported. When `precise` is `true`,
+    /// occlusion queries return the exact number of samples passed instead of any non-zero value.
+    ///
+    /// Call [`Draw::end_query`] after the draw commands to be measured.
+    ///
+    /// Returns [`DriverError::FeatureNotEnabled`] if `precise` is `true` and the device does not
+    /// support [`DeviceFeature::OcclusionQueryPrecise`].
+    pub fn begin_query(
+        &self,
+        query_pool: &QueryPool,
+        query: u32,
+        precise: bool,
+    ) -> Result<&Self, DriverError> {
+        debug_assert!(
+            matches!(
+                query_pool.info.ty,
+                vk::QueryType::OCCLUSION | vk::QueryType::PIPELINE_STATISTICS
+            ),
+            "unsupported query type"
+        );
+
+        let flags = if precise {
+            debug_assert_eq!(query_pool.info.ty, vk::QueryType::OCCLUSION);
+
+            if !Device::supports(self.device, DeviceFeature::OcclusionQueryPrecise) {
+                warn!("occlusion query precise feature not enabled");
+
+                return Err(DriverError::FeatureNotEnabled(
+                    DeviceFeature::OcclusionQueryPrecise,
+                ));
+            }
+
+            vk::QueryControlFlags::PRECISE
+        } else {
+            vk::QueryControlFlags::empty()
+        };
+
+        unsafe {
+            self.device
+                .cmd_begin_query(self.cmd_buf, **query_pool, query, flags);
+        }
+
+        Ok(self)
+    }
+
     /// Bind an index buffer to the current pass.
     ///
     /// # Examples
@@ -1894,7 +2855,7 @@ impl Draw<'_> {
     ///     std::slice::from_raw_parts(&cmd as *const _ as *const _, CMD_SIZE)
     /// };
     ///
-    /// let buf_flags = vk::BufferUsageFlags::STORAGE_BUFFER;
+    /// let buf_flags = vk::BufferUsageFlags::INDIRECT_BUFFER;
     /// let buf = Buffer::create_from_slice(&device, buf_flags, cmd_data)?;
     /// let buf_node = my_graph.bind_node(buf);
     ///
@@ -1903,7 +2864,7 @@ impl Draw<'_> {
     ///         .store_color(0, swapchain_image)
     ///         .read_node(my_idx_buf)
     ///         .read_node(my_vtx_buf)
-    ///         .read_node(buf_node)
+    ///         .read_indirect_buffer(buf_node)
     ///         .record_subpass(move |subpass, bindings| {
     ///             subpass.bind_index_buffer(my_idx_buf, vk::IndexType::UINT16)
     ///                    .bind_vertex_buffer(my_vtx_buf)
@@ -1921,6 +2882,8 @@ impl Draw<'_> {
     ) -> &Self {
         let buffer = buffer.into();
 
+        self.bindings.check_indirect_buffer(buffer);
+
         unsafe {
             self.device.cmd_draw_indexed_indirect(
                 self.cmd_buf,
@@ -1959,6 +2922,9 @@ impl Draw<'_> {
         let buffer = buffer.into();
         let count_buf = count_buf.into();
 
+        self.bindings.check_indirect_buffer(buffer);
+        self.bindings.check_indirect_buffer(count_buf);
+
         unsafe {
             self.device.cmd_draw_indexed_indirect_count(
                 self.cmd_buf,
@@ -1987,6 +2953,8 @@ impl Draw<'_> {
     ) -> &Self {
         let buffer = buffer.into();
 
+        self.bindings.check_indirect_buffer(buffer);
+
         unsafe {
             self.device.cmd_draw_indirect(
                 self.cmd_buf,
@@ -2016,6 +2984,9 @@ impl Draw<'_> {
         let buffer = buffer.into();
         let count_buf = count_buf.into();
 
+        self.bindings.check_indirect_buffer(buffer);
+        self.bindings.check_indirect_buffer(count_buf);
+
         unsafe {
             self.device.cmd_draw_indirect_count(
                 self.cmd_buf,
@@ -2031,6 +3002,22 @@ impl Draw<'_> {
         self
     }
 
+    /// Ends conditional rendering previously begun using [`Draw::begin_conditional`].
+    pub fn end_conditional(&self) -> &Self {
+        end_conditional_rendering(self.device, self.cmd_buf);
+
+        self
+    }
+
+    /// Ends a query previously begun using [`Draw::begin_query`].
+    pub fn end_query(&self, query_pool: &QueryPool, query: u32) -> &Self {
+        unsafe {
+            self.device.cmd_end_query(self.cmd_buf, **query_pool, query);
+        }
+
+        self
+    }
+
     /// Updates push constants.
     ///
     /// Push constants represent a high speed path to modify constant data in pipelines that is
@@ -2168,34 +3155,211 @@ impl Draw<'_> {
     /// # Ok(()) }
     /// ```
     ///
-    /// [gpuinfo.org]: https://vulkan.gpuinfo.org/displaydevicelimit.php?name=maxPushConstantsSize&platform=all
+    /// [gpuinfo.org]: https://vulkan.gpuinfo.org/displaydevicelimit.php?name=maxPushConstantsSize&platform=all
+    #[profiling::function]
+    pub fn push_constants_offset(&self, offset: u32, data: &[u8]) -> &Self {
+        for push_const in self.pipeline.push_constants.iter() {
+            // Determine the range of the overall pipline push constants which overlap with `data`
+            let push_const_end = push_const.offset + push_const.size;
+            let data_end = offset + data.len() as u32;
+            let end = data_end.min(push_const_end);
+            let start = offset.max(push_const.offset);
+
+            if end > start {
+                trace!(
+                    "      push constants {:?} {}..{}",
+                    push_const.stage_flags,
+                    start,
+                    end
+                );
+
+                unsafe {
+                    self.device.cmd_push_constants(
+                        self.cmd_buf,
+                        self.pipeline.layout,
+                        push_const.stage_flags,
+                        start,
+                        &data[(start - offset) as usize..(end - offset) as usize],
+                    );
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Updates push constants using the bytes of `data`.
+    ///
+    /// Behaves similarly to [`Draw::push_constants`] except that the size of `T` is checked
+    /// against the push constant ranges reflected from the shader code of the currently bound
+    /// pipeline. A warning is logged if the sizes do not match, which usually means the layout of
+    /// `T` has drifted from the shader code.
+    pub fn push_constants_typed<T: Pod>(&self, data: &T) -> &Self {
+        check_push_constants_size::<T>(self.pipeline.push_constants.iter());
+
+        self.push_constants(bytes_of(data))
+    }
+
+    /// Pushes a buffer descriptor into the push descriptor set of the pipeline.
+    ///
+    /// The descriptor applies to subsequent commands. The pipeline must have been created with a
+    /// `push_descriptor_set` which contains `descriptor`, and the buffer must have been accessed
+    /// by this pass (_such as by using [`PipelinePassRef::access_node`]_).
+    ///
+    /// Returns [`DriverError::InvalidData`] if `descriptor` is not part of the push descriptor set.
+    #[profiling::function]
+    pub fn push_buffer_descriptor(
+        &self,
+        descriptor: impl Into<Descriptor>,
+        buffer_node: impl Into<AnyBufferNode>,
+        range: Range<vk::DeviceSize>,
+    ) -> Result<&Self, DriverError> {
+        let descriptor = descriptor.into();
+        let buffer = &self.bindings[buffer_node.into()];
+        let buffer_info = vk::DescriptorBufferInfo::default()
+            .buffer(**buffer)
+            .offset(range.start)
+            .range(range.end - range.start);
+
+        push_descriptor(
+            &self.bindings,
+            vk::PipelineBindPoint::GRAPHICS,
+            &self.pipeline.device,
+            self.pipeline.layout,
+            &self.pipeline.descriptor_bindings,
+            &self.pipeline.descriptor_info,
+            descriptor,
+            vk::WriteDescriptorSet::default().buffer_info(std::slice::from_ref(&buffer_info)),
+        )?;
+
+        Ok(self)
+    }
+
+    /// Pushes an image descriptor into the push descriptor set of the pipeline.
+    ///
+    /// The descriptor applies to subsequent commands. The pipeline must have been created with a
+    /// `push_descriptor_set` which contains `descriptor`, and the image must have been accessed
+    /// by this pass (_such as by using [`PipelinePassRef::access_node`]_).
+    ///
+    /// Returns [`DriverError::InvalidData`] if `descriptor` is not part of the push descriptor set
+    /// or is not a sampled or storage image descriptor, or an error if the image view cannot be
+    /// created.
+    #[profiling::function]
+    pub fn push_image_descriptor(
+        &self,
+        descriptor: impl Into<Descriptor>,
+        image_node: impl Into<AnyImageNode>,
+        image_view_info: impl Into<ImageViewInfo>,
+    ) -> Result<&Self, DriverError> {
+        let descriptor = descriptor.into();
+        let image = &self.bindings[image_node.into()];
+        let mut image_view_info = image_view_info.into();
+
+        // Handle default views which did not specify a particular aspect
+        if image_view_info.aspect_mask.is_empty() {
+            image_view_info.aspect_mask = format_aspect_mask(image.info.fmt);
+        }
+
+        let descriptor_type = push_descriptor_type(
+            &self.pipeline.descriptor_bindings,
+            &self.pipeline.descriptor_info,
+            descriptor,
+        )?;
+        let Some(image_layout) =
+            image_descriptor_layout(descriptor_type, image_view_info.aspect_mask)
+        else {
+            warn!("unsupported push descriptor {descriptor:?} type {descriptor_type:?}");
+
+            return Err(DriverError::InvalidData);
+        };
+
+        let image_view = Image::view(image, image_view_info)?;
+        let image_info = vk::DescriptorImageInfo::default()
+            .image_layout(image_layout)
+            .image_view(image_view);
+
+        push_descriptor(
+            &self.bindings,
+            vk::PipelineBindPoint::GRAPHICS,
+            &self.pipeline.device,
+            self.pipeline.layout,
+            &self.pipeline.descriptor_bindings,
+            &self.pipeline.descriptor_info,
+            descriptor,
+            vk::WriteDescriptorSet::default().image_info(std::slice::from_ref(&image_info)),
+        )?;
+
+        Ok(self)
+    }
+
+    /// Sets the dynamic offset of a dynamic uniform or storage buffer descriptor.
+    ///
+    /// The offset applies to subsequent commands and must be a multiple of the device
+    /// `minUniformBufferOffsetAlignment` or `minStorageBufferOffsetAlignment` limit. Descriptors
+    /// which have not been given an offset use zero. Array descriptors specify the offset of each
+    /// element separately, such as `(0, 1, [2])` for the third element of set `0` binding `1`.
+    ///
+    /// The buffer must have been accessed using [`PipelinePassRef::access_descriptor_dynamic`].
+    #[profiling::function]
+    pub fn set_dynamic_offset(&self, descriptor: impl Into<Descriptor>, offset: u32) -> &Self {
+        let descriptor = descriptor.into();
+        let set = descriptor.set();
+        let mut dynamic_offsets = self.dynamic_offsets.borrow_mut();
+        let dynamic_offsets =
+            dynamic_offsets.set(&self.pipeline.descriptor_bindings, descriptor, offset);
+
+        trace!("      set dynamic offsets {set}: {dynamic_offsets:?}");
+
+        unsafe {
+            self.device.cmd_bind_descriptor_sets(
+                self.cmd_buf,
+                vk::PipelineBindPoint::GRAPHICS,
+                self.pipeline.layout,
+                set,
+                &[self.bindings.exec.descriptor_sets[set as usize]],
+                dynamic_offsets,
+            );
+        }
+
+        self
+    }
+
+    /// Set the depth bias dynamically for a pass.
+    ///
+    /// Depth bias is only applied by pipelines which enable
+    /// [`GraphicPipelineInfo::depth_bias`](crate::driver::graphic::GraphicPipelineInfo::depth_bias).
+    /// A non-zero `clamp` requires the `depthBiasClamp` device feature.
+    ///
+    /// The default values are `0.0`.
     #[profiling::function]
-    pub fn push_constants_offset(&self, offset: u32, data: &[u8]) -> &Self {
-        for push_const in self.pipeline.push_constants.iter() {
-            // Determine the range of the overall pipline push constants which overlap with `data`
-            let push_const_end = push_const.offset + push_const.size;
-            let data_end = offset + data.len() as u32;
-            let end = data_end.min(push_const_end);
-            let start = offset.max(push_const.offset);
+    pub fn set_depth_bias(&self, constant_factor: f32, clamp: f32, slope_factor: f32) -> &Self {
+        debug_assert!(
+            clamp == 0.0 || self.device.physical_device.features_v1_0.depth_bias_clamp,
+            "unsupported depth bias clamp feature"
+        );
 
-            if end > start {
-                trace!(
-                    "      push constants {:?} {}..{}",
-                    push_const.stage_flags,
-                    start,
-                    end
-                );
+        unsafe {
+            self.device
+                .cmd_set_depth_bias(self.cmd_buf, constant_factor, clamp, slope_factor);
+        }
 
-                unsafe {
-                    self.device.cmd_push_constants(
-                        self.cmd_buf,
-                        self.pipeline.layout,
-                        push_const.stage_flags,
-                        start,
-                        &data[(start - offset) as usize..(end - offset) as usize],
-                    );
-                }
-            }
+        self
+    }
+
+    /// Set the width of rasterized lines dynamically for a pass.
+    ///
+    /// Widths other than `1.0` require the `wideLines` device feature.
+    ///
+    /// The default value is `1.0`.
+    #[profiling::function]
+    pub fn set_line_width(&self, line_width: f32) -> &Self {
+        debug_assert!(
+            line_width == 1.0 || self.device.physical_device.features_v1_0.wide_lines,
+            "unsupported wide lines feature"
+        );
+
+        unsafe {
+            self.device.cmd_set_line_width(self.cmd_buf, line_width);
         }
 
         self
@@ -2219,6 +3383,9 @@ impl Draw<'_> {
     }
 
     /// Set scissor rectangles dynamically for a pass.
+    ///
+    /// Pipelines which use more than one scissor must specify
+    /// [`GraphicPipelineInfo::viewport_count`](crate::driver::graphic::GraphicPipelineInfo::viewport_count).
     #[profiling::function]
     pub fn set_scissors<S>(
         &self,
@@ -2277,6 +3444,9 @@ impl Draw<'_> {
     }
 
     /// Set the viewports dynamically for a pass.
+    ///
+    /// Pipelines which use more than one viewport must specify
+    /// [`GraphicPipelineInfo::viewport_count`](crate::driver::graphic::GraphicPipelineInfo::viewport_count).
     #[profiling::function]
     pub fn set_viewports<V>(
         &self,
@@ -2308,6 +3478,38 @@ impl Draw<'_> {
 
         self
     }
+
+    /// Records a by-region barrier which makes the color attachment writes of previous draws
+    /// visible to subpass input attachment reads of subsequent draws in this subpass.
+    ///
+    /// The pass must declare [`PipelinePassRef::subpass_self_dependency`], otherwise this function
+    /// panics (_in debug builds_) because the barrier is not valid within the subpass.
+    #[profiling::function]
+    pub fn subpass_barrier(&self) -> &Self {
+        debug_assert!(
+            self.bindings.exec.self_dependency,
+            "subpass self-dependency required: call subpass_self_dependency first"
+        );
+
+        let (src_stage_mask, src_access_mask, dst_stage_mask, dst_access_mask) =
+            SUBPASS_SELF_DEPENDENCY;
+
+        unsafe {
+            self.device.cmd_pipeline_barrier(
+                self.cmd_buf,
+                src_stage_mask,
+                dst_stage_mask,
+                vk::DependencyFlags::BY_REGION,
+                &[vk::MemoryBarrier::default()
+                    .src_access_mask(src_access_mask)
+                    .dst_access_mask(dst_access_mask)],
+                &[],
+                &[],
+            );
+        }
+
+        self
+    }
 }
 
 /// A general render pass which may contain acceleration structure commands, general commands, or
@@ -2322,8 +3524,13 @@ impl<'a> PassRef<'a> {
     pub(super) fn new(graph: &'a mut RenderGraph, name: String) -> PassRef<'a> {
         let pass_idx = graph.passes.len();
         graph.passes.push(Pass {
+            dependencies: vec![],
             execs: vec![Default::default()], // We start off with a default execution!
+            merged: false,
             name,
+            pass_indices: vec![pass_idx],
+            pinned: false,
+            queue_class: QueueClass::Universal,
             render_area: None,
         });
 
@@ -2438,6 +3645,13 @@ impl<'a> PassRef<'a> {
         binding.bind(self)
     }
 
+    /// Returns the index of this pass within the graph.
+    ///
+    /// See [`RenderGraph::add_dependency`].
+    pub fn index(&self) -> usize {
+        self.pass_idx
+    }
+
     /// Returns information used to crate a node.
     pub fn node_info<N>(&self, node: N) -> <N as Information>::Info
     where
@@ -2446,6 +3660,20 @@ impl<'a> PassRef<'a> {
         node.get(self.graph)
     }
 
+    /// Excludes this pass from reordering.
+    ///
+    /// All passes begun before this pass execute before it and all passes begun after this pass
+    /// execute after it. Other passes may still be reordered between pinned passes.
+    ///
+    /// _NOTE:_ Pinned passes limit the passes which may be reordered; overuse of this function
+    /// defeats the scheduler and may reduce performance. To declare a single ordering constraint
+    /// see [`RenderGraph::add_dependency`].
+    pub fn pin_order(mut self) -> Self {
+        self.as_mut().pinned = true;
+
+        self
+    }
+
     fn push_execute(
         &mut self,
         func: impl FnOnce(&Device, vk::CommandBuffer, Bindings<'_>) + Send + 'static,
@@ -2556,6 +3784,51 @@ impl<'a> PassRef<'a> {
         self.graph
     }
 
+    /// Sets the class of device queue this pass is submitted on.
+    ///
+    /// Passes tagged with [`QueueClass::AsyncCompute`] are submitted to a dedicated compute queue
+    /// by [`Resolver::submit`](super::Resolver::submit) so that long-running compute work may
+    /// overlap graphics work. The resolver inserts the required barriers and timeline semaphore
+    /// waits between the two queues.
+    ///
+    /// Such passes must only record commands supported by compute queues. Passes which use a
+    /// graphic pipeline or a swapchain image are always submitted on the universal queue.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::compute::{ComputePipeline, ComputePipelineInfo};
+    /// # use screen_13::driver::shader::Shader;
+    /// # use screen_13::graph::RenderGraph;
+    /// # use screen_13::graph::pass_ref::QueueClass;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::default())?);
+    /// # let my_shader_code = [0u8; 1];
+    /// # let my_light_culling_pipeline = Arc::new(ComputePipeline::create(
+    /// #     &device,
+    /// #     ComputePipelineInfo::default(),
+    /// #     Shader::new_compute(my_shader_code.as_slice()),
+    /// # )?);
+    /// # let mut my_graph = RenderGraph::new();
+    /// my_graph.begin_pass("light culling")
+    ///         .submit_on(QueueClass::AsyncCompute)
+    ///         .bind_pipeline(&my_light_culling_pipeline)
+    ///         .record_compute(|compute, _| {
+    ///             compute.dispatch(64, 64, 1);
+    ///         });
+    /// # Ok(()) }
+    /// ```
+    pub fn submit_on(mut self, queue_class: QueueClass) -> Self {
+        self.as_mut().queue_class = queue_class;
+
+        self
+    }
+
     /// Informs the pass that the next recorded command buffer will write the given `node` using
     /// [`AccessType::AnyShaderWrite`].
     ///
@@ -2596,6 +3869,11 @@ where
     /// This function must be called for `node` before it is read or written within a `record`
     /// function. For general purpose access, see [`PipelinePassRef::read_descriptor`] or
     /// [`PipelinePassRef::write_descriptor`].
+    ///
+    /// Acceleration structures accessed using
+    /// [`AccessType::RayTracingShaderReadAccelerationStructure`] are synchronized using
+    /// [`Access::ACCELERATION_STRUCTURE_READ`] of the bound pipeline, which allows compute and graphic
+    /// pipelines to use inline ray queries. See the `ray_query` example for details.
     pub fn access_descriptor<N>(
         self,
         descriptor: impl Into<Descriptor>,
@@ -2620,6 +3898,53 @@ where
     /// This function must be called for `node` before it is read or written within a `record`
     /// function. For general purpose access, see [`PipelinePassRef::read_descriptor_as`] or
     /// [`PipelinePassRef::write_descriptor_as`].
+    ///
+    /// Image views are cached by the image for each unique `view_info`, so binding the same view
+    /// each frame does not create additional `vk::ImageView` handles.
+    ///
+    /// # Examples
+    ///
+    /// Reading a single mip level of one layer of an image array:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::{AccessType, DriverError};
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::compute::{ComputePipeline, ComputePipelineInfo};
+    /// # use screen_13::driver::image::{Image, ImageInfo, ImageType};
+    /// # use screen_13::driver::shader::Shader;
+    /// # use screen_13::graph::RenderGraph;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let my_shader_code = [0u8; 1];
+    /// # let info = ComputePipelineInfo::default();
+    /// # let shader = Shader::new_compute(my_shader_code.as_slice());
+    /// # let my_compute_pipeline = Arc::new(ComputePipeline::create(&device, info, shader)?);
+    /// # let info = ImageInfo::image_2d_array(64, 64, 4, vk::Format::R8G8B8A8_UNORM, vk::ImageUsageFlags::SAMPLED);
+    /// # let image = Image::create(&device, info)?;
+    /// # let mut my_graph = RenderGraph::new();
+    /// let my_image_node = my_graph.bind_node(image);
+    /// let view_info = my_graph
+    ///     .node_info(my_image_node)
+    ///     .default_view_info()
+    ///     .with_ty(ImageType::Texture2D)
+    ///     .with_array_layers(2, 1)
+    ///     .with_mip_levels(3, 1);
+    ///
+    /// my_graph.begin_pass("read mip 3 of layer 2")
+    ///         .bind_pipeline(&my_compute_pipeline)
+    ///         .access_descriptor_as(
+    ///             0,
+    ///             my_image_node,
+    ///             AccessType::ComputeShaderReadSampledImageOrUniformTexelBuffer,
+    ///             view_info,
+    ///         )
+    ///         .record_compute(move |compute, _| {
+    ///             compute.dispatch(8, 8, 1);
+    ///         });
+    /// # Ok(()) }
+    /// ```
     pub fn access_descriptor_as<N>(
         self,
         descriptor: impl Into<Descriptor>,
@@ -2658,13 +3983,72 @@ where
         N: View,
         <N as View>::Information: Into<ViewType>,
     {
+        let descriptor = descriptor.into();
+        let view_info: ViewType = Into::<N::Information>::into(view_info).into();
+
+        // Ray queries from compute and graphic pipelines read acceleration structures outside of
+        // the ray tracing shader stage (this also covers acceleration structure read_descriptor)
+        let access = if matches!(view_info, ViewType::AccelerationStructure)
+            && (access == AccessType::RayTracingShaderReadAccelerationStructure
+                || access == <T as Access>::DEFAULT_READ)
+        {
+            <T as Access>::ACCELERATION_STRUCTURE_READ
+        } else {
+            access
+        };
+
+        if self.pass.graph.validation {
+            if let Some(pipeline) = &self.pass.as_ref().execs.last().unwrap().pipeline {
+                validate_descriptor(
+                    pipeline.descriptor_bindings(),
+                    descriptor,
+                    &view_info,
+                    access,
+                );
+            }
+        }
+
         self.pass
             .push_node_access(node, access, Some(subresource.into().into()));
-        self.push_node_view_bind(node, view_info.into(), descriptor.into());
+        self.push_node_view_bind(node, view_info, descriptor);
 
         self
     }
 
+    /// Informs the pass that the next recorded command buffer will read or write the given buffer
+    /// `node` at the specified dynamic shader descriptor using `access`.
+    ///
+    /// The descriptor is bound to the first `range_size` bytes of the buffer; each draw or dispatch
+    /// selects the bytes it uses by calling `set_dynamic_offset` on the recorder. Pipeline barriers
+    /// cover the whole buffer because the offsets are not known until recording.
+    ///
+    /// The shader must specify the descriptor using [`ShaderBuilder::dynamic_buffer`].
+    ///
+    /// [`ShaderBuilder::dynamic_buffer`]: crate::driver::shader::ShaderBuilder::dynamic_buffer
+    pub fn access_descriptor_dynamic(
+        self,
+        descriptor: impl Into<Descriptor>,
+        node: impl Into<AnyBufferNode>,
+        access: AccessType,
+        range_size: vk::DeviceSize,
+    ) -> Self {
+        let node = node.into();
+        let buffer_info = node.get(self.pass.graph);
+
+        debug_assert!(
+            range_size <= buffer_info.size,
+            "dynamic descriptor range exceeds buffer size"
+        );
+
+        self.access_descriptor_subrange(
+            descriptor,
+            node,
+            access,
+            0..range_size,
+            0..buffer_info.size,
+        )
+    }
+
     /// Informs the pass that the next recorded command buffer will read or write the given `node`
     /// using `access`.
     ///
@@ -2845,6 +4229,61 @@ where
         self.access_descriptor_subrange(descriptor, node, access, view_info, subresource)
     }
 
+    /// Informs the pass that the next recorded command buffer will source a conditional rendering
+    /// predicate from the given buffer `node`.
+    ///
+    /// `vk-sync` does not offer a conditional rendering access type, so this uses
+    /// [`AccessType::General`] in order to synchronize any previous pass which wrote the predicate
+    /// (_such as a compute pass which reduces occlusion query results_) with the conditional
+    /// rendering stage.
+    ///
+    /// This function must be called for `node` before it is used by [`Compute::begin_conditional`]
+    /// or [`Draw::begin_conditional`].
+    pub fn read_conditional_buffer(mut self, node: impl Into<AnyBufferNode>) -> Self {
+        self.read_conditional_buffer_mut(node);
+
+        self
+    }
+
+    /// Informs the pass that the next recorded command buffer will source a conditional rendering
+    /// predicate from the given buffer `node`.
+    ///
+    /// See [`PipelinePassRef::read_conditional_buffer`].
+    pub fn read_conditional_buffer_mut(&mut self, node: impl Into<AnyBufferNode>) {
+        let node = node.into();
+
+        self.access_node_mut(node, CONDITIONAL_RENDERING_ACCESS);
+
+        let pass = self.pass.as_mut();
+        let exec = pass.execs.last_mut().unwrap();
+
+        exec.conditional_buffers.insert(node.index());
+    }
+
+    /// Informs the pass that the next recorded command buffer will source indirect command
+    /// parameters, or an indirect draw count, from the given buffer `node`.
+    ///
+    /// This uses [`AccessType::IndirectBuffer`] so that any previous pass which wrote the command
+    /// parameters (_such as a compute pass_) is synchronized with the indirect command.
+    ///
+    /// This function should be called for `node` before it is used by
+    /// [`Compute::dispatch_indirect`], [`Draw::draw_indirect`], [`Draw::draw_indexed_indirect`] or
+    /// their `_count` variants, unless `node` is accessed using [`AccessType::General`]. Debug builds
+    /// log a warning otherwise.
+    pub fn read_indirect_buffer(mut self, node: impl Into<AnyBufferNode>) -> Self {
+        self.read_indirect_buffer_mut(node);
+
+        self
+    }
+
+    /// Informs the pass that the next recorded command buffer will source indirect command
+    /// parameters, or an indirect draw count, from the given buffer `node`.
+    ///
+    /// See [`PipelinePassRef::read_indirect_buffer`].
+    pub fn read_indirect_buffer_mut(&mut self, node: impl Into<AnyBufferNode>) {
+        self.access_node_mut(node.into(), AccessType::IndirectBuffer);
+    }
+
     /// Informs the pass that the next recorded command buffer will read the given `node`.
     ///
     /// The [`AccessType`] is inferred by the currently bound pipeline. See [`Access`] for details.
@@ -3049,6 +4488,7 @@ impl PipelinePassRef<'_, ComputePipeline> {
                     bindings,
                     cmd_buf,
                     device,
+                    dynamic_offsets: Default::default(),
                     pipeline,
                 },
                 bindings,
@@ -3419,6 +4859,9 @@ impl PipelinePassRef<'_, GraphicPipeline> {
 
     /// Clears the render pass attachment of any existing data.
     ///
+    /// Depth is cleared to `1.0` and stencil is cleared to `0`; see
+    /// [`Self::clear_depth_stencil_value`] to specify other values.
+    ///
     /// _NOTE:_ Order matters, call clear before resolve or store.
     pub fn clear_depth_stencil(self, image: impl Into<AnyImageNode>) -> Self {
         self.clear_depth_stencil_value(image, 1.0, 0)
@@ -3426,6 +4869,10 @@ impl PipelinePassRef<'_, GraphicPipeline> {
 
     /// Clears the render pass attachment of any existing data.
     ///
+    /// The depth aspect is cleared to `depth` and the stencil aspect is cleared to `stencil`, which
+    /// is useful for stencil-masked rendering which requires a non-zero reference value. Aspects
+    /// not included in the image format are not cleared.
+    ///
     /// _NOTE:_ Order matters, call clear before resolve or store.
     pub fn clear_depth_stencil_value(
         self,
@@ -3654,6 +5101,67 @@ impl PipelinePassRef<'_, GraphicPipeline> {
         self
     }
 
+    /// Reads an image in the fragment shader as the subpass input attachment at `attachment_idx`
+    /// (`layout(input_attachment_index = N) uniform subpassInput`).
+    ///
+    /// The image is loaded as a color attachment unless this pass already attaches, loads, or
+    /// stores it. Compatible passes which write the image as a color attachment are merged into
+    /// subpasses of the same render pass, so the image stays on tile, and the subpass dependency
+    /// waits for [`AccessType::FragmentShaderReadColorInputAttachment`].
+    pub fn read_input_attachment(
+        self,
+        attachment_idx: AttachmentIndex,
+        image: impl Into<AnyImageNode>,
+    ) -> Self {
+        let image: AnyImageNode = image.into();
+        let image_info = image.get(self.pass.graph);
+        let image_view_info: ImageViewInfo = image_info.into();
+
+        self.read_input_attachment_as(attachment_idx, image, image_view_info)
+    }
+
+    /// Reads an image in the fragment shader as the subpass input attachment at `attachment_idx`
+    /// (`layout(input_attachment_index = N) uniform subpassInput`).
+    ///
+    /// See [`PipelinePassRef::read_input_attachment`] for details.
+    pub fn read_input_attachment_as(
+        self,
+        attachment_idx: AttachmentIndex,
+        image: impl Into<AnyImageNode>,
+        image_view_info: impl Into<ImageViewInfo>,
+    ) -> Self {
+        let image = image.into();
+        let exec = self.pass.as_ref().execs.last().unwrap();
+
+        debug_assert!(
+            exec.pipeline
+                .as_ref()
+                .unwrap()
+                .unwrap_graphic()
+                .input_attachments
+                .contains(&attachment_idx),
+            "subpass input attachment {attachment_idx} not used by the pipeline"
+        );
+
+        let attachment = exec
+            .color_attachments
+            .get(&attachment_idx)
+            .or_else(|| exec.color_loads.get(&attachment_idx))
+            .or_else(|| exec.color_stores.get(&attachment_idx));
+
+        if let Some(attachment) = attachment {
+            debug_assert_eq!(
+                attachment.target,
+                image.index(),
+                "subpass input attachment {attachment_idx} already attached to another image"
+            );
+
+            self
+        } else {
+            self.load_color_as(attachment_idx, image, image_view_info)
+        }
+    }
+
     /// Specifies `VK_ATTACHMENT_LOAD_OP_LOAD` for the render pass attachment, and loads an image
     /// into the framebuffer.
     ///
@@ -3761,6 +5269,7 @@ impl PipelinePassRef<'_, GraphicPipeline> {
                     bindings,
                     cmd_buf,
                     device,
+                    dynamic_offsets: Default::default(),
                     pipeline,
                 },
                 bindings,
@@ -3773,6 +5282,10 @@ impl PipelinePassRef<'_, GraphicPipeline> {
     /// Resolves a multisample framebuffer to a non-multisample image for the render pass
     /// attachment.
     ///
+    /// The multisample color attachment `src_attachment_idx` is resolved into `image` at the end
+    /// of the subpass, without a separate `vkCmdResolveImage` command. `image` must be a
+    /// single-sample image.
+    ///
     /// _NOTE:_ Order matters, call resolve after clear or load.
     pub fn resolve_color(
         self,
@@ -3808,6 +5321,12 @@ impl PipelinePassRef<'_, GraphicPipeline> {
         let node_idx = image.index();
         let (_, sample_count) = self.image_info(node_idx);
 
+        debug_assert_eq!(
+            sample_count,
+            SampleCount::Type1,
+            "color resolve image must be single-sample"
+        );
+
         self.pass
             .as_mut()
             .execs
@@ -3898,6 +5417,9 @@ impl PipelinePassRef<'_, GraphicPipeline> {
     /// Resolves a multisample framebuffer to a non-multisample image for the render pass
     /// attachment.
     ///
+    /// The depth and stencil aspects are resolved using `depth_mode` and `stencil_mode`, which must
+    /// be supported by the device. `image` must be a single-sample image.
+    ///
     /// _NOTE:_ Order matters, call resolve after clear or load.
     pub fn resolve_depth_stencil(
         self,
@@ -3936,6 +5458,12 @@ impl PipelinePassRef<'_, GraphicPipeline> {
         let node_idx = image.index();
         let (_, sample_count) = self.image_info(node_idx);
 
+        debug_assert_eq!(
+            sample_count,
+            SampleCount::Type1,
+            "depth/stencil resolve image must be single-sample"
+        );
+
         self.pass
             .as_mut()
             .execs
@@ -3970,6 +5498,10 @@ impl PipelinePassRef<'_, GraphicPipeline> {
     }
 
     /// Sets a particular depth/stencil mode.
+    ///
+    /// If not set, the [`GraphicPipelineInfo::depth_stencil`] mode of the bound pipeline is used.
+    ///
+    /// [`GraphicPipelineInfo::depth_stencil`]: crate::driver::graphic::GraphicPipelineInfo::depth_stencil
     pub fn set_depth_stencil(mut self, depth_stencil: DepthStencilMode) -> Self {
         let pass = self.pass.as_mut();
         let exec = pass.execs.last_mut().unwrap();
@@ -3981,6 +5513,21 @@ impl PipelinePassRef<'_, GraphicPipeline> {
         self
     }
 
+    /// Declares a by-region self-dependency of this subpass, from color attachment writes to
+    /// fragment shader subpass input attachment reads.
+    ///
+    /// This allows draws to read a color attachment written by earlier draws of the same subpass,
+    /// such as tile-local programmable blending, without splitting the subpass. Record
+    /// [`Draw::subpass_barrier`] between those draws.
+    pub fn subpass_self_dependency(mut self) -> Self {
+        let pass = self.pass.as_mut();
+        let exec = pass.execs.last_mut().unwrap();
+
+        exec.self_dependency = true;
+
+        self
+    }
+
     /// Sets multiview view and correlation masks.
     ///
     /// See [`VkRenderPassMultiviewCreateInfo`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkRenderPassMultiviewCreateInfo.html#_description).
@@ -4218,6 +5765,49 @@ impl PipelinePassRef<'_, GraphicPipeline> {
 }
 
 impl PipelinePassRef<'_, RayTracePipeline> {
+    /// Binds the buffer of `shader_binding_table` to the graph and informs the pass that the next
+    /// recorded command buffer will read it during ray tracing.
+    ///
+    /// This keeps the shader binding table alive and synchronized for use with
+    /// [`RayTrace::trace_rays_table`].
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::ray_trace::{RayTracePipeline, RayTracePipelineInfo, RayTraceShaderGroup};
+    /// # use screen_13::driver::shader::Shader;
+    /// # use screen_13::graph::RenderGraph;
+    /// # use screen_13::pool::lazy::LazyPool;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let mut pool = LazyPool::new(&device);
+    /// # let info = RayTracePipelineInfo::new();
+    /// # let my_rgen_code = [0u8; 1];
+    /// # let my_ray_trace_pipeline = Arc::new(RayTracePipeline::create(&device, info,
+    /// #     [Shader::new_ray_gen(my_rgen_code.as_slice())],
+    /// #     [RayTraceShaderGroup::new_general(0)],
+    /// # )?);
+    /// let sbt = RayTracePipeline::create_shader_binding_table(&my_ray_trace_pipeline, &mut pool)?;
+    /// # let mut my_graph = RenderGraph::new();
+    /// my_graph.begin_pass("draw a cornell box")
+    ///         .bind_pipeline(&my_ray_trace_pipeline)
+    ///         .read_shader_binding_table(&sbt)
+    ///         .record_ray_trace(move |ray_trace, bindings| {
+    ///             ray_trace.trace_rays_table(&sbt, 320, 200, 1);
+    ///         });
+    /// # Ok(()) }
+    /// ```
+    pub fn read_shader_binding_table(mut self, shader_binding_table: &ShaderBindingTable) -> Self {
+        let buffer = self.bind_node(&shader_binding_table.buffer);
+
+        self.access_node(buffer, AccessType::RayTracingShaderReadOther)
+    }
+
     /// Begin recording a ray tracing command buffer.
     pub fn record_ray_trace(
         mut self,
@@ -4257,6 +5847,22 @@ impl PipelinePassRef<'_, RayTracePipeline> {
     }
 }
 
+/// Specifies the class of device queue a pass is submitted on.
+///
+/// See [`PassRef::submit_on`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum QueueClass {
+    /// The queue family given to [`Resolver::submit`](super::Resolver::submit).
+    #[default]
+    Universal,
+
+    /// A dedicated compute queue family, which executes alongside the universal queue.
+    ///
+    /// If the device does not offer a compute queue family without graphics support the pass is
+    /// submitted on the universal queue instead.
+    AsyncCompute,
+}
+
 /// Recording interface for ray tracing commands.
 ///
 /// This structure provides a strongly-typed set of methods which allow ray trace shader code to be
@@ -4473,6 +6079,18 @@ impl RayTrace<'_> {
         self
     }
 
+    /// Updates push constants using the bytes of `data`.
+    ///
+    /// Behaves similarly to [`RayTrace::push_constants`] except that the size of `T` is checked
+    /// against the push constant ranges reflected from the shader code of the currently bound
+    /// pipeline. A warning is logged if the sizes do not match, which usually means the layout of
+    /// `T` has drifted from the shader code.
+    pub fn push_constants_typed<T: Pod>(&self, data: &T) -> &Self {
+        check_push_constants_size::<T>(self.pipeline.push_constants.iter());
+
+        self.push_constants(bytes_of(data))
+    }
+
     /// Set the stack size dynamically for a ray trace pipeline.
     ///
     /// See
@@ -4572,6 +6190,30 @@ impl RayTrace<'_> {
         self
     }
 
+    /// Ray traces using the currently-bound [`RayTracePipeline`] and the regions of the given
+    /// shader binding table.
+    ///
+    /// See [`RayTracePipeline::create_shader_binding_table`] and
+    /// [`PipelinePassRef::read_shader_binding_table`].
+    #[profiling::function]
+    pub fn trace_rays_table(
+        &self,
+        shader_binding_table: &ShaderBindingTable,
+        width: u32,
+        height: u32,
+        depth: u32,
+    ) -> &Self {
+        self.trace_rays(
+            &shader_binding_table.raygen,
+            &shader_binding_table.miss,
+            &shader_binding_table.hit,
+            &shader_binding_table.callable,
+            width,
+            height,
+            depth,
+        )
+    }
+
     /// Ray traces using the currently-bound [`RayTracePipeline`] and the given shader binding
     /// tables.
     ///
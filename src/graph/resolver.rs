@@ -1,38 +1,49 @@
 use {
     super::{
-        Area, Attachment, Binding, Bindings, Edge, Execution, ExecutionPipeline, Node, Pass,
-        RenderGraph, Unbind,
+        pass_ref::image_descriptor_layout, Area, Attachment, Binding, Bindings, Edge, Execution,
+        ExecutionPipeline, Node, NodeIndex, Pass, QueueClass, RenderGraph, Unbind,
+        SUBPASS_SELF_DEPENDENCY,
     },
     crate::{
         driver::{
             accel_struct::AccelerationStructure,
             buffer::Buffer,
-            device::Device,
+            device::{Device, DeviceFeature},
             format_aspect_mask,
             graphic::DepthStencilMode,
             image::{Image, ImageViewInfo},
             image_access_layout, is_framebuffer_access, is_read_access, is_write_access,
-            pipeline_stage_access_flags, AttachmentInfo, AttachmentRef, CommandBuffer,
-            CommandBufferInfo, Descriptor, DescriptorInfo, DescriptorPool, DescriptorPoolInfo,
-            DescriptorSet, DriverError, FramebufferAttachmentImageInfo, FramebufferInfo,
-            RenderPass, RenderPassInfo, SubpassDependency, SubpassInfo,
+            pipeline_stage_access_flags,
+            query::{QueryPool, QueryPoolInfo},
+            AttachmentInfo, AttachmentRef, CommandBuffer, CommandBufferInfo, Descriptor,
+            DescriptorInfo, DescriptorPool, DescriptorPoolInfo, DescriptorSet, DriverError,
+            FramebufferAttachmentImageInfo, FramebufferInfo, RenderPass, RenderPassInfo,
+            SubmitToken, SubpassDependency, SubpassInfo,
         },
         pool::{Lease, Pool},
     },
     ash::vk,
     log::{
-        debug, log_enabled, trace,
+        debug, log_enabled, trace, warn,
         Level::{Debug, Trace},
     },
     std::{
         cell::RefCell,
-        collections::{BTreeMap, HashMap, VecDeque},
+        collections::{BTreeMap, HashMap, HashSet, VecDeque},
         iter::repeat,
         ops::Range,
+        sync::Arc,
+        time::Duration,
     },
     vk_sync::{cmd::pipeline_barrier, AccessType, BufferBarrier, GlobalBarrier, ImageBarrier},
 };
 
+#[cfg(feature = "parking_lot")]
+use parking_lot::Mutex;
+
+#[cfg(not(feature = "parking_lot"))]
+use std::sync::Mutex;
+
 #[derive(Default)]
 struct AccessCache {
     accesses: Vec<bool>,
@@ -155,6 +166,8 @@ impl Drop for PhysicalPass {
 pub struct Resolver {
     pub(super) graph: RenderGraph,
     physical_passes: Vec<PhysicalPass>,
+    timestamps: Option<Arc<PassTimestamps>>,
+    waits: Vec<SubmitToken>,
 }
 
 impl Resolver {
@@ -164,9 +177,41 @@ impl Resolver {
         Self {
             graph,
             physical_passes,
+            timestamps: None,
+            waits: vec![],
         }
     }
 
+    fn async_compute_queue_family_index(
+        device: &Device,
+        queue_family_index: usize,
+    ) -> Option<usize> {
+        device
+            .physical_device
+            .queue_families
+            .iter()
+            .enumerate()
+            .find(|(idx, properties)| {
+                *idx != queue_family_index
+                    && properties.queue_count > 0
+                    && properties.queue_flags.contains(vk::QueueFlags::COMPUTE)
+                    && !properties.queue_flags.contains(vk::QueueFlags::GRAPHICS)
+            })
+            .map(|(idx, _)| idx)
+    }
+
+    fn allow_async_compute(bindings: &[Binding], pass: &Pass) -> bool {
+        // Graphic pipelines require a graphics queue and swapchain images are exclusively owned by
+        // the presentation queue family
+        pass.execs.iter().all(|exec| {
+            !matches!(exec.pipeline, Some(ExecutionPipeline::Graphic(_)))
+                && exec
+                    .accesses
+                    .keys()
+                    .all(|node_idx| !matches!(bindings[*node_idx], Binding::SwapchainImage(..)))
+        })
+    }
+
     #[profiling::function]
     fn allow_merge_passes(lhs: &Pass, rhs: &Pass) -> bool {
         let lhs_pipeline = lhs
@@ -203,6 +248,7 @@ impl Resolver {
 
         // Must be same general rasterization modes
         if lhs_pipeline.info.blend != rhs_pipeline.info.blend
+            || lhs_pipeline.info.blend_attachments != rhs_pipeline.info.blend_attachments
             || lhs_pipeline.info.cull_mode != rhs_pipeline.info.cull_mode
             || lhs_pipeline.info.front_face != rhs_pipeline.info.front_face
             || lhs_pipeline.info.polygon_mode != rhs_pipeline.info.polygon_mode
@@ -447,6 +493,7 @@ impl Resolver {
                                 fmt: attachment.format,
                                 mip_level_count: Some(attachment.mip_level_count),
                                 ty: image.info.ty,
+                                ycbcr_conversion: None,
                             },
                         )?;
                     }
@@ -485,6 +532,7 @@ impl Resolver {
                                 fmt: attachment.format,
                                 mip_level_count: Some(attachment.mip_level_count),
                                 ty: image.info.ty,
+                                ycbcr_conversion: None,
                             },
                         )?;
                     }
@@ -521,6 +569,7 @@ impl Resolver {
                                 fmt: attachment.format,
                                 mip_level_count: Some(attachment.mip_level_count),
                                 ty: image.info.ty,
+                                ycbcr_conversion: None,
                             },
                         )?;
                     }
@@ -557,6 +606,7 @@ impl Resolver {
                                 fmt: attachment.format,
                                 mip_level_count: Some(attachment.mip_level_count),
                                 ty: image.info.ty,
+                                ycbcr_conversion: None,
                             },
                         )?;
                     }
@@ -591,6 +641,7 @@ impl Resolver {
                                 fmt: attachment.format,
                                 mip_level_count: Some(attachment.mip_level_count),
                                 ty: image.info.ty,
+                                ycbcr_conversion: None,
                             },
                         )?;
                     }
@@ -645,6 +696,7 @@ impl Resolver {
         if let Some(exec_descriptor_sets) = physical_pass.exec_descriptor_sets.get(&exec_idx) {
             thread_local! {
                 static DESCRIPTOR_SETS: RefCell<Vec<vk::DescriptorSet>> = Default::default();
+                static DYNAMIC_OFFSETS: RefCell<Vec<u32>> = Default::default();
             }
 
             if exec_descriptor_sets.is_empty() {
@@ -661,16 +713,30 @@ impl Resolver {
 
                 trace!("    bind descriptor sets {:?}", descriptor_sets);
 
-                unsafe {
-                    cmd_buf.device.cmd_bind_descriptor_sets(
-                        **cmd_buf,
-                        pipeline.bind_point(),
-                        pipeline.layout(),
+                // Dynamic buffers start at offset zero; recorders may re-bind with other offsets
+                DYNAMIC_OFFSETS.with_borrow_mut(|dynamic_offsets| {
+                    dynamic_offsets.clear();
+                    dynamic_offsets.resize(
+                        pipeline
+                            .descriptor_bindings()
+                            .values()
+                            .filter(|(descriptor_info, _)| descriptor_info.is_dynamic())
+                            .map(|(descriptor_info, _)| descriptor_info.binding_count() as usize)
+                            .sum(),
                         0,
-                        descriptor_sets,
-                        &[],
                     );
-                }
+
+                    unsafe {
+                        cmd_buf.device.cmd_bind_descriptor_sets(
+                            **cmd_buf,
+                            pipeline.bind_point(),
+                            pipeline.layout(),
+                            0,
+                            descriptor_sets,
+                            dynamic_offsets,
+                        );
+                    }
+                });
             });
         }
     }
@@ -741,6 +807,37 @@ impl Resolver {
         self.graph.passes.is_empty()
     }
 
+    /// Returns the position within `schedule` of the last pass which accesses each transient node.
+    ///
+    /// Transient nodes which are also accessed by passes that are not scheduled are not included.
+    fn transient_last_uses(&self, schedule: &[usize]) -> HashMap<NodeIndex, usize> {
+        let mut schedule_indices = vec![None; self.graph.passes.len()];
+        for (schedule_idx, pass_idx) in schedule.iter().copied().enumerate() {
+            schedule_indices[pass_idx] = Some(schedule_idx);
+        }
+
+        let mut last_uses = HashMap::<_, Option<usize>>::new();
+        for (pass_idx, pass) in self.graph.passes.iter().enumerate() {
+            let schedule_idx = schedule_indices[pass_idx];
+
+            for node_idx in pass
+                .execs
+                .iter()
+                .flat_map(|exec| exec.accesses.keys())
+                .copied()
+                .filter(|node_idx| self.graph.transients.contains(node_idx))
+            {
+                let last_use = last_uses.entry(node_idx).or_insert(schedule_idx);
+                *last_use = last_use.zip(schedule_idx).map(|(a, b)| a.max(b));
+            }
+        }
+
+        last_uses
+            .into_iter()
+            .filter_map(|(node_idx, last_use)| last_use.map(|last_use| (node_idx, last_use)))
+            .collect()
+    }
+
     #[allow(clippy::type_complexity)]
     #[profiling::function]
     fn lease_descriptor_pool<P>(
@@ -769,49 +866,18 @@ impl Resolver {
                 for (&descriptor_ty, &descriptor_count) in pool_size {
                     debug_assert_ne!(descriptor_count, 0);
 
-                    match descriptor_ty {
-                        vk::DescriptorType::ACCELERATION_STRUCTURE_KHR => {
-                            info.acceleration_structure_count += descriptor_count;
-                        }
-                        vk::DescriptorType::COMBINED_IMAGE_SAMPLER => {
-                            info.combined_image_sampler_count += descriptor_count;
-                        }
-                        vk::DescriptorType::INPUT_ATTACHMENT => {
-                            info.input_attachment_count += descriptor_count;
-                        }
-                        vk::DescriptorType::SAMPLED_IMAGE => {
-                            info.sampled_image_count += descriptor_count;
-                        }
-                        vk::DescriptorType::SAMPLER => {
-                            info.sampler_count += descriptor_count;
-                        }
-                        vk::DescriptorType::STORAGE_BUFFER => {
-                            info.storage_buffer_count += descriptor_count;
-                        }
-                        vk::DescriptorType::STORAGE_BUFFER_DYNAMIC => {
-                            info.storage_buffer_dynamic_count += descriptor_count;
-                        }
-                        vk::DescriptorType::STORAGE_IMAGE => {
-                            info.storage_image_count += descriptor_count;
-                        }
-                        vk::DescriptorType::STORAGE_TEXEL_BUFFER => {
-                            info.storage_texel_buffer_count += descriptor_count;
-                        }
-                        vk::DescriptorType::UNIFORM_BUFFER => {
-                            info.uniform_buffer_count += descriptor_count;
-                        }
-                        vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC => {
-                            info.uniform_buffer_dynamic_count += descriptor_count;
-                        }
-                        vk::DescriptorType::UNIFORM_TEXEL_BUFFER => {
-                            info.uniform_texel_buffer_count += descriptor_count;
-                        }
-                        _ => unimplemented!("{descriptor_ty:?}"),
-                    };
+                    info.add_descriptor_count(descriptor_ty, descriptor_count);
                 }
             }
         }
 
+        // Bindless pipelines require every set of the pass to come from an update-after-bind pool
+        info.update_after_bind = pass
+            .execs
+            .iter()
+            .flat_map(|exec| exec.pipeline.as_ref())
+            .any(|pipeline| pipeline.descriptor_info().update_after_bind);
+
         // It's possible to execute a command-only pipeline
         if info.is_empty() {
             return Ok(None);
@@ -848,6 +914,7 @@ impl Resolver {
         &self,
         pool: &mut P,
         pass_idx: usize,
+        is_last_use: impl Fn(NodeIndex) -> bool,
     ) -> Result<Lease<RenderPass>, DriverError>
     where
         P: Pool<RenderPassInfo, RenderPass> + ?Sized,
@@ -934,24 +1001,21 @@ impl Resolver {
                 let attachment = &mut attachments[color_attachment_count];
                 attachment.fmt = cleared_attachment.format;
                 attachment.sample_count = cleared_attachment.sample_count;
+                attachment.set_depth_stencil_load_op(
+                    cleared_attachment.aspect_mask,
+                    vk::AttachmentLoadOp::CLEAR,
+                );
                 attachment.initial_layout = if cleared_attachment
                     .aspect_mask
                     .contains(vk::ImageAspectFlags::DEPTH | vk::ImageAspectFlags::STENCIL)
                 {
-                    attachment.load_op = vk::AttachmentLoadOp::CLEAR;
-                    attachment.stencil_load_op = vk::AttachmentLoadOp::CLEAR;
-
                     vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL
                 } else if cleared_attachment
                     .aspect_mask
                     .contains(vk::ImageAspectFlags::DEPTH)
                 {
-                    attachment.load_op = vk::AttachmentLoadOp::CLEAR;
-
                     vk::ImageLayout::DEPTH_ATTACHMENT_OPTIMAL
                 } else {
-                    attachment.stencil_load_op = vk::AttachmentLoadOp::CLEAR;
-
                     vk::ImageLayout::STENCIL_ATTACHMENT_OPTIMAL
                 };
             } else if let Some(loaded_attachment) = first_exec.depth_stencil_load {
@@ -959,24 +1023,21 @@ impl Resolver {
                 let attachment = &mut attachments[color_attachment_count];
                 attachment.fmt = loaded_attachment.format;
                 attachment.sample_count = loaded_attachment.sample_count;
+                attachment.set_depth_stencil_load_op(
+                    loaded_attachment.aspect_mask,
+                    vk::AttachmentLoadOp::LOAD,
+                );
                 attachment.initial_layout = if loaded_attachment
                     .aspect_mask
                     .contains(vk::ImageAspectFlags::DEPTH | vk::ImageAspectFlags::STENCIL)
                 {
-                    attachment.load_op = vk::AttachmentLoadOp::LOAD;
-                    attachment.stencil_load_op = vk::AttachmentLoadOp::LOAD;
-
                     vk::ImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL
                 } else if loaded_attachment
                     .aspect_mask
                     .contains(vk::ImageAspectFlags::DEPTH)
                 {
-                    attachment.load_op = vk::AttachmentLoadOp::LOAD;
-
                     vk::ImageLayout::DEPTH_READ_ONLY_OPTIMAL
                 } else {
-                    attachment.stencil_load_op = vk::AttachmentLoadOp::LOAD;
-
                     vk::ImageLayout::STENCIL_READ_ONLY_OPTIMAL
                 };
             }
@@ -1051,8 +1112,29 @@ impl Resolver {
             }
         }
 
+        // Transient attachments are not stored if no other pass will access them
+        {
+            let last_exec = pass.execs.last().unwrap();
+
+            for (attachment_idx, stored_attachment) in &last_exec.color_stores {
+                if is_last_use(stored_attachment.target) {
+                    attachments[*attachment_idx as usize].store_op =
+                        vk::AttachmentStoreOp::DONT_CARE;
+                }
+            }
+
+            if let Some(stored_attachment) = last_exec.depth_stencil_store {
+                if is_last_use(stored_attachment.target) {
+                    let attachment = &mut attachments[color_attachment_count];
+                    attachment.store_op = vk::AttachmentStoreOp::DONT_CARE;
+                    attachment.stencil_store_op = vk::AttachmentStoreOp::DONT_CARE;
+                }
+            }
+        }
+
         for attachment in &mut attachments {
-            if attachment.load_op == vk::AttachmentLoadOp::DONT_CARE {
+            // Stencil-only attachments are loaded using stencil_load_op
+            if !attachment.is_loaded() {
                 attachment.initial_layout = attachment.final_layout;
             } else if attachment.store_op == vk::AttachmentStoreOp::DONT_CARE
                 && attachment.stencil_store_op == vk::AttachmentStoreOp::DONT_CARE
@@ -1097,9 +1179,13 @@ impl Resolver {
                 // We should preserve the attachment in the previous subpasses as needed
                 // (We're asserting that any input renderpasses are actually real subpasses
                 // here with prior passes..)
-                for prev_exec_idx in (0..exec_idx - 1).rev() {
+                for prev_exec_idx in (0..exec_idx).rev() {
                     let prev_exec = &pass.execs[prev_exec_idx];
-                    if prev_exec.color_stores.contains_key(attachment_idx) {
+                    if prev_exec.color_attachments.contains_key(attachment_idx)
+                        || prev_exec.color_clears.contains_key(attachment_idx)
+                        || prev_exec.color_loads.contains_key(attachment_idx)
+                        || prev_exec.color_stores.contains_key(attachment_idx)
+                    {
                         break;
                     }
 
@@ -1357,6 +1443,28 @@ impl Resolver {
                         }
                     }
 
+                    if exec.self_dependency {
+                        let (src_stage_mask, src_access_mask, dst_stage_mask, dst_access_mask) =
+                            SUBPASS_SELF_DEPENDENCY;
+                        let dep = dependencies.entry((exec_idx, exec_idx)).or_insert_with(|| {
+                            SubpassDependency::new(exec_idx as _, exec_idx as _)
+                        });
+
+                        // Draws of this exec read color attachments written by previous draws
+                        dep.src_stage_mask |= src_stage_mask;
+                        dep.src_access_mask |= src_access_mask;
+                        dep.dst_stage_mask |= dst_stage_mask;
+                        dep.dst_access_mask |= dst_access_mask;
+                        dep.dependency_flags |= vk::DependencyFlags::BY_REGION;
+                    }
+
+                    let input_attachments = &exec
+                        .pipeline
+                        .as_ref()
+                        .unwrap()
+                        .unwrap_graphic()
+                        .input_attachments;
+
                     // Look for attachments of this exec being read or written in other execs of the
                     // same pass
                     for (other_idx, other) in pass.execs[0..exec_idx].iter().enumerate() {
@@ -1379,6 +1487,17 @@ impl Resolver {
                                 // ... before we:
                                 dep.dst_stage_mask |= vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS;
                                 dep.dst_access_mask |= vk::AccessFlags::COLOR_ATTACHMENT_READ;
+
+                                // Subpass input is read by the fragment shader of this exec, which
+                                // only ever reads the same pixel and so it may stay on tile
+                                if input_attachments.contains(attachment_idx) {
+                                    let (stages, access) = pipeline_stage_access_flags(
+                                        AccessType::FragmentShaderReadColorInputAttachment,
+                                    );
+                                    dep.dst_stage_mask |= stages;
+                                    dep.dst_access_mask |= access;
+                                    dep.dependency_flags |= vk::DependencyFlags::BY_REGION;
+                                }
                             }
 
                             // look for reads in the other exec
@@ -1654,7 +1773,9 @@ impl Resolver {
     where
         P: Pool<DescriptorPoolInfo, DescriptorPool> + Pool<RenderPassInfo, RenderPass> + ?Sized,
     {
-        for pass_idx in schedule.iter().copied() {
+        let transient_last_uses = self.transient_last_uses(schedule);
+
+        for (schedule_idx, pass_idx) in schedule.iter().copied().enumerate() {
             // At the time this function runs the pass will already have been optimized into a
             // larger pass made out of anything that might have been merged into it - so we
             // only care about one pass at a time here
@@ -1678,8 +1799,16 @@ impl Resolver {
                             exec.pipeline.as_ref().map(|pipeline| (exec_idx, pipeline))
                         })
                 {
-                    let layouts = pipeline.descriptor_info().layouts.values();
-                    let mut descriptor_sets = Vec::with_capacity(layouts.len());
+                    // The push descriptor set is always the last set and is recorded directly
+                    let descriptor_info = pipeline.descriptor_info();
+                    let layouts = descriptor_info
+                        .layouts
+                        .iter()
+                        .filter(|(descriptor_set_idx, _)| {
+                            descriptor_info.push_descriptor_set != Some(**descriptor_set_idx)
+                        })
+                        .map(|(_, descriptor_set_layout)| descriptor_set_layout);
+                    let mut descriptor_sets = Vec::with_capacity(descriptor_info.layouts.len());
                     for descriptor_set_layout in layouts {
                         descriptor_sets.push(DescriptorPool::allocate_descriptor_set(
                             descriptor_pool,
@@ -1690,26 +1819,7 @@ impl Resolver {
                 }
             }
 
-            // Note that as a side effect of merging compatible passes all input passes should
-            // be globbed onto their preceeding passes by now. This allows subpasses to use
-            // input attachments without really doing anything, so we are provided a pass that
-            // starts with input we just blow up b/c we can't provide it, or at least shouldn't.
             debug_assert!(!pass.execs.is_empty());
-            debug_assert!(
-                pass.execs[0].pipeline.is_none()
-                    || !pass.execs[0].pipeline.as_ref().unwrap().is_graphic()
-                    || pass.execs[0]
-                        .pipeline
-                        .as_ref()
-                        .unwrap()
-                        .unwrap_graphic()
-                        .descriptor_info
-                        .pool_sizes
-                        .values()
-                        .filter_map(|pool| pool.get(&vk::DescriptorType::INPUT_ATTACHMENT))
-                        .next()
-                        .is_none()
-            );
 
             // Also the renderpass may just be None if the pass contained no graphic ops.
             let render_pass = if pass.execs[0]
@@ -1718,7 +1828,9 @@ impl Resolver {
                 .map(|pipeline| pipeline.is_graphic())
                 .unwrap_or_default()
             {
-                Some(self.lease_render_pass(pool, pass_idx)?)
+                Some(self.lease_render_pass(pool, pass_idx, |node_idx| {
+                    transient_last_uses.get(&node_idx) == Some(&schedule_idx)
+                })?)
             } else {
                 None
             };
@@ -1771,8 +1883,10 @@ impl Resolver {
                     }
                 }
 
-                if log_enabled!(Trace) && start != end {
+                if start != end {
                     trace!("merging {} passes into [{idx}: {}]", end - start, pass.name);
+
+                    pass.merged = true;
                 }
 
                 // Grow the merged pass once, not per merge
@@ -1787,6 +1901,7 @@ impl Resolver {
 
                     pass.name.reserve(name_additional);
                     pass.execs.reserve(execs_additional);
+                    pass.pass_indices.reserve(end - start);
                 }
 
                 for idx in start..end {
@@ -1794,6 +1909,7 @@ impl Resolver {
                     pass.name.push_str(" + ");
                     pass.name.push_str(other.name.as_str());
                     pass.execs.append(&mut other.execs);
+                    pass.pass_indices.append(&mut other.pass_indices);
                 }
 
                 self.graph.passes.push(pass);
@@ -1866,6 +1982,7 @@ impl Resolver {
         bindings: &mut [Binding],
         exec: &mut Execution,
         record_framebuffer_access: bool,
+        mut observed: Option<&mut Vec<PassBarrier>>,
     ) {
         use std::slice::from_ref;
 
@@ -1932,6 +2049,17 @@ impl Resolver {
                         unimplemented!();
                     };
 
+                    if let Some(observed) = observed.as_deref_mut() {
+                        // Framebuffer accesses of subpasses are synchronized by the render pass
+                        if record_framebuffer_access || !is_framebuffer_access(next_access) {
+                            observed.push(PassBarrier {
+                                next_access,
+                                node_idx: *node_idx,
+                                prev_access,
+                            });
+                        }
+                    }
+
                     // If we find a subresource then it must have a resource attached
                     if let Some(subresource) = early.subresource {
                         if let Some(buf) = binding.as_driver_buffer() {
@@ -2142,6 +2270,30 @@ impl Resolver {
         self.record_node_passes(pool, cmd_buf, node_idx, end_pass_idx)
     }
 
+    /// Records the first `pass_count` pending passes.
+    #[profiling::function]
+    fn record_leading_passes<P>(
+        &mut self,
+        pool: &mut P,
+        cmd_buf: &mut CommandBuffer,
+        pass_count: usize,
+    ) -> Result<(), DriverError>
+    where
+        P: Pool<DescriptorPoolInfo, DescriptorPool> + Pool<RenderPassInfo, RenderPass> + ?Sized,
+    {
+        thread_local! {
+            static SCHEDULE: RefCell<Schedule> = Default::default();
+        }
+
+        SCHEDULE.with_borrow_mut(|schedule| {
+            schedule.access_cache.update(&self.graph, pass_count);
+            schedule.passes.clear();
+            schedule.passes.extend(0..pass_count);
+
+            self.record_scheduled_passes(pool, cmd_buf, schedule, pass_count)
+        })
+    }
+
     #[profiling::function]
     fn record_node_passes<P>(
         &mut self,
@@ -2166,6 +2318,195 @@ impl Resolver {
         })
     }
 
+    /// Records the queue family ownership acquire barriers which pair with the release barriers
+    /// recorded on another queue by [`Self::record_queue_release`].
+    ///
+    /// The release barriers leave each resource in general access, so the acquire barriers are
+    /// supported by any queue.
+    #[profiling::function]
+    fn record_queue_acquire(
+        cmd_buf: &CommandBuffer,
+        bindings: &[Binding],
+        acquires: &[(NodeIndex, u32)],
+    ) {
+        if acquires.is_empty() {
+            return;
+        }
+
+        trace!("  acquire {} nodes", acquires.len());
+
+        let transfers = acquires
+            .iter()
+            .map(|&(node_idx, src_queue_family_index)| {
+                (
+                    node_idx,
+                    AccessType::General,
+                    src_queue_family_index,
+                    cmd_buf.info.queue_family_index,
+                )
+            })
+            .collect::<Box<_>>();
+
+        Self::record_queue_ownership_transfers(cmd_buf, bindings, &transfers);
+    }
+
+    /// Records queue family ownership transfer barriers which move the whole of each node from
+    /// the given previous access into general access.
+    fn record_queue_ownership_transfers(
+        cmd_buf: &CommandBuffer,
+        bindings: &[Binding],
+        transfers: &[(NodeIndex, AccessType, u32, u32)],
+    ) {
+        use std::slice::from_ref;
+
+        let mut buffer_barriers = vec![];
+        let mut image_barriers = vec![];
+
+        for (node_idx, prev_access, src_queue_family_index, dst_queue_family_index) in transfers {
+            let binding = &bindings[*node_idx];
+            let buffer = if let Some(buffer) = binding.as_driver_buffer() {
+                Some(buffer)
+            } else {
+                binding
+                    .as_driver_acceleration_structure()
+                    .map(|accel_struct| &accel_struct.accel_struct.1)
+            };
+
+            if let Some(buffer) = buffer {
+                buffer_barriers.push(BufferBarrier {
+                    next_accesses: from_ref(&AccessType::General),
+                    previous_accesses: from_ref(prev_access),
+                    src_queue_family_index: *src_queue_family_index,
+                    dst_queue_family_index: *dst_queue_family_index,
+                    buffer: **buffer,
+                    offset: 0,
+                    size: vk::WHOLE_SIZE as _,
+                });
+            } else if let Some(image) = binding.as_driver_image() {
+                image_barriers.push(ImageBarrier {
+                    next_accesses: from_ref(&AccessType::General),
+                    next_layout: image_access_layout(AccessType::General),
+                    previous_accesses: from_ref(prev_access),
+                    previous_layout: image_access_layout(*prev_access),
+                    discard_contents: false,
+                    src_queue_family_index: *src_queue_family_index,
+                    dst_queue_family_index: *dst_queue_family_index,
+                    image: **image,
+                    range: vk::ImageSubresourceRange {
+                        aspect_mask: format_aspect_mask(image.info.fmt),
+                        base_mip_level: 0,
+                        level_count: vk::REMAINING_MIP_LEVELS,
+                        base_array_layer: 0,
+                        layer_count: vk::REMAINING_ARRAY_LAYERS,
+                    },
+                });
+            }
+        }
+
+        pipeline_barrier(
+            &cmd_buf.device,
+            **cmd_buf,
+            None,
+            &buffer_barriers,
+            &image_barriers,
+        );
+    }
+
+    /// Records the queue family ownership release barriers of the given nodes, each of which is
+    /// next used by a queue of the paired queue family. Returns the nodes which were released,
+    /// along with their data.
+    ///
+    /// Images are first transitioned into general access on this queue because the matching
+    /// acquire barrier is recorded on the other queue, which may not support the pipeline stages
+    /// of the previous access. Nodes which have never been accessed have no contents to transfer
+    /// and are not released.
+    #[profiling::function]
+    fn record_queue_release<T>(
+        cmd_buf: &CommandBuffer,
+        bindings: &[Binding],
+        releases: impl Iterator<Item = (NodeIndex, u32, T)>,
+    ) -> Vec<(NodeIndex, T)> {
+        use std::slice::from_ref;
+
+        let mut image_transitions = vec![];
+        let mut released = vec![];
+        let mut transfers = vec![];
+
+        for (node_idx, dst_queue_family_index, data) in releases {
+            let binding = &bindings[node_idx];
+            let mut prev_access = if let Some(buffer) = binding.as_driver_buffer() {
+                Buffer::access(buffer, AccessType::General)
+            } else if let Some(image) = binding.as_driver_image() {
+                Image::access(image, AccessType::General)
+            } else if let Some(accel_struct) = binding.as_driver_acceleration_structure() {
+                AccelerationStructure::access(accel_struct, AccessType::General)
+            } else {
+                continue;
+            };
+
+            if prev_access == AccessType::Nothing {
+                // The other queue discards the contents of this node, so it keeps no access
+                if let Some(buffer) = binding.as_driver_buffer() {
+                    Buffer::access(buffer, AccessType::Nothing);
+                } else if let Some(image) = binding.as_driver_image() {
+                    Image::access(image, AccessType::Nothing);
+                } else if let Some(accel_struct) = binding.as_driver_acceleration_structure() {
+                    AccelerationStructure::access(accel_struct, AccessType::Nothing);
+                }
+
+                continue;
+            }
+
+            if let Some(image) = binding.as_driver_image() {
+                image_transitions.push((image, prev_access));
+                prev_access = AccessType::General;
+            }
+
+            released.push((node_idx, data));
+            transfers.push((
+                node_idx,
+                prev_access,
+                cmd_buf.info.queue_family_index,
+                dst_queue_family_index,
+            ));
+        }
+
+        if transfers.is_empty() {
+            return vec![];
+        }
+
+        trace!("  release {} nodes", transfers.len());
+
+        if !image_transitions.is_empty() {
+            let image_barriers = image_transitions
+                .iter()
+                .map(|(image, prev_access)| ImageBarrier {
+                    next_accesses: from_ref(&AccessType::General),
+                    next_layout: image_access_layout(AccessType::General),
+                    previous_accesses: from_ref(prev_access),
+                    previous_layout: image_access_layout(*prev_access),
+                    discard_contents: false,
+                    src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+                    dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+                    image: ***image,
+                    range: vk::ImageSubresourceRange {
+                        aspect_mask: format_aspect_mask(image.info.fmt),
+                        base_mip_level: 0,
+                        level_count: vk::REMAINING_MIP_LEVELS,
+                        base_array_layer: 0,
+                        layer_count: vk::REMAINING_ARRAY_LAYERS,
+                    },
+                })
+                .collect::<Box<_>>();
+
+            pipeline_barrier(&cmd_buf.device, **cmd_buf, None, &[], &image_barriers);
+        }
+
+        Self::record_queue_ownership_transfers(cmd_buf, bindings, &transfers);
+
+        released
+    }
+
     #[profiling::function]
     fn record_scheduled_passes<P>(
         &mut self,
@@ -2192,8 +2533,24 @@ impl Resolver {
             "Unsorted schedule"
         );
 
+        // Passes which are not scheduled will be shifted down once the scheduled passes have been
+        // recorded, so their explicit dependencies must be shifted too
+        for (pass_idx, pass) in self.graph.passes.iter_mut().enumerate() {
+            if schedule.passes.binary_search(&pass_idx).is_err() {
+                pass.dependencies.retain_mut(|dep_idx| {
+                    match schedule.passes.binary_search(dep_idx) {
+                        Ok(_) => false,
+                        Err(scheduled_count) => {
+                            *dep_idx -= scheduled_count;
+                            true
+                        }
+                    }
+                });
+            }
+        }
+
         // Optimize the schedule; leasing the required stuff it needs
-        Self::reorder_scheduled_passes(schedule, end_pass_idx);
+        Self::reorder_scheduled_passes(&self.graph.passes, schedule, end_pass_idx);
         self.merge_scheduled_passes(&mut schedule.passes);
         self.lease_scheduled_resources(pool, &schedule.passes)?;
 
@@ -2207,6 +2564,29 @@ impl Resolver {
 
             trace!("recording pass [{}: {}]", pass_idx, pass.name);
 
+            let mut pass_info = self.graph.pass_observer.is_some().then(|| {
+                let mut resources = pass
+                    .execs
+                    .iter()
+                    .flat_map(|exec| exec.accesses.keys().copied())
+                    .collect::<Vec<_>>();
+                resources.sort_unstable();
+                resources.dedup();
+
+                PassInfo {
+                    barriers: vec![],
+                    merged: pass.merged,
+                    name: pass.name.clone(),
+                    pass_indices: pass.pass_indices.clone(),
+                    resources,
+                }
+            });
+
+            let timestamp_query = self
+                .timestamps
+                .as_ref()
+                .and_then(|timestamps| timestamps.write_begin(cmd_buf, &pass.name));
+
             if !physical_pass.exec_descriptor_sets.is_empty() {
                 Self::write_descriptor_sets(cmd_buf, &self.graph.bindings, pass, physical_pass)?;
             }
@@ -2217,6 +2597,7 @@ impl Resolver {
                 &mut self.graph.bindings,
                 &mut pass.execs[0],
                 true,
+                pass_info.as_mut().map(|info| &mut info.barriers),
             );
 
             let render_area = if is_graphic {
@@ -2227,6 +2608,7 @@ impl Resolver {
                         &mut self.graph.bindings,
                         &mut pass.execs[exec_idx],
                         false,
+                        pass_info.as_mut().map(|info| &mut info.barriers),
                     );
                 }
 
@@ -2251,13 +2633,15 @@ impl Resolver {
                 }
 
                 if let Some(pipeline) = exec.pipeline.as_mut() {
-                    Self::bind_pipeline(
-                        cmd_buf,
-                        physical_pass,
-                        exec_idx,
-                        pipeline,
-                        exec.depth_stencil,
-                    )?;
+                    // Passes which do not specify depth/stencil use the graphic pipeline mode
+                    let depth_stencil = match pipeline {
+                        ExecutionPipeline::Graphic(pipeline) => {
+                            pipeline.info.depth_stencil_mode(exec.depth_stencil)
+                        }
+                        _ => exec.depth_stencil,
+                    };
+
+                    Self::bind_pipeline(cmd_buf, physical_pass, exec_idx, pipeline, depth_stencil)?;
 
                     if is_graphic && pass.render_area.is_none() {
                         let render_area = render_area.unwrap();
@@ -2266,7 +2650,7 @@ impl Resolver {
                             cmd_buf,
                             render_area.width as _,
                             render_area.height as _,
-                            exec.depth_stencil
+                            depth_stencil
                                 .map(|depth_stencil| {
                                     let min = depth_stencil.min.0;
                                     let max = depth_stencil.max.0;
@@ -2277,7 +2661,30 @@ impl Resolver {
                         Self::set_scissor(cmd_buf, render_area.width, render_area.height);
                     }
 
+                    if is_graphic {
+                        // Line width and depth bias are dynamic state which must be set
+                        Self::set_line_width_depth_bias(cmd_buf);
+                    }
+
                     Self::bind_descriptor_sets(cmd_buf, pipeline, physical_pass, exec_idx);
+
+                    // Recorders re-bind descriptor sets in order to set dynamic buffer offsets
+                    if pipeline
+                        .descriptor_bindings()
+                        .values()
+                        .any(|(descriptor_info, _)| descriptor_info.is_dynamic())
+                    {
+                        if let Some(exec_descriptor_sets) =
+                            physical_pass.exec_descriptor_sets.get(&exec_idx)
+                        {
+                            exec.descriptor_sets.clear();
+                            exec.descriptor_sets.extend(
+                                exec_descriptor_sets
+                                    .iter()
+                                    .map(|descriptor_set| **descriptor_set),
+                            );
+                        }
+                    }
                 }
 
                 if exec_idx > 0 && !is_graphic {
@@ -2287,6 +2694,7 @@ impl Resolver {
                         &mut self.graph.bindings,
                         exec,
                         true,
+                        pass_info.as_mut().map(|info| &mut info.barriers),
                     );
                 }
 
@@ -2299,7 +2707,7 @@ impl Resolver {
                     exec_func(
                         &cmd_buf.device,
                         **cmd_buf,
-                        Bindings::new(&self.graph.bindings, exec),
+                        Bindings::new(&self.graph.bindings, **cmd_buf, &cmd_buf.device, exec),
                     );
                 }
             }
@@ -2307,6 +2715,17 @@ impl Resolver {
             if is_graphic {
                 self.end_render_pass(cmd_buf);
             }
+
+            if let Some(timestamp_query) = timestamp_query {
+                self.timestamps
+                    .as_ref()
+                    .unwrap()
+                    .write_end(cmd_buf, timestamp_query);
+            }
+
+            if let Some(pass_info) = pass_info {
+                (self.graph.pass_observer.as_mut().unwrap().0)(pass_info);
+            }
         }
 
         thread_local! {
@@ -2364,19 +2783,7 @@ impl Resolver {
             return Ok(());
         }
 
-        thread_local! {
-            static SCHEDULE: RefCell<Schedule> = Default::default();
-        }
-
-        SCHEDULE.with_borrow_mut(|schedule| {
-            schedule
-                .access_cache
-                .update(&self.graph, self.graph.passes.len());
-            schedule.passes.clear();
-            schedule.passes.extend(0..self.graph.passes.len());
-
-            self.record_scheduled_passes(pool, cmd_buf, schedule, self.graph.passes.len())
-        })
+        self.record_leading_passes(pool, cmd_buf, self.graph.passes.len())
     }
 
     #[profiling::function]
@@ -2416,14 +2823,12 @@ impl Resolver {
     }
 
     #[profiling::function]
-    fn reorder_scheduled_passes(schedule: &mut Schedule, end_pass_idx: usize) {
+    fn reorder_scheduled_passes(passes: &[Pass], schedule: &mut Schedule, end_pass_idx: usize) {
         // It must be a party
         if schedule.passes.len() < 3 {
             return;
         }
 
-        let mut scheduled = 0;
-
         thread_local! {
             static UNSCHEDULED: RefCell<Vec<bool>> = Default::default();
         }
@@ -2433,42 +2838,73 @@ impl Resolver {
             unscheduled.fill(true);
             unscheduled.resize(end_pass_idx, true);
 
-            // Re-order passes by maximizing the distance between dependent nodes
+            let mut scheduled = 0;
+
             while scheduled < schedule.passes.len() {
-                let mut best_idx = scheduled;
-                let pass_idx = schedule.passes[best_idx];
-                let mut best_overlap_factor = schedule
-                    .access_cache
-                    .interdependent_passes(pass_idx, end_pass_idx)
-                    .count();
-
-                for (idx, pass_idx) in schedule.passes[best_idx + 1..schedule.passes.len()]
+                // Pinned passes are never moved: only the passes between them are re-ordered
+                let end = schedule.passes[scheduled..]
                     .iter()
-                    .enumerate()
-                {
-                    let mut overlap_factor = 0;
-
-                    for other_pass_idx in schedule
+                    .position(|pass_idx| passes[*pass_idx].pinned)
+                    .map(|idx| scheduled + idx)
+                    .unwrap_or(schedule.passes.len());
+
+                // Re-order passes by maximizing the distance between dependent nodes
+                while scheduled < end {
+                    // Passes with explicit dependencies can't be the candidate until those
+                    // dependencies are finished; the lowest remaining pass index always can be
+                    let first_idx = (scheduled..end)
+                        .find(|idx| {
+                            passes[schedule.passes[*idx]]
+                                .dependencies
+                                .iter()
+                                .all(|dep_idx| !unscheduled[*dep_idx])
+                        })
+                        .unwrap();
+                    let mut best_idx = first_idx;
+                    let pass_idx = schedule.passes[best_idx];
+                    let mut best_overlap_factor = schedule
                         .access_cache
-                        .interdependent_passes(*pass_idx, end_pass_idx)
-                    {
-                        if unscheduled[other_pass_idx] {
-                            // This pass can't be the candidate because it depends on unfinished work
-                            break;
+                        .interdependent_passes(pass_idx, end_pass_idx)
+                        .count();
+
+                    for (idx, pass_idx) in schedule.passes[first_idx + 1..end].iter().enumerate() {
+                        if passes[*pass_idx]
+                            .dependencies
+                            .iter()
+                            .any(|dep_idx| unscheduled[*dep_idx])
+                        {
+                            continue;
                         }
 
-                        overlap_factor += 1;
-                    }
+                        let mut overlap_factor = 0;
+
+                        for other_pass_idx in schedule
+                            .access_cache
+                            .interdependent_passes(*pass_idx, end_pass_idx)
+                        {
+                            if unscheduled[other_pass_idx] {
+                                // This pass can't be the candidate: it depends on unfinished work
+                                break;
+                            }
+
+                            overlap_factor += 1;
+                        }
 
-                    if overlap_factor > best_overlap_factor {
-                        best_idx += idx + 1;
-                        best_overlap_factor = overlap_factor;
+                        if overlap_factor > best_overlap_factor {
+                            best_idx = first_idx + idx + 1;
+                            best_overlap_factor = overlap_factor;
+                        }
                     }
+
+                    unscheduled[schedule.passes[best_idx]] = false;
+                    schedule.passes.swap(scheduled, best_idx);
+                    scheduled += 1;
                 }
 
-                unscheduled[schedule.passes[best_idx]] = false;
-                schedule.passes.swap(scheduled, best_idx);
-                scheduled += 1;
+                if let Some(pass_idx) = schedule.passes.get(end) {
+                    unscheduled[*pass_idx] = false;
+                    scheduled += 1;
+                }
             }
         });
     }
@@ -2526,49 +2962,87 @@ impl Resolver {
 
             trace!("secondary passes below");
 
-            // Now schedule all nodes that are required, going through the tree to find them
-            while let Some((node_idx, pass_idx)) = unchecked.pop_front() {
-                trace!("  node {node_idx} is dependent");
+            let mut explicit_idx = 0;
 
-                for pass_idx in schedule
-                    .access_cache
-                    .dependent_passes(node_idx, pass_idx + 1)
-                {
-                    let unscheduled = &mut unscheduled[pass_idx];
-                    if *unscheduled {
-                        *unscheduled = false;
-                        schedule.passes.push(pass_idx);
+            loop {
+                // Now schedule all nodes that are required, going through the tree to find them
+                while let Some((node_idx, pass_idx)) = unchecked.pop_front() {
+                    trace!("  node {node_idx} is dependent");
 
-                        trace!(
-                            "  pass [{pass_idx}: {}] is dependent",
-                            self.graph.passes[pass_idx].name
-                        );
+                    for pass_idx in schedule
+                        .access_cache
+                        .dependent_passes(node_idx, pass_idx + 1)
+                    {
+                        let unscheduled = &mut unscheduled[pass_idx];
+                        if *unscheduled {
+                            *unscheduled = false;
+                            schedule.passes.push(pass_idx);
+
+                            trace!(
+                                "  pass [{pass_idx}: {}] is dependent",
+                                self.graph.passes[pass_idx].name
+                            );
 
-                        for node_idx in schedule.access_cache.dependent_nodes(pass_idx) {
-                            trace!("    node {node_idx} is dependent");
+                            for node_idx in schedule.access_cache.dependent_nodes(pass_idx) {
+                                trace!("    node {node_idx} is dependent");
 
-                            let unresolved = &mut unresolved[node_idx];
-                            if *unresolved {
-                                *unresolved = false;
-                                unchecked.push_back((node_idx, pass_idx));
+                                let unresolved = &mut unresolved[node_idx];
+                                if *unresolved {
+                                    *unresolved = false;
+                                    unchecked.push_back((node_idx, pass_idx));
+                                }
                             }
                         }
                     }
                 }
-            }
 
-            schedule.passes.sort_unstable();
+                if explicit_idx == schedule.passes.len() {
+                    break;
+                }
 
-            if log_enabled!(Debug) {
-                if !schedule.passes.is_empty() {
-                    // These are the indexes of the passes this thread is about to resolve
-                    debug!(
-                        "schedule: {}",
-                        schedule
-                            .passes
-                            .iter()
-                            .copied()
-                            .map(|idx| format!("[{}: {}]", idx, self.graph.passes[idx].name))
+                // Schedule the explicit dependencies of all scheduled passes, which may in turn
+                // require additional nodes
+                while explicit_idx < schedule.passes.len() {
+                    let pass_idx = schedule.passes[explicit_idx];
+                    explicit_idx += 1;
+
+                    for dep_idx in self.graph.passes[pass_idx].dependencies.iter().copied() {
+                        let unscheduled = &mut unscheduled[dep_idx];
+                        if *unscheduled {
+                            *unscheduled = false;
+                            schedule.passes.push(dep_idx);
+
+                            trace!(
+                                "  pass [{dep_idx}: {}] is an explicit dependency",
+                                self.graph.passes[dep_idx].name
+                            );
+
+                            for node_idx in schedule.access_cache.dependent_nodes(dep_idx) {
+                                trace!("    node {node_idx} is dependent");
+
+                                let unresolved = &mut unresolved[node_idx];
+                                if *unresolved {
+                                    *unresolved = false;
+                                    unchecked.push_back((node_idx, dep_idx));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            schedule.passes.sort_unstable();
+
+            if log_enabled!(Debug) {
+                if !schedule.passes.is_empty() {
+                    // These are the indexes of the passes this thread is about to resolve
+                    debug!(
+                        "schedule: {}",
+                        schedule
+                            .passes
+                            .iter()
+                            .copied()
+                            .map(|idx| format!("[{}: {}]", idx, self.graph.passes[idx].name))
                             .collect::<Vec<_>>()
                             .join(", ")
                     );
@@ -2615,6 +3089,13 @@ impl Resolver {
         });
     }
 
+    fn set_line_width_depth_bias(cmd_buf: &CommandBuffer) {
+        unsafe {
+            cmd_buf.device.cmd_set_line_width(**cmd_buf, 1.0);
+            cmd_buf.device.cmd_set_depth_bias(**cmd_buf, 0.0, 0.0, 0.0);
+        }
+    }
+
     fn set_scissor(cmd_buf: &CommandBuffer, width: u32, height: u32) {
         use std::slice::from_ref;
 
@@ -2650,13 +3131,21 @@ impl Resolver {
     }
 
     /// Submits the remaining commands stored in this instance.
+    ///
+    /// Passes tagged with [`QueueClass::AsyncCompute`] are submitted to a dedicated compute queue
+    /// family, if the device offers one, and all other passes are submitted to the given queue.
+    /// Work on the two queues is synchronized using timeline semaphores. The returned command
+    /// buffer has executed once all passes of this graph have executed.
+    ///
+    /// The returned token may be passed to [`Resolver::wait_on`] to make the submission of another
+    /// graph wait on this one.
     #[profiling::function]
     pub fn submit<P>(
         mut self,
         pool: &mut P,
         queue_family_index: usize,
         queue_index: usize,
-    ) -> Result<Lease<CommandBuffer>, DriverError>
+    ) -> Result<(Lease<CommandBuffer>, SubmitToken), DriverError>
     where
         P: Pool<CommandBufferInfo, CommandBuffer>
             + Pool<DescriptorPoolInfo, DescriptorPool>
@@ -2666,7 +3155,7 @@ impl Resolver {
 
         trace!("submit");
 
-        let mut cmd_buf = pool.lease(CommandBufferInfo::new(queue_family_index as _))?;
+        let cmd_buf = pool.lease(CommandBufferInfo::new(queue_family_index as _))?;
 
         debug_assert!(
             queue_family_index < cmd_buf.device.physical_device.queue_families.len(),
@@ -2679,39 +3168,189 @@ impl Resolver {
             "Queue index must be within the range of the available queues created by the device."
         );
 
-        unsafe {
-            Device::wait_for_fence(&cmd_buf.device, &cmd_buf.fence)
-                .map_err(|_| DriverError::OutOfMemory)?;
+        let async_compute_queue_family_index = if self
+            .graph
+            .passes
+            .iter()
+            .any(|pass| pass.queue_class == QueueClass::AsyncCompute)
+        {
+            Self::async_compute_queue_family_index(&cmd_buf.device, queue_family_index)
+        } else {
+            None
+        };
 
-            cmd_buf
-                .device
-                .begin_command_buffer(
-                    **cmd_buf,
-                    &vk::CommandBufferBeginInfo::default()
-                        .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT),
-                )
-                .map_err(|_| DriverError::OutOfMemory)?;
+        // Split the passes into batches of consecutive passes which run on the same queue
+        let mut batches: Vec<(QueueClass, usize, HashSet<NodeIndex>)> = vec![];
+        for pass in &self.graph.passes {
+            let queue_class = if async_compute_queue_family_index.is_some()
+                && pass.queue_class == QueueClass::AsyncCompute
+            {
+                if Self::allow_async_compute(&self.graph.bindings, pass) {
+                    QueueClass::AsyncCompute
+                } else {
+                    warn!("pass [{}] requires the universal queue", pass.name);
+
+                    QueueClass::Universal
+                }
+            } else {
+                QueueClass::Universal
+            };
+            let nodes = pass
+                .execs
+                .iter()
+                .flat_map(|exec| exec.accesses.keys().copied());
+
+            match batches.last_mut() {
+                Some((batch_queue_class, pass_count, batch_nodes))
+                    if *batch_queue_class == queue_class =>
+                {
+                    *pass_count += 1;
+                    batch_nodes.extend(nodes);
+                }
+                _ => batches.push((queue_class, 1, nodes.collect())),
+            }
         }
 
-        self.record_unscheduled_passes(pool, &mut cmd_buf)?;
+        // Resources are used on the universal queue before and after this graph, so the first and
+        // last batches are always universal; the first batch stands in for previous submissions
+        if !matches!(batches.first(), Some((QueueClass::Universal, ..))) {
+            batches.insert(0, (QueueClass::Universal, 0, HashSet::new()));
+        }
 
-        unsafe {
-            cmd_buf
-                .device
-                .end_command_buffer(**cmd_buf)
-                .map_err(|_| DriverError::OutOfMemory)?;
-            cmd_buf
-                .device
-                .reset_fences(from_ref(&cmd_buf.fence))
-                .map_err(|_| DriverError::OutOfMemory)?;
-            cmd_buf
-                .device
-                .queue_submit(
-                    cmd_buf.device.queues[queue_family_index][queue_index],
-                    from_ref(&vk::SubmitInfo::default().command_buffers(from_ref(&cmd_buf))),
-                    cmd_buf.fence,
-                )
-                .map_err(|_| DriverError::OutOfMemory)?;
+        if !matches!(batches.last(), Some((QueueClass::Universal, ..))) {
+            batches.push((QueueClass::Universal, 0, HashSet::new()));
+        }
+
+        batches[0].2 = batches
+            .iter()
+            .flat_map(|(_, _, nodes)| nodes.iter().copied())
+            .collect();
+
+        // Async compute passes use the queue index of the universal queue, if available, so that
+        // graphs submitted to different universal queues also use different compute queues
+        let device = Arc::clone(&cmd_buf.device);
+        let queue_class_queue = |queue_class| match queue_class {
+            QueueClass::Universal => Some((queue_family_index, queue_index)),
+            QueueClass::AsyncCompute => async_compute_queue_family_index.map(|idx| {
+                let queue_count = device.physical_device.queue_families[idx].queue_count;
+
+                (idx, queue_index % queue_count as usize)
+            }),
+        };
+
+        let mut cmd_bufs: Vec<Lease<CommandBuffer>> = Vec::with_capacity(batches.len());
+        let mut tokens: Vec<SubmitToken> = Vec::with_capacity(batches.len());
+        let mut acquires = vec![vec![]; batches.len()];
+        let mut first_cmd_buf = Some(cmd_buf);
+
+        for batch_idx in 0..batches.len() {
+            let (queue_class, pass_count, ref nodes) = batches[batch_idx];
+            let (queue_family_index, queue_index) =
+                queue_class_queue(queue_class).expect("queue class not available");
+            let mut cmd_buf = match first_cmd_buf.take() {
+                Some(cmd_buf) => cmd_buf,
+                None => pool.lease(CommandBufferInfo::new(queue_family_index as _))?,
+            };
+
+            trace!("batch {batch_idx}: {pass_count} passes on {queue_class:?} queue");
+
+            unsafe {
+                Device::wait_for_fence(&cmd_buf.device, &cmd_buf.fence)
+                    .map_err(|_| DriverError::OutOfMemory)?;
+
+                cmd_buf
+                    .device
+                    .begin_command_buffer(
+                        **cmd_buf,
+                        &vk::CommandBufferBeginInfo::default()
+                            .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT),
+                    )
+                    .map_err(|_| DriverError::OutOfMemory)?;
+            }
+
+            // Acquire the resources which were released to this queue by previous batches
+            Self::record_queue_acquire(&cmd_buf, &self.graph.bindings, &acquires[batch_idx]);
+
+            self.record_leading_passes(pool, &mut cmd_buf, pass_count)?;
+
+            // Release the resources which are next used on the other queue; the next batch which
+            // uses each resource acquires it, or the last batch if it is not used again
+            let releases = nodes.iter().copied().filter_map(|node_idx| {
+                let next_batch_idx = batches[batch_idx + 1..]
+                    .iter()
+                    .position(|(_, _, next_nodes)| next_nodes.contains(&node_idx))
+                    .map(|idx| batch_idx + 1 + idx)
+                    .unwrap_or(batches.len() - 1);
+                let (next_queue_class, ..) = batches[next_batch_idx];
+
+                if next_queue_class == queue_class {
+                    return None;
+                }
+
+                let (next_queue_family_index, _) = queue_class_queue(next_queue_class).unwrap();
+
+                Some((node_idx, next_queue_family_index as u32, next_batch_idx))
+            });
+
+            for (node_idx, next_batch_idx) in
+                Self::record_queue_release(&cmd_buf, &self.graph.bindings, releases)
+            {
+                acquires[next_batch_idx].push((node_idx, queue_family_index as u32));
+            }
+
+            // Wait on the latest batch of the other queue which shares resources with this batch;
+            // the last batch always waits so that its fence signals after all batches execute
+            let is_last_batch = batch_idx + 1 == batches.len();
+            let wait = batches[..batch_idx]
+                .iter()
+                .zip(&tokens)
+                .rev()
+                .find(|((other_queue_class, _, other_nodes), _)| {
+                    *other_queue_class != queue_class
+                        && (is_last_batch || !other_nodes.is_disjoint(nodes))
+                })
+                .map(|(_, token)| *token);
+
+            // The first batch also waits on any tokens given to wait_on; tokens of command buffers
+            // which were never submitted are always complete
+            let waits = self
+                .waits
+                .drain(..)
+                .chain(wait)
+                .filter(|token| token.semaphore != vk::Semaphore::null())
+                .map(|token| (token, vk::PipelineStageFlags::ALL_COMMANDS))
+                .collect::<Box<_>>();
+
+            unsafe {
+                cmd_buf
+                    .device
+                    .end_command_buffer(**cmd_buf)
+                    .map_err(|_| DriverError::OutOfMemory)?;
+                cmd_buf
+                    .device
+                    .reset_fences(from_ref(&cmd_buf.fence))
+                    .map_err(|_| DriverError::OutOfMemory)?;
+            }
+
+            cmd_buf.submit_token = Device::queue_submit(
+                &cmd_buf.device,
+                queue_family_index,
+                queue_index,
+                **cmd_buf,
+                cmd_buf.fence,
+                &waits,
+                &[],
+            )?;
+
+            tokens.push(cmd_buf.submit_token);
+            cmd_bufs.push(cmd_buf);
+        }
+
+        let mut cmd_buf = cmd_bufs.pop().unwrap();
+
+        // The other command buffers must not be reused before the last one has executed
+        if !cmd_bufs.is_empty() {
+            CommandBuffer::push_fenced_drop(&mut cmd_buf, cmd_bufs);
         }
 
         // This graph contains references to buffers, images, and other resources which must be kept
@@ -2721,7 +3360,197 @@ impl Resolver {
         // has been signalled.
         CommandBuffer::push_fenced_drop(&mut cmd_buf, self);
 
-        Ok(cmd_buf)
+        let submit_token = cmd_buf.submit_token;
+
+        Ok((cmd_buf, submit_token))
+    }
+
+    /// Returns a [Graphviz](https://graphviz.org/) DOT description of the passes of this graph
+    /// which have not yet been recorded.
+    ///
+    /// Passes are drawn as boxes, in the order they were added, and resources are drawn as ellipses.
+    /// Edges between passes are labeled with the node and access types which order them; bold
+    /// edges require a pipeline barrier (_a write is involved_). Dashed edges connect adjacent
+    /// passes which are eligible to be merged into subpasses of a single render pass.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use screen_13::graph::RenderGraph;
+    /// # let my_graph = RenderGraph::new();
+    /// let dot = my_graph.resolve().to_dot();
+    ///
+    /// std::fs::write("graph.dot", dot).unwrap();
+    /// ```
+    pub fn to_dot(&self) -> String {
+        use std::fmt::Write;
+
+        fn escape(s: &str) -> String {
+            s.replace('\\', "\\\\").replace('"', "\\\"")
+        }
+
+        let mut dot = String::from("digraph render_graph {\n    rankdir=LR;\n");
+        let mut last_accesses = HashMap::<NodeIndex, (usize, AccessType)>::new();
+        let mut nodes = BTreeMap::new();
+
+        for (pass_idx, pass) in self.graph.passes.iter().enumerate() {
+            writeln!(
+                dot,
+                "    pass{pass_idx} [shape=box, label=\"{}\\n{:?}, {} execution(s)\"];",
+                escape(&pass.name),
+                pass.queue_class,
+                pass.execs.len(),
+            )
+            .unwrap();
+
+            if let Some(next_pass) = self.graph.passes.get(pass_idx + 1) {
+                if Self::allow_merge_passes(pass, next_pass) {
+                    writeln!(
+                        dot,
+                        "    pass{pass_idx} -> pass{} [style=dashed, label=\"merge\"];",
+                        pass_idx + 1
+                    )
+                    .unwrap();
+                }
+            }
+
+            for exec in &pass.execs {
+                let mut accesses = exec.accesses.iter().collect::<Vec<_>>();
+                accesses.sort_unstable_by_key(|(node_idx, _)| **node_idx);
+
+                for (&node_idx, [early, late]) in accesses {
+                    let binding = &self.graph.bindings[node_idx];
+                    let node_name = if binding.as_driver_image().is_some() {
+                        "image"
+                    } else if binding.as_driver_buffer().is_some() {
+                        "buffer"
+                    } else {
+                        "acceleration structure"
+                    };
+
+                    match last_accesses.insert(node_idx, (pass_idx, late.access)) {
+                        Some((prev_pass_idx, _)) if prev_pass_idx == pass_idx => {}
+                        Some((prev_pass_idx, prev_access)) => {
+                            let style =
+                                if is_write_access(prev_access) || is_write_access(early.access) {
+                                    ", style=bold"
+                                } else {
+                                    ""
+                                };
+
+                            writeln!(
+                                dot,
+                                "    pass{prev_pass_idx} -> pass{pass_idx} [label=\"{node_name} \
+                                {node_idx}\\n{prev_access:?} -> {:?}\"{style}];",
+                                early.access
+                            )
+                            .unwrap();
+                        }
+                        None => {
+                            nodes.insert(node_idx, node_name);
+
+                            writeln!(
+                                dot,
+                                "    node{node_idx} -> pass{pass_idx} [label=\"{:?}\"];",
+                                early.access
+                            )
+                            .unwrap();
+                        }
+                    }
+                }
+            }
+        }
+
+        for (node_idx, node_name) in nodes {
+            writeln!(
+                dot,
+                "    node{node_idx} [shape=ellipse, label=\"{node_name} {node_idx}\"];"
+            )
+            .unwrap();
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Records GPU timestamps at the start and end of each pass submitted by this resolver.
+    ///
+    /// Any previously written timestamps are reset, so the previous submission which used
+    /// `timestamps` must have finished executing. Once the command buffer returned by
+    /// [`Resolver::submit`] has executed, use [`PassTimestamps::durations`] to read the results.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::graph::{PassTimestamps, RenderGraph};
+    /// # use screen_13::pool::lazy::LazyPool;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::default())?);
+    /// # let mut pool = LazyPool::new(&device);
+    /// # let render_graph = RenderGraph::new();
+    /// let timestamps = Arc::new(PassTimestamps::create(&device, 64)?);
+    /// let (cmd_buf, _) = render_graph
+    ///     .resolve()
+    ///     .write_timestamps(&timestamps)
+    ///     .submit(&mut pool, 0, 0)?;
+    ///
+    /// cmd_buf.wait_until_executed()?;
+    ///
+    /// for (name, duration) in timestamps.durations()? {
+    ///     println!("{name}: {duration:?}");
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn write_timestamps(mut self, timestamps: &Arc<PassTimestamps>) -> Self {
+        timestamps.reset();
+        self.timestamps = Some(Arc::clone(timestamps));
+
+        self
+    }
+
+    /// Makes the submission of this graph wait on the device until the work represented by
+    /// `token` has executed.
+    ///
+    /// Unlike [`CommandBuffer::wait_until_executed`] this does not block the current thread, so it
+    /// may be used to pipeline multiple graphs, such as an upload graph and a render graph,
+    /// without a round-trip through the host.
+    ///
+    /// _NOTE:_ Only [`Resolver::submit`] waits on the given tokens.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::graph::RenderGraph;
+    /// # use screen_13::pool::lazy::LazyPool;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::default())?);
+    /// # let mut pool = LazyPool::new(&device);
+    /// # let upload_graph = RenderGraph::new();
+    /// # let render_graph = RenderGraph::new();
+    /// let (_, upload) = upload_graph.resolve().submit(&mut pool, 0, 0)?;
+    ///
+    /// render_graph
+    ///     .resolve()
+    ///     .wait_on(upload)
+    ///     .submit(&mut pool, 0, 0)?;
+    /// # Ok(()) }
+    /// ```
+    pub fn wait_on(mut self, token: SubmitToken) -> Self {
+        self.waits.push(token);
+
+        self
     }
 
     pub(crate) fn unbind_node<N>(&mut self, node: N) -> <N as Edge<Self>>::Result
@@ -2768,11 +3597,21 @@ impl Resolver {
             })
             .filter(|(.., pipeline)| !pipeline.descriptor_info().layouts.is_empty())
         {
-            let descriptor_sets = &physical_pass.exec_descriptor_sets[&exec_idx];
+            // Pipelines which only use a push descriptor set have no allocated sets
+            let Some(descriptor_sets) = physical_pass.exec_descriptor_sets.get(&exec_idx) else {
+                continue;
+            };
+            let push_descriptor_set = pipeline.descriptor_info().push_descriptor_set;
 
             // Write the manually bound things (access, read, and write functions)
             for (descriptor, (node_idx, view_info)) in exec.bindings.iter() {
                 let (descriptor_set_idx, dst_binding, binding_offset) = descriptor.into_tuple();
+
+                // Push descriptors are written by the recorder
+                if push_descriptor_set == Some(descriptor_set_idx) {
+                    continue;
+                }
+
                 let (descriptor_info, _) = pipeline
                         .descriptor_bindings()
                         .get(&Descriptor { set: descriptor_set_idx, binding: dst_binding })
@@ -2788,31 +3627,16 @@ impl Resolver {
                         image_view_info.aspect_mask = format_aspect_mask(image.info.fmt);
                     }
 
-                    let image_view = Image::view(image, image_view_info)?;
-                    let image_layout = match descriptor_type {
-                        vk::DescriptorType::COMBINED_IMAGE_SAMPLER
-                        | vk::DescriptorType::SAMPLED_IMAGE => {
-                            if image_view_info.aspect_mask.contains(
-                                vk::ImageAspectFlags::DEPTH | vk::ImageAspectFlags::STENCIL,
-                            ) {
-                                vk::ImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL
-                            } else if image_view_info
-                                .aspect_mask
-                                .contains(vk::ImageAspectFlags::DEPTH)
-                            {
-                                vk::ImageLayout::DEPTH_READ_ONLY_OPTIMAL
-                            } else if image_view_info
-                                .aspect_mask
-                                .contains(vk::ImageAspectFlags::STENCIL)
-                            {
-                                vk::ImageLayout::STENCIL_READ_ONLY_OPTIMAL
-                            } else {
-                                vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL
-                            }
-                        }
-                        vk::DescriptorType::STORAGE_IMAGE => vk::ImageLayout::GENERAL,
-                        _ => unimplemented!("{descriptor_type:?}"),
+                    let Some(image_layout) =
+                        image_descriptor_layout(descriptor_type, image_view_info.aspect_mask)
+                    else {
+                        warn!(
+                            "unsupported image descriptor {descriptor:?} type {descriptor_type:?}"
+                        );
+
+                        return Err(DriverError::InvalidData);
                     };
+                    let image_view = Image::view(image, image_view_info)?;
 
                     if binding_offset == 0 {
                         tls.image_writes.push(IndexWrite {
@@ -2888,73 +3712,82 @@ impl Resolver {
 
             if let ExecutionPipeline::Graphic(pipeline) = pipeline {
                 // Write graphic render pass input attachments (they're automatic)
-                if exec_idx > 0 {
-                    for (
-                        &Descriptor {
-                            set: descriptor_set_idx,
-                            binding: dst_binding,
-                        },
-                        (descriptor_info, _),
-                    ) in &pipeline.descriptor_bindings
-                    {
-                        if let DescriptorInfo::InputAttachment(_, attachment_idx) = *descriptor_info
-                        {
-                            let is_random_access = exec.color_stores.contains_key(&attachment_idx)
-                                || exec.color_resolves.contains_key(&attachment_idx);
-                            let (attachment, write_exec) = pass.execs[0..exec_idx]
-                                .iter()
-                                .rev()
-                                .find_map(|exec| {
-                                    exec.color_stores
-                                        .get(&attachment_idx)
-                                        .copied()
-                                        .map(|attachment| (attachment, exec))
-                                        .or_else(|| {
-                                            exec.color_resolves.get(&attachment_idx).map(
-                                                |(resolved_attachment, _)| {
-                                                    (*resolved_attachment, exec)
-                                                },
-                                            )
-                                        })
-                                })
-                                .expect("input attachment not written");
-                            let [_, late] = &write_exec.accesses[&attachment.target];
-                            let image_subresource =
-                                late.subresource.as_ref().unwrap().unwrap_image();
-                            let image_binding = &bindings[attachment.target];
-                            let image = image_binding.as_driver_image().unwrap();
-                            let image_view_info = ImageViewInfo {
-                                array_layer_count: image_subresource.array_layer_count,
-                                aspect_mask: attachment.aspect_mask,
-                                base_array_layer: image_subresource.base_array_layer,
-                                base_mip_level: image_subresource.base_mip_level,
-                                fmt: attachment.format,
-                                mip_level_count: image_subresource.mip_level_count,
-                                ty: image.info.ty,
-                            };
-                            let image_view = Image::view(image, image_view_info)?;
-
-                            tls.image_writes.push(IndexWrite {
-                                idx: tls.image_infos.len(),
-                                write: vk::WriteDescriptorSet {
-                                    dst_set: *descriptor_sets[descriptor_set_idx as usize],
-                                    dst_binding,
-                                    descriptor_type: vk::DescriptorType::INPUT_ATTACHMENT,
-                                    descriptor_count: 1,
-                                    ..Default::default()
-                                },
-                            });
+                for (
+                    &Descriptor {
+                        set: descriptor_set_idx,
+                        binding: dst_binding,
+                    },
+                    (descriptor_info, _),
+                ) in &pipeline.descriptor_bindings
+                {
+                    if push_descriptor_set == Some(descriptor_set_idx) {
+                        continue;
+                    }
 
-                            tls.image_infos.push(vk::DescriptorImageInfo {
-                                image_layout: Self::attachment_layout(
-                                    attachment.aspect_mask,
-                                    is_random_access,
-                                    true,
-                                ),
-                                image_view,
-                                sampler: vk::Sampler::null(),
-                            });
-                        }
+                    if let DescriptorInfo::InputAttachment(_, attachment_idx) = *descriptor_info {
+                        let is_random_access = exec.color_stores.contains_key(&attachment_idx)
+                            || exec.color_resolves.contains_key(&attachment_idx);
+                        let (attachment, write_exec) = pass.execs[0..exec_idx]
+                            .iter()
+                            .rev()
+                            .find_map(|exec| {
+                                exec.color_stores
+                                    .get(&attachment_idx)
+                                    .copied()
+                                    .map(|attachment| (attachment, exec))
+                                    .or_else(|| {
+                                        exec.color_resolves.get(&attachment_idx).map(
+                                            |(resolved_attachment, _)| (*resolved_attachment, exec),
+                                        )
+                                    })
+                            })
+                            .or_else(|| {
+                                // The first subpass which uses the attachment reads what was
+                                // loaded (or attached) before the render pass
+                                exec.color_attachments
+                                    .get(&attachment_idx)
+                                    .or_else(|| exec.color_loads.get(&attachment_idx))
+                                    .or_else(|| exec.color_stores.get(&attachment_idx))
+                                    .copied()
+                                    .map(|attachment| (attachment, exec))
+                            })
+                            .expect("input attachment not attached, loaded, or written");
+                        let [_, late] = &write_exec.accesses[&attachment.target];
+                        let image_subresource = late.subresource.as_ref().unwrap().unwrap_image();
+                        let image_binding = &bindings[attachment.target];
+                        let image = image_binding.as_driver_image().unwrap();
+                        let image_view_info = ImageViewInfo {
+                            array_layer_count: image_subresource.array_layer_count,
+                            aspect_mask: attachment.aspect_mask,
+                            base_array_layer: image_subresource.base_array_layer,
+                            base_mip_level: image_subresource.base_mip_level,
+                            fmt: attachment.format,
+                            mip_level_count: image_subresource.mip_level_count,
+                            ty: image.info.ty,
+                            ycbcr_conversion: None,
+                        };
+                        let image_view = Image::view(image, image_view_info)?;
+
+                        tls.image_writes.push(IndexWrite {
+                            idx: tls.image_infos.len(),
+                            write: vk::WriteDescriptorSet {
+                                dst_set: *descriptor_sets[descriptor_set_idx as usize],
+                                dst_binding,
+                                descriptor_type: vk::DescriptorType::INPUT_ATTACHMENT,
+                                descriptor_count: 1,
+                                ..Default::default()
+                            },
+                        });
+
+                        tls.image_infos.push(vk::DescriptorImageInfo {
+                            image_layout: Self::attachment_layout(
+                                attachment.aspect_mask,
+                                is_random_access,
+                                true,
+                            ),
+                            image_view,
+                            sampler: vk::Sampler::null(),
+                        });
                     }
                 }
             }
@@ -3001,8 +3834,655 @@ impl Resolver {
     }
 }
 
+/// A pipeline barrier recorded before a pass, or before an execution of a pass.
+///
+/// See [`PassInfo`].
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct PassBarrier {
+    /// The access of the node by the pass.
+    pub next_access: AccessType,
+
+    /// The index of the node being synchronized.
+    ///
+    /// Matches the index used by [`Resolver::to_dot`].
+    pub node_idx: usize,
+
+    /// The previous access of the node.
+    pub prev_access: AccessType,
+}
+
+/// Information about a pass provided to the observer of a render graph as the pass is recorded.
+///
+/// See [`RenderGraph::set_pass_observer`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct PassInfo {
+    /// The pipeline barriers recorded for the pass, in the order they were recorded.
+    ///
+    /// _NOTE:_ Accesses of attachments between subpasses are synchronized using subpass
+    /// dependencies and are not included.
+    pub barriers: Vec<PassBarrier>,
+
+    /// `true` if two or more passes were merged into the subpasses of this pass.
+    pub merged: bool,
+
+    /// The name of the pass.
+    ///
+    /// Merged passes have names joined using `" + "`.
+    pub name: String,
+
+    /// The indexes of the render graph passes recorded by this pass, in subpass order.
+    ///
+    /// Passes are indexed in the order they were begun using [`RenderGraph::begin_pass`]. More than
+    /// one index means the passes were merged into the subpasses of a single render pass, which is
+    /// useful for asserting that passes which should share on-tile attachments are merged.
+    pub pass_indices: Vec<usize>,
+
+    /// The sorted and unique indexes of the nodes accessed by the pass.
+    pub resources: Vec<usize>,
+}
+
+/// GPU timestamps recorded at the start and end of each pass submitted by a [`Resolver`].
+///
+/// See [`Resolver::write_timestamps`].
+#[derive(Debug)]
+pub struct PassTimestamps {
+    host_query_reset: bool,
+
+    /// The name of each recorded pass and the mask of valid timestamp bits of its queue.
+    passes: Mutex<Vec<(String, u64)>>,
+
+    query_pool: QueryPool,
+    timestamp_period: f32,
+}
+
+impl PassTimestamps {
+    /// Creates storage for the timestamps of up to `max_pass_count` passes.
+    ///
+    /// _NOTE:_ Merged passes are recorded as one pass.
+    pub fn create(device: &Arc<Device>, max_pass_count: u32) -> Result<Self, DriverError> {
+        let query_pool = QueryPool::create(
+            device,
+            QueryPoolInfo::new(vk::QueryType::TIMESTAMP, max_pass_count * 2),
+        )?;
+        let timestamp_period = device
+            .physical_device
+            .properties_v1_0
+            .limits
+            .timestamp_period;
+
+        Ok(Self {
+            host_query_reset: Device::supports(device, DeviceFeature::HostQueryReset),
+            passes: Mutex::new(vec![]),
+            query_pool,
+            timestamp_period,
+        })
+    }
+
+    /// Returns the name and GPU duration of each recorded pass, in the order they were recorded.
+    ///
+    /// Durations are `None` if the pass has not finished executing.
+    #[profiling::function]
+    pub fn durations(&self) -> Result<Vec<(String, Option<Duration>)>, DriverError> {
+        let passes = self.passes.lock();
+
+        #[cfg(not(feature = "parking_lot"))]
+        let passes = passes.unwrap();
+
+        let mut results = vec![None; passes.len() * 2];
+        QueryPool::try_results(&self.query_pool, 0, &mut results)?;
+
+        Ok(passes
+            .iter()
+            .zip(results.chunks_exact(2))
+            .map(|((pass_name, mask), results)| {
+                let duration = results[0].zip(results[1]).map(|(begin, end)| {
+                    // Bits outside of the valid bits are undefined, and the counter may wrap
+                    let ticks = (end & mask).wrapping_sub(begin & mask) & mask;
+
+                    Duration::from_nanos((ticks as f64 * self.timestamp_period as f64) as u64)
+                });
+
+                (pass_name.clone(), duration)
+            })
+            .collect())
+    }
+
+    fn reset(&self) {
+        #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+        let mut passes = self.passes.lock();
+
+        #[cfg(not(feature = "parking_lot"))]
+        let mut passes = passes.unwrap();
+
+        passes.clear();
+
+        // Without host query reset each query is reset by the command buffer which writes it
+        if self.host_query_reset {
+            QueryPool::reset(&self.query_pool, 0, self.query_pool.info.query_count).unwrap();
+        }
+    }
+
+    fn write_begin(&self, cmd_buf: &CommandBuffer, pass_name: &str) -> Option<u32> {
+        #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+        let mut passes = self.passes.lock();
+
+        #[cfg(not(feature = "parking_lot"))]
+        let mut passes = passes.unwrap();
+
+        let query = passes.len() as u32 * 2;
+        if query + 2 > self.query_pool.info.query_count {
+            warn!("pass timestamp capacity exceeded: {pass_name}");
+
+            return None;
+        }
+
+        let timestamp_valid_bits = cmd_buf.device.physical_device.queue_families
+            [cmd_buf.info.queue_family_index as usize]
+            .timestamp_valid_bits;
+        if timestamp_valid_bits == 0 {
+            warn!("timestamps not supported by queue family: {pass_name}");
+
+            return None;
+        }
+
+        let mask = u64::MAX >> (64 - timestamp_valid_bits);
+        passes.push((pass_name.to_owned(), mask));
+
+        unsafe {
+            if !self.host_query_reset {
+                cmd_buf
+                    .device
+                    .cmd_reset_query_pool(**cmd_buf, *self.query_pool, query, 2);
+            }
+
+            cmd_buf.device.cmd_write_timestamp(
+                **cmd_buf,
+                vk::PipelineStageFlags::TOP_OF_PIPE,
+                *self.query_pool,
+                query,
+            );
+        }
+
+        Some(query)
+    }
+
+    fn write_end(&self, cmd_buf: &CommandBuffer, query: u32) {
+        unsafe {
+            cmd_buf.device.cmd_write_timestamp(
+                **cmd_buf,
+                vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                *self.query_pool,
+                query + 1,
+            );
+        }
+    }
+}
+
 #[derive(Default)]
 struct Schedule {
     access_cache: AccessCache,
     passes: Vec<usize>,
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            driver::{
+                buffer::BufferInfo,
+                device::test_device,
+                graphic::{GraphicPipeline, GraphicPipelineInfo},
+                image::{Image, ImageInfo},
+                shader::Shader,
+            },
+            pool::lazy::LazyPool,
+        },
+    };
+
+    /// A pool which records the information of each leased render pass.
+    struct RenderPassRecorder {
+        pool: LazyPool,
+        render_passes: Vec<RenderPassInfo>,
+    }
+
+    impl Pool<CommandBufferInfo, CommandBuffer> for RenderPassRecorder {
+        fn lease(&mut self, info: CommandBufferInfo) -> Result<Lease<CommandBuffer>, DriverError> {
+            self.pool.lease(info)
+        }
+    }
+
+    impl Pool<DescriptorPoolInfo, DescriptorPool> for RenderPassRecorder {
+        fn lease(
+            &mut self,
+            info: DescriptorPoolInfo,
+        ) -> Result<Lease<DescriptorPool>, DriverError> {
+            self.pool.lease(info)
+        }
+    }
+
+    impl Pool<RenderPassInfo, RenderPass> for RenderPassRecorder {
+        fn lease(&mut self, info: RenderPassInfo) -> Result<Lease<RenderPass>, DriverError> {
+            self.render_passes.push(info.clone());
+            self.pool.lease(info)
+        }
+    }
+
+    fn async_compute_device() -> Option<Arc<Device>> {
+        test_device()
+            .filter(|device| Resolver::async_compute_queue_family_index(device, 0).is_some())
+    }
+
+    #[test]
+    pub fn async_compute_buffer_ownership() {
+        let Some(device) = async_compute_device() else {
+            return;
+        };
+
+        let buf = Buffer::create(
+            &device,
+            BufferInfo::device_mem(
+                16,
+                vk::BufferUsageFlags::TRANSFER_DST | vk::BufferUsageFlags::TRANSFER_SRC,
+            ),
+        )
+        .unwrap();
+        let host_buf = Arc::new(
+            Buffer::create(
+                &device,
+                BufferInfo::host_mem(16, vk::BufferUsageFlags::TRANSFER_DST),
+            )
+            .unwrap(),
+        );
+
+        let mut graph = RenderGraph::new();
+        let buf = graph.bind_node(buf);
+        let host_buf_node = graph.bind_node(Arc::clone(&host_buf));
+
+        graph
+            .begin_pass("fill on compute queue")
+            .submit_on(QueueClass::AsyncCompute)
+            .access_node(buf, AccessType::TransferWrite)
+            .record_cmd_buf(move |device, cmd_buf, bindings| unsafe {
+                device.cmd_fill_buffer(cmd_buf, *bindings[buf], 0, vk::WHOLE_SIZE, 0x01020304);
+            })
+            .submit_pass()
+            .copy_buffer(buf, host_buf_node);
+
+        let mut pool = LazyPool::new(&device);
+        let (cmd_buf, _) = graph.resolve().submit(&mut pool, 0, 0).unwrap();
+        cmd_buf.wait_until_executed().unwrap();
+
+        assert_eq!(
+            Buffer::mapped_slice(&host_buf),
+            0x01020304u32.to_ne_bytes().repeat(4)
+        );
+    }
+
+    #[test]
+    pub fn async_compute_image_ownership() {
+        let Some(device) = async_compute_device() else {
+            return;
+        };
+
+        let image = Image::create(
+            &device,
+            ImageInfo::image_2d(
+                1,
+                1,
+                vk::Format::R8G8B8A8_UNORM,
+                vk::ImageUsageFlags::TRANSFER_DST | vk::ImageUsageFlags::TRANSFER_SRC,
+            ),
+        )
+        .unwrap();
+        let host_buf = Arc::new(
+            Buffer::create(
+                &device,
+                BufferInfo::host_mem(4, vk::BufferUsageFlags::TRANSFER_DST),
+            )
+            .unwrap(),
+        );
+
+        let mut graph = RenderGraph::new();
+        let image = graph.bind_node(image);
+        let host_buf_node = graph.bind_node(Arc::clone(&host_buf));
+
+        graph
+            .begin_pass("clear on compute queue")
+            .submit_on(QueueClass::AsyncCompute)
+            .access_node(image, AccessType::TransferWrite)
+            .record_cmd_buf(move |device, cmd_buf, bindings| unsafe {
+                device.cmd_clear_color_image(
+                    cmd_buf,
+                    *bindings[image],
+                    vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                    &vk::ClearColorValue {
+                        float32: [1.0, 0.0, 0.0, 1.0],
+                    },
+                    &[vk::ImageSubresourceRange {
+                        aspect_mask: vk::ImageAspectFlags::COLOR,
+                        base_mip_level: 0,
+                        level_count: 1,
+                        base_array_layer: 0,
+                        layer_count: 1,
+                    }],
+                );
+            })
+            .submit_pass()
+            .copy_image_to_buffer(image, host_buf_node);
+
+        let mut pool = LazyPool::new(&device);
+        let (cmd_buf, _) = graph.resolve().submit(&mut pool, 0, 0).unwrap();
+        cmd_buf.wait_until_executed().unwrap();
+
+        assert_eq!(Buffer::mapped_slice(&host_buf), [0xff, 0x00, 0x00, 0xff]);
+    }
+
+    #[test]
+    pub fn pass_timestamps() {
+        let Some(device) = test_device() else {
+            return;
+        };
+
+        let buf = Buffer::create(
+            &device,
+            BufferInfo::device_mem(16, vk::BufferUsageFlags::TRANSFER_DST),
+        )
+        .unwrap();
+        let timestamps = Arc::new(PassTimestamps::create(&device, 2).unwrap());
+
+        let mut graph = RenderGraph::new();
+        let buf = graph.bind_node(buf);
+        graph.fill_buffer(buf, 0);
+        graph.fill_buffer(buf, 1);
+
+        let mut pool = LazyPool::new(&device);
+        let (cmd_buf, _) = graph
+            .resolve()
+            .write_timestamps(&timestamps)
+            .submit(&mut pool, 0, 0)
+            .unwrap();
+        cmd_buf.wait_until_executed().unwrap();
+
+        let durations = timestamps.durations().unwrap();
+
+        assert_eq!(durations.len(), 2);
+        assert!(durations.iter().all(|(_, duration)| duration.is_some()));
+    }
+
+    /// Resolves a pass which clears a depth/stencil image of the given format and returns the
+    /// attachment of the render pass.
+    fn clear_depth_stencil_attachment(device: &Arc<Device>, fmt: vk::Format) -> AttachmentInfo {
+        let pipeline = Arc::new(
+            GraphicPipeline::create(
+                device,
+                GraphicPipelineInfo::default(),
+                [
+                    Shader::new_vertex(inline_spirv::inline_spirv!(
+                        r#"
+                        #version 450
+
+                        void main() {
+                            gl_Position = vec4(0.0);
+                        }
+                        "#,
+                        vert
+                    )),
+                    Shader::new_fragment(inline_spirv::inline_spirv!(
+                        r#"
+                        #version 450
+
+                        void main() {}
+                        "#,
+                        frag
+                    )),
+                ],
+            )
+            .unwrap(),
+        );
+        let image = Image::create(
+            device,
+            ImageInfo::image_2d(1, 1, fmt, vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT),
+        )
+        .unwrap();
+
+        let mut graph = RenderGraph::new();
+        let image = graph.bind_node(image);
+
+        graph
+            .begin_pass("clear")
+            .bind_pipeline(&pipeline)
+            .clear_depth_stencil(image)
+            .store_depth_stencil(image)
+            .record_subpass(|_, _| {});
+
+        let mut pool = RenderPassRecorder {
+            pool: LazyPool::new(device),
+            render_passes: vec![],
+        };
+        let (cmd_buf, _) = graph.resolve().submit(&mut pool, 0, 0).unwrap();
+        cmd_buf.wait_until_executed().unwrap();
+
+        assert_eq!(pool.render_passes.len(), 1);
+        assert_eq!(pool.render_passes[0].attachments.len(), 1);
+
+        pool.render_passes[0].attachments[0]
+    }
+
+    #[test]
+    pub fn clear_depth_attachment() {
+        let Some(device) = test_device() else {
+            return;
+        };
+
+        let attachment = clear_depth_stencil_attachment(&device, vk::Format::D32_SFLOAT);
+
+        assert_eq!(attachment.load_op, vk::AttachmentLoadOp::CLEAR);
+        assert_eq!(attachment.stencil_load_op, vk::AttachmentLoadOp::DONT_CARE);
+    }
+
+    #[test]
+    pub fn clear_depth_stencil_attachment_load_ops() {
+        let Some(device) = test_device() else {
+            return;
+        };
+
+        let Some(fmt) = [
+            vk::Format::D24_UNORM_S8_UINT,
+            vk::Format::D32_SFLOAT_S8_UINT,
+        ]
+        .into_iter()
+        .find(|&fmt| {
+            Device::format_properties(&device, fmt)
+                .optimal_tiling_features
+                .contains(vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT)
+        }) else {
+            return;
+        };
+
+        let attachment = clear_depth_stencil_attachment(&device, fmt);
+
+        assert_eq!(attachment.load_op, vk::AttachmentLoadOp::CLEAR);
+        assert_eq!(attachment.stencil_load_op, vk::AttachmentLoadOp::CLEAR);
+    }
+
+    #[test]
+    pub fn first_subpass_input_attachment() {
+        let Some(device) = test_device() else {
+            return;
+        };
+
+        let pipeline = Arc::new(
+            GraphicPipeline::create(
+                &device,
+                GraphicPipelineInfo::default(),
+                [
+                    Shader::new_vertex(inline_spirv::inline_spirv!(
+                        r#"
+                        #version 450
+
+                        void main() {
+                            vec2 position = vec2(gl_VertexIndex & 1, gl_VertexIndex >> 1);
+                            gl_Position = vec4(position * 4.0 - 1.0, 0.0, 1.0);
+                        }
+                        "#,
+                        vert
+                    )),
+                    Shader::new_fragment(inline_spirv::inline_spirv!(
+                        r#"
+                        #version 450
+
+                        layout(input_attachment_index = 0, set = 0, binding = 0)
+                            uniform subpassInput color_in;
+
+                        layout(location = 0) out vec4 color_out_0;
+                        layout(location = 1) out vec4 color_out_1;
+
+                        void main() {
+                            color_out_0 = subpassLoad(color_in);
+                            color_out_1 = color_out_0;
+                        }
+                        "#,
+                        frag
+                    )),
+                ],
+            )
+            .unwrap(),
+        );
+        let input_image = Image::create(
+            &device,
+            ImageInfo::image_2d(
+                1,
+                1,
+                vk::Format::R8G8B8A8_UNORM,
+                vk::ImageUsageFlags::COLOR_ATTACHMENT
+                    | vk::ImageUsageFlags::INPUT_ATTACHMENT
+                    | vk::ImageUsageFlags::TRANSFER_DST,
+            ),
+        )
+        .unwrap();
+        let output_image = Image::create(
+            &device,
+            ImageInfo::image_2d(
+                1,
+                1,
+                vk::Format::R8G8B8A8_UNORM,
+                vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::TRANSFER_SRC,
+            ),
+        )
+        .unwrap();
+        let host_buf = Arc::new(
+            Buffer::create(
+                &device,
+                BufferInfo::host_mem(4, vk::BufferUsageFlags::TRANSFER_DST),
+            )
+            .unwrap(),
+        );
+
+        let mut graph = RenderGraph::new();
+        let input_image = graph.bind_node(input_image);
+        let output_image = graph.bind_node(output_image);
+        let host_buf_node = graph.bind_node(Arc::clone(&host_buf));
+
+        graph.clear_color_image_value(input_image, [0xffu8, 0x00, 0x00, 0xff]);
+
+        // The only subpass of this pass reads what was loaded before the render pass
+        graph
+            .begin_pass("read input")
+            .bind_pipeline(&pipeline)
+            .read_input_attachment(0, input_image)
+            .store_color(0, input_image)
+            .clear_color(1, output_image)
+            .store_color(1, output_image)
+            .record_subpass(|subpass, _| {
+                subpass.draw(3, 1, 0, 0);
+            });
+
+        graph.copy_image_to_buffer(output_image, host_buf_node);
+
+        let mut pool = LazyPool::new(&device);
+        let (cmd_buf, _) = graph.resolve().submit(&mut pool, 0, 0).unwrap();
+        cmd_buf.wait_until_executed().unwrap();
+
+        assert_eq!(Buffer::mapped_slice(&host_buf), [0xff, 0x00, 0x00, 0xff]);
+    }
+
+    /// Returns the order of the given scheduled passes after reordering.
+    fn reorder_passes(graph: &RenderGraph, passes: &[usize]) -> Vec<usize> {
+        let mut schedule = Schedule::default();
+        schedule.access_cache.update(graph, graph.passes.len());
+        schedule.passes.extend_from_slice(passes);
+
+        Resolver::reorder_scheduled_passes(&graph.passes, &mut schedule, graph.passes.len());
+
+        schedule.passes
+    }
+
+    #[test]
+    pub fn reorder_explicit_dependency() {
+        let mut graph = RenderGraph::new();
+        let a = graph.begin_pass("a").index();
+        let b = graph.begin_pass("b").index();
+        let c = graph.begin_pass("c").index();
+        graph.add_dependency(a, c);
+
+        assert_eq!(reorder_passes(&graph, &[c, a, b]), [a, c, b]);
+    }
+
+    #[test]
+    pub fn reorder_pinned() {
+        let mut graph = RenderGraph::new();
+        let a = graph.begin_pass("a").index();
+        let b = graph.begin_pass("b").index();
+        let c = graph.begin_pass("c").pin_order().index();
+        let d = graph.begin_pass("d").index();
+        graph.add_dependency(a, b);
+
+        // Only the passes before the pinned pass are reordered
+        assert_eq!(reorder_passes(&graph, &[b, a, c, d]), [a, b, c, d]);
+        assert_eq!(reorder_passes(&graph, &[a, b, c, d]), [a, b, c, d]);
+    }
+
+    #[test]
+    pub fn schedule_explicit_dependency() {
+        let Some(device) = test_device() else {
+            return;
+        };
+
+        // Schedules the passes required to resolve the second of two unrelated passes
+        let schedule_passes = |explicit_dependency: bool| {
+            let info = BufferInfo::device_mem(16, vk::BufferUsageFlags::TRANSFER_DST);
+            let mut graph = RenderGraph::new();
+            let buf_a = graph.bind_node(Buffer::create(&device, info).unwrap());
+            let buf_b = graph.bind_node(Buffer::create(&device, info).unwrap());
+            let pass_a = graph
+                .begin_pass("a")
+                .access_node(buf_a, AccessType::TransferWrite)
+                .record_cmd_buf(|_, _, _| {})
+                .index();
+            let pass_b = graph
+                .begin_pass("b")
+                .access_node(buf_b, AccessType::TransferWrite)
+                .record_cmd_buf(|_, _, _| {})
+                .index();
+
+            if explicit_dependency {
+                graph.add_dependency(pass_a, pass_b);
+            }
+
+            let resolver = graph.resolve();
+            let mut schedule = Schedule::default();
+            schedule.access_cache.update(&resolver.graph, 2);
+            resolver.schedule_node_passes(buf_b.index(), 2, &mut schedule);
+
+            schedule.passes
+        };
+
+        assert_eq!(schedule_passes(false), [1]);
+
+        // Resolving a pass also resolves its explicit dependencies
+        assert_eq!(schedule_passes(true), [0, 1]);
+    }
+}
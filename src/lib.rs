@@ -270,12 +270,31 @@ part of the name within GLSL or HLSL shader code. The `_sampler_123` suffix shou
     - `m` is `MIRRORED_REPEAT`
     - `r` is `REPEAT`
 
+The suffix may end with up to two optional characters. The border color used by `b` addressing
+(`FLOAT_TRANSPARENT_BLACK` by default) is selected by:
+
+- `k` is `FLOAT_OPAQUE_BLACK`
+- `t` is `FLOAT_TRANSPARENT_BLACK`
+- `w` is `FLOAT_OPAQUE_WHITE`
+
+Depth comparison, as used by shadow map samplers, is enabled by:
+
+- `l` is `LESS` and `L` is `LESS_OR_EQUAL`
+- `g` is `GREATER` and `G` is `GREATER_OR_EQUAL`
+
 For example, the following sampler named `pages_sampler_nnr` specifies nearest texel/mipmap modes and repeat addressing:
 
 ```glsl
 layout(set = 0, binding = 0) uniform sampler2D pages_sampler_nnr[NUM_PAGES];
 ```
 
+And the following sampler named `shadow_sampler_llbwL` specifies a linear `LESS_OR_EQUAL`
+comparison sampler which treats samples outside of the image as unshadowed (_opaque white_):
+
+```glsl
+layout(set = 0, binding = 1) uniform sampler2DShadow shadow_sampler_llbwL;
+```
+
 For more complex image sampling, use [`ShaderBuilder::image_sampler`] to specify the exact image
 sampling mode.
 
@@ -292,7 +311,8 @@ Add `_ibindX` for per-instance data, or the matching `_vbindX` for per-vertex da
 replaced with the vertex buffer binding index in each case.
 
 For more complex vertex layouts, use the [`ShaderBuilder::vertex_input`] to specify the exact
-layout.
+layout. Per-instance data which advances only once every few instances may be specified using
+[`ShaderBuilder::vertex_binding_divisor`].
 
 [`AccelerationStructureInfo`]: driver::accel_struct::AccelerationStructureInfo
 [`AccelerationStructure::create`]: driver::accel_struct::AccelerationStructure::create
@@ -314,6 +334,7 @@ layout.
 [`RayTracePipelineInfo`]: driver::ray_trace::RayTracePipelineInfo
 [`RenderGraph`]: graph::RenderGraph
 [`ShaderBuilder::image_sampler`]: driver::shader::ShaderBuilder::image_sampler
+[`ShaderBuilder::vertex_binding_divisor`]: driver::shader::ShaderBuilder::vertex_binding_divisor
 [`ShaderBuilder::vertex_input`]: driver::shader::ShaderBuilder::vertex_input
 
 */
@@ -325,11 +346,12 @@ pub mod graph;
 pub mod pool;
 
 mod display;
+mod uploader;
 
 /// Things which are used in almost every single _Screen 13_ program.
 pub mod prelude {
     pub use super::{
-        display::{Display, DisplayError, ResolverPool},
+        display::{Display, DisplayError, HeadlessDisplay, HeadlessRing, ResolverPool},
         driver::{
             accel_struct::{
                 AccelerationStructure, AccelerationStructureGeometry,
@@ -340,7 +362,10 @@ pub mod prelude {
             ash::vk,
             buffer::{Buffer, BufferInfo, BufferInfoBuilder, BufferSubresource},
             compute::{ComputePipeline, ComputePipelineInfo, ComputePipelineInfoBuilder},
-            device::{Device, DeviceInfo, DeviceInfoBuilder},
+            device::{
+                Device, DeviceFeature, DeviceInfo, DeviceInfoBuilder, MemoryBudget,
+                MemoryHeapBudget,
+            },
             graphic::{
                 BlendMode, BlendModeBuilder, DepthStencilMode, DepthStencilModeBuilder,
                 GraphicPipeline, GraphicPipelineInfo, GraphicPipelineInfoBuilder, StencilMode,
@@ -351,9 +376,9 @@ pub mod prelude {
             },
             physical_device::{
                 AccelerationStructureProperties, PhysicalDevice, RayQueryFeatures,
-                RayTraceFeatures, RayTraceProperties, Vulkan10Features, Vulkan10Limits,
-                Vulkan10Properties, Vulkan11Features, Vulkan11Properties, Vulkan12Features,
-                Vulkan12Properties,
+                RayTraceFeatures, RayTraceProperties, VertexAttributeDivisorFeatures,
+                Vulkan10Features, Vulkan10Limits, Vulkan10Properties, Vulkan11Features,
+                Vulkan11Properties, Vulkan12Features, Vulkan12Properties,
             },
             ray_trace::{
                 RayTracePipeline, RayTracePipelineInfo, RayTracePipelineInfoBuilder,
@@ -362,7 +387,8 @@ pub mod prelude {
             render_pass::ResolveMode,
             shader::{
                 SamplerInfo, SamplerInfoBuilder, Shader, ShaderBuilder, ShaderCode,
-                SpecializationInfo,
+                SpecializationBuilder, SpecializationConstant, SpecializationInfo, Vertex,
+                YcbcrConversionInfo, YcbcrConversionInfoBuilder,
             },
             surface::Surface,
             swapchain::{
@@ -386,7 +412,11 @@ pub mod prelude {
             lazy::LazyPool,
             Lease, Pool, PoolInfo, PoolInfoBuilder,
         },
+        uploader::Uploader,
     };
 }
 
-pub use self::display::{Display, DisplayError, ResolverPool};
+pub use self::{
+    display::{Display, DisplayError, HeadlessDisplay, HeadlessRing, ResolverPool},
+    uploader::Uploader,
+};
@@ -0,0 +1,158 @@
+//! Sub-allocating buffer arena.
+
+use {
+    crate::driver::{
+        buffer::{Buffer, BufferInfo},
+        device::Device,
+        DriverError,
+    },
+    ash::vk,
+    log::{trace, warn},
+    std::sync::Arc,
+};
+
+/// A mappable buffer which hands out small, aligned sub-ranges using a bump pointer.
+///
+/// Useful for packing per-object uniform or storage data for many draws into a single buffer and
+/// a single descriptor binding. Allocated offsets are aligned to the device
+/// `min_uniform_buffer_offset_alignment` and `min_storage_buffer_offset_alignment` limits (_and
+/// the alignment of the buffer info_) so that they may be used as dynamic descriptor offsets.
+///
+/// Each frame, allocate all data before binding [`BufferArena::buffer`] to a render graph, and then
+/// call [`BufferArena::reset`] at the start of the next frame. Buffers which are still in use by
+/// the GPU are never written to; the arena switches to (_or creates_) an unused buffer instead.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use ash::vk;
+/// # use screen_13::driver::DriverError;
+/// # use screen_13::driver::device::{Device, DeviceInfo};
+/// # use screen_13::driver::buffer::BufferInfo;
+/// # use screen_13::graph::RenderGraph;
+/// # use screen_13::pool::arena::BufferArena;
+/// # fn main() -> Result<(), DriverError> {
+/// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+/// # let mut my_graph = RenderGraph::new();
+/// let info = BufferInfo::host_mem(65_536, vk::BufferUsageFlags::UNIFORM_BUFFER);
+/// let mut arena = BufferArena::create(&device, info)?;
+///
+/// let (offset, data) = arena.alloc(64)?;
+/// data.copy_from_slice(&[0xff; 64]);
+///
+/// let buf_node = my_graph.bind_node(arena.buffer());
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct BufferArena {
+    alignment: vk::DeviceSize,
+    buffers: Vec<Arc<Buffer>>,
+    device: Arc<Device>,
+    info: BufferInfo,
+    offset: vk::DeviceSize,
+}
+
+impl BufferArena {
+    /// Creates a new buffer arena on the given device.
+    ///
+    /// `info` must describe a mappable buffer; its size is the capacity of the arena.
+    #[profiling::function]
+    pub fn create(device: &Arc<Device>, info: impl Into<BufferInfo>) -> Result<Self, DriverError> {
+        let info = info.into();
+
+        debug_assert!(info.mappable, "Buffer arena info must be mappable");
+
+        let limits = &device.physical_device.properties_v1_0.limits;
+        let alignment = info
+            .alignment
+            .max(limits.min_uniform_buffer_offset_alignment)
+            .max(limits.min_storage_buffer_offset_alignment);
+        let buffer = Arc::new(Buffer::create(device, info)?);
+
+        Ok(Self {
+            alignment,
+            buffers: vec![buffer],
+            device: Arc::clone(device),
+            info,
+            offset: 0,
+        })
+    }
+
+    /// The alignment of all allocated offsets.
+    pub fn alignment(&self) -> vk::DeviceSize {
+        self.alignment
+    }
+
+    /// Allocates `size` bytes and returns the offset of the allocation within
+    /// [`BufferArena::buffer`] along with the mapped memory of the allocation.
+    ///
+    /// Returns [`DriverError::OutOfMemory`] if the arena does not have enough remaining capacity,
+    /// or [`DriverError::InvalidData`] if the current buffer has already been bound to a render
+    /// graph.
+    #[profiling::function]
+    pub fn alloc(
+        &mut self,
+        size: vk::DeviceSize,
+    ) -> Result<(vk::DeviceSize, &mut [u8]), DriverError> {
+        let offset = self.offset.next_multiple_of(self.alignment);
+        let end = offset + size;
+
+        if end > self.info.size {
+            warn!("buffer arena capacity exceeded: {end} > {}", self.info.size);
+
+            return Err(DriverError::OutOfMemory);
+        }
+
+        let buffer = Arc::get_mut(&mut self.buffers[0]).ok_or_else(|| {
+            warn!("buffer arena in use: allocate before binding the buffer");
+
+            DriverError::InvalidData
+        })?;
+
+        self.offset = end;
+
+        Ok((
+            offset,
+            &mut Buffer::mapped_slice_mut(buffer)[offset as usize..end as usize],
+        ))
+    }
+
+    /// The buffer which currently backs allocations.
+    pub fn buffer(&self) -> &Arc<Buffer> {
+        &self.buffers[0]
+    }
+
+    /// Frees all allocations.
+    ///
+    /// If the current buffer is still in use (_by a render graph or pending command buffer_)
+    /// another unused buffer is selected, creating a new one if required.
+    #[profiling::function]
+    pub fn reset(&mut self) -> Result<(), DriverError> {
+        self.offset = 0;
+
+        if Arc::strong_count(&self.buffers[0]) == 1 {
+            return Ok(());
+        }
+
+        if let Some(idx) = self
+            .buffers
+            .iter()
+            .position(|buffer| Arc::strong_count(buffer) == 1)
+        {
+            self.buffers.swap(0, idx);
+        } else {
+            trace!("creating buffer arena buffer #{}", self.buffers.len());
+
+            let buffer = Arc::new(Buffer::create(&self.device, self.info)?);
+            self.buffers.push(buffer);
+
+            let idx = self.buffers.len() - 1;
+            self.buffers.swap(0, idx);
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 //! Pool which leases from a single bucket per resource type.
 
 use {
-    super::{lease_command_buffer, Cache, Lease, Pool, PoolInfo},
+    super::{drain_cache, lease_command_buffer, Cache, Lease, Pool, PoolInfo},
     crate::driver::{
         accel_struct::{AccelerationStructure, AccelerationStructureInfo},
         buffer::{Buffer, BufferInfo},
@@ -10,6 +10,7 @@ use {
         CommandBuffer, CommandBufferInfo, DescriptorPool, DescriptorPoolInfo, DriverError,
         RenderPass, RenderPassInfo,
     },
+    ash::vk,
     log::debug,
     std::{collections::HashMap, sync::Arc},
 };
@@ -75,26 +76,30 @@ impl FifoPool {
         }
     }
 
-    /// Clears the pool, removing all resources.
-    pub fn clear(&mut self) {
-        self.clear_accel_structs();
-        self.clear_buffers();
-        self.clear_images();
+    /// Clears the pool, removing all unused resources.
+    ///
+    /// Returns the number of bytes of device memory freed. Resources which are currently leased
+    /// are not affected and return to the pool as usual.
+    pub fn clear(&mut self) -> vk::DeviceSize {
+        self.clear_accel_structs() + self.clear_buffers() + self.clear_images()
     }
 
-    /// Clears the pool of acceleration structure resources.
-    pub fn clear_accel_structs(&mut self) {
-        self.accel_struct_cache = PoolInfo::explicit_cache(self.info.accel_struct_capacity);
+    /// Clears the pool of unused acceleration structure resources, returning the number of bytes
+    /// of device memory freed.
+    pub fn clear_accel_structs(&mut self) -> vk::DeviceSize {
+        drain_cache(&self.accel_struct_cache, AccelerationStructure::memory_size)
     }
 
-    /// Clears the pool of buffer resources.
-    pub fn clear_buffers(&mut self) {
-        self.buffer_cache = PoolInfo::explicit_cache(self.info.buffer_capacity);
+    /// Clears the pool of unused buffer resources, returning the number of bytes of device memory
+    /// freed.
+    pub fn clear_buffers(&mut self) -> vk::DeviceSize {
+        drain_cache(&self.buffer_cache, Buffer::memory_size)
     }
 
-    /// Clears the pool of image resources.
-    pub fn clear_images(&mut self) {
-        self.image_cache = PoolInfo::explicit_cache(self.info.image_capacity);
+    /// Clears the pool of unused image resources, returning the number of bytes of device memory
+    /// freed.
+    pub fn clear_images(&mut self) -> vk::DeviceSize {
+        drain_cache(&self.image_cache, Image::memory_size)
     }
 }
 
@@ -132,6 +137,10 @@ impl Pool<AccelerationStructureInfo, AccelerationStructure> for FifoPool {
 
         Ok(Lease::new(cache_ref, item))
     }
+
+    fn clear(&mut self) -> vk::DeviceSize {
+        self.clear_accel_structs()
+    }
 }
 
 impl Pool<BufferInfo, Buffer> for FifoPool {
@@ -170,6 +179,10 @@ impl Pool<BufferInfo, Buffer> for FifoPool {
 
         Ok(Lease::new(cache_ref, item))
     }
+
+    fn clear(&mut self) -> vk::DeviceSize {
+        self.clear_buffers()
+    }
 }
 
 impl Pool<CommandBufferInfo, CommandBuffer> for FifoPool {
@@ -233,6 +246,7 @@ impl Pool<DescriptorPoolInfo, DescriptorPool> for FifoPool {
                     && item.info.uniform_buffer_count >= info.uniform_buffer_count
                     && item.info.uniform_buffer_dynamic_count >= info.uniform_buffer_dynamic_count
                     && item.info.uniform_texel_buffer_count >= info.uniform_texel_buffer_count
+                    && item.info.update_after_bind == info.update_after_bind
                 {
                     let item = cache.swap_remove(idx);
 
@@ -292,6 +306,10 @@ impl Pool<ImageInfo, Image> for FifoPool {
 
         Ok(Lease::new(cache_ref, item))
     }
+
+    fn clear(&mut self) -> vk::DeviceSize {
+        self.clear_images()
+    }
 }
 
 impl Pool<RenderPassInfo, RenderPass> for FifoPool {
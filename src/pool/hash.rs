@@ -1,7 +1,7 @@
 //! Pool which leases by exactly matching the information before creating new resources.
 
 use {
-    super::{lease_command_buffer, Cache, Lease, Pool, PoolInfo},
+    super::{drain_cache, lease_command_buffer, Cache, Lease, Pool, PoolInfo},
     crate::driver::{
         accel_struct::{AccelerationStructure, AccelerationStructureInfo},
         buffer::{Buffer, BufferInfo},
@@ -10,6 +10,7 @@ use {
         CommandBuffer, CommandBufferInfo, DescriptorPool, DescriptorPoolInfo, DriverError,
         RenderPass, RenderPassInfo,
     },
+    ash::vk,
     log::debug,
     paste::paste,
     std::{collections::HashMap, sync::Arc},
@@ -71,21 +72,26 @@ impl HashPool {
         }
     }
 
-    /// Clears the pool, removing all resources.
-    pub fn clear(&mut self) {
-        self.clear_accel_structs();
-        self.clear_buffers();
-        self.clear_images();
+    /// Clears the pool, removing all unused resources.
+    ///
+    /// Returns the number of bytes of device memory freed. Resources which are currently leased
+    /// are not affected and are dropped when they are returned.
+    pub fn clear(&mut self) -> vk::DeviceSize {
+        self.clear_accel_structs() + self.clear_buffers() + self.clear_images()
     }
 }
 
 macro_rules! resource_mgmt_fns {
-    ($fn_plural:literal, $doc_singular:literal, $ty:ty, $field:ident) => {
+    ($fn_plural:literal, $doc_singular:literal, $ty:ty, $field:ident, $item:ident) => {
         paste! {
             impl HashPool {
-                #[doc = "Clears the pool of " $doc_singular " resources."]
-                pub fn [<clear_ $fn_plural>](&mut self) {
-                    self.$field.clear();
+                #[doc = "Clears the pool of unused " $doc_singular " resources, returning the number
+of bytes of device memory freed."]
+                pub fn [<clear_ $fn_plural>](&mut self) -> vk::DeviceSize {
+                    self.$field
+                        .drain()
+                        .map(|(_, cache)| drain_cache(&cache, $item::memory_size))
+                        .sum()
                 }
 
                 #[doc = "Clears the pool of all " $doc_singular " resources matching the given
@@ -121,10 +127,11 @@ resource_mgmt_fns!(
     "accel_structs",
     "acceleration structure",
     AccelerationStructureInfo,
-    acceleration_structure_cache
+    acceleration_structure_cache,
+    AccelerationStructure
 );
-resource_mgmt_fns!("buffers", "buffer", BufferInfo, buffer_cache);
-resource_mgmt_fns!("images", "image", ImageInfo, image_cache);
+resource_mgmt_fns!("buffers", "buffer", BufferInfo, buffer_cache, Buffer);
+resource_mgmt_fns!("images", "image", ImageInfo, image_cache, Image);
 
 impl Pool<CommandBufferInfo, CommandBuffer> for HashPool {
     #[profiling::function]
@@ -216,7 +223,7 @@ impl Pool<RenderPassInfo, RenderPass> for HashPool {
 
 // Enable leasing items using their basic info
 macro_rules! lease {
-    ($info:ident => $item:ident, $capacity:ident) => {
+    ($info:ident => $item:ident, $capacity:ident, $fn_plural:ident) => {
         paste::paste! {
             impl Pool<$info, $item> for HashPool {
                 #[profiling::function]
@@ -243,11 +250,15 @@ macro_rules! lease {
 
                     Ok(Lease::new(Arc::downgrade(cache_ref), item))
                 }
+
+                fn clear(&mut self) -> vk::DeviceSize {
+                    self.[<clear_ $fn_plural>]()
+                }
             }
         }
     };
 }
 
-lease!(AccelerationStructureInfo => AccelerationStructure, accel_struct_capacity);
-lease!(BufferInfo => Buffer, buffer_capacity);
-lease!(ImageInfo => Image, image_capacity);
+lease!(AccelerationStructureInfo => AccelerationStructure, accel_struct_capacity, accel_structs);
+lease!(BufferInfo => Buffer, buffer_capacity, buffers);
+lease!(ImageInfo => Image, image_capacity, images);
@@ -1,7 +1,7 @@
 //! Pool which leases by looking for compatibile information before creating new resources.
 
 use {
-    super::{lease_command_buffer, Cache, Lease, Pool, PoolInfo},
+    super::{drain_cache, lease_command_buffer, Cache, Lease, Pool, PoolInfo},
     crate::driver::{
         accel_struct::{AccelerationStructure, AccelerationStructureInfo},
         buffer::{Buffer, BufferInfo},
@@ -105,16 +105,21 @@ impl LazyPool {
         }
     }
 
-    /// Clears the pool, removing all resources.
-    pub fn clear(&mut self) {
-        self.clear_accel_structs();
-        self.clear_buffers();
-        self.clear_images();
+    /// Clears the pool, removing all unused resources.
+    ///
+    /// Returns the number of bytes of device memory freed. Resources which are currently leased
+    /// are not affected and are dropped when they are returned.
+    pub fn clear(&mut self) -> vk::DeviceSize {
+        self.clear_accel_structs() + self.clear_buffers() + self.clear_images()
     }
 
-    /// Clears the pool of acceleration structure resources.
-    pub fn clear_accel_structs(&mut self) {
-        self.accel_struct_cache.clear();
+    /// Clears the pool of unused acceleration structure resources, returning the number of bytes
+    /// of device memory freed.
+    pub fn clear_accel_structs(&mut self) -> vk::DeviceSize {
+        self.accel_struct_cache
+            .drain()
+            .map(|(_, cache)| drain_cache(&cache, AccelerationStructure::memory_size))
+            .sum()
     }
 
     /// Clears the pool of all acceleration structure resources matching the given type.
@@ -122,14 +127,22 @@ impl LazyPool {
         self.accel_struct_cache.remove(&ty);
     }
 
-    /// Clears the pool of buffer resources.
-    pub fn clear_buffers(&mut self) {
-        self.buffer_cache.clear();
+    /// Clears the pool of unused buffer resources, returning the number of bytes of device memory
+    /// freed.
+    pub fn clear_buffers(&mut self) -> vk::DeviceSize {
+        self.buffer_cache
+            .drain()
+            .map(|(_, cache)| drain_cache(&cache, Buffer::memory_size))
+            .sum()
     }
 
-    /// Clears the pool of image resources.
-    pub fn clear_images(&mut self) {
-        self.image_cache.clear();
+    /// Clears the pool of unused image resources, returning the number of bytes of device memory
+    /// freed.
+    pub fn clear_images(&mut self) -> vk::DeviceSize {
+        self.image_cache
+            .drain()
+            .map(|(_, cache)| drain_cache(&cache, Image::memory_size))
+            .sum()
     }
 
     /// Clears the pool of image resources matching the given information.
@@ -194,6 +207,10 @@ impl Pool<AccelerationStructureInfo, AccelerationStructure> for LazyPool {
 
         Ok(Lease::new(cache_ref, item))
     }
+
+    fn clear(&mut self) -> vk::DeviceSize {
+        self.clear_accel_structs()
+    }
 }
 
 impl Pool<BufferInfo, Buffer> for LazyPool {
@@ -231,6 +248,10 @@ impl Pool<BufferInfo, Buffer> for LazyPool {
 
         Ok(Lease::new(cache_ref, item))
     }
+
+    fn clear(&mut self) -> vk::DeviceSize {
+        self.clear_buffers()
+    }
 }
 
 impl Pool<CommandBufferInfo, CommandBuffer> for LazyPool {
@@ -293,6 +314,7 @@ impl Pool<DescriptorPoolInfo, DescriptorPool> for LazyPool {
                     && item.info.uniform_buffer_count >= info.uniform_buffer_count
                     && item.info.uniform_buffer_dynamic_count >= info.uniform_buffer_dynamic_count
                     && item.info.uniform_texel_buffer_count >= info.uniform_texel_buffer_count
+                    && item.info.update_after_bind == info.update_after_bind
                 {
                     let item = cache.swap_remove(idx);
 
@@ -344,6 +366,10 @@ impl Pool<ImageInfo, Image> for LazyPool {
 
         Ok(Lease::new(cache_ref, item))
     }
+
+    fn clear(&mut self) -> vk::DeviceSize {
+        self.clear_images()
+    }
 }
 
 impl Pool<RenderPassInfo, RenderPass> for LazyPool {
@@ -85,6 +85,7 @@
 //! * Aliased resources are typed `Arc<Lease<T>>` and are not guaranteed to be mutable or unique
 
 pub mod alias;
+pub mod arena;
 pub mod fifo;
 pub mod hash;
 pub mod lazy;
@@ -98,6 +99,7 @@ use {
         image::{Image, ImageInfo, ImageInfoBuilder},
         CommandBuffer, DriverError,
     },
+    ash::vk,
     derive_builder::{Builder, UninitializedFieldError},
     std::{
         fmt::Debug,
@@ -117,6 +119,19 @@ use std::sync::Mutex;
 type Cache<T> = Arc<Mutex<Vec<T>>>;
 type CacheRef<T> = Weak<Mutex<Vec<T>>>;
 
+/// Drops the unused resources of a cache, returning the number of bytes of device memory freed.
+///
+/// Leased resources are not affected and return to the cache as usual.
+fn drain_cache<T>(cache: &Cache<T>, memory_size: fn(&T) -> vk::DeviceSize) -> vk::DeviceSize {
+    #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+    let mut cache = cache.lock();
+
+    #[cfg(not(feature = "parking_lot"))]
+    let mut cache = cache.unwrap();
+
+    cache.drain(..).map(|item| memory_size(&item)).sum()
+}
+
 fn lease_command_buffer(cache: &mut Vec<CommandBuffer>) -> Option<CommandBuffer> {
     for idx in 0..cache.len() {
         if unsafe {
@@ -216,6 +231,82 @@ impl<T> Drop for Lease<T> {
 pub trait Pool<I, T> {
     /// Lease a resource.
     fn lease(&mut self, info: I) -> Result<Lease<T>, DriverError>;
+
+    /// Drops the unused cached resources of this type, returning the number of bytes of device
+    /// memory freed.
+    ///
+    /// Resources which are currently leased are not affected and may be returned to the pool as
+    /// usual. The default implementation frees nothing.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::image::{Image, ImageInfo};
+    /// # use screen_13::pool::Pool;
+    /// # use screen_13::pool::lazy::LazyPool;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// let mut pool = LazyPool::new(&device);
+    ///
+    /// // Free all cached images at a scene transition
+    /// let freed = Pool::<ImageInfo, Image>::clear(&mut pool);
+    /// # Ok(()) }
+    /// ```
+    fn clear(&mut self) -> vk::DeviceSize {
+        0
+    }
+
+    /// Eagerly creates resources for each of the given information and returns them to the pool,
+    /// so that later leases of the same information do not allocate.
+    ///
+    /// Resources are leased at the same time, so repeated information creates additional
+    /// resources. Resources in excess of the capacity of the pool are dropped, as with any other
+    /// lease.
+    ///
+    /// This is useful for moving allocation costs to a loading screen instead of the first frames
+    /// which use the resources.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::image::ImageInfo;
+    /// # use screen_13::pool::Pool;
+    /// # use screen_13::pool::hash::HashPool;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// let mut pool = HashPool::new(&device);
+    /// let info = ImageInfo::image_2d(1920, 1080, vk::Format::R8G8B8A8_UNORM, vk::ImageUsageFlags::SAMPLED);
+    ///
+    /// // Two images will be ready for leasing
+    /// pool.preallocate([info, info])?;
+    /// # Ok(()) }
+    /// ```
+    #[profiling::function]
+    fn preallocate(&mut self, infos: impl IntoIterator<Item = I>) -> Result<(), DriverError>
+    where
+        Self: Sized,
+    {
+        let leases = infos
+            .into_iter()
+            .map(|info| self.lease(info))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        drop(leases);
+
+        Ok(())
+    }
 }
 
 // Enable leasing items using their info builder type for convenience
@@ -228,6 +319,10 @@ macro_rules! lease_builder {
 
                     self.lease(info)
                 }
+
+                fn clear(&mut self) -> vk::DeviceSize {
+                    Pool::<$info, $item>::clear(self)
+                }
             }
         }
     };
@@ -0,0 +1,236 @@
+use {
+    super::{
+        driver::{
+            buffer::{Buffer, BufferInfo},
+            device::Device,
+            DriverError,
+        },
+        graph::{node::BufferLeaseNode, RenderGraph},
+        pool::Pool,
+    },
+    ash::vk,
+    log::trace,
+    std::sync::Arc,
+};
+
+/// Streams data, such as dynamic geometry, to device-local buffers using a ring of persistently
+/// mapped staging buffers.
+///
+/// Each call to [`upload`](Self::upload) writes data into the current staging buffer and records
+/// a copy into a device-local buffer leased from a pool. Once a staging buffer is full the next
+/// one is used, and staging buffers are only reused after every render graph which copies from
+/// them has been dropped. Submitted render graphs are dropped once their command buffer has
+/// executed and is released, such as by [`HeadlessRing`](crate::HeadlessRing) or when the pool
+/// leases the command buffer again.
+///
+/// The ring is not bounded: new staging buffers are created as required, so uploading never
+/// blocks. Call [`free_unused`](Self::free_unused) to free staging buffers which are no longer
+/// used, for example after a burst of uploads.
+///
+/// _NOTE:_ Leased buffers of the exact uploaded size are requested from the pool; a pool which
+/// returns compatible buffers, such as [`LazyPool`](crate::pool::lazy::LazyPool), avoids creating
+/// new buffers when the size of uploads varies.
+#[derive(Debug)]
+pub struct Uploader {
+    capacity: vk::DeviceSize,
+    device: Arc<Device>,
+    offset: vk::DeviceSize,
+    staging_bufs: Vec<Arc<Buffer>>,
+    usage: vk::BufferUsageFlags,
+}
+
+impl Uploader {
+    /// Constructs a new `Uploader` which stages data in buffers of `capacity` bytes and uploads it
+    /// to buffers with the given `usage`.
+    pub fn new(
+        device: &Arc<Device>,
+        capacity: vk::DeviceSize,
+        usage: vk::BufferUsageFlags,
+    ) -> Self {
+        Self {
+            capacity,
+            device: Arc::clone(device),
+            offset: 0,
+            staging_bufs: vec![],
+            usage,
+        }
+    }
+
+    /// Uploads `data` to a new device-local buffer which is ready for use by any later pass of
+    /// `render_graph`.
+    #[profiling::function]
+    pub fn upload<P>(
+        &mut self,
+        render_graph: &mut RenderGraph,
+        pool: &mut P,
+        data: &[u8],
+    ) -> Result<BufferLeaseNode, DriverError>
+    where
+        P: Pool<BufferInfo, Buffer>,
+    {
+        debug_assert!(!data.is_empty(), "data must not be empty");
+
+        let len = data.len() as vk::DeviceSize;
+        let staging_buf = self.staging_buf(len)?;
+        let offset = self.offset;
+        self.offset += len;
+
+        unsafe {
+            // Staging buffers are never exposed and later uploads only write past the ranges which
+            // in-flight render graphs read from
+            Buffer::copy_from_slice_unchecked(&staging_buf, offset, data);
+        }
+
+        Buffer::flush_range(&staging_buf, offset, len)?;
+
+        let src_buf = render_graph.bind_node(staging_buf);
+        let dst_buf = render_graph.bind_node(pool.lease(BufferInfo::device_mem(
+            len,
+            self.usage | vk::BufferUsageFlags::TRANSFER_DST,
+        ))?);
+        render_graph.copy_buffer_region(
+            src_buf,
+            dst_buf,
+            vk::BufferCopy {
+                src_offset: offset,
+                dst_offset: 0,
+                size: len,
+            },
+        );
+
+        Ok(dst_buf)
+    }
+
+    /// Frees the staging buffers which are not used by any render graph, except for the current
+    /// staging buffer, and returns the number of bytes of memory freed.
+    #[profiling::function]
+    pub fn free_unused(&mut self) -> vk::DeviceSize {
+        let current_idx = self.staging_bufs.len().saturating_sub(1);
+        let mut freed = 0;
+        let mut idx = 0;
+
+        self.staging_bufs.retain(|staging_buf| {
+            let is_used = idx == current_idx || Arc::strong_count(staging_buf) > 1;
+            idx += 1;
+
+            if !is_used {
+                freed += staging_buf.info.size;
+            }
+
+            is_used
+        });
+
+        freed
+    }
+
+    /// Returns the staging buffer for the next `len` bytes, moving on to an unused staging buffer
+    /// when the current one is full.
+    fn staging_buf(&mut self, len: vk::DeviceSize) -> Result<Arc<Buffer>, DriverError> {
+        if let Some(staging_buf) = self.staging_bufs.last() {
+            if self.offset + len <= staging_buf.info.size {
+                return Ok(Arc::clone(staging_buf));
+            }
+        }
+
+        self.offset = 0;
+
+        // Staging buffers which are referenced only by this uploader are no longer used by any
+        // render graph
+        let unused_idx = self.staging_bufs.iter().position(|staging_buf| {
+            Arc::strong_count(staging_buf) == 1 && len <= staging_buf.info.size
+        });
+        let staging_buf = if let Some(unused_idx) = unused_idx {
+            self.staging_bufs.remove(unused_idx)
+        } else {
+            trace!("creating staging buffer");
+
+            Arc::new(Buffer::create(
+                &self.device,
+                BufferInfo::host_mem(self.capacity.max(len), vk::BufferUsageFlags::TRANSFER_SRC),
+            )?)
+        };
+
+        self.staging_bufs.push(Arc::clone(&staging_buf));
+
+        Ok(staging_buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            driver::{device::test_device, CommandBuffer},
+            pool::lazy::LazyPool,
+        },
+    };
+
+    #[test]
+    pub fn upload() {
+        let Some(device) = test_device() else {
+            return;
+        };
+
+        let mut uploader = Uploader::new(&device, 16, vk::BufferUsageFlags::TRANSFER_SRC);
+        let mut pool = LazyPool::new(&device);
+        let mut graph = RenderGraph::new();
+        let buf = uploader
+            .upload(&mut graph, &mut pool, &[1, 2, 3, 4])
+            .unwrap();
+
+        let data = graph.copy_buffer_to_host(buf, 0..4, &mut pool).unwrap();
+
+        assert_eq!(data, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    pub fn upload_reuses_staging_bufs() {
+        let Some(device) = test_device() else {
+            return;
+        };
+
+        let mut uploader = Uploader::new(&device, 16, vk::BufferUsageFlags::VERTEX_BUFFER);
+        let mut pool = LazyPool::new(&device);
+
+        // The second upload does not fit and the first staging buffer is still in use
+        let mut graph = RenderGraph::new();
+        uploader.upload(&mut graph, &mut pool, &[0; 12]).unwrap();
+        uploader.upload(&mut graph, &mut pool, &[0; 12]).unwrap();
+
+        assert_eq!(uploader.staging_bufs.len(), 2);
+
+        let (mut cmd_buf, _) = graph.resolve().submit(&mut pool, 0, 0).unwrap();
+        cmd_buf.wait_until_executed().unwrap();
+        CommandBuffer::drop_fenced(&mut cmd_buf);
+
+        // The first staging buffer is reused once the render graph has been dropped
+        let mut graph = RenderGraph::new();
+        uploader.upload(&mut graph, &mut pool, &[0; 12]).unwrap();
+
+        assert_eq!(uploader.staging_bufs.len(), 2);
+    }
+
+    #[test]
+    pub fn free_unused() {
+        let Some(device) = test_device() else {
+            return;
+        };
+
+        let mut uploader = Uploader::new(&device, 16, vk::BufferUsageFlags::VERTEX_BUFFER);
+        let mut pool = LazyPool::new(&device);
+
+        let mut graph = RenderGraph::new();
+        uploader.upload(&mut graph, &mut pool, &[0; 12]).unwrap();
+        uploader.upload(&mut graph, &mut pool, &[0; 12]).unwrap();
+
+        // Staging buffers used by a render graph are not freed
+        assert_eq!(uploader.free_unused(), 0);
+
+        drop(graph);
+
+        // The current staging buffer is kept
+        assert_eq!(uploader.free_unused(), 16);
+        assert_eq!(uploader.staging_bufs.len(), 1);
+    }
+}